@@ -0,0 +1,26 @@
+-- 1. Add the replacement column with the wider CHECK
+ALTER TABLE execution_processes
+  ADD COLUMN status_new TEXT NOT NULL DEFAULT 'running'
+    CHECK (status_new IN ('running',
+                          'completed',
+                          'failed',
+                          'killed',
+                          'limitexceeded'));
+
+-- 2. Copy existing values across
+UPDATE execution_processes
+  SET status_new = status;
+
+-- 3. Drop any indexes that mention the old column
+DROP INDEX IF EXISTS idx_execution_processes_status;
+
+-- 4. Remove the old column (requires 3.35+)
+ALTER TABLE execution_processes DROP COLUMN status;
+
+-- 5. Rename the new column back to the canonical name
+ALTER TABLE execution_processes
+  RENAME COLUMN status_new TO status;
+
+-- 6. Re-create the index
+CREATE INDEX idx_execution_processes_status
+        ON execution_processes(status);
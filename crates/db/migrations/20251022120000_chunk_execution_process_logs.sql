@@ -0,0 +1,33 @@
+PRAGMA foreign_keys = ON;
+
+-- Logs are stored as sequenced chunks instead of one growing row per process.
+-- The newest chunk is appended to uncompressed and zstd compressed once sealed.
+CREATE TABLE execution_process_log_chunks (
+    execution_id      BLOB NOT NULL,
+    seq               INTEGER NOT NULL,
+    first_line        INTEGER NOT NULL,   -- index of the chunk's first JSONL line in the process log
+    line_count        INTEGER NOT NULL,
+    byte_size         INTEGER NOT NULL,   -- uncompressed size
+    compressed        INTEGER NOT NULL DEFAULT 0 CHECK (compressed IN (0, 1)),
+    data              BLOB NOT NULL,
+    inserted_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (execution_id, seq),
+    FOREIGN KEY (execution_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_execution_process_log_chunks_inserted_at ON execution_process_log_chunks(inserted_at);
+
+-- Existing logs become a single open chunk, split and compressed at startup
+INSERT INTO execution_process_log_chunks
+    (execution_id, seq, first_line, line_count, byte_size, compressed, data, inserted_at)
+SELECT execution_id,
+       0,
+       0,
+       LENGTH(logs) - LENGTH(REPLACE(logs, char(10), '')),
+       byte_size,
+       0,
+       CAST(logs AS BLOB),
+       inserted_at
+FROM execution_process_logs;
+
+DROP TABLE execution_process_logs;
@@ -0,0 +1,16 @@
+PRAGMA foreign_keys = ON;
+
+-- Final normalized conversation of finished execution processes, so they are served as
+-- they rendered at the time instead of re-running the executor's log parser
+CREATE TABLE execution_process_conversations (
+    execution_id        BLOB PRIMARY KEY,
+    normalizer_version  INTEGER NOT NULL,
+    entry_count         INTEGER NOT NULL,
+    data                BLOB NOT NULL,      -- zstd compressed JSON array of entries
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_execution_process_conversations_version
+    ON execution_process_conversations(normalizer_version);
@@ -0,0 +1,12 @@
+PRAGMA foreign_keys = ON;
+
+-- Summary of raw logs removed by the retention policy
+CREATE TABLE execution_process_log_prunes (
+    execution_id  BLOB PRIMARY KEY,
+    line_count    INTEGER NOT NULL,
+    byte_size     INTEGER NOT NULL,   -- uncompressed size of the removed logs
+    stored_size   INTEGER NOT NULL,   -- size the logs took in the database
+    reason        TEXT NOT NULL CHECK (reason IN ('expired', 'dropped', 'quota')),
+    pruned_at     TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
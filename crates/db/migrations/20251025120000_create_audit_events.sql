@@ -0,0 +1,30 @@
+PRAGMA foreign_keys = ON;
+
+-- Append-only record of who changed what. Rows outlive the entities they mention, so the ids
+-- are not foreign keys.
+CREATE TABLE audit_events (
+    id               BLOB PRIMARY KEY,
+    project_id       BLOB,
+    task_id          BLOB,
+    task_attempt_id  BLOB,
+    actor            TEXT NOT NULL CHECK (actor IN ('user', 'agent', 'mcp', 'pr_monitor', 'system')),
+    action           TEXT NOT NULL,
+    details          TEXT,   -- JSON
+    created_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_audit_events_project_id_created_at ON audit_events(project_id, created_at);
+CREATE INDEX idx_audit_events_actor_created_at ON audit_events(actor, created_at);
+CREATE INDEX idx_audit_events_created_at ON audit_events(created_at);
+
+CREATE TRIGGER audit_events_no_update
+BEFORE UPDATE ON audit_events
+BEGIN
+    SELECT RAISE(ABORT, 'audit_events is append-only');
+END;
+
+CREATE TRIGGER audit_events_no_delete
+BEFORE DELETE ON audit_events
+BEGIN
+    SELECT RAISE(ABORT, 'audit_events is append-only');
+END;
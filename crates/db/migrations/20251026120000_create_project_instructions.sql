@@ -0,0 +1,33 @@
+PRAGMA foreign_keys = ON;
+
+-- Standing instructions placed before the prompts of every coding agent run in a project
+CREATE TABLE project_instructions (
+    project_id  BLOB PRIMARY KEY,
+    content     TEXT NOT NULL DEFAULT '',
+    sync_path   TEXT,   -- file in the repository appended to the content, e.g. AGENTS.md
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+-- Every distinct text agents of a project were given, numbered per project
+CREATE TABLE project_instruction_versions (
+    id          BLOB PRIMARY KEY,
+    project_id  BLOB NOT NULL,
+    version     INTEGER NOT NULL,
+    content     TEXT NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    UNIQUE (project_id, version)
+);
+
+-- The version an attempt's agents were given. Pinned when its first coding agent starts.
+CREATE TABLE task_attempt_instructions (
+    task_attempt_id  BLOB PRIMARY KEY,
+    version_id       BLOB NOT NULL,
+    created_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE,
+    FOREIGN KEY (version_id) REFERENCES project_instruction_versions(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_attempt_instructions_version_id ON task_attempt_instructions(version_id);
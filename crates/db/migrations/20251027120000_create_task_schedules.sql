@@ -0,0 +1,39 @@
+PRAGMA foreign_keys = ON;
+
+-- Cron schedules that create a task from a template, and optionally start an attempt on it
+CREATE TABLE task_schedules (
+    id                   BLOB PRIMARY KEY,
+    project_id           BLOB NOT NULL,
+    tag_id               BLOB NOT NULL,   -- template whose content becomes the task description
+    title                TEXT NOT NULL,
+    cron                 TEXT NOT NULL,   -- evaluated in the server's local time zone
+    executor_profile_id  TEXT,            -- JSON ExecutorProfileId, set to start an attempt
+    base_branch          TEXT,            -- defaults to the repository's current branch
+    catch_up             TEXT NOT NULL DEFAULT 'once' CHECK (catch_up IN ('skip', 'once', 'all')),
+    paused               INTEGER NOT NULL DEFAULT 0,
+    next_run_at          TEXT NOT NULL,
+    last_run_at          TEXT,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_schedules_project_id ON task_schedules(project_id);
+CREATE INDEX idx_task_schedules_paused_next_run_at ON task_schedules(paused, next_run_at);
+
+-- One row per scheduled time, which also keeps a time from running twice
+CREATE TABLE task_schedule_runs (
+    id               BLOB PRIMARY KEY,
+    schedule_id      BLOB NOT NULL,
+    scheduled_for    TEXT NOT NULL,
+    status           TEXT NOT NULL CHECK (status IN ('pending', 'created', 'started', 'skipped', 'failed')),
+    task_id          BLOB,
+    task_attempt_id  BLOB,
+    error            TEXT,
+    created_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (schedule_id) REFERENCES task_schedules(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE SET NULL,
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE SET NULL,
+    UNIQUE (schedule_id, scheduled_for)
+);
@@ -0,0 +1,38 @@
+PRAGMA foreign_keys = ON;
+
+-- Issue trackers whose open issues are imported into a project as tasks
+CREATE TABLE issue_trackers (
+    id              BLOB PRIMARY KEY,
+    project_id      BLOB NOT NULL,
+    kind            TEXT NOT NULL CHECK (kind IN ('github', 'rest')),
+    url             TEXT,             -- issues endpoint of a REST tracker
+    token_env       TEXT,             -- project env var holding the REST tracker's bearer token
+    label           TEXT,             -- only import GitHub issues with this label
+    auto_sync       INTEGER NOT NULL DEFAULT 1,
+    last_synced_at  TEXT,
+    last_error      TEXT,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_issue_trackers_project_id ON issue_trackers(project_id);
+
+-- Issues imported from a tracker. The row outlives its task, so an issue whose task was deleted
+-- isn't imported again.
+CREATE TABLE task_issue_links (
+    id                  BLOB PRIMARY KEY,
+    tracker_id          BLOB NOT NULL,
+    task_id             BLOB UNIQUE,
+    external_id         TEXT NOT NULL,
+    url                 TEXT,
+    remote_title        TEXT NOT NULL,   -- title and description as last seen on the tracker
+    remote_description  TEXT,
+    posted_status       TEXT CHECK (posted_status IN ('inreview', 'done')),
+    posted_pr_url       TEXT,
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (tracker_id) REFERENCES issue_trackers(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE SET NULL,
+    UNIQUE (tracker_id, external_id)
+);
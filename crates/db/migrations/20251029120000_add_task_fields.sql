@@ -0,0 +1,60 @@
+PRAGMA foreign_keys = ON;
+
+ALTER TABLE tasks ADD COLUMN priority TEXT CHECK (priority IN ('low', 'medium', 'high', 'urgent'));
+ALTER TABLE tasks ADD COLUMN assignee TEXT;
+ALTER TABLE tasks ADD COLUMN due_date TEXT;   -- YYYY-MM-DD
+
+-- Labels tasks of a project can be tagged with
+CREATE TABLE labels (
+    id          BLOB PRIMARY KEY,
+    project_id  BLOB NOT NULL,
+    name        TEXT NOT NULL COLLATE NOCASE,
+    color       TEXT,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    UNIQUE (project_id, name)
+);
+
+CREATE TABLE task_labels (
+    task_id   BLOB NOT NULL,
+    label_id  BLOB NOT NULL,
+    PRIMARY KEY (task_id, label_id),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (label_id) REFERENCES labels(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_labels_label_id ON task_labels(label_id);
+
+-- Typed fields a project defines for its tasks
+CREATE TABLE custom_fields (
+    id          BLOB PRIMARY KEY,
+    project_id  BLOB NOT NULL,
+    name        TEXT NOT NULL COLLATE NOCASE,
+    field_type  TEXT NOT NULL CHECK (field_type IN ('text', 'number', 'boolean', 'date', 'select')),
+    options     TEXT NOT NULL DEFAULT '[]',   -- JSON array of the choices of a select field
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    UNIQUE (project_id, name)
+);
+
+CREATE TABLE task_custom_field_values (
+    task_id   BLOB NOT NULL,
+    field_id  BLOB NOT NULL,
+    value     TEXT NOT NULL,   -- JSON, normalised for the field's type so values compare as text
+    PRIMARY KEY (task_id, field_id),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (field_id) REFERENCES custom_fields(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_custom_field_values_field_id ON task_custom_field_values(field_id);
+
+-- Labels and custom fields belong to a project, so a task moved to another project loses them
+CREATE TRIGGER tasks_project_changed_clear_fields
+AFTER UPDATE OF project_id ON tasks
+WHEN NEW.project_id IS NOT OLD.project_id
+BEGIN
+    DELETE FROM task_labels WHERE task_id = NEW.id;
+    DELETE FROM task_custom_field_values WHERE task_id = NEW.id;
+END;
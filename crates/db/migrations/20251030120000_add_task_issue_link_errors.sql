@@ -0,0 +1,4 @@
+PRAGMA foreign_keys = ON;
+
+-- Why the issue last failed to sync. A failing issue doesn't stop the sync of the others.
+ALTER TABLE task_issue_links ADD COLUMN last_error TEXT;
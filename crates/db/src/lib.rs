@@ -25,6 +25,19 @@ impl DBService {
         Ok(DBService { pool })
     }
 
+    /// A private in-memory database with every migration applied, for tests
+    pub async fn new_in_memory() -> Result<DBService, Error> {
+        // Each connection to :memory: opens its own database, so the pool keeps exactly one
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect_with(SqliteConnectOptions::from_str("sqlite::memory:")?)
+            .await?;
+        sqlx::migrate!("./migrations").run(&pool).await?;
+        Ok(DBService { pool })
+    }
+
     pub async fn new_with_after_connect<F>(after_connect: F) -> Result<DBService, Error>
     where
         F: for<'a> Fn(
@@ -0,0 +1,190 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Largest page returned by [`AuditEvent::find`]
+pub const MAX_AUDIT_PAGE: i64 = 500;
+
+/// Who caused an audited change
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "audit_actor", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum AuditActor {
+    /// A request from the web UI or the API
+    User,
+    /// A coding agent, e.g. when its run finished or it asked for an approval
+    Agent,
+    /// A tool call on the MCP server
+    Mcp,
+    /// The background check of open pull requests
+    PrMonitor,
+    /// Housekeeping such as starting executions or recovering after a restart
+    System,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "audit_action", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    TaskStatusChanged,
+    TaskDeleted,
+    TaskMoved,
+    AttemptMerged,
+    BranchPushed,
+    PrCreated,
+    PrAttached,
+    ApprovalResponded,
+    ConfigUpdated,
+    ProfilesUpdated,
+    McpServersUpdated,
+    ProjectUpdated,
+    ProjectDeleted,
+}
+
+/// An entry of the append-only audit log
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct AuditEvent {
+    pub id: Uuid,
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub task_attempt_id: Option<Uuid>,
+    pub actor: AuditActor,
+    pub action: AuditAction,
+    /// Action specific context, e.g. the previous and new status of a task
+    #[ts(type = "JsonValue | null")]
+    pub details: Option<Json<Value>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateAuditEvent {
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub task_attempt_id: Option<Uuid>,
+    pub actor: AuditActor,
+    pub action: AuditAction,
+    pub details: Option<Value>,
+}
+
+impl CreateAuditEvent {
+    pub fn new(actor: AuditActor, action: AuditAction) -> Self {
+        Self {
+            project_id: None,
+            task_id: None,
+            task_attempt_id: None,
+            actor,
+            action,
+            details: None,
+        }
+    }
+
+    pub fn project(mut self, project_id: Uuid) -> Self {
+        self.project_id = Some(project_id);
+        self
+    }
+
+    pub fn task(mut self, project_id: Uuid, task_id: Uuid) -> Self {
+        self.project_id = Some(project_id);
+        self.task_id = Some(task_id);
+        self
+    }
+
+    pub fn attempt(mut self, task_attempt_id: Uuid) -> Self {
+        self.task_attempt_id = Some(task_attempt_id);
+        self
+    }
+
+    pub fn details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+}
+
+/// Filters of an audit log query. Results are newest first.
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+pub struct AuditQuery {
+    #[ts(optional)]
+    pub project_id: Option<Uuid>,
+    #[ts(optional)]
+    pub actor: Option<AuditActor>,
+    /// Only events at or after this time
+    #[ts(optional)]
+    pub from: Option<DateTime<Utc>>,
+    /// Only events before this time
+    #[ts(optional)]
+    pub to: Option<DateTime<Utc>>,
+    /// Defaults to 100, capped at 500
+    #[ts(optional, type = "number")]
+    pub limit: Option<i64>,
+}
+
+impl AuditEvent {
+    pub async fn create(pool: &SqlitePool, data: CreateAuditEvent) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let details = data.details.map(Json);
+        sqlx::query_as!(
+            AuditEvent,
+            r#"INSERT INTO audit_events
+                   (id, project_id, task_id, task_attempt_id, actor, action, details)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               RETURNING id as "id!: Uuid", project_id as "project_id: Uuid", task_id as "task_id: Uuid", task_attempt_id as "task_attempt_id: Uuid",
+                         actor as "actor!: AuditActor", action as "action!: AuditAction", details as "details: Json<Value>", created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            data.task_id,
+            data.task_attempt_id,
+            data.actor,
+            data.action,
+            details
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Record an event without failing the change it describes
+    pub async fn record(pool: &SqlitePool, data: CreateAuditEvent) {
+        let action = data.action;
+        if let Err(e) = Self::create(pool, data).await {
+            tracing::error!("Failed to record audit event {:?}: {}", action, e);
+        }
+    }
+
+    pub async fn find_by_rowid(pool: &SqlitePool, rowid: i64) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AuditEvent,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id: Uuid", task_id as "task_id: Uuid", task_attempt_id as "task_attempt_id: Uuid",
+                      actor as "actor!: AuditActor", action as "action!: AuditAction", details as "details: Json<Value>", created_at as "created_at!: DateTime<Utc>"
+               FROM audit_events
+               WHERE rowid = $1"#,
+            rowid
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find(pool: &SqlitePool, query: &AuditQuery) -> Result<Vec<Self>, sqlx::Error> {
+        let limit = query.limit.unwrap_or(100).clamp(1, MAX_AUDIT_PAGE);
+        sqlx::query_as!(
+            AuditEvent,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id: Uuid", task_id as "task_id: Uuid", task_attempt_id as "task_attempt_id: Uuid",
+                      actor as "actor!: AuditActor", action as "action!: AuditAction", details as "details: Json<Value>", created_at as "created_at!: DateTime<Utc>"
+               FROM audit_events
+               WHERE ($1 IS NULL OR project_id = $1)
+                 AND ($2 IS NULL OR actor = $2)
+                 AND ($3 IS NULL OR created_at >= datetime($3, 'subsec'))
+                 AND ($4 IS NULL OR created_at < datetime($4, 'subsec'))
+               ORDER BY created_at DESC, rowid DESC
+               LIMIT $5"#,
+            query.project_id,
+            query.actor,
+            query.from,
+            query.to,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
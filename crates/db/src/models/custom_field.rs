@@ -0,0 +1,351 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::TaskFieldsError;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "custom_field_type", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum CustomFieldType {
+    Text,
+    Number,
+    Boolean,
+    /// A date as YYYY-MM-DD
+    Date,
+    /// One of the field's options
+    Select,
+}
+
+impl CustomFieldType {
+    fn expected(self) -> &'static str {
+        match self {
+            CustomFieldType::Text => "text",
+            CustomFieldType::Number => "a number",
+            CustomFieldType::Boolean => "true or false",
+            CustomFieldType::Date => "a date as YYYY-MM-DD",
+            CustomFieldType::Select => "one of the field's options",
+        }
+    }
+
+    /// Select fields need options to choose from, and other fields can't have any
+    pub fn validate_options(self, options: &[String]) -> Result<(), TaskFieldsError> {
+        let valid = match self {
+            CustomFieldType::Select => {
+                !options.is_empty() && options.iter().all(|option| !option.trim().is_empty())
+            }
+            _ => options.is_empty(),
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(TaskFieldsError::InvalidOptions)
+        }
+    }
+}
+
+/// A typed field a project defines for its tasks
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct CustomField {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    /// Unique within the project, ignoring case
+    pub name: String,
+    pub field_type: CustomFieldType,
+    /// Choices of a select field
+    #[sqlx(json)]
+    pub options: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateCustomField {
+    pub project_id: Uuid,
+    pub name: String,
+    pub field_type: CustomFieldType,
+    #[serde(default)]
+    pub options: Vec<String>,
+}
+
+/// The type of a field can't change, as its values would no longer fit it
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateCustomField {
+    pub name: String,
+    #[serde(default)]
+    pub options: Vec<String>,
+}
+
+/// A task's value for one of its project's custom fields
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskCustomFieldValue {
+    pub field_id: Uuid,
+    pub name: String,
+    pub field_type: CustomFieldType,
+    #[sqlx(json)]
+    pub value: Value,
+}
+
+/// A custom field as stored, with its options still wrapped as JSON
+struct CustomFieldRow {
+    id: Uuid,
+    project_id: Uuid,
+    name: String,
+    field_type: CustomFieldType,
+    options: Json<Vec<String>>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<CustomFieldRow> for CustomField {
+    fn from(row: CustomFieldRow) -> Self {
+        CustomField {
+            id: row.id,
+            project_id: row.project_id,
+            name: row.name,
+            field_type: row.field_type,
+            options: row.options.0,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+impl CustomField {
+    /// The value in the form it's stored and compared in, or an error if it doesn't suit the
+    /// field
+    pub fn normalize(&self, value: &Value) -> Result<Value, TaskFieldsError> {
+        let normalized = match (self.field_type, value) {
+            (CustomFieldType::Text, Value::String(_)) => Some(value.clone()),
+            // Stored as floats, so 3 and 3.0 are the same value
+            (CustomFieldType::Number, Value::Number(number)) => number.as_f64().map(Value::from),
+            (CustomFieldType::Boolean, Value::Bool(_)) => Some(value.clone()),
+            (CustomFieldType::Date, Value::String(date)) => {
+                NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+                    .ok()
+                    .map(|date| Value::String(date.format("%Y-%m-%d").to_string()))
+            }
+            (CustomFieldType::Select, Value::String(option)) => {
+                self.options.contains(option).then(|| value.clone())
+            }
+            _ => None,
+        };
+        normalized.ok_or_else(|| TaskFieldsError::InvalidValue {
+            field: self.name.clone(),
+            expected: self.field_type.expected(),
+        })
+    }
+
+    /// Parse a value given as text, e.g. in a query string. None if no value of the field could
+    /// match it.
+    pub fn parse_value(&self, text: &str) -> Option<Value> {
+        let value = match self.field_type {
+            CustomFieldType::Number => Value::from(text.trim().parse::<f64>().ok()?),
+            CustomFieldType::Boolean => Value::Bool(text.trim().parse().ok()?),
+            CustomFieldType::Text | CustomFieldType::Date | CustomFieldType::Select => {
+                Value::String(text.to_string())
+            }
+        };
+        self.normalize(&value).ok()
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        let row = sqlx::query_as!(
+            CustomFieldRow,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", name, field_type as "field_type!: CustomFieldType",
+                      options as "options!: Json<Vec<String>>", created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM custom_fields
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(Self::from))
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            CustomFieldRow,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", name, field_type as "field_type!: CustomFieldType",
+                      options as "options!: Json<Vec<String>>", created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM custom_fields
+               WHERE project_id = $1
+               ORDER BY name ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(Self::from).collect())
+    }
+
+    pub async fn find_by_name(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        name: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let name = name.trim();
+        let row = sqlx::query_as!(
+            CustomFieldRow,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", name, field_type as "field_type!: CustomFieldType",
+                      options as "options!: Json<Vec<String>>", created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM custom_fields
+               WHERE project_id = $1 AND name = $2"#,
+            project_id,
+            name
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(Self::from))
+    }
+
+    /// Ids of the tasks with a value for the field
+    pub async fn find_task_ids(pool: &SqlitePool, id: Uuid) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT task_id as "task_id!: Uuid" FROM task_custom_field_values WHERE field_id = $1"#,
+            id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateCustomField,
+        id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let name = data.name.trim();
+        let options = Json(&data.options);
+        let row = sqlx::query_as!(
+            CustomFieldRow,
+            r#"INSERT INTO custom_fields (id, project_id, name, field_type, options)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", name, field_type as "field_type!: CustomFieldType",
+                      options as "options!: Json<Vec<String>>", created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            name,
+            data.field_type,
+            options
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(row.into())
+    }
+
+    /// Rename the field and replace its options. Values that are no longer an option are
+    /// cleared.
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateCustomField,
+    ) -> Result<Self, sqlx::Error> {
+        let name = data.name.trim();
+        let options = Json(&data.options);
+        let mut tx = pool.begin().await?;
+        let field: CustomField = sqlx::query_as!(
+            CustomFieldRow,
+            r#"UPDATE custom_fields
+               SET name = $2, options = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", name, field_type as "field_type!: CustomFieldType",
+                      options as "options!: Json<Vec<String>>", created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            name,
+            options
+        )
+        .fetch_one(&mut *tx)
+        .await?
+        .into();
+        if field.field_type == CustomFieldType::Select {
+            sqlx::query!(
+                r#"DELETE FROM task_custom_field_values
+                   WHERE field_id = $1
+                     AND value NOT IN (SELECT json_quote(value) FROM json_each($2))"#,
+                id,
+                options
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(field)
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM custom_fields WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl TaskCustomFieldValue {
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let records = sqlx::query!(
+            r#"SELECT v.field_id as "field_id!: Uuid", f.name, f.field_type as "field_type!: CustomFieldType",
+                      v.value as "value!: Json<Value>"
+               FROM task_custom_field_values v
+               JOIN custom_fields f ON f.id = v.field_id
+               WHERE v.task_id = $1
+               ORDER BY f.name ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(records
+            .into_iter()
+            .map(|rec| TaskCustomFieldValue {
+                field_id: rec.field_id,
+                name: rec.name,
+                field_type: rec.field_type,
+                value: rec.value.0,
+            })
+            .collect())
+    }
+
+    /// The custom field values of every task of the project, as (task id, value) pairs
+    pub async fn find_by_project_tasks(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<(Uuid, Self)>, sqlx::Error> {
+        let records = sqlx::query!(
+            r#"SELECT v.task_id as "task_id!: Uuid", v.field_id as "field_id!: Uuid", f.name,
+                      f.field_type as "field_type!: CustomFieldType", v.value as "value!: Json<Value>"
+               FROM task_custom_field_values v
+               JOIN custom_fields f ON f.id = v.field_id
+               WHERE f.project_id = $1
+               ORDER BY f.name ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(records
+            .into_iter()
+            .map(|rec| {
+                (
+                    rec.task_id,
+                    TaskCustomFieldValue {
+                        field_id: rec.field_id,
+                        name: rec.name,
+                        field_type: rec.field_type,
+                        value: rec.value.0,
+                    },
+                )
+            })
+            .collect())
+    }
+}
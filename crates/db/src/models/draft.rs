@@ -214,6 +214,13 @@ impl Draft {
         .map(Draft::from)
     }
 
+    /// Number of follow-up drafts waiting to start
+    pub async fn count_queued(pool: &SqlitePool) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(r#"SELECT COUNT(*) as "count!: i64" FROM drafts WHERE queued = 1"#)
+            .fetch_one(pool)
+            .await
+    }
+
     pub async fn clear_after_send(
         pool: &SqlitePool,
         task_attempt_id: Uuid,
@@ -6,6 +6,7 @@ use executors::{
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sqlx::{FromRow, SqlitePool, Type};
+use strum_macros::Display;
 use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
@@ -28,20 +29,24 @@ pub enum ExecutionProcessError {
     ValidationError(String),
 }
 
-#[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS)]
+#[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS, Display)]
 #[sqlx(type_name = "execution_process_status", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
 #[ts(use_ts_enum)]
 pub enum ExecutionProcessStatus {
     Running,
     Completed,
     Failed,
     Killed,
+    /// Stopped for exceeding its profile's memory or wall-clock limit
+    LimitExceeded,
 }
 
-#[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS)]
+#[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS, Display)]
 #[sqlx(type_name = "execution_process_run_reason", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
 pub enum ExecutionProcessRunReason {
     SetupScript,
     CleanupScript,
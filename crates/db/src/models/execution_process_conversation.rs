@@ -0,0 +1,94 @@
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::{FromRow, SqlitePool};
+use uuid::Uuid;
+
+use super::execution_process_logs::ExecutionProcessLogsError;
+
+const ZSTD_LEVEL: i32 = 3;
+
+/// The normalized conversation of a finished execution process, stored so it renders the same
+/// way later even if the executor's log parser changes.
+#[derive(Debug, Clone, FromRow)]
+pub struct ExecutionProcessConversation {
+    pub execution_id: Uuid,
+    /// Version of the normalizers that produced the entries
+    pub normalizer_version: i64,
+    pub entry_count: i64,
+    /// zstd compressed JSON array of conversation entries
+    pub data: Vec<u8>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ExecutionProcessConversation {
+    pub async fn find_by_execution_id(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcessConversation,
+            r#"SELECT execution_id as "execution_id!: Uuid", normalizer_version, entry_count, data,
+                      created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM execution_process_conversations
+               WHERE execution_id = $1"#,
+            execution_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Up to `limit` execution processes whose stored conversation came from an older normalizer
+    /// version, in id order starting after `after`
+    pub async fn find_outdated(
+        pool: &SqlitePool,
+        current_version: i64,
+        after: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT execution_id as "execution_id!: Uuid" FROM execution_process_conversations
+               WHERE normalizer_version < $1 AND ($2 IS NULL OR execution_id > $2)
+               ORDER BY execution_id ASC
+               LIMIT $3"#,
+            current_version,
+            after,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+        normalizer_version: i64,
+        entries: &[Value],
+    ) -> Result<(), ExecutionProcessLogsError> {
+        let data = zstd::encode_all(serde_json::to_vec(entries)?.as_slice(), ZSTD_LEVEL)?;
+        let entry_count = entries.len() as i64;
+        sqlx::query!(
+            r#"INSERT INTO execution_process_conversations
+                   (execution_id, normalizer_version, entry_count, data)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT (execution_id) DO UPDATE
+               SET normalizer_version = EXCLUDED.normalizer_version,
+                   entry_count = EXCLUDED.entry_count,
+                   data = EXCLUDED.data,
+                   updated_at = datetime('now', 'subsec')"#,
+            execution_id,
+            normalizer_version,
+            entry_count,
+            data
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Decode the stored entries
+    pub fn entries(&self) -> Result<Vec<Value>, ExecutionProcessLogsError> {
+        let json = zstd::decode_all(self.data.as_slice())?;
+        Ok(serde_json::from_slice(&json)?)
+    }
+}
@@ -1,119 +1,791 @@
+use std::io;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{FromRow, SqliteConnection, SqlitePool, Type};
+use thiserror::Error;
 use ts_rs::TS;
-use utils::log_msg::LogMsg;
+use utils::log_msg::TimedLogMsg;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
-pub struct ExecutionProcessLogs {
+/// Uncompressed size at which the open chunk is sealed and a new one started
+const CHUNK_TARGET_BYTES: i64 = 256 * 1024;
+const ZSTD_LEVEL: i32 = 3;
+
+#[derive(Debug, Error)]
+pub enum ExecutionProcessLogsError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Failed to decode log chunk: {0}")]
+    Decode(#[from] io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/// A sequenced slice of an execution's JSONL log. The newest chunk stays uncompressed while the
+/// process appends to it and is zstd compressed once it is sealed.
+#[derive(Debug, Clone, FromRow)]
+pub struct ExecutionProcessLogChunk {
     pub execution_id: Uuid,
-    pub logs: String, // JSONL format
+    pub seq: i64,
+    /// Index of the chunk's first line within the whole log
+    pub first_line: i64,
+    pub line_count: i64,
+    /// Uncompressed size in bytes
     pub byte_size: i64,
+    pub compressed: bool,
+    pub data: Vec<u8>,
     pub inserted_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize, TS)]
-pub struct CreateExecutionProcessLogs {
+impl ExecutionProcessLogChunk {
+    fn lines(&self) -> Result<Vec<String>, io::Error> {
+        let raw = if self.compressed {
+            zstd::decode_all(self.data.as_slice())?
+        } else {
+            self.data.clone()
+        };
+        let text =
+            String::from_utf8(raw).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+}
+
+/// Size summary of an execution's stored logs
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct ExecutionProcessLogs {
     pub execution_id: Uuid,
-    pub logs: String,
+    pub line_count: i64,
+    /// Uncompressed size in bytes
     pub byte_size: i64,
+    /// Size on disk after compression
+    pub stored_size: i64,
+    pub chunk_count: i64,
+}
+
+/// A window of raw log entries
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ExecutionProcessLogPage {
+    /// Index of the first entry in `entries`
+    pub offset: i64,
+    /// Number of entries stored for the process
+    pub total: i64,
+    /// `at` is the capture time, missing for logs stored before it was recorded
+    #[ts(type = "Array<({ Stdout: string } | { Stderr: string }) & { at?: string }>")]
+    pub entries: Vec<TimedLogMsg>,
+    /// Set when the retention policy removed the logs
+    pub pruned: Option<ExecutionProcessLogPrune>,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "log_prune_reason", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum LogPruneReason {
+    /// Older than the configured retention period
+    Expired,
+    /// The process was dropped from the attempt history
+    Dropped,
+    /// The project went over its log storage quota
+    Quota,
+}
+
+/// What was removed when an execution's raw logs were pruned
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct ExecutionProcessLogPrune {
+    pub execution_id: Uuid,
+    pub line_count: i64,
+    pub byte_size: i64,
+    pub stored_size: i64,
+    pub reason: LogPruneReason,
+    pub pruned_at: DateTime<Utc>,
+}
+
+/// Stored logs of a finished execution process, as seen by the retention policy
+#[derive(Debug, Clone, FromRow)]
+pub struct StoredLogUsage {
+    pub execution_id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub project_id: Uuid,
+    pub dropped: bool,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub stored_size: i64,
+}
+
+struct ChunkHead {
+    seq: i64,
+    first_line: i64,
+    line_count: i64,
+    byte_size: i64,
+    compressed: bool,
 }
 
 impl ExecutionProcessLogs {
-    /// Find logs by execution process ID
+    /// Find the log summary for an execution process, None if nothing was logged
     pub async fn find_by_execution_id(
         pool: &SqlitePool,
         execution_id: Uuid,
     ) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             ExecutionProcessLogs,
-            r#"SELECT 
-                execution_id as "execution_id!: Uuid",
-                logs,
-                byte_size,
-                inserted_at as "inserted_at!: DateTime<Utc>"
-               FROM execution_process_logs 
-               WHERE execution_id = $1"#,
+            r#"SELECT execution_id        AS "execution_id!: Uuid",
+                      SUM(line_count)     AS "line_count!: i64",
+                      SUM(byte_size)      AS "byte_size!: i64",
+                      SUM(LENGTH(data))   AS "stored_size!: i64",
+                      COUNT(*)            AS "chunk_count!: i64"
+               FROM execution_process_log_chunks
+               WHERE execution_id = $1
+               GROUP BY execution_id"#,
             execution_id
         )
         .fetch_optional(pool)
         .await
     }
 
-    /// Create or update execution process logs
-    pub async fn upsert(
+    /// Load every log message of an execution process, None if nothing was logged
+    pub async fn load_all(
         pool: &SqlitePool,
-        data: &CreateExecutionProcessLogs,
-    ) -> Result<Self, sqlx::Error> {
-        let now = Utc::now();
+        execution_id: Uuid,
+    ) -> Result<Option<Vec<TimedLogMsg>>, ExecutionProcessLogsError> {
+        let chunks = sqlx::query_as!(
+            ExecutionProcessLogChunk,
+            r#"SELECT execution_id as "execution_id!: Uuid", seq, first_line, line_count, byte_size,
+                      compressed as "compressed!: bool", data, inserted_at as "inserted_at!: DateTime<Utc>"
+               FROM execution_process_log_chunks
+               WHERE execution_id = $1
+               ORDER BY seq ASC"#,
+            execution_id
+        )
+        .fetch_all(pool)
+        .await?;
+        if chunks.is_empty() {
+            return Ok(None);
+        }
 
+        let mut messages = Vec::new();
+        for chunk in &chunks {
+            for line in chunk.lines()? {
+                messages.push(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(Some(messages))
+    }
+
+    /// Read `limit` entries starting at `offset`, decoding only the chunks that overlap the range
+    pub async fn read_range(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+        offset: i64,
+        limit: i64,
+    ) -> Result<ExecutionProcessLogPage, ExecutionProcessLogsError> {
+        let total = Self::find_by_execution_id(pool, execution_id)
+            .await?
+            .map(|summary| summary.line_count)
+            .unwrap_or(0);
+        let offset = offset.clamp(0, total);
+        let end = offset.saturating_add(limit.max(0)).min(total);
+
+        let chunks = sqlx::query_as!(
+            ExecutionProcessLogChunk,
+            r#"SELECT execution_id as "execution_id!: Uuid", seq, first_line, line_count, byte_size,
+                      compressed as "compressed!: bool", data, inserted_at as "inserted_at!: DateTime<Utc>"
+               FROM execution_process_log_chunks
+               WHERE execution_id = $1
+                 AND first_line + line_count > $2
+                 AND first_line < $3
+               ORDER BY seq ASC"#,
+            execution_id,
+            offset,
+            end
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let mut entries = Vec::with_capacity((end - offset) as usize);
+        for chunk in &chunks {
+            let skip = (offset - chunk.first_line).max(0) as usize;
+            let take = (end - chunk.first_line.max(offset)).max(0) as usize;
+            for line in chunk.lines()?.into_iter().skip(skip).take(take) {
+                entries.push(serde_json::from_str(&line)?);
+            }
+        }
+
+        Ok(ExecutionProcessLogPage {
+            offset,
+            total,
+            entries,
+            pruned: Self::find_prune(pool, execution_id).await?,
+        })
+    }
+
+    /// Read the last `limit` entries
+    pub async fn read_tail(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+        limit: i64,
+    ) -> Result<ExecutionProcessLogPage, ExecutionProcessLogsError> {
+        let total = Self::find_by_execution_id(pool, execution_id)
+            .await?
+            .map(|summary| summary.line_count)
+            .unwrap_or(0);
+        Self::read_range(pool, execution_id, total - limit.max(0), limit).await
+    }
+
+    pub async fn find_prune(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+    ) -> Result<Option<ExecutionProcessLogPrune>, sqlx::Error> {
         sqlx::query_as!(
-            ExecutionProcessLogs,
-            r#"INSERT INTO execution_process_logs (execution_id, logs, byte_size, inserted_at)
-               VALUES ($1, $2, $3, $4)
-               ON CONFLICT (execution_id) DO UPDATE
-               SET logs = EXCLUDED.logs, 
-                   byte_size = EXCLUDED.byte_size,
-                   inserted_at = EXCLUDED.inserted_at
-               RETURNING 
-                execution_id as "execution_id!: Uuid",
-                logs,
-                byte_size,
-                inserted_at as "inserted_at!: DateTime<Utc>""#,
-            data.execution_id,
-            data.logs,
-            data.byte_size,
-            now
+            ExecutionProcessLogPrune,
+            r#"SELECT execution_id as "execution_id!: Uuid", line_count, byte_size, stored_size,
+                      reason as "reason!: LogPruneReason", pruned_at as "pruned_at!: DateTime<Utc>"
+               FROM execution_process_log_prunes
+               WHERE execution_id = $1"#,
+            execution_id
         )
-        .fetch_one(pool)
+        .fetch_optional(pool)
         .await
     }
 
-    /// Parse JSONL logs back into Vec<LogMsg>
-    pub fn parse_logs(&self) -> Result<Vec<LogMsg>, serde_json::Error> {
-        let mut messages = Vec::new();
-        for line in self.logs.lines() {
-            if !line.trim().is_empty() {
-                let msg: LogMsg = serde_json::from_str(line)?;
-                messages.push(msg);
-            }
-        }
-        Ok(messages)
+    /// Stored logs of every finished process with a stored conversation, oldest first.
+    /// Processes without one are left out, as their raw logs are all they can be viewed by.
+    pub async fn find_stored_usage(pool: &SqlitePool) -> Result<Vec<StoredLogUsage>, sqlx::Error> {
+        sqlx::query_as!(
+            StoredLogUsage,
+            r#"SELECT ep.id               AS "execution_id!: Uuid",
+                      ep.task_attempt_id  AS "task_attempt_id!: Uuid",
+                      t.project_id        AS "project_id!: Uuid",
+                      ep.dropped          AS "dropped!: bool",
+                      ep.completed_at     AS "completed_at: DateTime<Utc>",
+                      SUM(LENGTH(c.data)) AS "stored_size!: i64"
+               FROM execution_process_log_chunks c
+               JOIN execution_processes ep ON ep.id = c.execution_id
+               JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+               JOIN tasks t ON t.id = ta.task_id
+               WHERE ep.status != 'running'
+                 AND EXISTS (SELECT 1 FROM execution_process_conversations epc
+                             WHERE epc.execution_id = ep.id)
+               GROUP BY ep.id
+               ORDER BY COALESCE(ep.completed_at, ep.created_at) ASC"#
+        )
+        .fetch_all(pool)
+        .await
     }
 
-    /// Convert Vec<LogMsg> to JSONL format
-    pub fn serialize_logs(messages: &[LogMsg]) -> Result<String, serde_json::Error> {
-        let mut jsonl = String::new();
-        for msg in messages {
-            let line = serde_json::to_string(msg)?;
-            jsonl.push_str(&line);
-            jsonl.push('\n');
+    /// Delete the raw logs of an execution process, keeping a summary of what was removed.
+    /// Its stored conversation is kept, so the process can still be viewed. Returns false,
+    /// leaving the logs in place, if the process has no stored conversation.
+    pub async fn prune(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+        reason: LogPruneReason,
+    ) -> Result<bool, sqlx::Error> {
+        let mut tx = pool.begin_with("BEGIN IMMEDIATE").await?;
+        let has_conversation = sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM execution_process_conversations WHERE execution_id = $1)
+                   AS "exists!: bool""#,
+            execution_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if !has_conversation {
+            return Ok(false);
         }
-        Ok(jsonl)
+        sqlx::query!(
+            r#"INSERT INTO execution_process_log_prunes
+                   (execution_id, line_count, byte_size, stored_size, reason)
+               SELECT execution_id, SUM(line_count), SUM(byte_size), SUM(LENGTH(data)), $1
+               FROM execution_process_log_chunks
+               WHERE execution_id = $2
+               GROUP BY execution_id
+               ON CONFLICT (execution_id) DO UPDATE
+               SET line_count = line_count + EXCLUDED.line_count,
+                   byte_size = byte_size + EXCLUDED.byte_size,
+                   stored_size = stored_size + EXCLUDED.stored_size,
+                   reason = EXCLUDED.reason,
+                   pruned_at = datetime('now', 'subsec')"#,
+            reason,
+            execution_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "DELETE FROM execution_process_log_chunks WHERE execution_id = $1",
+            execution_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(true)
     }
 
-    /// Append a JSONL line to the logs for an execution process
+    /// Append a JSONL line to the open chunk, sealing it once it reaches the target size
     pub async fn append_log_line(
         pool: &SqlitePool,
         execution_id: Uuid,
         jsonl_line: &str,
-    ) -> Result<(), sqlx::Error> {
+    ) -> Result<(), ExecutionProcessLogsError> {
         let byte_size = jsonl_line.len() as i64;
+        let line_count = jsonl_line.matches('\n').count().max(1) as i64;
+        let data = jsonl_line.as_bytes();
+
+        // Take the write lock up front, so a concurrent append can't pick the same chunk head
+        let mut tx = pool.begin_with("BEGIN IMMEDIATE").await?;
+        let head = sqlx::query_as!(
+            ChunkHead,
+            r#"SELECT seq, first_line, line_count, byte_size, compressed as "compressed!: bool"
+               FROM execution_process_log_chunks
+               WHERE execution_id = $1
+               ORDER BY seq DESC
+               LIMIT 1"#,
+            execution_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let (seq, chunk_size) = match head {
+            Some(head) if !head.compressed => {
+                sqlx::query!(
+                    r#"UPDATE execution_process_log_chunks
+                       SET data = CAST(data || $1 AS BLOB),
+                           line_count = line_count + $2,
+                           byte_size = byte_size + $3,
+                           inserted_at = datetime('now', 'subsec')
+                       WHERE execution_id = $4 AND seq = $5"#,
+                    data,
+                    line_count,
+                    byte_size,
+                    execution_id,
+                    head.seq
+                )
+                .execute(&mut *tx)
+                .await?;
+                (head.seq, head.byte_size + byte_size)
+            }
+            head => {
+                let (seq, first_line) = head
+                    .map(|h| (h.seq + 1, h.first_line + h.line_count))
+                    .unwrap_or((0, 0));
+                sqlx::query!(
+                    r#"INSERT INTO execution_process_log_chunks
+                           (execution_id, seq, first_line, line_count, byte_size, compressed, data)
+                       VALUES ($1, $2, $3, $4, $5, 0, $6)"#,
+                    execution_id,
+                    seq,
+                    first_line,
+                    line_count,
+                    byte_size,
+                    data
+                )
+                .execute(&mut *tx)
+                .await?;
+                (seq, byte_size)
+            }
+        };
+
+        if chunk_size >= CHUNK_TARGET_BYTES {
+            Self::seal(&mut tx, execution_id, seq).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Compress an open chunk so the next append starts a new one
+    pub async fn seal_chunk(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+        seq: i64,
+    ) -> Result<(), ExecutionProcessLogsError> {
+        let mut conn = pool.acquire().await?;
+        Self::seal(&mut conn, execution_id, seq).await
+    }
+
+    async fn seal(
+        conn: &mut SqliteConnection,
+        execution_id: Uuid,
+        seq: i64,
+    ) -> Result<(), ExecutionProcessLogsError> {
+        let Some(data) = sqlx::query_scalar!(
+            r#"SELECT data FROM execution_process_log_chunks
+               WHERE execution_id = $1 AND seq = $2 AND compressed = 0"#,
+            execution_id,
+            seq
+        )
+        .fetch_optional(&mut *conn)
+        .await?
+        else {
+            return Ok(());
+        };
+
+        let compressed = zstd::encode_all(data.as_slice(), ZSTD_LEVEL)?;
         sqlx::query!(
-            r#"INSERT INTO execution_process_logs (execution_id, logs, byte_size, inserted_at)
-               VALUES ($1, $2, $3, datetime('now', 'subsec'))
-               ON CONFLICT (execution_id) DO UPDATE
-               SET logs = logs || $2,
-                   byte_size = byte_size + $3,
-                   inserted_at = datetime('now', 'subsec')"#,
+            r#"UPDATE execution_process_log_chunks
+               SET data = $1, compressed = 1
+               WHERE execution_id = $2 AND seq = $3 AND compressed = 0"#,
+            compressed,
             execution_id,
-            jsonl_line,
-            byte_size
+            seq
         )
-        .execute(pool)
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Seal chunks of processes that are no longer running: chunks left open by an unclean
+    /// shutdown and logs migrated from the single-row format, which are split to the target size.
+    /// Call at startup after orphaned executions have been cleaned up.
+    pub async fn seal_open_chunks(pool: &SqlitePool) -> Result<usize, ExecutionProcessLogsError> {
+        let open = sqlx::query_as!(
+            ExecutionProcessLogChunk,
+            r#"SELECT c.execution_id as "execution_id!: Uuid", c.seq, c.first_line, c.line_count,
+                      c.byte_size, c.compressed as "compressed!: bool", c.data,
+                      c.inserted_at as "inserted_at!: DateTime<Utc>"
+               FROM execution_process_log_chunks c
+               JOIN execution_processes ep ON ep.id = c.execution_id
+               WHERE c.compressed = 0 AND ep.status != 'running'"#
+        )
+        .fetch_all(pool)
         .await?;
 
+        let mut sealed = 0;
+        for chunk in open {
+            let last_seq = sqlx::query_scalar!(
+                r#"SELECT MAX(seq) as "seq!: i64" FROM execution_process_log_chunks WHERE execution_id = $1"#,
+                chunk.execution_id
+            )
+            .fetch_one(pool)
+            .await?;
+
+            if chunk.byte_size <= CHUNK_TARGET_BYTES || chunk.seq != last_seq {
+                Self::seal_chunk(pool, chunk.execution_id, chunk.seq).await?;
+            } else {
+                Self::split_chunk(pool, &chunk).await?;
+            }
+            sealed += 1;
+        }
+        Ok(sealed)
+    }
+
+    /// Replace an oversized trailing chunk with compressed chunks of the target size
+    async fn split_chunk(
+        pool: &SqlitePool,
+        chunk: &ExecutionProcessLogChunk,
+    ) -> Result<(), ExecutionProcessLogsError> {
+        let mut pieces: Vec<(String, i64)> = Vec::new();
+        for line in chunk.lines()? {
+            match pieces.last_mut() {
+                Some((piece, count)) if (piece.len() as i64) < CHUNK_TARGET_BYTES => {
+                    piece.push_str(&line);
+                    piece.push('\n');
+                    *count += 1;
+                }
+                _ => pieces.push((format!("{line}\n"), 1)),
+            }
+        }
+
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            "DELETE FROM execution_process_log_chunks WHERE execution_id = $1 AND seq = $2",
+            chunk.execution_id,
+            chunk.seq
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let mut first_line = chunk.first_line;
+        for (i, (piece, count)) in pieces.into_iter().enumerate() {
+            let seq = chunk.seq + i as i64;
+            let byte_size = piece.len() as i64;
+            let data = zstd::encode_all(piece.as_bytes(), ZSTD_LEVEL)?;
+            sqlx::query!(
+                r#"INSERT INTO execution_process_log_chunks
+                       (execution_id, seq, first_line, line_count, byte_size, compressed, data, inserted_at)
+                   VALUES ($1, $2, $3, $4, $5, 1, $6, $7)"#,
+                chunk.execution_id,
+                seq,
+                first_line,
+                count,
+                byte_size,
+                data,
+                chunk.inserted_at
+            )
+            .execute(&mut *tx)
+            .await?;
+            first_line += count;
+        }
+        tx.commit().await?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+    use utils::log_msg::LogMsg;
+
+    use super::*;
+    use crate::{
+        DBService,
+        models::{
+            execution_process_conversation::ExecutionProcessConversation,
+            project::{CreateProject, Project},
+        },
+    };
+
+    /// Insert a finished execution process to attach logs to
+    async fn finished_process(pool: &SqlitePool) -> Uuid {
+        let (project_id, task_id, attempt_id, execution_id) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+        Project::create(
+            pool,
+            &CreateProject::from_name_repo_path("p".to_string(), project_id.to_string()),
+            project_id,
+        )
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO tasks (id, project_id, title) VALUES (?, ?, 't')")
+            .bind(task_id)
+            .bind(project_id)
+            .execute(pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO task_attempts (id, task_id) VALUES (?, ?)")
+            .bind(attempt_id)
+            .bind(task_id)
+            .execute(pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO execution_processes (id, task_attempt_id, executor_action, status)
+             VALUES (?, ?, '{}', 'completed')",
+        )
+        .bind(execution_id)
+        .bind(attempt_id)
+        .execute(pool)
+        .await
+        .unwrap();
+        execution_id
+    }
+
+    /// A JSONL line of about `size` bytes whose content starts with `n`
+    fn line(n: usize, size: usize) -> String {
+        let msg = TimedLogMsg {
+            at: None,
+            msg: LogMsg::Stdout(format!("{n} {}", "x".repeat(size))),
+        };
+        format!("{}\n", serde_json::to_string(&msg).unwrap())
+    }
+
+    fn contents(page: &ExecutionProcessLogPage) -> Vec<usize> {
+        page.entries
+            .iter()
+            .map(|entry| match &entry.msg {
+                LogMsg::Stdout(content) => content.split(' ').next().unwrap().parse().unwrap(),
+                other => panic!("unexpected entry {other:?}"),
+            })
+            .collect()
+    }
+
+    async fn chunks(pool: &SqlitePool, execution_id: Uuid) -> Vec<(i64, i64, i64, bool)> {
+        sqlx::query_as(
+            "SELECT seq, first_line, line_count, compressed FROM execution_process_log_chunks
+             WHERE execution_id = ? ORDER BY seq",
+        )
+        .bind(execution_id)
+        .fetch_all(pool)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn appends_seal_full_chunks_and_start_new_ones() {
+        let pool = DBService::new_in_memory().await.unwrap().pool;
+        let execution_id = finished_process(&pool).await;
+
+        // Three 100 KiB lines fill the first chunk past the target, the fourth starts another
+        for n in 0..4 {
+            ExecutionProcessLogs::append_log_line(&pool, execution_id, &line(n, 100 * 1024))
+                .await
+                .unwrap();
+        }
+        ExecutionProcessLogs::append_log_line(&pool, execution_id, &line(4, 10))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            chunks(&pool, execution_id).await,
+            vec![(0, 0, 3, true), (1, 3, 2, false)]
+        );
+        let summary = ExecutionProcessLogs::find_by_execution_id(&pool, execution_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(summary.line_count, 5);
+        assert_eq!(summary.chunk_count, 2);
+        assert!(summary.stored_size < summary.byte_size);
+    }
+
+    #[tokio::test]
+    async fn prune_keeps_logs_without_a_stored_conversation() {
+        let pool = DBService::new_in_memory().await.unwrap().pool;
+        let execution_id = finished_process(&pool).await;
+        ExecutionProcessLogs::append_log_line(&pool, execution_id, &line(0, 10))
+            .await
+            .unwrap();
+
+        assert!(
+            ExecutionProcessLogs::find_stored_usage(&pool)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        let pruned = ExecutionProcessLogs::prune(&pool, execution_id, LogPruneReason::Expired)
+            .await
+            .unwrap();
+        assert!(!pruned);
+        assert_eq!(chunks(&pool, execution_id).await.len(), 1);
+        assert!(
+            ExecutionProcessLogs::find_prune(&pool, execution_id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        ExecutionProcessConversation::upsert(&pool, execution_id, 1, &[])
+            .await
+            .unwrap();
+        let usage = ExecutionProcessLogs::find_stored_usage(&pool)
+            .await
+            .unwrap();
+        assert_eq!(usage.len(), 1);
+        assert_eq!(usage[0].execution_id, execution_id);
+        let pruned = ExecutionProcessLogs::prune(&pool, execution_id, LogPruneReason::Expired)
+            .await
+            .unwrap();
+        assert!(pruned);
+        assert!(chunks(&pool, execution_id).await.is_empty());
+        let prune = ExecutionProcessLogs::find_prune(&pool, execution_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(prune.line_count, 1);
+        assert_eq!(prune.reason, LogPruneReason::Expired);
+    }
+
+    #[tokio::test]
+    async fn ranges_and_tails_span_chunks() {
+        let pool = DBService::new_in_memory().await.unwrap().pool;
+        let execution_id = finished_process(&pool).await;
+        for n in 0..7 {
+            ExecutionProcessLogs::append_log_line(&pool, execution_id, &line(n, 100 * 1024))
+                .await
+                .unwrap();
+        }
+        // Chunks hold lines 0-2 and 3-5, both sealed, and the open one line 6
+        assert_eq!(
+            chunks(&pool, execution_id).await,
+            vec![(0, 0, 3, true), (1, 3, 3, true), (2, 6, 1, false)]
+        );
+
+        let page = ExecutionProcessLogs::read_range(&pool, execution_id, 2, 3)
+            .await
+            .unwrap();
+        assert_eq!((page.offset, page.total), (2, 7));
+        assert_eq!(contents(&page), vec![2, 3, 4]);
+
+        let page = ExecutionProcessLogs::read_range(&pool, execution_id, 5, 100)
+            .await
+            .unwrap();
+        assert_eq!(contents(&page), vec![5, 6]);
+
+        // Out of range offsets are clamped rather than failing
+        let page = ExecutionProcessLogs::read_range(&pool, execution_id, 50, 10)
+            .await
+            .unwrap();
+        assert_eq!((page.offset, page.entries.len()), (7, 0));
+        let page = ExecutionProcessLogs::read_range(&pool, execution_id, -3, 2)
+            .await
+            .unwrap();
+        assert_eq!(contents(&page), vec![0, 1]);
+
+        let page = ExecutionProcessLogs::read_tail(&pool, execution_id, 4)
+            .await
+            .unwrap();
+        assert_eq!(page.offset, 3);
+        assert_eq!(contents(&page), vec![3, 4, 5, 6]);
+        let page = ExecutionProcessLogs::read_tail(&pool, execution_id, 100)
+            .await
+            .unwrap();
+        assert_eq!(contents(&page), (0..7).collect::<Vec<_>>());
+
+        let all = ExecutionProcessLogs::load_all(&pool, execution_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(all.len(), 7);
+    }
+
+    #[tokio::test]
+    async fn single_row_logs_are_migrated_then_split_into_chunks() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let migrator = sqlx::migrate!("./migrations");
+        let chunked_at = 20251022120000;
+        for migration in migrator.iter().filter(|m| m.version < chunked_at) {
+            sqlx::raw_sql(&migration.sql).execute(&pool).await.unwrap();
+        }
+
+        let execution_id = finished_process(&pool).await;
+        let logs: String = (0..6).map(|n| line(n, 100 * 1024)).collect();
+        sqlx::query(
+            "INSERT INTO execution_process_logs (execution_id, logs, byte_size, inserted_at)
+             VALUES (?, ?, ?, datetime('now'))",
+        )
+        .bind(execution_id)
+        .bind(&logs)
+        .bind(logs.len() as i64)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        for migration in migrator.iter().filter(|m| m.version >= chunked_at) {
+            sqlx::raw_sql(&migration.sql).execute(&pool).await.unwrap();
+        }
+
+        // The old row becomes one open chunk with every line
+        assert_eq!(chunks(&pool, execution_id).await, vec![(0, 0, 6, false)]);
+
+        // At startup it is split at the target size and compressed
+        assert_eq!(
+            ExecutionProcessLogs::seal_open_chunks(&pool).await.unwrap(),
+            1
+        );
+        assert_eq!(
+            chunks(&pool, execution_id).await,
+            vec![(0, 0, 3, true), (1, 3, 3, true)]
+        );
+        let page = ExecutionProcessLogs::read_range(&pool, execution_id, 1, 4)
+            .await
+            .unwrap();
+        assert_eq!(contents(&page), vec![1, 2, 3, 4]);
+
+        // New lines go to a new chunk after the split ones
+        ExecutionProcessLogs::append_log_line(&pool, execution_id, &line(6, 10))
+            .await
+            .unwrap();
+        assert_eq!(
+            chunks(&pool, execution_id).await.last(),
+            Some(&(2, 6, 1, false))
+        );
+    }
+}
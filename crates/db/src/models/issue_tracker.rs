@@ -0,0 +1,364 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::TaskStatus;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "issue_tracker_kind", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum IssueTrackerKind {
+    /// Issues of the GitHub repository the project's remote points to
+    GitHub,
+    /// A tracker with a JSON API, see the docs for what it must serve
+    Rest,
+}
+
+/// A tracker whose open issues are imported into a project as tasks
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct IssueTracker {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub kind: IssueTrackerKind,
+    /// Issues endpoint of a REST tracker
+    pub url: Option<String>,
+    /// Project env var holding the REST tracker's bearer token
+    pub token_env: Option<String>,
+    /// Only import GitHub issues with this label
+    pub label: Option<String>,
+    /// Sync in the background, as well as when asked to
+    pub auto_sync: bool,
+    pub last_synced_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateIssueTracker {
+    pub project_id: Uuid,
+    pub kind: IssueTrackerKind,
+    pub url: Option<String>,
+    pub token_env: Option<String>,
+    pub label: Option<String>,
+    #[serde(default = "default_auto_sync")]
+    pub auto_sync: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateIssueTracker {
+    pub url: Option<String>,
+    pub token_env: Option<String>,
+    pub label: Option<String>,
+    #[serde(default = "default_auto_sync")]
+    pub auto_sync: bool,
+}
+
+fn default_auto_sync() -> bool {
+    true
+}
+
+/// An issue imported from a tracker, and what was last synced for it
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct TaskIssueLink {
+    pub id: Uuid,
+    pub tracker_id: Uuid,
+    /// Null once the task is deleted, which keeps the issue from being imported again
+    pub task_id: Option<Uuid>,
+    pub external_id: String,
+    pub url: Option<String>,
+    pub remote_title: String,
+    pub remote_description: Option<String>,
+    /// Task status last posted back to the tracker
+    pub posted_status: Option<TaskStatus>,
+    pub posted_pr_url: Option<String>,
+    /// Why the issue last failed to sync, cleared once a sync of it goes through
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl IssueTracker {
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            IssueTracker,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", kind as "kind!: IssueTrackerKind", url, token_env, label,
+                      auto_sync as "auto_sync!: bool", last_synced_at as "last_synced_at: DateTime<Utc>", last_error,
+                      created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM issue_trackers
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Trackers, optionally of one project, oldest first
+    pub async fn find_all(
+        pool: &SqlitePool,
+        project_id: Option<Uuid>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            IssueTracker,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", kind as "kind!: IssueTrackerKind", url, token_env, label,
+                      auto_sync as "auto_sync!: bool", last_synced_at as "last_synced_at: DateTime<Utc>", last_error,
+                      created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM issue_trackers
+               WHERE $1 IS NULL OR project_id = $1
+               ORDER BY created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_auto_sync(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            IssueTracker,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", kind as "kind!: IssueTrackerKind", url, token_env, label,
+                      auto_sync as "auto_sync!: bool", last_synced_at as "last_synced_at: DateTime<Utc>", last_error,
+                      created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM issue_trackers
+               WHERE auto_sync = 1
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateIssueTracker,
+        id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            IssueTracker,
+            r#"INSERT INTO issue_trackers (id, project_id, kind, url, token_env, label, auto_sync)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", kind as "kind!: IssueTrackerKind", url, token_env, label,
+                      auto_sync as "auto_sync!: bool", last_synced_at as "last_synced_at: DateTime<Utc>", last_error,
+                      created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            data.kind,
+            data.url,
+            data.token_env,
+            data.label,
+            data.auto_sync
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateIssueTracker,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            IssueTracker,
+            r#"UPDATE issue_trackers
+               SET url = $2, token_env = $3, label = $4, auto_sync = $5,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", kind as "kind!: IssueTrackerKind", url, token_env, label,
+                      auto_sync as "auto_sync!: bool", last_synced_at as "last_synced_at: DateTime<Utc>", last_error,
+                      created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.url,
+            data.token_env,
+            data.label,
+            data.auto_sync
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Record the outcome of a sync, clearing the last error if it succeeded
+    pub async fn record_sync(
+        pool: &SqlitePool,
+        id: Uuid,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE issue_trackers
+               SET last_synced_at = datetime('now', 'subsec'), last_error = $2
+               WHERE id = $1"#,
+            id,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM issue_trackers WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl TaskIssueLink {
+    pub async fn find_by_tracker_id(
+        pool: &SqlitePool,
+        tracker_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskIssueLink,
+            r#"SELECT id as "id!: Uuid", tracker_id as "tracker_id!: Uuid", task_id as "task_id: Uuid", external_id, url,
+                      remote_title, remote_description, posted_status as "posted_status: TaskStatus", posted_pr_url,
+                      last_error, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_issue_links
+               WHERE tracker_id = $1
+               ORDER BY created_at ASC"#,
+            tracker_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_external_id(
+        pool: &SqlitePool,
+        tracker_id: Uuid,
+        external_id: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskIssueLink,
+            r#"SELECT id as "id!: Uuid", tracker_id as "tracker_id!: Uuid", task_id as "task_id: Uuid", external_id, url,
+                      remote_title, remote_description, posted_status as "posted_status: TaskStatus", posted_pr_url,
+                      last_error, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_issue_links
+               WHERE tracker_id = $1 AND external_id = $2"#,
+            tracker_id,
+            external_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskIssueLink,
+            r#"SELECT id as "id!: Uuid", tracker_id as "tracker_id!: Uuid", task_id as "task_id: Uuid", external_id, url,
+                      remote_title, remote_description, posted_status as "posted_status: TaskStatus", posted_pr_url,
+                      last_error, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_issue_links
+               WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create(
+        pool: &SqlitePool,
+        tracker_id: Uuid,
+        task_id: Uuid,
+        external_id: &str,
+        url: Option<&str>,
+        remote_title: &str,
+        remote_description: Option<&str>,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            TaskIssueLink,
+            r#"INSERT INTO task_issue_links
+                   (id, tracker_id, task_id, external_id, url, remote_title, remote_description)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               RETURNING id as "id!: Uuid", tracker_id as "tracker_id!: Uuid", task_id as "task_id: Uuid", external_id, url,
+                      remote_title, remote_description, posted_status as "posted_status: TaskStatus", posted_pr_url,
+                      last_error, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            tracker_id,
+            task_id,
+            external_id,
+            url,
+            remote_title,
+            remote_description
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Remember the issue's title and description as synced to the task
+    pub async fn set_remote(
+        pool: &SqlitePool,
+        id: Uuid,
+        url: Option<&str>,
+        remote_title: &str,
+        remote_description: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE task_issue_links
+               SET url = $2, remote_title = $3, remote_description = $4, last_error = NULL,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            url,
+            remote_title,
+            remote_description
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_posted(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: &TaskStatus,
+        pr_url: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE task_issue_links
+               SET posted_status = $2, posted_pr_url = $3, last_error = NULL,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            status,
+            pr_url
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn record_error(pool: &SqlitePool, id: Uuid, error: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE task_issue_links
+               SET last_error = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// URL of the newest pull request opened from any attempt of the task
+    pub async fn latest_pr_url(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT m.pr_url FROM merges m
+               JOIN task_attempts ta ON ta.id = m.task_attempt_id
+               WHERE ta.task_id = $1 AND m.merge_type = 'pr'
+               ORDER BY m.created_at DESC
+               LIMIT 1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+        .map(Option::flatten)
+    }
+}
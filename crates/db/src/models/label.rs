@@ -0,0 +1,173 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A label the tasks of a project can be tagged with
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct Label {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    /// Unique within the project, ignoring case
+    pub name: String,
+    pub color: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateLabel {
+    pub project_id: Uuid,
+    pub name: String,
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateLabel {
+    pub name: String,
+    pub color: Option<String>,
+}
+
+impl Label {
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Label,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", name, color,
+                      created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM labels
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Label,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", name, color,
+                      created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM labels
+               WHERE project_id = $1
+               ORDER BY name ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Label,
+            r#"SELECT l.id as "id!: Uuid", l.project_id as "project_id!: Uuid", l.name, l.color,
+                      l.created_at as "created_at!: DateTime<Utc>", l.updated_at as "updated_at!: DateTime<Utc>"
+               FROM labels l
+               JOIN task_labels tl ON tl.label_id = l.id
+               WHERE tl.task_id = $1
+               ORDER BY l.name ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The labels of every task of the project, as (task id, label) pairs
+    pub async fn find_by_project_tasks(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<(Uuid, Self)>, sqlx::Error> {
+        let records = sqlx::query!(
+            r#"SELECT tl.task_id as "task_id!: Uuid", l.id as "id!: Uuid", l.project_id as "project_id!: Uuid", l.name, l.color,
+                      l.created_at as "created_at!: DateTime<Utc>", l.updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_labels tl
+               JOIN labels l ON l.id = tl.label_id
+               WHERE l.project_id = $1
+               ORDER BY l.name ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(records
+            .into_iter()
+            .map(|rec| {
+                (
+                    rec.task_id,
+                    Label {
+                        id: rec.id,
+                        project_id: rec.project_id,
+                        name: rec.name,
+                        color: rec.color,
+                        created_at: rec.created_at,
+                        updated_at: rec.updated_at,
+                    },
+                )
+            })
+            .collect())
+    }
+
+    /// Ids of the tasks tagged with the label
+    pub async fn find_task_ids(pool: &SqlitePool, id: Uuid) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT task_id as "task_id!: Uuid" FROM task_labels WHERE label_id = $1"#,
+            id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateLabel,
+        id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let name = data.name.trim();
+        sqlx::query_as!(
+            Label,
+            r#"INSERT INTO labels (id, project_id, name, color)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", name, color,
+                      created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            name,
+            data.color
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateLabel,
+    ) -> Result<Self, sqlx::Error> {
+        let name = data.name.trim();
+        sqlx::query_as!(
+            Label,
+            r#"UPDATE labels
+               SET name = $2, color = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", name, color,
+                      created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            name,
+            data.color
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM labels WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -1,10 +1,17 @@
+pub mod audit_event;
+pub mod custom_field;
 pub mod draft;
 pub mod execution_process;
+pub mod execution_process_conversation;
 pub mod execution_process_logs;
 pub mod executor_session;
 pub mod image;
+pub mod issue_tracker;
+pub mod label;
 pub mod merge;
 pub mod project;
+pub mod project_instructions;
 pub mod tag;
 pub mod task;
 pub mod task_attempt;
+pub mod task_schedule;
@@ -48,6 +48,21 @@ pub struct CreateProject {
     pub copy_files: Option<String>,
 }
 
+impl CreateProject {
+    /// A project for an existing repository, without scripts
+    pub fn from_name_repo_path(name: String, git_repo_path: String) -> Self {
+        Self {
+            name,
+            git_repo_path,
+            use_existing_repo: true,
+            setup_script: None,
+            dev_script: None,
+            cleanup_script: None,
+            copy_files: None,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, TS)]
 pub struct UpdateProject {
     pub name: Option<String>,
@@ -0,0 +1,232 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Standing instructions placed before the prompts of a project's coding agents
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct ProjectInstructions {
+    pub project_id: Uuid,
+    pub content: String,
+    /// File in the repository whose contents follow `content`, relative to the repository root
+    pub sync_path: Option<String>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateProjectInstructions {
+    pub content: String,
+    pub sync_path: Option<String>,
+}
+
+/// Text the agents of a project were given, numbered from 1 per project
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct ProjectInstructionVersion {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub version: i64,
+    pub content: String,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl ProjectInstructions {
+    /// Instructions of a project, empty if none were saved
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let instructions = sqlx::query_as!(
+            ProjectInstructions,
+            r#"SELECT project_id as "project_id!: Uuid", content, sync_path
+               FROM project_instructions
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(instructions.unwrap_or(Self {
+            project_id,
+            content: String::new(),
+            sync_path: None,
+        }))
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpdateProjectInstructions,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectInstructions,
+            r#"INSERT INTO project_instructions (project_id, content, sync_path)
+               VALUES ($1, $2, $3)
+               ON CONFLICT (project_id) DO UPDATE
+               SET content = EXCLUDED.content,
+                   sync_path = EXCLUDED.sync_path,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid", content, sync_path"#,
+            project_id,
+            data.content,
+            data.sync_path
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
+
+impl ProjectInstructionVersion {
+    /// Versions of a project's instructions, newest first
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectInstructionVersion,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", version, content, created_at as "created_at!: DateTime<Utc>"
+               FROM project_instruction_versions
+               WHERE project_id = $1
+               ORDER BY version DESC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The version of `content`, which is the latest one if the text didn't change since.
+    /// Reading the latest version and numbering the next one happen in one write transaction,
+    /// so concurrent attempts of a project can't both record the same version.
+    pub async fn record(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        content: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let mut tx = pool.begin_with("BEGIN IMMEDIATE").await?;
+        let latest = sqlx::query_as!(
+            ProjectInstructionVersion,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", version, content, created_at as "created_at!: DateTime<Utc>"
+               FROM project_instruction_versions
+               WHERE project_id = $1
+               ORDER BY version DESC
+               LIMIT 1"#,
+            project_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        if let Some(latest) = latest
+            && latest.content == content
+        {
+            tx.commit().await?;
+            return Ok(latest);
+        }
+
+        let id = Uuid::new_v4();
+        let version = sqlx::query_as!(
+            ProjectInstructionVersion,
+            r#"INSERT INTO project_instruction_versions (id, project_id, version, content)
+               SELECT $1, $2, COALESCE(MAX(version), 0) + 1, $3
+               FROM project_instruction_versions
+               WHERE project_id = $2
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", version, content, created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            content
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(version)
+    }
+
+    /// The version the agents of an attempt were given
+    pub async fn find_for_attempt(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectInstructionVersion,
+            r#"SELECT v.id as "id!: Uuid", v.project_id as "project_id!: Uuid", v.version, v.content, v.created_at as "created_at!: DateTime<Utc>"
+               FROM task_attempt_instructions tai
+               JOIN project_instruction_versions v ON v.id = tai.version_id
+               WHERE tai.task_attempt_id = $1"#,
+            task_attempt_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Record that an attempt uses this version. An attempt keeps the first version pinned.
+    pub async fn pin_to_attempt(
+        &self,
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO task_attempt_instructions (task_attempt_id, version_id)
+               VALUES ($1, $2)
+               ON CONFLICT (task_attempt_id) DO NOTHING"#,
+            task_attempt_id,
+            self.id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+
+    use super::*;
+    use crate::models::project::{CreateProject, Project};
+
+    #[tokio::test]
+    async fn concurrent_records_of_the_same_text_share_one_version() {
+        // A file database, so each connection of the pool can run its own transaction
+        let dir = tempfile::tempdir().unwrap();
+        let options = SqliteConnectOptions::from_str(&format!(
+            "sqlite://{}",
+            dir.path().join("db.sqlite").to_string_lossy()
+        ))
+        .unwrap()
+        .create_if_missing(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(8)
+            .connect_with(options)
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let project_id = Uuid::new_v4();
+        Project::create(
+            &pool,
+            &CreateProject::from_name_repo_path("p".to_string(), project_id.to_string()),
+            project_id,
+        )
+        .await
+        .unwrap();
+
+        let records = (0..8).map(|_| {
+            let pool = pool.clone();
+            tokio::spawn(async move {
+                ProjectInstructionVersion::record(&pool, project_id, "Use tabs.").await
+            })
+        });
+        for record in records.collect::<Vec<_>>() {
+            assert_eq!(record.await.unwrap().unwrap().version, 1);
+        }
+        let changed = ProjectInstructionVersion::record(&pool, project_id, "Use spaces.")
+            .await
+            .unwrap();
+        assert_eq!(changed.version, 2);
+        assert_eq!(
+            ProjectInstructionVersion::find_by_project_id(&pool, project_id)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+}
@@ -1,11 +1,38 @@
-use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use sqlx::{Executor, FromRow, Sqlite, SqlitePool, Type};
 use strum_macros::{Display, EnumString};
+use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::{project::Project, task_attempt::TaskAttempt};
+use super::{
+    audit_event::{AuditAction, AuditActor, AuditEvent, CreateAuditEvent},
+    custom_field::{CustomField, TaskCustomFieldValue},
+    label::Label,
+    project::Project,
+    task_attempt::TaskAttempt,
+};
+
+#[derive(Debug, Error)]
+pub enum TaskFieldsError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Label {0} doesn't belong to the task's project")]
+    UnknownLabel(Uuid),
+    #[error("Custom field {0} doesn't belong to the task's project")]
+    UnknownCustomField(Uuid),
+    #[error("Invalid value for custom field '{field}', expected {expected}")]
+    InvalidValue {
+        field: String,
+        expected: &'static str,
+    },
+    #[error("Select fields need at least one option, and other fields can't have options")]
+    InvalidOptions,
+}
 
 #[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS, EnumString, Display)]
 #[sqlx(type_name = "task_status", rename_all = "lowercase")]
@@ -19,6 +46,17 @@ pub enum TaskStatus {
     Cancelled,
 }
 
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS, EnumString, Display)]
+#[sqlx(type_name = "task_priority", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum TaskPriority {
+    Low,
+    Medium,
+    High,
+    Urgent,
+}
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct Task {
     pub id: Uuid,
@@ -31,11 +69,25 @@ pub struct Task {
     pub updated_at: DateTime<Utc>,
 }
 
+/// What a task is triaged by, beyond its status
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+pub struct TaskFields {
+    pub priority: Option<TaskPriority>,
+    /// Who owns the task, free-form
+    pub assignee: Option<String>,
+    pub due_date: Option<NaiveDate>,
+    pub labels: Vec<Label>,
+    pub custom_fields: Vec<TaskCustomFieldValue>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct TaskWithAttemptStatus {
     #[serde(flatten)]
     #[ts(flatten)]
     pub task: Task,
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub fields: TaskFields,
     pub has_in_progress_attempt: bool,
     pub has_merged_attempt: bool,
     pub last_attempt_failed: bool,
@@ -87,6 +139,35 @@ impl CreateTask {
     }
 }
 
+/// Narrows down the tasks of a project. Every filter that's set must match.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+pub struct TaskFilter {
+    pub priority: Option<TaskPriority>,
+    /// Matched ignoring case
+    pub assignee: Option<String>,
+    /// Name of a label the task must have
+    pub label: Option<String>,
+    /// Only tasks due on or before this date
+    pub due_before: Option<NaiveDate>,
+    /// Name of a custom field the task must have a value for
+    pub custom_field: Option<String>,
+    /// Value the custom field must have, as text
+    pub custom_value: Option<String>,
+}
+
+/// Replaces all of a task's fields
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateTaskFields {
+    pub priority: Option<TaskPriority>,
+    pub assignee: Option<String>,
+    pub due_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub label_ids: Vec<Uuid>,
+    /// Values by custom field id. Fields left out or set to null have no value.
+    #[serde(default)]
+    pub custom_fields: HashMap<Uuid, Value>,
+}
+
 #[derive(Debug, Serialize, Deserialize, TS)]
 pub struct UpdateTask {
     pub title: Option<String>,
@@ -109,10 +190,47 @@ impl Task {
         Project::find_by_id(pool, self.project_id).await
     }
 
+    /// Number of tasks in each status, across all projects
+    pub async fn count_by_status(pool: &SqlitePool) -> Result<Vec<(TaskStatus, i64)>, sqlx::Error> {
+        let records = sqlx::query!(
+            r#"SELECT status as "status!: TaskStatus", COUNT(*) as "count!: i64"
+               FROM tasks
+               GROUP BY status"#
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(records
+            .into_iter()
+            .map(|rec| (rec.status, rec.count))
+            .collect())
+    }
+
     pub async fn find_by_project_id_with_attempt_status(
         pool: &SqlitePool,
         project_id: Uuid,
+        filter: &TaskFilter,
     ) -> Result<Vec<TaskWithAttemptStatus>, sqlx::Error> {
+        // Values are stored normalised for their field's type, so the filter value is normalised
+        // the same way before comparing
+        let (custom_field_id, custom_value) = match &filter.custom_field {
+            Some(name) => {
+                let Some(field) = CustomField::find_by_name(pool, project_id, name).await? else {
+                    return Ok(Vec::new());
+                };
+                let value = match &filter.custom_value {
+                    Some(text) => match field.parse_value(text) {
+                        Some(value) => Some(value.to_string()),
+                        None => return Ok(Vec::new()),
+                    },
+                    None => None,
+                };
+                (Some(field.id), value)
+            }
+            None => (None, None),
+        };
+        let assignee = filter.assignee.as_deref().map(str::trim);
+        let label = filter.label.as_deref().map(str::trim);
+
         let records = sqlx::query!(
             r#"SELECT
   t.id                            AS "id!: Uuid",
@@ -123,6 +241,9 @@ impl Task {
   t.parent_task_attempt           AS "parent_task_attempt: Uuid",
   t.created_at                    AS "created_at!: DateTime<Utc>",
   t.updated_at                    AS "updated_at!: DateTime<Utc>",
+  t.priority                      AS "priority: TaskPriority",
+  t.assignee,
+  t.due_date                      AS "due_date: NaiveDate",
 
   CASE WHEN EXISTS (
     SELECT 1
@@ -147,24 +268,64 @@ impl Task {
   ) IN ('failed','killed') THEN 1 ELSE 0 END
                                  AS "last_attempt_failed!: i64",
 
-  ( SELECT ta.executor
+  COALESCE(( SELECT ta.executor
       FROM task_attempts ta
       WHERE ta.task_id = t.id
      ORDER BY ta.created_at DESC
       LIMIT 1
-    )                               AS "executor!: String"
+    ), '')                          AS "executor!: String"
 
 FROM tasks t
 WHERE t.project_id = $1
+  AND ($2 IS NULL OR t.priority = $2)
+  AND ($3 IS NULL OR t.assignee = $3 COLLATE NOCASE)
+  AND ($4 IS NULL OR EXISTS (
+    SELECT 1
+      FROM task_labels tl
+      JOIN labels l ON l.id = tl.label_id
+     WHERE tl.task_id = t.id AND l.name = $4
+  ))
+  AND ($5 IS NULL OR t.due_date <= $5)
+  AND ($6 IS NULL OR EXISTS (
+    SELECT 1
+      FROM task_custom_field_values v
+     WHERE v.task_id = t.id
+       AND v.field_id = $6
+       AND ($7 IS NULL OR v.value = $7)
+  ))
 ORDER BY t.created_at DESC"#,
-            project_id
+            project_id,
+            filter.priority,
+            assignee,
+            label,
+            filter.due_before,
+            custom_field_id,
+            custom_value
         )
         .fetch_all(pool)
         .await?;
 
+        let mut labels: HashMap<Uuid, Vec<Label>> = HashMap::new();
+        for (task_id, label) in Label::find_by_project_tasks(pool, project_id).await? {
+            labels.entry(task_id).or_default().push(label);
+        }
+        let mut custom_fields: HashMap<Uuid, Vec<TaskCustomFieldValue>> = HashMap::new();
+        for (task_id, value) in
+            TaskCustomFieldValue::find_by_project_tasks(pool, project_id).await?
+        {
+            custom_fields.entry(task_id).or_default().push(value);
+        }
+
         let tasks = records
             .into_iter()
             .map(|rec| TaskWithAttemptStatus {
+                fields: TaskFields {
+                    priority: rec.priority,
+                    assignee: rec.assignee,
+                    due_date: rec.due_date,
+                    labels: labels.remove(&rec.id).unwrap_or_default(),
+                    custom_fields: custom_fields.remove(&rec.id).unwrap_or_default(),
+                },
                 task: Task {
                     id: rec.id,
                     project_id: rec.project_id,
@@ -185,6 +346,108 @@ ORDER BY t.created_at DESC"#,
         Ok(tasks)
     }
 
+    pub async fn find_fields(pool: &SqlitePool, id: Uuid) -> Result<TaskFields, sqlx::Error> {
+        let record = sqlx::query!(
+            r#"SELECT priority as "priority: TaskPriority", assignee, due_date as "due_date: NaiveDate"
+               FROM tasks
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(TaskFields {
+            priority: record.priority,
+            assignee: record.assignee,
+            due_date: record.due_date,
+            labels: Label::find_by_task_id(pool, id).await?,
+            custom_fields: TaskCustomFieldValue::find_by_task_id(pool, id).await?,
+        })
+    }
+
+    /// Replace the task's fields in one transaction. Labels and custom fields must be the
+    /// task's project's, and values must suit their field.
+    pub async fn update_fields(
+        pool: &SqlitePool,
+        task: &Task,
+        data: &UpdateTaskFields,
+    ) -> Result<TaskFields, TaskFieldsError> {
+        let project_labels: HashSet<Uuid> = Label::find_by_project_id(pool, task.project_id)
+            .await?
+            .into_iter()
+            .map(|label| label.id)
+            .collect();
+        let label_ids: HashSet<Uuid> = data.label_ids.iter().copied().collect();
+        if let Some(unknown) = label_ids.difference(&project_labels).next() {
+            return Err(TaskFieldsError::UnknownLabel(*unknown));
+        }
+
+        let project_fields: HashMap<Uuid, CustomField> =
+            CustomField::find_by_project_id(pool, task.project_id)
+                .await?
+                .into_iter()
+                .map(|field| (field.id, field))
+                .collect();
+        let mut values = Vec::new();
+        for (field_id, value) in &data.custom_fields {
+            let field = project_fields
+                .get(field_id)
+                .ok_or(TaskFieldsError::UnknownCustomField(*field_id))?;
+            if !value.is_null() {
+                values.push((field.id, field.normalize(value)?.to_string()));
+            }
+        }
+
+        let assignee = data
+            .assignee
+            .as_deref()
+            .map(str::trim)
+            .filter(|assignee| !assignee.is_empty());
+
+        let mut tx = pool.begin().await?;
+        sqlx::query!("DELETE FROM task_labels WHERE task_id = $1", task.id)
+            .execute(&mut *tx)
+            .await?;
+        for label_id in label_ids {
+            sqlx::query!(
+                "INSERT INTO task_labels (task_id, label_id) VALUES ($1, $2)",
+                task.id,
+                label_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        sqlx::query!(
+            "DELETE FROM task_custom_field_values WHERE task_id = $1",
+            task.id
+        )
+        .execute(&mut *tx)
+        .await?;
+        for (field_id, value) in values {
+            sqlx::query!(
+                "INSERT INTO task_custom_field_values (task_id, field_id, value) VALUES ($1, $2, $3)",
+                task.id,
+                field_id,
+                value
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        sqlx::query!(
+            r#"UPDATE tasks
+               SET priority = $2, assignee = $3, due_date = $4, updated_at = CURRENT_TIMESTAMP
+               WHERE id = $1"#,
+            task.id,
+            data.priority,
+            assignee,
+            data.due_date
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(Self::find_fields(pool, task.id).await?)
+    }
+
     pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Task,
@@ -288,6 +551,69 @@ ORDER BY t.created_at DESC"#,
         Ok(())
     }
 
+    /// Update a task's status, recording the transition in the audit log when it changed
+    pub async fn transition_status(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: TaskStatus,
+        actor: AuditActor,
+        task_attempt_id: Option<Uuid>,
+    ) -> Result<(), sqlx::Error> {
+        let previous = Self::find_by_id(pool, id).await?;
+        Self::update_status(pool, id, status.clone()).await?;
+        if let Some(previous) = previous
+            && previous.status != status
+        {
+            let mut event = CreateAuditEvent::new(actor, AuditAction::TaskStatusChanged)
+                .task(previous.project_id, id)
+                .details(serde_json::json!({ "from": previous.status, "to": status }));
+            if let Some(task_attempt_id) = task_attempt_id {
+                event = event.attempt(task_attempt_id);
+            }
+            AuditEvent::record(pool, event).await;
+        }
+        Ok(())
+    }
+
+    /// Update a task's status within a transaction, without recording it in the audit log
+    pub async fn set_status<'e, E>(
+        executor: E,
+        id: Uuid,
+        status: &TaskStatus,
+    ) -> Result<u64, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let result = sqlx::query!(
+            "UPDATE tasks SET status = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+            id,
+            status
+        )
+        .execute(executor)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Move a task to another project. Its parent attempt stays with the old project, so the
+    /// link to it is dropped.
+    pub async fn set_project<'e, E>(
+        executor: E,
+        id: Uuid,
+        project_id: Uuid,
+    ) -> Result<u64, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let result = sqlx::query!(
+            "UPDATE tasks SET project_id = $2, parent_task_attempt = NULL, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+            id,
+            project_id
+        )
+        .execute(executor)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
     /// Nullify parent_task_attempt for all tasks that reference the given attempt ID
     /// This breaks parent-child relationships before deleting a parent task
     pub async fn nullify_children_by_attempt_id<'e, E>(
@@ -381,3 +707,245 @@ ORDER BY t.created_at DESC"#,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        DBService,
+        models::{
+            custom_field::{CreateCustomField, CustomFieldType},
+            label::CreateLabel,
+            project::CreateProject,
+        },
+    };
+
+    async fn project(pool: &SqlitePool) -> Uuid {
+        let project_id = Uuid::new_v4();
+        Project::create(
+            pool,
+            &CreateProject::from_name_repo_path("p".to_string(), project_id.to_string()),
+            project_id,
+        )
+        .await
+        .unwrap();
+        project_id
+    }
+
+    async fn task(pool: &SqlitePool, project_id: Uuid, title: &str) -> Task {
+        let data = CreateTask::from_title_description(project_id, title.to_string(), None);
+        Task::create(pool, &data, Uuid::new_v4()).await.unwrap()
+    }
+
+    async fn label(pool: &SqlitePool, project_id: Uuid, name: &str) -> Label {
+        let data = CreateLabel {
+            project_id,
+            name: name.to_string(),
+            color: None,
+        };
+        Label::create(pool, &data, Uuid::new_v4()).await.unwrap()
+    }
+
+    async fn points_field(pool: &SqlitePool, project_id: Uuid) -> CustomField {
+        let data = CreateCustomField {
+            project_id,
+            name: "Points".to_string(),
+            field_type: CustomFieldType::Number,
+            options: Vec::new(),
+        };
+        CustomField::create(pool, &data, Uuid::new_v4())
+            .await
+            .unwrap()
+    }
+
+    fn date(text: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+    }
+
+    async fn titles(pool: &SqlitePool, project_id: Uuid, filter: TaskFilter) -> Vec<String> {
+        let mut titles: Vec<String> =
+            Task::find_by_project_id_with_attempt_status(pool, project_id, &filter)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|task| task.task.title)
+                .collect();
+        titles.sort();
+        titles
+    }
+
+    #[tokio::test]
+    async fn filters_match_every_field_that_is_set() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let pool = &db.pool;
+        let project_id = project(pool).await;
+        let bug = label(pool, project_id, "bug").await;
+        let points = points_field(pool, project_id).await;
+        let login = task(pool, project_id, "login").await;
+        task(pool, project_id, "logout").await;
+        Task::update_fields(
+            pool,
+            &login,
+            &UpdateTaskFields {
+                priority: Some(TaskPriority::High),
+                assignee: Some(" Alice ".to_string()),
+                due_date: Some(date("2025-01-10")),
+                label_ids: vec![bug.id],
+                custom_fields: HashMap::from([(points.id, json!(3))]),
+            },
+        )
+        .await
+        .unwrap();
+
+        let filter = |f: fn(&mut TaskFilter)| {
+            let mut filter = TaskFilter::default();
+            f(&mut filter);
+            filter
+        };
+        assert_eq!(
+            titles(pool, project_id, TaskFilter::default()).await,
+            ["login", "logout"]
+        );
+        let matching_login = [
+            filter(|f| f.priority = Some(TaskPriority::High)),
+            filter(|f| f.assignee = Some("ALICE".to_string())),
+            filter(|f| f.label = Some("bug".to_string())),
+            filter(|f| f.due_before = Some(date("2025-01-10"))),
+            filter(|f| f.custom_field = Some("Points".to_string())),
+            filter(|f| {
+                f.custom_field = Some("Points".to_string());
+                f.custom_value = Some("3.0".to_string());
+            }),
+            filter(|f| {
+                f.priority = Some(TaskPriority::High);
+                f.label = Some("bug".to_string());
+            }),
+        ];
+        for filter in matching_login {
+            assert_eq!(
+                titles(pool, project_id, filter.clone()).await,
+                ["login"],
+                "{filter:?}"
+            );
+        }
+        let matching_none = [
+            filter(|f| f.priority = Some(TaskPriority::Low)),
+            filter(|f| f.label = Some("feature".to_string())),
+            filter(|f| f.due_before = Some(date("2025-01-09"))),
+            filter(|f| f.custom_field = Some("Estimate".to_string())),
+            filter(|f| {
+                f.custom_field = Some("Points".to_string());
+                f.custom_value = Some("4".to_string());
+            }),
+            filter(|f| {
+                f.custom_field = Some("Points".to_string());
+                f.custom_value = Some("three".to_string());
+            }),
+            filter(|f| {
+                f.priority = Some(TaskPriority::High);
+                f.label = Some("feature".to_string());
+            }),
+        ];
+        for filter in matching_none {
+            assert!(
+                titles(pool, project_id, filter.clone()).await.is_empty(),
+                "{filter:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn update_fields_replaces_everything_or_nothing() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let pool = &db.pool;
+        let project_id = project(pool).await;
+        let other_project_id = project(pool).await;
+        let bug = label(pool, project_id, "bug").await;
+        let foreign = label(pool, other_project_id, "bug").await;
+        let points = points_field(pool, project_id).await;
+        let task = task(pool, project_id, "login").await;
+        let data = UpdateTaskFields {
+            priority: Some(TaskPriority::Urgent),
+            assignee: Some("  ".to_string()),
+            due_date: None,
+            label_ids: vec![bug.id, bug.id],
+            custom_fields: HashMap::from([(points.id, json!(2))]),
+        };
+
+        let fields = Task::update_fields(pool, &task, &data).await.unwrap();
+        assert_eq!(fields.priority, Some(TaskPriority::Urgent));
+        assert_eq!(fields.assignee, None);
+        assert_eq!(fields.labels.len(), 1);
+        assert_eq!(fields.custom_fields[0].value, json!(2.0));
+
+        let foreign_label = UpdateTaskFields {
+            label_ids: vec![foreign.id],
+            ..data.clone()
+        };
+        assert!(matches!(
+            Task::update_fields(pool, &task, &foreign_label).await,
+            Err(TaskFieldsError::UnknownLabel(id)) if id == foreign.id
+        ));
+        let invalid_value = UpdateTaskFields {
+            priority: None,
+            custom_fields: HashMap::from([(points.id, json!("two"))]),
+            ..data.clone()
+        };
+        assert!(matches!(
+            Task::update_fields(pool, &task, &invalid_value).await,
+            Err(TaskFieldsError::InvalidValue { .. })
+        ));
+        let unchanged = Task::find_fields(pool, task.id).await.unwrap();
+        assert_eq!(unchanged.priority, Some(TaskPriority::Urgent));
+        assert_eq!(unchanged.custom_fields[0].value, json!(2.0));
+
+        let cleared = UpdateTaskFields {
+            priority: None,
+            assignee: None,
+            due_date: None,
+            label_ids: Vec::new(),
+            custom_fields: HashMap::from([(points.id, Value::Null)]),
+        };
+        let fields = Task::update_fields(pool, &task, &cleared).await.unwrap();
+        assert_eq!(fields.priority, None);
+        assert!(fields.labels.is_empty());
+        assert!(fields.custom_fields.is_empty());
+    }
+
+    #[tokio::test]
+    async fn moving_a_task_clears_its_labels_and_custom_values() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let pool = &db.pool;
+        let project_id = project(pool).await;
+        let other_project_id = project(pool).await;
+        let bug = label(pool, project_id, "bug").await;
+        let points = points_field(pool, project_id).await;
+        let task = task(pool, project_id, "login").await;
+        Task::update_fields(
+            pool,
+            &task,
+            &UpdateTaskFields {
+                priority: Some(TaskPriority::Low),
+                assignee: Some("alice".to_string()),
+                due_date: None,
+                label_ids: vec![bug.id],
+                custom_fields: HashMap::from([(points.id, json!(1))]),
+            },
+        )
+        .await
+        .unwrap();
+
+        Task::set_project(pool, task.id, other_project_id)
+            .await
+            .unwrap();
+
+        // Labels and custom fields belong to the old project, the other fields move along
+        let fields = Task::find_fields(pool, task.id).await.unwrap();
+        assert!(fields.labels.is_empty());
+        assert!(fields.custom_fields.is_empty());
+        assert_eq!(fields.priority, Some(TaskPriority::Low));
+        assert_eq!(fields.assignee.as_deref(), Some("alice"));
+    }
+}
@@ -0,0 +1,451 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// What a schedule does about times it missed while the server was down
+#[derive(Debug, Clone, Copy, Default, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "schedule_catch_up", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduleCatchUp {
+    /// Record missed times as skipped
+    Skip,
+    /// Run once for the latest missed time
+    #[default]
+    Once,
+    /// Run for every missed time, up to a limit
+    All,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "schedule_run_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduleRunStatus {
+    Pending,
+    /// The task was created
+    Created,
+    /// The task was created and an attempt started on it
+    Started,
+    Skipped,
+    Failed,
+}
+
+/// A cron schedule creating tasks from a template
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct TaskSchedule {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    /// The template whose content becomes the task description
+    pub tag_id: Uuid,
+    pub title: String,
+    /// Five field cron expression in the server's local time zone
+    pub cron: String,
+    /// Profile to start an attempt with, or null to only create the task
+    #[ts(type = "ExecutorProfileId | null")]
+    pub executor_profile_id: Option<Json<ExecutorProfileId>>,
+    pub base_branch: Option<String>,
+    pub catch_up: ScheduleCatchUp,
+    pub paused: bool,
+    pub next_run_at: DateTime<Utc>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateTaskSchedule {
+    pub project_id: Uuid,
+    pub tag_id: Uuid,
+    pub title: String,
+    pub cron: String,
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    pub base_branch: Option<String>,
+    #[serde(default)]
+    pub catch_up: ScheduleCatchUp,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateTaskSchedule {
+    pub tag_id: Uuid,
+    pub title: String,
+    pub cron: String,
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    pub base_branch: Option<String>,
+    #[serde(default)]
+    pub catch_up: ScheduleCatchUp,
+}
+
+/// A time a schedule ran at, or skipped
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct TaskScheduleRun {
+    pub id: Uuid,
+    pub schedule_id: Uuid,
+    pub scheduled_for: DateTime<Utc>,
+    pub status: ScheduleRunStatus,
+    pub task_id: Option<Uuid>,
+    pub task_attempt_id: Option<Uuid>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TaskSchedule {
+    pub fn executor_profile_id(&self) -> Option<&ExecutorProfileId> {
+        self.executor_profile_id.as_ref().map(|profile| &profile.0)
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", tag_id as "tag_id!: Uuid", title, cron,
+                      executor_profile_id as "executor_profile_id: Json<ExecutorProfileId>", base_branch,
+                      catch_up as "catch_up!: ScheduleCatchUp", paused as "paused!: bool",
+                      next_run_at as "next_run_at!: DateTime<Utc>", last_run_at as "last_run_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Schedules, optionally of one project, oldest first
+    pub async fn find_all(
+        pool: &SqlitePool,
+        project_id: Option<Uuid>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", tag_id as "tag_id!: Uuid", title, cron,
+                      executor_profile_id as "executor_profile_id: Json<ExecutorProfileId>", base_branch,
+                      catch_up as "catch_up!: ScheduleCatchUp", paused as "paused!: bool",
+                      next_run_at as "next_run_at!: DateTime<Utc>", last_run_at as "last_run_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE $1 IS NULL OR project_id = $1
+               ORDER BY created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Schedules that aren't paused and whose next run is at or before `now`
+    pub async fn find_due(pool: &SqlitePool, now: DateTime<Utc>) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", tag_id as "tag_id!: Uuid", title, cron,
+                      executor_profile_id as "executor_profile_id: Json<ExecutorProfileId>", base_branch,
+                      catch_up as "catch_up!: ScheduleCatchUp", paused as "paused!: bool",
+                      next_run_at as "next_run_at!: DateTime<Utc>", last_run_at as "last_run_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE paused = 0 AND next_run_at <= $1
+               ORDER BY next_run_at ASC"#,
+            now
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateTaskSchedule,
+        id: Uuid,
+        next_run_at: DateTime<Utc>,
+    ) -> Result<Self, sqlx::Error> {
+        let executor_profile_id = data.executor_profile_id.clone().map(Json);
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"INSERT INTO task_schedules
+                   (id, project_id, tag_id, title, cron, executor_profile_id, base_branch,
+                    catch_up, next_run_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", tag_id as "tag_id!: Uuid", title, cron,
+                      executor_profile_id as "executor_profile_id: Json<ExecutorProfileId>", base_branch,
+                      catch_up as "catch_up!: ScheduleCatchUp", paused as "paused!: bool",
+                      next_run_at as "next_run_at!: DateTime<Utc>", last_run_at as "last_run_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            data.tag_id,
+            data.title,
+            data.cron,
+            executor_profile_id,
+            data.base_branch,
+            data.catch_up,
+            next_run_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateTaskSchedule,
+        next_run_at: DateTime<Utc>,
+    ) -> Result<Self, sqlx::Error> {
+        let executor_profile_id = data.executor_profile_id.clone().map(Json);
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"UPDATE task_schedules
+               SET tag_id = $2, title = $3, cron = $4, executor_profile_id = $5, base_branch = $6,
+                   catch_up = $7, next_run_at = $8, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", tag_id as "tag_id!: Uuid", title, cron,
+                      executor_profile_id as "executor_profile_id: Json<ExecutorProfileId>", base_branch,
+                      catch_up as "catch_up!: ScheduleCatchUp", paused as "paused!: bool",
+                      next_run_at as "next_run_at!: DateTime<Utc>", last_run_at as "last_run_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.tag_id,
+            data.title,
+            data.cron,
+            executor_profile_id,
+            data.base_branch,
+            data.catch_up,
+            next_run_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Pause or resume. Resuming needs the next run, as times missed while paused don't run.
+    pub async fn set_paused(
+        pool: &SqlitePool,
+        id: Uuid,
+        paused: bool,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"UPDATE task_schedules
+               SET paused = $2, next_run_at = COALESCE($3, next_run_at),
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", tag_id as "tag_id!: Uuid", title, cron,
+                      executor_profile_id as "executor_profile_id: Json<ExecutorProfileId>", base_branch,
+                      catch_up as "catch_up!: ScheduleCatchUp", paused as "paused!: bool",
+                      next_run_at as "next_run_at!: DateTime<Utc>", last_run_at as "last_run_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            paused,
+            next_run_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Move on to the next run after running or skipping the due ones
+    pub async fn advance(
+        pool: &SqlitePool,
+        id: Uuid,
+        next_run_at: DateTime<Utc>,
+        last_run_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE task_schedules
+               SET next_run_at = $2, last_run_at = COALESCE($3, last_run_at)
+               WHERE id = $1"#,
+            id,
+            next_run_at,
+            last_run_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM task_schedules WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl TaskScheduleRun {
+    /// Runs of a schedule, newest first
+    pub async fn find_by_schedule_id(
+        pool: &SqlitePool,
+        schedule_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskScheduleRun,
+            r#"SELECT id as "id!: Uuid", schedule_id as "schedule_id!: Uuid", scheduled_for as "scheduled_for!: DateTime<Utc>",
+                      status as "status!: ScheduleRunStatus", task_id as "task_id: Uuid", task_attempt_id as "task_attempt_id: Uuid",
+                      error, created_at as "created_at!: DateTime<Utc>"
+               FROM task_schedule_runs
+               WHERE schedule_id = $1
+               ORDER BY scheduled_for DESC
+               LIMIT $2"#,
+            schedule_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Claim a scheduled time. None if it was claimed before, so a time never runs twice.
+    pub async fn claim(
+        pool: &SqlitePool,
+        schedule_id: Uuid,
+        scheduled_for: DateTime<Utc>,
+        status: ScheduleRunStatus,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            TaskScheduleRun,
+            r#"INSERT INTO task_schedule_runs (id, schedule_id, scheduled_for, status)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT (schedule_id, scheduled_for) DO NOTHING
+               RETURNING id as "id!: Uuid", schedule_id as "schedule_id!: Uuid", scheduled_for as "scheduled_for!: DateTime<Utc>",
+                      status as "status!: ScheduleRunStatus", task_id as "task_id: Uuid", task_attempt_id as "task_attempt_id: Uuid",
+                      error, created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            schedule_id,
+            scheduled_for,
+            status
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn finish(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: ScheduleRunStatus,
+        task_id: Option<Uuid>,
+        task_attempt_id: Option<Uuid>,
+        error: Option<&str>,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            TaskScheduleRun,
+            r#"UPDATE task_schedule_runs
+               SET status = $2, task_id = $3, task_attempt_id = $4, error = $5
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", schedule_id as "schedule_id!: Uuid", scheduled_for as "scheduled_for!: DateTime<Utc>",
+                      status as "status!: ScheduleRunStatus", task_id as "task_id: Uuid", task_attempt_id as "task_attempt_id: Uuid",
+                      error, created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            status,
+            task_id,
+            task_attempt_id,
+            error
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::{
+        DBService,
+        models::{
+            project::{CreateProject, Project},
+            tag::{CreateTag, Tag},
+        },
+    };
+
+    async fn schedule(pool: &SqlitePool, next_run_at: DateTime<Utc>) -> TaskSchedule {
+        let project_id = Uuid::new_v4();
+        Project::create(
+            pool,
+            &CreateProject::from_name_repo_path("p".to_string(), project_id.to_string()),
+            project_id,
+        )
+        .await
+        .unwrap();
+        let tag = Tag::create(
+            pool,
+            &CreateTag {
+                tag_name: format!("template_{project_id}"),
+                content: "Check the build".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+        TaskSchedule::create(
+            pool,
+            &CreateTaskSchedule {
+                project_id,
+                tag_id: tag.id,
+                title: "Nightly".to_string(),
+                cron: "0 3 * * *".to_string(),
+                executor_profile_id: None,
+                base_branch: None,
+                catch_up: ScheduleCatchUp::Once,
+            },
+            Uuid::new_v4(),
+            next_run_at,
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn a_scheduled_time_can_only_be_claimed_once() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let now = Utc::now();
+        let schedule = schedule(&db.pool, now).await;
+
+        let run = TaskScheduleRun::claim(&db.pool, schedule.id, now, ScheduleRunStatus::Pending)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(run.status, ScheduleRunStatus::Pending);
+
+        // Neither running it again nor skipping it may claim the time a second time
+        for status in [ScheduleRunStatus::Pending, ScheduleRunStatus::Skipped] {
+            let claimed = TaskScheduleRun::claim(&db.pool, schedule.id, now, status)
+                .await
+                .unwrap();
+            assert!(claimed.is_none());
+        }
+
+        let later = now + Duration::days(1);
+        assert!(
+            TaskScheduleRun::claim(&db.pool, schedule.id, later, ScheduleRunStatus::Pending)
+                .await
+                .unwrap()
+                .is_some()
+        );
+        let runs = TaskScheduleRun::find_by_schedule_id(&db.pool, schedule.id, 10)
+            .await
+            .unwrap();
+        assert_eq!(runs.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn paused_schedules_are_never_due() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let now = Utc::now();
+        let schedule = schedule(&db.pool, now - Duration::hours(1)).await;
+        let due = |at| TaskSchedule::find_due(&db.pool, at);
+        assert_eq!(due(now).await.unwrap().len(), 1);
+
+        let paused = TaskSchedule::set_paused(&db.pool, schedule.id, true, None)
+            .await
+            .unwrap();
+        assert!(paused.paused);
+        assert_eq!(paused.next_run_at, schedule.next_run_at);
+        assert!(due(now).await.unwrap().is_empty());
+
+        // Resuming moves past the times missed while paused
+        let next_run_at = now + Duration::hours(1);
+        let resumed = TaskSchedule::set_paused(&db.pool, schedule.id, false, Some(next_run_at))
+            .await
+            .unwrap();
+        assert!(!resumed.paused);
+        assert!(due(now).await.unwrap().is_empty());
+        assert_eq!(due(next_run_at).await.unwrap()[0].id, schedule.id);
+    }
+}
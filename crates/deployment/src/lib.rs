@@ -6,6 +6,7 @@ use axum::response::sse::Event;
 use db::{
     DBService,
     models::{
+        audit_event::AuditActor,
         execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
         project::{CreateProject, Project},
         task::{Task, TaskStatus},
@@ -29,7 +30,9 @@ use services::services::{
     filesystem_watcher::FilesystemWatcherError,
     git::{GitService, GitServiceError},
     image::{ImageError, ImageService},
+    issue_sync::IssueSyncService,
     pr_monitor::PrMonitorService,
+    project_env::{ProjectEnvError, ProjectEnvService},
     worktree_manager::WorktreeError,
 };
 use sqlx::{Error as SqlxError, types::Uuid};
@@ -68,6 +71,8 @@ pub enum DeploymentError {
     #[error(transparent)]
     Config(#[from] ConfigError),
     #[error(transparent)]
+    ProjectEnv(#[from] ProjectEnvError),
+    #[error(transparent)]
     Other(#[from] AnyhowError),
 }
 
@@ -105,6 +110,8 @@ pub trait Deployment: Clone + Send + Sync + 'static {
 
     fn drafts(&self) -> &DraftsService;
 
+    fn project_env(&self) -> &ProjectEnvService;
+
     async fn update_sentry_scope(&self) -> Result<(), DeploymentError> {
         let user_id = self.user_id();
         let config = self.config().read().await;
@@ -128,6 +135,19 @@ pub trait Deployment: Clone + Send + Sync + 'static {
         PrMonitorService::spawn(db, config, analytics).await
     }
 
+    async fn spawn_issue_sync_service(&self) -> tokio::task::JoinHandle<()> {
+        IssueSyncService::spawn(
+            self.db().clone(),
+            self.config().clone(),
+            self.git().clone(),
+            self.project_env().clone(),
+        )
+        .await
+    }
+
+    /// Start the background service that creates tasks from due schedules
+    async fn spawn_schedule_service(&self) -> tokio::task::JoinHandle<()>;
+
     async fn track_if_analytics_allowed(&self, event_name: &str, properties: Value) {
         let analytics_enabled = self.config().read().await.analytics_enabled;
         // Only skip tracking if user explicitly opted out (Some(false))
@@ -190,8 +210,14 @@ pub trait Deployment: Clone + Send + Sync + 'static {
             ) && let Ok(Some(task_attempt)) =
                 TaskAttempt::find_by_id(&self.db().pool, process.task_attempt_id).await
                 && let Ok(Some(task)) = task_attempt.parent_task(&self.db().pool).await
-                && let Err(e) =
-                    Task::update_status(&self.db().pool, task.id, TaskStatus::InReview).await
+                && let Err(e) = Task::transition_status(
+                    &self.db().pool,
+                    task.id,
+                    TaskStatus::InReview,
+                    AuditActor::System,
+                    Some(task_attempt.id),
+                )
+                .await
             {
                 tracing::error!(
                     "Failed to update task status to InReview for orphaned attempt: {}",
@@ -322,12 +348,13 @@ pub trait Deployment: Clone + Send + Sync + 'static {
         }
     }
 
+    /// The SSE event stream, resumed after the sequence number `after` when given
     async fn stream_events(
         &self,
+        after: Option<u64>,
     ) -> futures::stream::BoxStream<'static, Result<Event, std::io::Error>> {
         self.events()
-            .msg_store()
-            .history_plus_stream()
+            .stream_events(after)
             .map_ok(|m| m.to_sse_event())
             .boxed()
     }
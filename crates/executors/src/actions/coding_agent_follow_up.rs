@@ -1,11 +1,11 @@
-use std::{path::Path, sync::Arc};
+use std::{collections::HashMap, path::Path, sync::Arc};
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
 use crate::{
-    actions::Executable,
+    actions::{Executable, PromptContext},
     approvals::ExecutorApprovalService,
     executors::{BaseCodingAgent, ExecutorError, SpawnedChild, StandardCodingAgentExecutor},
     profile::{ExecutorConfigs, ExecutorProfileId},
@@ -38,6 +38,8 @@ impl Executable for CodingAgentFollowUpRequest {
         &self,
         current_dir: &Path,
         approvals: Arc<dyn ExecutorApprovalService>,
+        env: &HashMap<String, String>,
+        prompt_context: &PromptContext,
     ) -> Result<SpawnedChild, ExecutorError> {
         let executor_profile_id = self.get_executor_profile_id();
         let mut agent = ExecutorConfigs::get_cached()
@@ -47,9 +49,15 @@ impl Executable for CodingAgentFollowUpRequest {
             ))?;
 
         agent.use_approvals(approvals.clone());
+        agent.use_env(env.clone());
+        agent.use_prompt_vars(&prompt_context.vars);
 
         agent
-            .spawn_follow_up(current_dir, &self.prompt, &self.session_id)
+            .spawn_follow_up(
+                current_dir,
+                &prompt_context.apply(&self.prompt),
+                &self.session_id,
+            )
             .await
     }
 }
@@ -1,11 +1,11 @@
-use std::{path::Path, sync::Arc};
+use std::{collections::HashMap, path::Path, sync::Arc};
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
 use crate::{
-    actions::Executable,
+    actions::{Executable, PromptContext},
     approvals::ExecutorApprovalService,
     executors::{BaseCodingAgent, ExecutorError, SpawnedChild, StandardCodingAgentExecutor},
     profile::{ExecutorConfigs, ExecutorProfileId},
@@ -32,6 +32,8 @@ impl Executable for CodingAgentInitialRequest {
         &self,
         current_dir: &Path,
         approvals: Arc<dyn ExecutorApprovalService>,
+        env: &HashMap<String, String>,
+        prompt_context: &PromptContext,
     ) -> Result<SpawnedChild, ExecutorError> {
         let executor_profile_id = self.executor_profile_id.clone();
         let mut agent = ExecutorConfigs::get_cached()
@@ -41,7 +43,11 @@ impl Executable for CodingAgentInitialRequest {
             ))?;
 
         agent.use_approvals(approvals.clone());
+        agent.use_env(env.clone());
+        agent.use_prompt_vars(&prompt_context.vars);
 
-        agent.spawn(current_dir, &self.prompt).await
+        agent
+            .spawn(current_dir, &prompt_context.apply(&self.prompt))
+            .await
     }
 }
@@ -1,8 +1,9 @@
-use std::{path::Path, sync::Arc};
+use std::{collections::HashMap, path::Path, sync::Arc};
 
 use async_trait::async_trait;
 use enum_dispatch::enum_dispatch;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use ts_rs::TS;
 
 use crate::{
@@ -12,6 +13,7 @@ use crate::{
     },
     approvals::ExecutorApprovalService,
     executors::{BaseCodingAgent, ExecutorError, SpawnedChild},
+    profile::ExecutorProfileId,
 };
 pub mod coding_agent_follow_up;
 pub mod coding_agent_initial;
@@ -54,6 +56,39 @@ impl ExecutorAction {
             ExecutorActionType::ScriptRequest(_) => None,
         }
     }
+
+    /// Profile of the coding agent run by this action, or by the first one later in the chain
+    pub fn executor_profile_id(&self) -> Option<ExecutorProfileId> {
+        match self.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                Some(request.executor_profile_id.clone())
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                Some(request.get_executor_profile_id())
+            }
+            ExecutorActionType::ScriptRequest(_) => self
+                .next_action()
+                .and_then(|action| action.executor_profile_id()),
+        }
+    }
+}
+
+/// What the container adds to the prompt of a coding agent action
+#[derive(Debug, Clone, Default)]
+pub struct PromptContext {
+    /// Values for the template variables of the profile's append prompt
+    pub vars: Value,
+    /// The project's instructions, placed before the prompt
+    pub instructions: Option<String>,
+}
+
+impl PromptContext {
+    pub fn apply(&self, prompt: &str) -> String {
+        match &self.instructions {
+            Some(instructions) => format!("{instructions}\n\n{prompt}"),
+            None => prompt.to_string(),
+        }
+    }
 }
 
 #[async_trait]
@@ -63,6 +98,8 @@ pub trait Executable {
         &self,
         current_dir: &Path,
         approvals: Arc<dyn ExecutorApprovalService>,
+        env: &HashMap<String, String>,
+        prompt_context: &PromptContext,
     ) -> Result<SpawnedChild, ExecutorError>;
 }
 
@@ -72,7 +109,11 @@ impl Executable for ExecutorAction {
         &self,
         current_dir: &Path,
         approvals: Arc<dyn ExecutorApprovalService>,
+        env: &HashMap<String, String>,
+        prompt_context: &PromptContext,
     ) -> Result<SpawnedChild, ExecutorError> {
-        self.typ.spawn(current_dir, approvals).await
+        self.typ
+            .spawn(current_dir, approvals, env, prompt_context)
+            .await
     }
 }
@@ -1,15 +1,15 @@
-use std::{path::Path, sync::Arc};
+use std::{collections::HashMap, path::Path, sync::Arc};
 
 use async_trait::async_trait;
-use command_group::AsyncCommandGroup;
 use serde::{Deserialize, Serialize};
 use tokio::process::Command;
 use ts_rs::TS;
 use workspace_utils::shell::get_shell_command;
 
 use crate::{
-    actions::Executable,
+    actions::{Executable, PromptContext},
     approvals::ExecutorApprovalService,
+    command::group_spawn,
     executors::{ExecutorError, SpawnedChild},
 };
 
@@ -38,6 +38,8 @@ impl Executable for ScriptRequest {
         &self,
         current_dir: &Path,
         _approvals: Arc<dyn ExecutorApprovalService>,
+        env: &HashMap<String, String>,
+        _prompt_context: &PromptContext,
     ) -> Result<SpawnedChild, ExecutorError> {
         let (shell_cmd, shell_arg) = get_shell_command();
         let mut command = Command::new(shell_cmd);
@@ -47,9 +49,10 @@ impl Executable for ScriptRequest {
             .stderr(std::process::Stdio::piped())
             .arg(shell_arg)
             .arg(&self.script)
-            .current_dir(current_dir);
+            .current_dir(current_dir)
+            .envs(env);
 
-        let child = command.group_spawn()?;
+        let child = group_spawn(&mut command)?;
 
         Ok(child.into())
     }
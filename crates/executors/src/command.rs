@@ -1,6 +1,68 @@
+use std::{collections::HashMap, io};
+
+use command_group::{AsyncCommandGroup, AsyncGroupChild};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use tokio::process::Command;
 use ts_rs::TS;
+use workspace_utils::path::expand_tilde;
+
+const GIT_COMMON_DIR: &str =
+    "\"$(git rev-parse --path-format=absolute --git-common-dir 2>/dev/null || echo \"$PWD\")\"";
+
+/// Limits applied to the processes of an attempt (Linux only)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema, Default)]
+pub struct ResourceLimits {
+    /// CPU cores the processes may use, e.g. 2.0 (requires cgroups v2)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpus: Option<f64>,
+    /// Memory limit in MiB (requires cgroups v2)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_mb: Option<u64>,
+    /// Wall-clock limit in seconds (dev servers are exempt)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+}
+
+/// Set by the container, in the env it passes to executors, to the `cgroup.procs` file of the
+/// cgroup that limits an execution process
+pub const CGROUP_PROCS_ENV: &str = "VIBE_KANBAN_CGROUP_PROCS";
+
+/// Spawn `command` as the leader of a new process group. If its env names a cgroup through
+/// [`CGROUP_PROCS_ENV`], the child joins it before it execs, so nothing it starts runs outside
+/// the limits.
+pub fn group_spawn(command: &mut Command) -> io::Result<AsyncGroupChild> {
+    #[cfg(target_os = "linux")]
+    join_cgroup_before_exec(command)?;
+    command.group_spawn()
+}
+
+#[cfg(target_os = "linux")]
+fn join_cgroup_before_exec(command: &mut Command) -> io::Result<()> {
+    use std::io::Write;
+
+    let procs = command
+        .as_std()
+        .get_envs()
+        .find(|(key, _)| *key == CGROUP_PROCS_ENV)
+        .and_then(|(_, value)| value.map(std::path::PathBuf::from));
+    let Some(procs) = procs else {
+        return Ok(());
+    };
+    command.env_remove(CGROUP_PROCS_ENV);
+
+    // Opened before forking: between fork and exec the child may only make async-signal-safe
+    // calls. The file is opened close-on-exec, so the program doesn't inherit it.
+    let file = std::fs::OpenOptions::new().write(true).open(&procs)?;
+    // SAFETY: the hook only calls write(2) on the already open file
+    unsafe {
+        command.pre_exec(move || {
+            // Writing 0 moves the writing process, i.e. the child, into the cgroup
+            (&file).write_all(b"0")
+        });
+    }
+    Ok(())
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema, Default)]
 pub struct CmdOverrides {
@@ -16,6 +78,37 @@ pub struct CmdOverrides {
     )]
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub additional_params: Option<Vec<String>>,
+    #[schemars(
+        title = "Resource Limits",
+        description = "CPU, memory and wall-clock limits for the attempt's processes (Linux only)"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limits: Option<ResourceLimits>,
+    #[schemars(
+        title = "Filesystem Sandbox",
+        description = "Only allow writes inside the worktree and temp directories (Linux only, requires bubblewrap)"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fs_sandbox: Option<bool>,
+    #[schemars(
+        title = "Sandbox Writable Paths",
+        description = "Extra paths the agent may write to inside the sandbox, e.g. its config directory"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sandbox_writable_paths: Option<Vec<String>>,
+    /// Environment set at spawn time from the project's env vars and secrets, never persisted
+    #[serde(skip)]
+    #[ts(skip)]
+    #[schemars(skip)]
+    pub injected_env: HashMap<String, String>,
+}
+
+impl CmdOverrides {
+    /// The executable `command` launches, taking the base command override into account
+    pub fn program(&self, command: &str) -> Option<String> {
+        let base = self.base_command_override.as_deref().unwrap_or(command);
+        shlex::split(base).and_then(|parts| parts.into_iter().next())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
@@ -24,6 +117,11 @@ pub struct CommandBuilder {
     pub base: String,
     /// Optional parameters to append to the base command
     pub params: Option<Vec<String>>,
+    /// Extra writable paths when the command runs in the filesystem sandbox
+    #[serde(skip)]
+    #[ts(skip)]
+    #[schemars(skip)]
+    sandbox: Option<Vec<String>>,
 }
 
 impl CommandBuilder {
@@ -31,9 +129,16 @@ impl CommandBuilder {
         Self {
             base: base.into(),
             params: None,
+            sandbox: None,
         }
     }
 
+    /// Run the built command inside the filesystem sandbox
+    pub fn sandboxed(mut self, writable_paths: Vec<String>) -> Self {
+        self.sandbox = Some(writable_paths);
+        self
+    }
+
     pub fn params<I>(mut self, params: I) -> Self
     where
         I: IntoIterator,
@@ -65,7 +170,7 @@ impl CommandBuilder {
         if let Some(ref params) = self.params {
             parts.extend(params.clone());
         }
-        parts.join(" ")
+        self.finish(parts.join(" "))
     }
 
     pub fn build_follow_up(&self, additional_args: &[String]) -> String {
@@ -74,8 +179,67 @@ impl CommandBuilder {
             parts.extend(params.clone());
         }
         parts.extend(additional_args.iter().cloned());
-        parts.join(" ")
+        self.finish(parts.join(" "))
     }
+
+    fn finish(&self, command: String) -> String {
+        match &self.sandbox {
+            Some(writable_paths) => sandbox_command(&command, writable_paths),
+            None => command,
+        }
+    }
+}
+
+/// Wrap a shell command with bubblewrap so it can only write to the working directory, temp
+/// directories and `writable_paths`. The rest of the filesystem stays readable.
+pub fn sandbox_command(command: &str, writable_paths: &[String]) -> String {
+    if !cfg!(target_os = "linux") {
+        tracing::warn!("Filesystem sandbox is only supported on Linux, running unsandboxed");
+        return command.to_string();
+    }
+
+    let quote = |s: &str| {
+        shlex::try_quote(&s.replace('\0', ""))
+            .map(|q| q.into_owned())
+            .unwrap_or_default()
+    };
+
+    let mut parts: Vec<String> = [
+        "bwrap",
+        "--ro-bind",
+        "/",
+        "/",
+        "--dev",
+        "/dev",
+        "--proc",
+        "/proc",
+        "--bind",
+        "\"$PWD\"",
+        "\"$PWD\"",
+        "--bind",
+        "/tmp",
+        "/tmp",
+        "--bind-try",
+        "\"${TMPDIR:-/tmp}\"",
+        "\"${TMPDIR:-/tmp}\"",
+        // Worktrees keep their index and refs in the main repository's git dir
+        "--bind",
+        GIT_COMMON_DIR,
+        GIT_COMMON_DIR,
+        "--die-with-parent",
+    ]
+    .into_iter()
+    .map(str::to_string)
+    .collect();
+
+    for path in writable_paths {
+        let path = quote(&expand_tilde(path).to_string_lossy());
+        parts.extend(["--bind-try".to_string(), path.clone(), path]);
+    }
+
+    parts.extend(["--".to_string(), "sh".to_string(), "-c".to_string()]);
+    parts.push(quote(command));
+    parts.join(" ")
 }
 
 pub fn apply_overrides(builder: CommandBuilder, overrides: &CmdOverrides) -> CommandBuilder {
@@ -84,9 +248,87 @@ pub fn apply_overrides(builder: CommandBuilder, overrides: &CmdOverrides) -> Com
     } else {
         builder
     };
-    if let Some(ref extra) = overrides.additional_params {
+    let builder = if let Some(ref extra) = overrides.additional_params {
         builder.extend_params(extra.clone())
     } else {
         builder
+    };
+    if overrides.fs_sandbox.unwrap_or(false) {
+        builder.sandboxed(overrides.sandbox_writable_paths.clone().unwrap_or_default())
+    } else {
+        builder
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    /// The arguments bubblewrap ends up with once the shell has parsed the wrapped command
+    fn args(wrapped: &str) -> Vec<String> {
+        shlex::split(wrapped).expect("valid shell words")
+    }
+
+    #[test]
+    fn sandbox_command_passes_the_command_through_as_one_argument() {
+        let command = r#"echo "it's $HOME" && printf '%s\n' `id -u`; exit 3"#;
+        let args = args(&sandbox_command(command, &[]));
+
+        assert_eq!(args.first().map(String::as_str), Some("bwrap"));
+        assert_eq!(&args[args.len() - 4..args.len() - 1], ["--", "sh", "-c"]);
+        assert_eq!(args.last().map(String::as_str), Some(command));
+    }
+
+    #[test]
+    fn sandbox_command_quotes_writable_paths() {
+        let writable = vec![
+            "/data/with space".to_string(),
+            "/data/it's; rm -rf /".to_string(),
+            "~/.config/agent".to_string(),
+            "/data/nul\0byte".to_string(),
+        ];
+        let args = args(&sandbox_command("true", &writable));
+
+        let home = expand_tilde("~/.config/agent")
+            .to_string_lossy()
+            .into_owned();
+        for path in [
+            "/data/with space",
+            "/data/it's; rm -rf /",
+            home.as_str(),
+            "/data/nulbyte",
+        ] {
+            let at = args
+                .windows(3)
+                .position(|w| w[0] == "--bind-try" && w[1] == path && w[2] == path);
+            assert!(at.is_some(), "{path} is bound read-write: {args:?}");
+        }
+    }
+
+    #[test]
+    fn only_sandboxed_builders_wrap_the_command() {
+        let overrides = CmdOverrides {
+            fs_sandbox: Some(true),
+            sandbox_writable_paths: Some(vec!["/opt/agent".to_string()]),
+            ..Default::default()
+        };
+        let builder = CommandBuilder::new("agent").params(["--print"]);
+
+        assert_eq!(builder.build_initial(), "agent --print");
+        let sandboxed = apply_overrides(builder, &overrides).build_initial();
+        assert!(sandboxed.starts_with("bwrap "));
+        assert_eq!(args(&sandboxed).last().unwrap(), "agent --print");
+    }
+
+    #[test]
+    fn program_uses_the_base_command_override() {
+        let mut overrides = CmdOverrides::default();
+        assert_eq!(overrides.program("npx -y agent"), Some("npx".to_string()));
+
+        overrides.base_command_override = Some("'/opt/my agent/bin' --acp".to_string());
+        assert_eq!(
+            overrides.program("npx -y agent"),
+            Some("/opt/my agent/bin".to_string())
+        );
     }
 }
@@ -0,0 +1,114 @@
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use workspace_utils::{msg_store::MsgStore, shell::resolve_executable_path};
+
+use super::AcpAgentHarness;
+use crate::{
+    command::{CmdOverrides, CommandBuilder, apply_overrides},
+    executors::{AppendPrompt, ExecutorError, SpawnedChild, StandardCodingAgentExecutor},
+    mcp_config::configured_mcp_config_path,
+};
+
+const DEFAULT_SESSION_NAMESPACE: &str = "acp_sessions";
+
+/// Generic executor for any agent that speaks the Agent Client Protocol over stdio.
+/// Everything needed to launch the agent comes from the profile, so new ACP agents
+/// can be added through `profiles.json` without a code change.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+pub struct Acp {
+    #[serde(default)]
+    pub append_prompt: AppendPrompt,
+    #[schemars(
+        title = "Command",
+        description = "Command that starts the agent in ACP mode (e.g. \"npx -y my-agent --acp\")"
+    )]
+    pub command: String,
+    #[schemars(title = "Arguments", description = "Arguments passed to the command")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub args: Option<Vec<String>>,
+    #[schemars(
+        title = "Environment",
+        description = "Extra environment variables for the agent process"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<HashMap<String, String>>,
+    #[schemars(
+        title = "Session Namespace",
+        description = "Directory name used to persist session history for follow-ups (defaults to acp_sessions)"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_namespace: Option<String>,
+    #[schemars(
+        title = "MCP Config Path",
+        description = "Path to the agent's MCP configuration file (JSON with an mcpServers object)"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mcp_config_path: Option<String>,
+    #[serde(flatten)]
+    pub cmd: CmdOverrides,
+}
+
+impl Acp {
+    fn build_command_builder(&self) -> CommandBuilder {
+        let mut builder = CommandBuilder::new(self.command.clone());
+
+        if let Some(args) = &self.args {
+            builder = builder.extend_params(args.clone());
+        }
+
+        apply_overrides(builder, &self.cmd)
+    }
+
+    fn harness(&self) -> AcpAgentHarness {
+        let namespace = self
+            .session_namespace
+            .as_deref()
+            .filter(|ns| !ns.trim().is_empty())
+            .unwrap_or(DEFAULT_SESSION_NAMESPACE);
+        let mut env = self.cmd.injected_env.clone();
+        env.extend(self.env.clone().unwrap_or_default());
+        AcpAgentHarness::with_session_namespace(namespace).with_env(env)
+    }
+}
+
+#[async_trait]
+impl StandardCodingAgentExecutor for Acp {
+    async fn spawn(&self, current_dir: &Path, prompt: &str) -> Result<SpawnedChild, ExecutorError> {
+        let acp_command = self.build_command_builder().build_initial();
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        self.harness()
+            .spawn_with_command(current_dir, combined_prompt, acp_command)
+            .await
+    }
+
+    async fn spawn_follow_up(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: &str,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let acp_command = self.build_command_builder().build_follow_up(&[]);
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        self.harness()
+            .spawn_follow_up_with_command(current_dir, combined_prompt, session_id, acp_command)
+            .await
+    }
+
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
+        super::normalize_logs(msg_store, worktree_path);
+    }
+
+    fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
+        configured_mcp_config_path(self.mcp_config_path.as_deref())
+    }
+
+    async fn check_availability(&self) -> bool {
+        self.cmd
+            .program(&self.command)
+            .is_some_and(|program| resolve_executable_path(&program).is_some())
+    }
+}
@@ -1,12 +1,14 @@
 use std::{
+    collections::HashMap,
     path::{Path, PathBuf},
     process::Stdio,
     sync::Arc,
+    task::Poll,
 };
 
 use agent_client_protocol as proto;
 use agent_client_protocol::Agent as _;
-use command_group::{AsyncCommandGroup, AsyncGroupChild};
+use command_group::AsyncGroupChild;
 use futures::StreamExt;
 use tokio::{io::AsyncWriteExt, process::Command, sync::mpsc};
 use tokio_util::{
@@ -17,11 +19,16 @@ use tracing::error;
 use workspace_utils::{shell::get_shell_command, stream_lines::LinesStreamExt};
 
 use super::{AcpClient, SessionManager};
-use crate::executors::{ExecutorError, SpawnedChild, acp::AcpEvent};
+use crate::{
+    command::group_spawn,
+    executors::{ExecutorError, SpawnedChild, acp::AcpEvent},
+    steering::{self, SteeringError, SteeringLogLine, SteeringMessage, SteeringReceiver},
+};
 
 /// Reusable harness for ACP-based conns (Gemini, Qwen, etc.)
 pub struct AcpAgentHarness {
     session_namespace: String,
+    env: HashMap<String, String>,
 }
 
 impl Default for AcpAgentHarness {
@@ -36,6 +43,7 @@ impl AcpAgentHarness {
     pub fn new() -> Self {
         Self {
             session_namespace: "gemini_sessions".to_string(),
+            env: HashMap::new(),
         }
     }
 
@@ -43,9 +51,16 @@ impl AcpAgentHarness {
     pub fn with_session_namespace(namespace: impl Into<String>) -> Self {
         Self {
             session_namespace: namespace.into(),
+            env: HashMap::new(),
         }
     }
 
+    /// Extra environment variables passed to the spawned agent process
+    pub fn with_env(mut self, env: HashMap<String, String>) -> Self {
+        self.env = env;
+        self
+    }
+
     pub async fn spawn_with_command(
         &self,
         current_dir: &Path,
@@ -62,11 +77,13 @@ impl AcpAgentHarness {
             .current_dir(current_dir)
             .arg(shell_arg)
             .arg(full_command)
-            .env("NODE_NO_WARNINGS", "1");
+            .env("NODE_NO_WARNINGS", "1")
+            .envs(&self.env);
 
-        let mut child = command.group_spawn()?;
+        let mut child = group_spawn(&mut command)?;
 
         let (exit_tx, exit_rx) = tokio::sync::oneshot::channel::<()>();
+        let (steering_tx, steering_rx) = steering::channel();
         Self::bootstrap_acp_connection(
             &mut child,
             current_dir.to_path_buf(),
@@ -74,12 +91,14 @@ impl AcpAgentHarness {
             prompt,
             Some(exit_tx),
             self.session_namespace.clone(),
+            steering_rx,
         )
         .await?;
 
         Ok(SpawnedChild {
             child,
             exit_signal: Some(exit_rx),
+            steering: Some(steering_tx),
         })
     }
 
@@ -100,11 +119,13 @@ impl AcpAgentHarness {
             .current_dir(current_dir)
             .arg(shell_arg)
             .arg(full_command)
-            .env("NODE_NO_WARNINGS", "1");
+            .env("NODE_NO_WARNINGS", "1")
+            .envs(&self.env);
 
-        let mut child = command.group_spawn()?;
+        let mut child = group_spawn(&mut command)?;
 
         let (exit_tx, exit_rx) = tokio::sync::oneshot::channel::<()>();
+        let (steering_tx, steering_rx) = steering::channel();
         Self::bootstrap_acp_connection(
             &mut child,
             current_dir.to_path_buf(),
@@ -112,12 +133,14 @@ impl AcpAgentHarness {
             prompt,
             Some(exit_tx),
             self.session_namespace.clone(),
+            steering_rx,
         )
         .await?;
 
         Ok(SpawnedChild {
             child,
             exit_signal: Some(exit_rx),
+            steering: Some(steering_tx),
         })
     }
 
@@ -128,6 +151,7 @@ impl AcpAgentHarness {
         prompt: String,
         exit_signal: Option<tokio::sync::oneshot::Sender<()>>,
         session_namespace: String,
+        mut steering_rx: SteeringReceiver,
     ) -> Result<(), ExecutorError> {
         // Take child's stdio for ACP wiring
         let orig_stdout = child.inner().stdout.take().ok_or_else(|| {
@@ -351,39 +375,104 @@ impl AcpAgentHarness {
                                 .unwrap_or_default(),
                         );
 
-                        // Build prompt request
-                        let req = proto::PromptRequest {
-                            session_id: proto::SessionId(acp_session_id.clone().into()),
-                            prompt: vec![proto::ContentBlock::Text(proto::TextContent {
-                                annotations: None,
-                                text: prompt_to_send,
+                        // Send prompts one turn at a time. ACP agents handle a single prompt per
+                        // session at once, so steering messages that arrive mid-turn are held and
+                        // sent together as the next prompt in the same session. They are acked once
+                        // that prompt is sent, and fail if the session ends first, so the caller
+                        // can queue them as a follow-up instead.
+                        let mut held: Vec<SteeringMessage> = Vec::new();
+                        let mut next_prompt = Some((prompt_to_send, Vec::new()));
+                        while let Some((text, steering)) = next_prompt.take() {
+                            let req = proto::PromptRequest {
+                                session_id: proto::SessionId(acp_session_id.clone().into()),
+                                prompt: vec![proto::ContentBlock::Text(proto::TextContent {
+                                    annotations: None,
+                                    text,
+                                    meta: None,
+                                })],
                                 meta: None,
-                            })],
-                            meta: None,
-                        };
+                            };
 
-                        // Send the prompt and await completion to obtain stop_reason
-                        match conn.prompt(req).await {
-                            Ok(resp) => {
-                                // Emit done with stop_reason
-                                let stop_reason =
-                                    serde_json::to_string(&resp.stop_reason).unwrap_or_default();
-                                let _ = log_tx.send(AcpEvent::Done(stop_reason).to_string());
-                            }
-                            Err(e) => {
-                                tracing::debug!("error {} {e} {:?}", e.code, e.data);
-                                if e.code == agent_client_protocol::ErrorCode::INTERNAL_ERROR.code
-                                    && e.data
-                                        .as_ref()
-                                        .is_some_and(|d| d == "server shut down unexpectedly")
-                                {
-                                    tracing::debug!("ACP server killed");
-                                } else {
+                            // The request is written to the agent when the future is first polled
+                            let prompt_fut = conn.prompt(req);
+                            tokio::pin!(prompt_fut);
+                            let sent = futures::poll!(&mut prompt_fut);
+                            if let Poll::Ready(Err(e)) = &sent {
+                                for message in steering {
+                                    message.complete(Err(SteeringError::DeliveryFailed(
+                                        e.to_string(),
+                                    )));
+                                }
+                            } else {
+                                for message in steering {
                                     let _ =
-                                        log_tx.send(AcpEvent::Error(format!("{e}")).to_string());
+                                        log_tx.send(SteeringLogLine::new(&message.content).raw());
+                                    let _ = session_manager.append_raw_line(
+                                        &display_session_id,
+                                        &serde_json::to_string(
+                                            &serde_json::json!({ "user": message.content }),
+                                        )
+                                        .unwrap_or_default(),
+                                    );
+                                    message.complete(Ok(()));
                                 }
                             }
+
+                            // Send the prompt and await completion to obtain stop_reason
+                            let result = match sent {
+                                Poll::Ready(result) => result,
+                                Poll::Pending => loop {
+                                    tokio::select! {
+                                        result = &mut prompt_fut => break result,
+                                        Some(message) = steering_rx.recv() => held.push(message),
+                                    }
+                                },
+                            };
+
+                            match result {
+                                Ok(resp) => {
+                                    // Emit done with stop_reason
+                                    let stop_reason = serde_json::to_string(&resp.stop_reason)
+                                        .unwrap_or_default();
+                                    let _ = log_tx.send(AcpEvent::Done(stop_reason).to_string());
+                                }
+                                Err(e) => {
+                                    tracing::debug!("error {} {e} {:?}", e.code, e.data);
+                                    if e.code
+                                        == agent_client_protocol::ErrorCode::INTERNAL_ERROR.code
+                                        && e.data
+                                            .as_ref()
+                                            .is_some_and(|d| d == "server shut down unexpectedly")
+                                    {
+                                        tracing::debug!("ACP server killed");
+                                    } else {
+                                        let _ = log_tx
+                                            .send(AcpEvent::Error(format!("{e}")).to_string());
+                                    }
+                                    break;
+                                }
+                            }
+
+                            if !held.is_empty() {
+                                let steering = std::mem::take(&mut held);
+                                let text = steering
+                                    .iter()
+                                    .map(|message| message.content.as_str())
+                                    .collect::<Vec<_>>()
+                                    .join("\n\n");
+                                next_prompt = Some((text, steering));
+                            }
                         }
+                        // Stop accepting steering messages before the process is torn down, and
+                        // fail the ones that never reached the agent
+                        steering_rx.close();
+                        while let Ok(message) = steering_rx.try_recv() {
+                            held.push(message);
+                        }
+                        for message in held {
+                            message.complete(Err(SteeringError::Closed));
+                        }
+
                         // Notify container of completion
                         if let Some(tx) = exit_signal_tx.take() {
                             let _ = tx.send(());
@@ -5,20 +5,24 @@ use std::{
 };
 
 use agent_client_protocol::{self as acp, SessionNotification};
+use chrono::{DateTime, Utc};
 use futures::StreamExt;
 use lazy_static::lazy_static;
 use regex::Regex;
 use serde::Deserialize;
 use tracing::debug;
-use workspace_utils::msg_store::MsgStore;
+use workspace_utils::msg_store::{MsgStore, TimedOutput};
 
 pub use super::AcpAgentHarness;
 use super::AcpEvent;
-use crate::logs::{
-    ActionType, FileChange, NormalizedEntry, NormalizedEntryType, ToolResult, ToolResultValueType,
-    ToolStatus as LogToolStatus,
-    stderr_processor::normalize_stderr_logs,
-    utils::{ConversationPatch, EntryIndexProvider},
+use crate::{
+    logs::{
+        ActionType, FileChange, NormalizedEntry, NormalizedEntryType, ToolResult,
+        ToolResultValueType, ToolStatus as LogToolStatus,
+        stderr_processor::normalize_stderr_logs,
+        utils::{ConversationPatch, EntryIndexProvider},
+    },
+    steering::SteeringLogLine,
 };
 
 pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
@@ -36,8 +40,19 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
         let mut streaming: StreamingState = StreamingState::default();
         let mut tool_states: ToolStates = HashMap::new();
 
-        let mut stdout_lines = msg_store.stdout_lines_stream();
-        while let Some(Ok(line)) = stdout_lines.next().await {
+        let mut stdout_lines = msg_store.stdout_timed_lines();
+        while let Some(Ok(TimedOutput { at, text: line })) = stdout_lines.next().await {
+            if let Some(steering) = SteeringLogLine::parse(&line) {
+                streaming.assistant_text = None;
+                streaming.thinking_text = None;
+                let idx = entry_index.next();
+                msg_store.push_patch_at(
+                    ConversationPatch::add_normalized_entry(idx, steering.to_normalized_entry()),
+                    at,
+                );
+                continue;
+            }
+
             if let Some(parsed) = AcpEventParser::parse_line(&line) {
                 debug!("Parsed ACP line: {:?}", parsed);
                 match parsed {
@@ -55,7 +70,8 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                             content: msg,
                             metadata: None,
                         };
-                        msg_store.push_patch(ConversationPatch::add_normalized_entry(idx, entry));
+                        msg_store
+                            .push_patch_at(ConversationPatch::add_normalized_entry(idx, entry), at);
                     }
                     AcpEvent::Done(_) => {
                         streaming.assistant_text = None;
@@ -85,7 +101,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                                 } else {
                                     ConversationPatch::replace(s.index, entry)
                                 };
-                                msg_store.push_patch(patch);
+                                msg_store.push_patch_at(patch, at);
                             }
                         }
                     }
@@ -113,7 +129,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                                 } else {
                                     ConversationPatch::replace(s.index, entry)
                                 };
-                                msg_store.push_patch(patch);
+                                msg_store.push_patch_at(patch, at);
                             }
                         }
                     }
@@ -131,7 +147,8 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                             content: body,
                             metadata: None,
                         };
-                        msg_store.push_patch(ConversationPatch::add_normalized_entry(idx, entry));
+                        msg_store
+                            .push_patch_at(ConversationPatch::add_normalized_entry(idx, entry), at);
                     }
                     AcpEvent::AvailableCommands(cmds) => {
                         let mut body = String::from("Available commands:\n");
@@ -145,7 +162,8 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                             content: body,
                             metadata: None,
                         };
-                        msg_store.push_patch(ConversationPatch::add_normalized_entry(idx, entry));
+                        msg_store
+                            .push_patch_at(ConversationPatch::add_normalized_entry(idx, entry), at);
                     }
                     AcpEvent::CurrentMode(mode_id) => {
                         let idx = entry_index.next();
@@ -155,7 +173,8 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                             content: format!("Current mode: {}", mode_id.0),
                             metadata: None,
                         };
-                        msg_store.push_patch(ConversationPatch::add_normalized_entry(idx, entry));
+                        msg_store
+                            .push_patch_at(ConversationPatch::add_normalized_entry(idx, entry), at);
                     }
                     AcpEvent::RequestPermission(perm) => {
                         if let Ok(tc) = agent_client_protocol::ToolCall::try_from(perm.tool_call) {
@@ -166,6 +185,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                                 &mut tool_states,
                                 &entry_index,
                                 &msg_store,
+                                at,
                             );
                         }
                     }
@@ -176,6 +196,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                         &mut tool_states,
                         &entry_index,
                         &msg_store,
+                        at,
                     ),
                     AcpEvent::ToolUpdate(update) => {
                         let mut update = update;
@@ -194,6 +215,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                                 &mut tool_states,
                                 &entry_index,
                                 &msg_store,
+                                at,
                             );
                         } else {
                             debug!("Failed to convert tool call update to ToolCall");
@@ -211,6 +233,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
             tool_states: &mut ToolStates,
             entry_index: &EntryIndexProvider,
             msg_store: &Arc<MsgStore>,
+            at: Option<DateTime<Utc>>,
         ) {
             streaming.assistant_text = None;
             streaming.thinking_text = None;
@@ -237,7 +260,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
             } else {
                 ConversationPatch::replace(tool_data.index, entry)
             };
-            msg_store.push_patch(patch);
+            msg_store.push_patch_at(patch, at);
         }
 
         fn map_to_action_type(tc: &PartialToolCallData) -> ActionType {
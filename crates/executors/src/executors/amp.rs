@@ -1,7 +1,6 @@
 use std::{path::Path, process::Stdio, sync::Arc};
 
 use async_trait::async_trait;
-use command_group::AsyncCommandGroup;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tokio::{io::AsyncWriteExt, process::Command};
@@ -9,7 +8,7 @@ use ts_rs::TS;
 use workspace_utils::{msg_store::MsgStore, shell::get_shell_command};
 
 use crate::{
-    command::{CmdOverrides, CommandBuilder, apply_overrides},
+    command::{CmdOverrides, CommandBuilder, apply_overrides, group_spawn},
     executors::{
         AppendPrompt, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
         claude::{ClaudeLogProcessor, HistoryStrategy},
@@ -58,9 +57,10 @@ impl StandardCodingAgentExecutor for Amp {
             .stderr(Stdio::piped())
             .current_dir(current_dir)
             .arg(shell_arg)
-            .arg(&amp_command);
+            .arg(&amp_command)
+            .envs(&self.cmd.injected_env);
 
-        let mut child = command.group_spawn()?;
+        let mut child = group_spawn(&mut command)?;
 
         // Feed the prompt in, then close the pipe so amp sees EOF
         if let Some(mut stdin) = child.inner().stdin.take() {
@@ -93,6 +93,7 @@ impl StandardCodingAgentExecutor for Amp {
             .current_dir(current_dir)
             .arg(shell_arg)
             .arg(&fork_cmd)
+            .envs(&self.cmd.injected_env)
             .output()
             .await?;
         let stdout_str = String::from_utf8_lossy(&fork_output.stdout);
@@ -128,9 +129,10 @@ impl StandardCodingAgentExecutor for Amp {
             .stderr(Stdio::piped())
             .current_dir(current_dir)
             .arg(shell_arg)
-            .arg(&continue_cmd);
+            .arg(&continue_cmd)
+            .envs(&self.cmd.injected_env);
 
-        let mut child = command.group_spawn()?;
+        let mut child = group_spawn(&mut command)?;
 
         // Feed the prompt in, then close the pipe so amp sees EOF
         if let Some(mut stdin) = child.inner().stdin.take() {
@@ -3,30 +3,34 @@ use std::os::unix::fs::PermissionsExt;
 use std::{collections::HashMap, path::Path, process::Stdio, sync::Arc};
 
 use async_trait::async_trait;
-use command_group::AsyncCommandGroup;
 use futures::StreamExt;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tokio::{io::AsyncWriteExt, process::Command, sync::OnceCell};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader},
+    process::{ChildStdin, ChildStdout, Command},
+    sync::OnceCell,
+};
 use ts_rs::TS;
 use workspace_utils::{
     approvals::APPROVAL_TIMEOUT_SECONDS,
     diff::{concatenate_diff_hunks, create_unified_diff, create_unified_diff_hunk},
-    log_msg::LogMsg,
-    msg_store::MsgStore,
+    msg_store::{MsgStore, TimedOutput},
     path::make_path_relative,
     port_file::read_port_file,
     shell::get_shell_command,
 };
 
 use crate::{
-    command::{CmdOverrides, CommandBuilder, apply_overrides},
+    command::{CmdOverrides, CommandBuilder, apply_overrides, group_spawn},
     executors::{AppendPrompt, ExecutorError, SpawnedChild, StandardCodingAgentExecutor},
     logs::{
         ActionType, FileChange, NormalizedEntry, NormalizedEntryType, TodoItem, ToolStatus,
         stderr_processor::normalize_stderr_logs,
         utils::{EntryIndexProvider, patch::ConversationPatch},
     },
+    stdout_dup::create_stdout_pipe_writer,
+    steering::{self, SteeringError, SteeringLogLine, SteeringReceiver},
 };
 
 static BACKEND_PORT: OnceCell<u16> = OnceCell::const_new();
@@ -119,20 +123,21 @@ impl ClaudeCode {
         }
         builder = builder.extend_params([
             "--verbose",
+            "--input-format=stream-json",
             "--output-format=stream-json",
             "--include-partial-messages",
         ]);
 
         apply_overrides(builder, &self.cmd)
     }
-}
 
-#[async_trait]
-impl StandardCodingAgentExecutor for ClaudeCode {
-    async fn spawn(&self, current_dir: &Path, prompt: &str) -> Result<SpawnedChild, ExecutorError> {
+    async fn spawn_internal(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        mut base_command: String,
+    ) -> Result<SpawnedChild, ExecutorError> {
         let (shell_cmd, shell_arg) = get_shell_command();
-        let command_builder = self.build_command_builder().await;
-        let mut base_command = command_builder.build_initial();
 
         if self.plan.unwrap_or(false) {
             base_command = create_watchkill_script(&base_command);
@@ -152,17 +157,48 @@ impl StandardCodingAgentExecutor for ClaudeCode {
             .stderr(Stdio::piped())
             .current_dir(current_dir)
             .arg(shell_arg)
-            .arg(&base_command);
-
-        let mut child = command.group_spawn()?;
+            .arg(&base_command)
+            .envs(&self.cmd.injected_env);
+
+        let mut child = group_spawn(&mut command)?;
+
+        let mut stdin =
+            child.inner().stdin.take().ok_or_else(|| {
+                ExecutorError::Io(std::io::Error::other("Claude Code missing stdin"))
+            })?;
+        let stdout = child.inner().stdout.take().ok_or_else(|| {
+            ExecutorError::Io(std::io::Error::other("Claude Code missing stdout"))
+        })?;
+        let log_writer = create_stdout_pipe_writer(&mut child)?;
+
+        // Feed the prompt in as the first stream-json message; stdin stays open for steering
+        stdin
+            .write_all(stream_json_user_message(&combined_prompt).as_bytes())
+            .await?;
+        stdin.flush().await?;
+
+        let (steering_tx, steering_rx) = steering::channel();
+        tokio::spawn(run_stream_json_session(
+            stdin,
+            stdout,
+            log_writer,
+            steering_rx,
+        ));
 
-        // Feed the prompt in, then close the pipe so Claude sees EOF
-        if let Some(mut stdin) = child.inner().stdin.take() {
-            stdin.write_all(combined_prompt.as_bytes()).await?;
-            stdin.shutdown().await?;
-        }
+        Ok(SpawnedChild {
+            child,
+            exit_signal: None,
+            steering: Some(steering_tx),
+        })
+    }
+}
 
-        Ok(child.into())
+#[async_trait]
+impl StandardCodingAgentExecutor for ClaudeCode {
+    async fn spawn(&self, current_dir: &Path, prompt: &str) -> Result<SpawnedChild, ExecutorError> {
+        let command_builder = self.build_command_builder().await;
+        let base_command = command_builder.build_initial();
+        self.spawn_internal(current_dir, prompt, base_command).await
     }
 
     async fn spawn_follow_up(
@@ -171,44 +207,14 @@ impl StandardCodingAgentExecutor for ClaudeCode {
         prompt: &str,
         session_id: &str,
     ) -> Result<SpawnedChild, ExecutorError> {
-        let (shell_cmd, shell_arg) = get_shell_command();
         let command_builder = self.build_command_builder().await;
         // Build follow-up command with --resume {session_id}
-        let mut base_command = command_builder.build_follow_up(&[
+        let base_command = command_builder.build_follow_up(&[
             "--fork-session".to_string(),
             "--resume".to_string(),
             session_id.to_string(),
         ]);
-
-        if self.plan.unwrap_or(false) {
-            base_command = create_watchkill_script(&base_command);
-        }
-
-        if self.approvals.unwrap_or(false) || self.plan.unwrap_or(false) {
-            write_python_hook(current_dir).await?
-        }
-
-        let combined_prompt = self.append_prompt.combine_prompt(prompt);
-
-        let mut command = Command::new(shell_cmd);
-        command
-            .kill_on_drop(true)
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .arg(shell_arg)
-            .arg(&base_command);
-
-        let mut child = command.group_spawn()?;
-
-        // Feed the followup prompt in, then close the pipe
-        if let Some(mut stdin) = child.inner().stdin.take() {
-            stdin.write_all(combined_prompt.as_bytes()).await?;
-            stdin.shutdown().await?;
-        }
-
-        Ok(child.into())
+        self.spawn_internal(current_dir, prompt, base_command).await
     }
 
     fn normalize_logs(&self, msg_store: Arc<MsgStore>, current_dir: &Path) {
@@ -291,6 +297,96 @@ async fn settings_json(plan: bool) -> Result<String, std::io::Error> {
     .to_string())
 }
 
+/// A user message in Claude Code's stream-json input format, newline terminated
+fn stream_json_user_message(content: &str) -> String {
+    let mut line = serde_json::json!({
+        "type": "user",
+        "message": {
+            "role": "user",
+            "content": [{ "type": "text", "text": content }],
+        },
+    })
+    .to_string();
+    line.push('\n');
+    line
+}
+
+/// Forward Claude's stdout line by line while writing steering messages to its stdin.
+///
+/// With stream-json input Claude keeps running until stdin is closed, so stdin is closed as soon as
+/// the first `result` arrives. Messages written before that are still processed by Claude; later
+/// ones are rejected so the caller can queue them as a follow-up instead.
+async fn run_stream_json_session(
+    stdin: ChildStdin,
+    stdout: ChildStdout,
+    mut log_writer: impl AsyncWrite + Unpin,
+    mut steering_rx: SteeringReceiver,
+) {
+    let mut stdin = Some(stdin);
+    let mut reader = BufReader::new(stdout);
+    let mut line = Vec::new();
+
+    loop {
+        tokio::select! {
+            read = reader.read_until(b'\n', &mut line) => {
+                match read {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) => {}
+                }
+                if stdin.is_some() && is_result_line(&line) {
+                    steering_rx.close();
+                    while let Ok(message) = steering_rx.try_recv() {
+                        message.complete(Err(SteeringError::Closed));
+                    }
+                    if let Some(mut stdin) = stdin.take() {
+                        let _ = stdin.shutdown().await;
+                    }
+                }
+                if log_writer.write_all(&line).await.is_err() {
+                    break;
+                }
+                let _ = log_writer.flush().await;
+                line.clear();
+            }
+            Some(message) = steering_rx.recv(), if stdin.is_some() => {
+                let Some(writer) = stdin.as_mut() else {
+                    message.complete(Err(SteeringError::Closed));
+                    continue;
+                };
+                let delivered = async {
+                    writer
+                        .write_all(stream_json_user_message(&message.content).as_bytes())
+                        .await?;
+                    writer.flush().await
+                }
+                .await;
+                match delivered {
+                    Ok(()) => {
+                        let mut echo = SteeringLogLine::new(&message.content).raw();
+                        echo.push('\n');
+                        let _ = log_writer.write_all(echo.as_bytes()).await;
+                        message.complete(Ok(()));
+                    }
+                    Err(e) => message.complete(Err(SteeringError::DeliveryFailed(e.to_string()))),
+                }
+            }
+        }
+    }
+
+    if !line.is_empty() {
+        let _ = log_writer.write_all(&line).await;
+    }
+    let _ = log_writer.flush().await;
+    if let Some(mut stdin) = stdin.take() {
+        let _ = stdin.shutdown().await;
+    }
+}
+
+fn is_result_line(line: &[u8]) -> bool {
+    serde_json::from_slice::<serde_json::Value>(line)
+        .is_ok_and(|value| value.get("type").and_then(|t| t.as_str()) == Some("result"))
+}
+
 fn create_watchkill_script(command: &str) -> String {
     // Hack: we concatenate so that Claude doesn't trigger the watchkill when reading this file
     // during development, since it contains the stop phrase
@@ -388,95 +484,67 @@ impl ClaudeLogProcessor {
     ) {
         let current_dir_clone = current_dir.to_owned();
         tokio::spawn(async move {
-            let mut stream = msg_store.history_plus_stream();
-            let mut buffer = String::new();
+            let mut lines = msg_store.stdout_timed_lines();
             let worktree_path = current_dir_clone.to_string_lossy().to_string();
             let mut session_id_extracted = false;
             let mut processor = Self::new_with_strategy(strategy);
 
-            while let Some(Ok(msg)) = stream.next().await {
-                let chunk = match msg {
-                    LogMsg::Stdout(x) => x,
-                    LogMsg::JsonPatch(_) | LogMsg::SessionId(_) | LogMsg::Stderr(_) => continue,
-                    LogMsg::Finished => break,
-                };
-
-                buffer.push_str(&chunk);
+            while let Some(Ok(TimedOutput { at, text: line })) = lines.next().await {
+                let trimmed = line.trim();
+                if trimmed.is_empty() {
+                    continue;
+                }
 
-                // Process complete JSON lines
-                for line in buffer
-                    .split_inclusive('\n')
-                    .filter(|l| l.ends_with('\n'))
-                    .map(str::to_owned)
-                    .collect::<Vec<_>>()
+                // Filter out claude-code-router service messages
+                if trimmed.starts_with("Service not running, starting service")
+                    || trimmed.contains("claude code router service has been successfully stopped")
                 {
-                    let trimmed = line.trim();
-                    if trimmed.is_empty() {
-                        continue;
-                    }
-
-                    // Filter out claude-code-router service messages
-                    if trimmed.starts_with("Service not running, starting service")
-                        || trimmed
-                            .contains("claude code router service has been successfully stopped")
-                    {
-                        continue;
-                    }
+                    continue;
+                }
 
-                    match serde_json::from_str::<ClaudeJson>(trimmed) {
-                        Ok(claude_json) => {
-                            // Extract session ID if present
-                            if !session_id_extracted
-                                && let Some(session_id) = Self::extract_session_id(&claude_json)
-                            {
-                                msg_store.push_session_id(session_id);
-                                session_id_extracted = true;
-                            }
+                if let Some(steering) = SteeringLogLine::parse(trimmed) {
+                    let patch = ConversationPatch::add_normalized_entry(
+                        entry_index_provider.next(),
+                        steering.to_normalized_entry(),
+                    );
+                    msg_store.push_patch_at(patch, at);
+                    continue;
+                }
 
-                            let patches = processor.normalize_entries(
-                                &claude_json,
-                                &worktree_path,
-                                &entry_index_provider,
-                            );
-                            for patch in patches {
-                                msg_store.push_patch(patch);
-                            }
+                match serde_json::from_str::<ClaudeJson>(trimmed) {
+                    Ok(claude_json) => {
+                        // Extract session ID if present
+                        if !session_id_extracted
+                            && let Some(session_id) = Self::extract_session_id(&claude_json)
+                        {
+                            msg_store.push_session_id(session_id);
+                            session_id_extracted = true;
                         }
-                        Err(_) => {
-                            // Handle non-JSON output as raw system message
-                            if !trimmed.is_empty() {
-                                let entry = NormalizedEntry {
-                                    timestamp: None,
-                                    entry_type: NormalizedEntryType::SystemMessage,
-                                    content: trimmed.to_string(),
-                                    metadata: None,
-                                };
 
-                                let patch_id = entry_index_provider.next();
-                                let patch =
-                                    ConversationPatch::add_normalized_entry(patch_id, entry);
-                                msg_store.push_patch(patch);
-                            }
+                        let patches = processor.normalize_entries(
+                            &claude_json,
+                            &worktree_path,
+                            &entry_index_provider,
+                        );
+                        for patch in patches {
+                            msg_store.push_patch_at(patch, at);
                         }
                     }
-                }
-
-                // Keep the partial line in the buffer
-                buffer = buffer.rsplit('\n').next().unwrap_or("").to_owned();
-            }
-
-            // Handle any remaining content in buffer
-            if !buffer.trim().is_empty() {
-                let entry = NormalizedEntry {
-                    timestamp: None,
-                    entry_type: NormalizedEntryType::SystemMessage,
-                    content: buffer.trim().to_string(),
-                    metadata: None,
-                };
+                    Err(_) => {
+                        // Handle non-JSON output (including an unterminated last line) as a raw
+                        // system message
+                        let entry = NormalizedEntry {
+                            timestamp: None,
+                            entry_type: NormalizedEntryType::SystemMessage,
+                            content: trimmed.to_string(),
+                            metadata: None,
+                        };
 
-                let patch_id = entry_index_provider.next();
-                let patch = ConversationPatch::add_normalized_entry(patch_id, entry);
-                msg_store.push_patch(patch);
+                        let patch_id = entry_index_provider.next();
+                        let patch = ConversationPatch::add_normalized_entry(patch_id, entry);
+                        msg_store.push_patch_at(patch, at);
+                    }
+                }
             }
         });
     }
@@ -2002,10 +2070,7 @@ mod tests {
             model: None,
             append_prompt: AppendPrompt::default(),
             dangerously_skip_permissions: None,
-            cmd: crate::command::CmdOverrides {
-                base_command_override: None,
-                additional_params: None,
-            },
+            cmd: crate::command::CmdOverrides::default(),
         };
         let msg_store = Arc::new(MsgStore::new());
         let current_dir = std::path::PathBuf::from("/tmp/test-worktree");
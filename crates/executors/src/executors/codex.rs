@@ -13,7 +13,6 @@ use codex_app_server_protocol::NewConversationParams;
 use codex_protocol::{
     config_types::SandboxMode as CodexSandboxMode, protocol::AskForApproval as CodexAskForApproval,
 };
-use command_group::AsyncCommandGroup;
 use derivative::Derivative;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -31,12 +30,13 @@ use self::{
 };
 use crate::{
     approvals::ExecutorApprovalService,
-    command::{CmdOverrides, CommandBuilder, apply_overrides},
+    command::{CmdOverrides, CommandBuilder, apply_overrides, group_spawn},
     executors::{
         AppendPrompt, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
         codex::{jsonrpc::ExitSignalSender, normalize_logs::Error},
     },
     stdout_dup::create_stdout_pipe_writer,
+    steering::{self, SteeringError, SteeringLogLine, SteeringReceiver},
 };
 
 /// Sandbox policy modes for Codex
@@ -264,9 +264,10 @@ impl Codex {
             .arg(&command)
             .env("NODE_NO_WARNINGS", "1")
             .env("NO_COLOR", "1")
-            .env("RUST_LOG", "error");
+            .env("RUST_LOG", "error")
+            .envs(&self.cmd.injected_env);
 
-        let mut child = process.group_spawn()?;
+        let mut child = group_spawn(&mut process)?;
 
         let child_stdout = child.inner().stdout.take().ok_or_else(|| {
             ExecutorError::Io(std::io::Error::other("Codex app server missing stdout"))
@@ -285,6 +286,7 @@ impl Codex {
             (Some(SandboxMode::DangerFullAccess), None)
         );
         let approvals = self.approvals.clone();
+        let (steering_tx, steering_rx) = steering::channel();
         tokio::spawn(async move {
             let exit_signal_tx = ExitSignalSender::new(exit_signal_tx);
             let log_writer = LogWriter::new(new_stdout);
@@ -298,6 +300,7 @@ impl Codex {
                 exit_signal_tx.clone(),
                 approvals,
                 auto_approve,
+                steering_rx,
             )
             .await
             {
@@ -318,6 +321,7 @@ impl Codex {
         Ok(SpawnedChild {
             child,
             exit_signal: Some(exit_signal_rx),
+            steering: Some(steering_tx),
         })
     }
 
@@ -332,13 +336,14 @@ impl Codex {
         exit_signal_tx: ExitSignalSender,
         approvals: Option<Arc<dyn ExecutorApprovalService>>,
         auto_approve: bool,
+        mut steering_rx: SteeringReceiver,
     ) -> Result<(), ExecutorError> {
-        let client = AppServerClient::new(log_writer, approvals, auto_approve);
+        let client = AppServerClient::new(log_writer.clone(), approvals, auto_approve);
         let rpc_peer =
             JsonRpcPeer::spawn(child_stdin, child_stdout, client.clone(), exit_signal_tx);
         client.connect(rpc_peer);
         client.initialize().await?;
-        match resume_session {
+        let conversation_id = match resume_session {
             None => {
                 let params = conversation_params;
                 let response = client.new_conversation(params).await?;
@@ -348,6 +353,7 @@ impl Codex {
                 client
                     .send_user_message(conversation_id, combined_prompt)
                     .await?;
+                conversation_id
             }
             Some(session_id) => {
                 let (rollout_path, _forked_session_id) =
@@ -368,6 +374,26 @@ impl Codex {
                 client
                     .send_user_message(conversation_id, combined_prompt)
                     .await?;
+                conversation_id
+            }
+        };
+
+        // Codex queues user messages sent mid-turn into the running task
+        while let Some(message) = steering_rx.recv().await {
+            match client
+                .send_user_message(conversation_id, message.content.clone())
+                .await
+            {
+                Ok(_) => {
+                    if let Err(err) = log_writer
+                        .log_raw(&SteeringLogLine::new(&message.content).raw())
+                        .await
+                    {
+                        tracing::warn!("Failed to record steering message: {err}");
+                    }
+                    message.complete(Ok(()));
+                }
+                Err(err) => message.complete(Err(SteeringError::DeliveryFailed(err.to_string()))),
             }
         }
         Ok(())
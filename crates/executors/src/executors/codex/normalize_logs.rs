@@ -4,6 +4,7 @@ use std::{
     sync::Arc,
 };
 
+use chrono::{DateTime, Utc};
 use codex_app_server_protocol::{
     JSONRPCNotification, JSONRPCResponse, NewConversationResponse, ServerNotification,
 };
@@ -29,7 +30,7 @@ use serde_json::Value;
 use workspace_utils::{
     approvals::ApprovalStatus,
     diff::{concatenate_diff_hunks, extract_unified_diff_hunks},
-    msg_store::MsgStore,
+    msg_store::{MsgStore, TimedOutput},
     path::make_path_relative,
 };
 
@@ -42,6 +43,7 @@ use crate::{
         stderr_processor::normalize_stderr_logs,
         utils::{ConversationPatch, EntryIndexProvider},
     },
+    steering::SteeringLogLine,
 };
 
 trait ToNormalizedEntry {
@@ -315,14 +317,15 @@ fn upsert_normalized_entry(
     index: usize,
     normalized_entry: NormalizedEntry,
     is_new: bool,
+    at: Option<DateTime<Utc>>,
 ) {
     if is_new {
-        msg_store.push_patch(ConversationPatch::add_normalized_entry(
-            index,
-            normalized_entry,
-        ));
+        msg_store.push_patch_at(
+            ConversationPatch::add_normalized_entry(index, normalized_entry),
+            at,
+        );
     } else {
-        msg_store.push_patch(ConversationPatch::replace(index, normalized_entry));
+        msg_store.push_patch_at(ConversationPatch::replace(index, normalized_entry), at);
     }
 }
 
@@ -330,9 +333,10 @@ fn add_normalized_entry(
     msg_store: &Arc<MsgStore>,
     index_provider: &EntryIndexProvider,
     normalized_entry: NormalizedEntry,
+    at: Option<DateTime<Utc>>,
 ) -> usize {
     let index = index_provider.next();
-    upsert_normalized_entry(msg_store, index, normalized_entry, true);
+    upsert_normalized_entry(msg_store, index, normalized_entry, true, at);
     index
 }
 
@@ -340,8 +344,9 @@ fn replace_normalized_entry(
     msg_store: &Arc<MsgStore>,
     index: usize,
     normalized_entry: NormalizedEntry,
+    at: Option<DateTime<Utc>>,
 ) {
-    upsert_normalized_entry(msg_store, index, normalized_entry, false);
+    upsert_normalized_entry(msg_store, index, normalized_entry, false, at);
 }
 
 fn normalize_file_changes(
@@ -398,23 +403,28 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
     let worktree_path_str = worktree_path.to_string_lossy().to_string();
     tokio::spawn(async move {
         let mut state = LogState::new(entry_index.clone());
-        let mut stdout_lines = msg_store.stdout_lines_stream();
+        let mut stdout_lines = msg_store.stdout_timed_lines();
+
+        while let Some(Ok(TimedOutput { at, text: line })) = stdout_lines.next().await {
+            if let Some(steering) = SteeringLogLine::parse(&line) {
+                add_normalized_entry(&msg_store, &entry_index, steering.to_normalized_entry(), at);
+                continue;
+            }
 
-        while let Some(Ok(line)) = stdout_lines.next().await {
             if let Ok(error) = serde_json::from_str::<Error>(&line) {
-                add_normalized_entry(&msg_store, &entry_index, error.to_normalized_entry());
+                add_normalized_entry(&msg_store, &entry_index, error.to_normalized_entry(), at);
                 continue;
             }
 
             if let Ok(approval) = serde_json::from_str::<Approval>(&line) {
                 if let Some(entry) = approval.to_normalized_entry_opt() {
-                    add_normalized_entry(&msg_store, &entry_index, entry);
+                    add_normalized_entry(&msg_store, &entry_index, entry, at);
                 }
                 continue;
             }
 
             if let Ok(response) = serde_json::from_str::<JSONRPCResponse>(&line) {
-                handle_jsonrpc_response(response, &msg_store, &entry_index);
+                handle_jsonrpc_response(response, &msg_store, &entry_index, at);
                 continue;
             }
 
@@ -428,6 +438,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                         session_configured.reasoning_effort,
                         &msg_store,
                         &entry_index,
+                        at,
                     );
                 };
                 continue;
@@ -466,28 +477,29 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                         payload.reasoning_effort,
                         &msg_store,
                         &entry_index,
+                        at,
                     );
                 }
                 EventMsg::AgentMessageDelta(AgentMessageDeltaEvent { delta }) => {
                     state.thinking = None;
                     let (entry, index, is_new) = state.assistant_message_append(delta);
-                    upsert_normalized_entry(&msg_store, index, entry, is_new);
+                    upsert_normalized_entry(&msg_store, index, entry, is_new, at);
                 }
                 EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent { delta }) => {
                     state.assistant = None;
                     let (entry, index, is_new) = state.thinking_append(delta);
-                    upsert_normalized_entry(&msg_store, index, entry, is_new);
+                    upsert_normalized_entry(&msg_store, index, entry, is_new, at);
                 }
                 EventMsg::AgentMessage(AgentMessageEvent { message }) => {
                     state.thinking = None;
                     let (entry, index, is_new) = state.assistant_message(message);
-                    upsert_normalized_entry(&msg_store, index, entry, is_new);
+                    upsert_normalized_entry(&msg_store, index, entry, is_new, at);
                     state.assistant = None;
                 }
                 EventMsg::AgentReasoning(AgentReasoningEvent { text }) => {
                     state.assistant = None;
                     let (entry, index, is_new) = state.thinking(text);
-                    upsert_normalized_entry(&msg_store, index, entry, is_new);
+                    upsert_normalized_entry(&msg_store, index, entry, is_new, at);
                     state.thinking = None;
                 }
                 EventMsg::AgentReasoningSectionBreak(AgentReasoningSectionBreakEvent {}) => {
@@ -522,12 +534,14 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                             &msg_store,
                             index,
                             command_state.to_normalized_entry(),
+                            at,
                         );
                     } else {
                         let index = add_normalized_entry(
                             &msg_store,
                             &entry_index,
                             command_state.to_normalized_entry(),
+                            at,
                         );
                         command_state.index = Some(index);
                     }
@@ -546,7 +560,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
 
                     for entry in patch_state.entries.drain(..) {
                         if let Some(index) = entry.index {
-                            msg_store.push_patch(ConversationPatch::remove(index));
+                            msg_store.push_patch_at(ConversationPatch::remove(index), at);
                         }
                     }
 
@@ -563,6 +577,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                             &msg_store,
                             &entry_index,
                             entry.to_normalized_entry(),
+                            at,
                         );
                         entry.index = Some(index);
                         patch_state.entries.push(entry);
@@ -596,6 +611,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                         &msg_store,
                         &entry_index,
                         command_state.to_normalized_entry(),
+                        at,
                     );
                     command_state.index = Some(index)
                 }
@@ -621,6 +637,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                             &msg_store,
                             index,
                             command_state.to_normalized_entry(),
+                            at,
                         );
                     }
                 }
@@ -650,6 +667,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                             &msg_store,
                             index,
                             command_state.to_normalized_entry(),
+                            at,
                         );
                     }
                 }
@@ -663,6 +681,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                             content: format!("Background event: {message}"),
                             metadata: None,
                         },
+                        at,
                     );
                 }
                 EventMsg::StreamError(StreamErrorEvent { message }) => {
@@ -675,6 +694,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                             content: format!("Stream error: {message}"),
                             metadata: None,
                         },
+                        at,
                     );
                 }
                 EventMsg::McpToolCallBegin(McpToolCallBeginEvent {
@@ -697,6 +717,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                         &msg_store,
                         &entry_index,
                         mcp_tool_state.to_normalized_entry(),
+                        at,
                     );
                     mcp_tool_state.index = Some(index);
                 }
@@ -760,6 +781,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                             &msg_store,
                             index,
                             mcp_tool_state.to_normalized_entry(),
+                            at,
                         );
                     }
                 }
@@ -783,12 +805,14 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                                     &msg_store,
                                     index,
                                     entry.to_normalized_entry(),
+                                    at,
                                 );
                             } else {
                                 let index = add_normalized_entry(
                                     &msg_store,
                                     &entry_index,
                                     entry.to_normalized_entry(),
+                                    at,
                                 );
                                 entry.index = Some(index);
                             }
@@ -806,6 +830,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                                 &msg_store,
                                 &entry_index,
                                 entry.to_normalized_entry(),
+                                at,
                             );
                             entry.index = Some(index);
                             patch_state.entries.push(entry);
@@ -826,6 +851,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                                 &msg_store,
                                 &entry_index,
                                 patch_entry.to_normalized_entry(),
+                                at,
                             );
                             patch_entry.index = Some(index);
                         }
@@ -855,6 +881,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                                 &msg_store,
                                 index,
                                 entry.to_normalized_entry(),
+                                at,
                             );
                         }
                     }
@@ -867,7 +894,8 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                         .insert(call_id.clone(), WebSearchState::new());
                     let web_search_state = state.web_searches.get_mut(&call_id).unwrap();
                     let normalized_entry = web_search_state.to_normalized_entry();
-                    let index = add_normalized_entry(&msg_store, &entry_index, normalized_entry);
+                    let index =
+                        add_normalized_entry(&msg_store, &entry_index, normalized_entry, at);
                     web_search_state.index = Some(index);
                 }
                 EventMsg::WebSearchEnd(WebSearchEndEvent { call_id, query }) => {
@@ -881,7 +909,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                             tracing::error!("missing entry index for existing websearch entry");
                             continue;
                         };
-                        replace_normalized_entry(&msg_store, index, normalized_entry);
+                        replace_normalized_entry(&msg_store, index, normalized_entry, at);
                     }
                 }
                 EventMsg::ViewImageToolCall(ViewImageToolCallEvent { call_id: _, path }) => {
@@ -904,6 +932,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                             content: format!("`{relative_path}`"),
                             metadata: None,
                         },
+                        at,
                     );
                 }
                 EventMsg::PlanUpdate(UpdatePlanArgs { plan, explanation }) => {
@@ -944,6 +973,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                             content,
                             metadata: None,
                         },
+                        at,
                     );
                 }
                 EventMsg::Error(ErrorEvent { message }) => {
@@ -956,6 +986,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                             content: message,
                             metadata: None,
                         },
+                        at,
                     );
                 }
                 EventMsg::TokenCount(payload) => {
@@ -986,6 +1017,7 @@ fn handle_jsonrpc_response(
     response: JSONRPCResponse,
     msg_store: &Arc<MsgStore>,
     entry_index: &EntryIndexProvider,
+    at: Option<DateTime<Utc>>,
 ) {
     let Ok(response) = serde_json::from_value::<NewConversationResponse>(response.result.clone())
     else {
@@ -1002,6 +1034,7 @@ fn handle_jsonrpc_response(
         response.reasoning_effort,
         msg_store,
         entry_index,
+        at,
     );
 }
 
@@ -1010,6 +1043,7 @@ fn handle_model_params(
     reasoning_effort: Option<ReasoningEffort>,
     msg_store: &Arc<MsgStore>,
     entry_index: &EntryIndexProvider,
+    at: Option<DateTime<Utc>>,
 ) {
     let mut params = vec![];
     params.push(format!("model: {model}"));
@@ -1026,6 +1060,7 @@ fn handle_model_params(
             content: params.join("  ").to_string(),
             metadata: None,
         },
+        at,
     );
 }
 
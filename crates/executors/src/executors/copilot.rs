@@ -6,7 +6,6 @@ use std::{
 };
 
 use async_trait::async_trait;
-use command_group::AsyncCommandGroup;
 use futures::StreamExt;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -19,11 +18,13 @@ use tokio::{
 use ts_rs::TS;
 use uuid::Uuid;
 use workspace_utils::{
-    msg_store::MsgStore, path::get_vibe_kanban_temp_dir, shell::get_shell_command,
+    msg_store::{MsgStore, TimedOutput},
+    path::get_vibe_kanban_temp_dir,
+    shell::get_shell_command,
 };
 
 use crate::{
-    command::{CmdOverrides, CommandBuilder, apply_overrides},
+    command::{CmdOverrides, CommandBuilder, apply_overrides, group_spawn},
     executors::{AppendPrompt, ExecutorError, SpawnedChild, StandardCodingAgentExecutor},
     logs::{
         NormalizedEntry, NormalizedEntryType, plain_text_processor::PlainTextLogProcessor,
@@ -114,9 +115,10 @@ impl StandardCodingAgentExecutor for Copilot {
             .current_dir(current_dir)
             .arg(shell_arg)
             .arg(copilot_command)
-            .env("NODE_NO_WARNINGS", "1");
+            .env("NODE_NO_WARNINGS", "1")
+            .envs(&self.cmd.injected_env);
 
-        let mut child = command.group_spawn()?;
+        let mut child = group_spawn(&mut command)?;
 
         // Write prompt to stdin
         if let Some(mut stdin) = child.inner().stdin.take() {
@@ -154,9 +156,10 @@ impl StandardCodingAgentExecutor for Copilot {
             .current_dir(current_dir)
             .arg(shell_arg)
             .arg(copilot_command)
-            .env("NODE_NO_WARNINGS", "1");
+            .env("NODE_NO_WARNINGS", "1")
+            .envs(&self.cmd.injected_env);
 
-        let mut child = command.group_spawn()?;
+        let mut child = group_spawn(&mut command)?;
 
         // Write comprehensive prompt to stdin
         if let Some(mut stdin) = child.inner().stdin.take() {
@@ -179,18 +182,18 @@ impl StandardCodingAgentExecutor for Copilot {
 
         // Normalize Agent logs
         tokio::spawn(async move {
-            let mut stdout_lines = msg_store.stdout_lines_stream();
+            let mut stdout_lines = msg_store.stdout_timed_lines();
 
             let mut processor = Self::create_simple_stdout_normalizer(entry_index_counter);
 
-            while let Some(Ok(line)) = stdout_lines.next().await {
+            while let Some(Ok(TimedOutput { at, text: line })) = stdout_lines.next().await {
                 if let Some(session_id) = line.strip_prefix(Self::SESSION_PREFIX) {
                     msg_store.push_session_id(session_id.trim().to_string());
                     continue;
                 }
 
                 for patch in processor.process(line + "\n") {
-                    msg_store.push_patch(patch);
+                    msg_store.push_patch_at(patch, at);
                 }
             }
         });
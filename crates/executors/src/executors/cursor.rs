@@ -2,7 +2,6 @@ use core::str;
 use std::{collections::HashMap, path::Path, process::Stdio, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
-use command_group::AsyncCommandGroup;
 use futures::StreamExt;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -13,13 +12,13 @@ use workspace_utils::{
         concatenate_diff_hunks, create_unified_diff, create_unified_diff_hunk,
         extract_unified_diff_hunks,
     },
-    msg_store::MsgStore,
+    msg_store::{MsgStore, TimedOutput},
     path::make_path_relative,
     shell::{get_shell_command, resolve_executable_path},
 };
 
 use crate::{
-    command::{CmdOverrides, CommandBuilder, apply_overrides},
+    command::{CmdOverrides, CommandBuilder, apply_overrides, group_spawn},
     executors::{AppendPrompt, ExecutorError, SpawnedChild, StandardCodingAgentExecutor},
     logs::{
         ActionType, FileChange, NormalizedEntry, NormalizedEntryType, TodoItem, ToolStatus,
@@ -80,9 +79,10 @@ impl StandardCodingAgentExecutor for CursorAgent {
             .stderr(Stdio::piped())
             .current_dir(current_dir)
             .arg(shell_arg)
-            .arg(&agent_cmd);
+            .arg(&agent_cmd)
+            .envs(&self.cmd.injected_env);
 
-        let mut child = command.group_spawn()?;
+        let mut child = group_spawn(&mut command)?;
 
         if let Some(mut stdin) = child.inner().stdin.take() {
             stdin.write_all(combined_prompt.as_bytes()).await?;
@@ -115,9 +115,10 @@ impl StandardCodingAgentExecutor for CursorAgent {
             .stderr(Stdio::piped())
             .current_dir(current_dir)
             .arg(shell_arg)
-            .arg(&agent_cmd);
+            .arg(&agent_cmd)
+            .envs(&self.cmd.injected_env);
 
-        let mut child = command.group_spawn()?;
+        let mut child = group_spawn(&mut command)?;
 
         if let Some(mut stdin) = child.inner().stdin.take() {
             stdin.write_all(combined_prompt.as_bytes()).await?;
@@ -135,7 +136,7 @@ impl StandardCodingAgentExecutor for CursorAgent {
         // Process Cursor stdout JSONL with typed serde models
         let current_dir = worktree_path.to_path_buf();
         tokio::spawn(async move {
-            let mut lines = msg_store.stdout_lines_stream();
+            let mut lines = msg_store.stdout_timed_lines();
 
             // Cursor agent doesn't use STDERR. Everything comes through STDOUT, both JSONL and raw error output.
             let mut error_plaintext_processor = PlainTextLogProcessor::builder()
@@ -164,7 +165,7 @@ impl StandardCodingAgentExecutor for CursorAgent {
             // Track tool call_id -> entry index
             let mut call_index_map: HashMap<String, usize> = HashMap::new();
 
-            while let Some(Ok(line)) = lines.next().await {
+            while let Some(Ok(TimedOutput { at, text: line })) = lines.next().await {
                 // Parse line as CursorJson
                 let cursor_json: CursorJson = match serde_json::from_str(&line) {
                     Ok(cursor_json) => cursor_json,
@@ -184,7 +185,7 @@ impl StandardCodingAgentExecutor for CursorAgent {
                         };
 
                         for patch in error_plaintext_processor.process(line + "\n") {
-                            msg_store.push_patch(patch);
+                            msg_store.push_patch_at(patch, at);
                         }
                         continue;
                     }
@@ -218,8 +219,10 @@ impl StandardCodingAgentExecutor for CursorAgent {
                                 metadata: None,
                             };
                             let id = entry_index_provider.next();
-                            msg_store
-                                .push_patch(ConversationPatch::add_normalized_entry(id, entry));
+                            msg_store.push_patch_at(
+                                ConversationPatch::add_normalized_entry(id, entry),
+                                at,
+                            );
                             model_reported = true;
                         }
                     }
@@ -236,14 +239,17 @@ impl StandardCodingAgentExecutor for CursorAgent {
                                 metadata: None,
                             };
                             if let Some(id) = current_assistant_message_index {
-                                msg_store.push_patch(ConversationPatch::replace(id, replace_entry))
+                                msg_store.push_patch_at(
+                                    ConversationPatch::replace(id, replace_entry),
+                                    at,
+                                )
                             } else {
                                 let id = entry_index_provider.next();
                                 current_assistant_message_index = Some(id);
-                                msg_store.push_patch(ConversationPatch::add_normalized_entry(
-                                    id,
-                                    replace_entry,
-                                ));
+                                msg_store.push_patch_at(
+                                    ConversationPatch::add_normalized_entry(id, replace_entry),
+                                    at,
+                                );
                             };
                         }
                     }
@@ -259,12 +265,14 @@ impl StandardCodingAgentExecutor for CursorAgent {
                                 metadata: None,
                             };
                             if let Some(id) = current_thinking_message_index {
-                                msg_store.push_patch(ConversationPatch::replace(id, entry));
+                                msg_store.push_patch_at(ConversationPatch::replace(id, entry), at);
                             } else {
                                 let id = entry_index_provider.next();
                                 current_thinking_message_index = Some(id);
-                                msg_store
-                                    .push_patch(ConversationPatch::add_normalized_entry(id, entry));
+                                msg_store.push_patch_at(
+                                    ConversationPatch::add_normalized_entry(id, entry),
+                                    at,
+                                );
                             }
                         }
                     }
@@ -299,8 +307,10 @@ impl StandardCodingAgentExecutor for CursorAgent {
                             if let Some(cid) = call_id.as_ref() {
                                 call_index_map.insert(cid.clone(), id);
                             }
-                            msg_store
-                                .push_patch(ConversationPatch::add_normalized_entry(id, entry));
+                            msg_store.push_patch_at(
+                                ConversationPatch::add_normalized_entry(id, entry),
+                                at,
+                            );
                         } else if subtype
                             .as_deref()
                             .map(|s| s.eq_ignore_ascii_case("completed"))
@@ -416,7 +426,7 @@ impl StandardCodingAgentExecutor for CursorAgent {
                                 content: content_str,
                                 metadata: None,
                             };
-                            msg_store.push_patch(ConversationPatch::replace(idx, entry));
+                            msg_store.push_patch_at(ConversationPatch::replace(idx, entry), at);
                         }
                     }
 
@@ -432,7 +442,8 @@ impl StandardCodingAgentExecutor for CursorAgent {
                             metadata: None,
                         };
                         let id = entry_index_provider.next();
-                        msg_store.push_patch(ConversationPatch::add_normalized_entry(id, entry));
+                        msg_store
+                            .push_patch_at(ConversationPatch::add_normalized_entry(id, entry), at);
                     }
                 }
             }
@@ -63,7 +63,7 @@ impl Gemini {
 #[async_trait]
 impl StandardCodingAgentExecutor for Gemini {
     async fn spawn(&self, current_dir: &Path, prompt: &str) -> Result<SpawnedChild, ExecutorError> {
-        let harness = AcpAgentHarness::new();
+        let harness = AcpAgentHarness::new().with_env(self.cmd.injected_env.clone());
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
         let gemini_command = self.build_command_builder().build_initial();
         harness
@@ -77,7 +77,7 @@ impl StandardCodingAgentExecutor for Gemini {
         prompt: &str,
         session_id: &str,
     ) -> Result<SpawnedChild, ExecutorError> {
-        let harness = AcpAgentHarness::new();
+        let harness = AcpAgentHarness::new().with_env(self.cmd.injected_env.clone());
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
         let gemini_command = self.build_command_builder().build_follow_up(&[]);
         harness
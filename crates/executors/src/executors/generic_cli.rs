@@ -0,0 +1,910 @@
+//! Config-driven executor for arbitrary command line agents.
+//!
+//! The command, how the prompt is handed over, how stdout is mapped onto normalized entries and how
+//! sessions are resumed are all declared in the profile, so an agent can be integrated through
+//! `profiles.json` alone instead of writing a dedicated executor.
+
+use std::{collections::HashMap, path::Path, process::Stdio, sync::Arc};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use json_patch::Patch;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::{io::AsyncWriteExt, process::Command};
+use ts_rs::TS;
+use workspace_utils::{
+    msg_store::{MsgStore, TimedOutput},
+    path::make_path_relative,
+    shell::{get_shell_command, resolve_executable_path},
+};
+
+use crate::{
+    command::{CmdOverrides, CommandBuilder, apply_overrides, group_spawn},
+    executors::{AppendPrompt, ExecutorError, SpawnedChild, StandardCodingAgentExecutor},
+    logs::{
+        ActionType, CommandExitStatus, CommandRunResult, FileChange, NormalizedEntry,
+        NormalizedEntryType, ToolResult, ToolResultValueType, ToolStatus,
+        plain_text_processor::PlainTextLogProcessor,
+        stderr_processor::normalize_stderr_logs,
+        utils::{ConversationPatch, EntryIndexProvider},
+    },
+    mcp_config::configured_mcp_config_path,
+};
+
+/// Placeholder in `args` replaced by the prompt when it is passed as an argument
+const PROMPT_PLACEHOLDER: &str = "{prompt}";
+/// Placeholder in `resume_args` replaced by the session id of the previous run
+const SESSION_ID_PLACEHOLDER: &str = "{session_id}";
+
+lazy_static! {
+    static ref TEMPLATE_FIELD: Regex = Regex::new(r"\{([^{}]+)\}").unwrap();
+}
+
+/// How the prompt is handed to the agent
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptInput {
+    /// Written to stdin, which is then closed
+    #[default]
+    Stdin,
+    /// Substituted for `{prompt}` in the arguments, or appended as the last argument
+    Argument,
+}
+
+/// Kind of normalized entry produced by a matching rule
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MappedEntryType {
+    UserMessage,
+    AssistantMessage,
+    Thinking,
+    SystemMessage,
+    ErrorMessage,
+    /// A tool invocation, described by `tool_name`, `action` and `target`
+    ToolUse,
+    /// Completes an earlier `tool_use` with the same `tool_call_id`
+    ToolResult,
+}
+
+/// Action shown for a `tool_use` entry
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MappedAction {
+    FileRead,
+    FileEdit,
+    CommandRun,
+    Search,
+    WebFetch,
+    #[default]
+    Tool,
+    Other,
+}
+
+/// Describes how a matched line becomes a normalized entry.
+///
+/// String fields are templates: `{field}` is replaced with the value at a dotted JSON path
+/// (`json_lines`) or with a capture group, by name or number (`regex`). Text outside braces is kept
+/// as-is, so `"bash"` is a literal and `"{tool.name}"` a lookup.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+pub struct EntryMapping {
+    pub entry_type: MappedEntryType,
+    /// Entry content; defaults to the whole line
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<MappedAction>,
+    /// Path, command, query or URL depending on `action`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// Unified diff for `file_edit` actions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unified_diff: Option<String>,
+    /// Correlates `tool_use` and `tool_result` entries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+    /// For `tool_result`: renders to `true`/`1` when the tool failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failed: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+pub struct JsonLineRule {
+    /// Dotted JSON paths that must equal the given values for the rule to apply
+    #[serde(default)]
+    pub when: HashMap<String, Value>,
+    #[serde(flatten)]
+    pub mapping: EntryMapping,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+pub struct RegexRule {
+    pub pattern: String,
+    #[serde(flatten)]
+    pub mapping: EntryMapping,
+}
+
+/// How stdout is turned into normalized entries. The first matching rule wins.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema, Default)]
+#[serde(tag = "format", rename_all = "snake_case")]
+pub enum OutputParser {
+    /// Every line becomes part of an assistant message
+    #[default]
+    PlainText,
+    JsonLines {
+        rules: Vec<JsonLineRule>,
+    },
+    Regex {
+        rules: Vec<RegexRule>,
+    },
+}
+
+/// What to do with output lines that no rule matches
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UnmatchedLines {
+    #[default]
+    Drop,
+    AssistantMessage,
+    SystemMessage,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionIdExtractor {
+    /// Dotted path into JSON output lines
+    JsonPath(String),
+    /// Regex applied to each output line; uses the `session_id` group, or the first group
+    Regex(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+pub struct SessionResume {
+    pub extractor: SessionIdExtractor,
+    /// Arguments added for follow-ups, with `{session_id}` replaced by the captured id
+    pub resume_args: Vec<String>,
+}
+
+/// Generic executor for command line agents that don't have a dedicated integration.
+/// The command, prompt delivery, output parsing and session handling all come from the profile.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+pub struct GenericCli {
+    #[serde(default)]
+    pub append_prompt: AppendPrompt,
+    #[schemars(title = "Command", description = "Command that runs the agent")]
+    pub command: String,
+    #[schemars(
+        title = "Arguments",
+        description = "Arguments passed to the command; {prompt} is replaced by the prompt when prompt_input is argument"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub args: Option<Vec<String>>,
+    #[schemars(
+        title = "Environment",
+        description = "Extra environment variables for the agent process"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<HashMap<String, String>>,
+    #[schemars(
+        title = "Prompt Input",
+        description = "Pass the prompt on stdin or as an argument"
+    )]
+    #[serde(default)]
+    pub prompt_input: PromptInput,
+    #[schemars(
+        title = "Output Parser",
+        description = "How stdout is mapped to conversation entries"
+    )]
+    #[serde(default)]
+    pub output: OutputParser,
+    #[schemars(
+        title = "Unmatched Lines",
+        description = "What to do with output lines no rule matches"
+    )]
+    #[serde(default)]
+    pub unmatched_lines: UnmatchedLines,
+    #[schemars(
+        title = "Session Resume",
+        description = "How to capture a session id and resume it for follow-ups"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session: Option<SessionResume>,
+    #[schemars(
+        title = "MCP Config Path",
+        description = "Path to the agent's MCP configuration file (JSON with an mcpServers object)"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mcp_config_path: Option<String>,
+    #[serde(flatten)]
+    pub cmd: CmdOverrides,
+}
+
+impl GenericCli {
+    fn build_command(
+        &self,
+        prompt: &str,
+        session_id: Option<&str>,
+    ) -> Result<String, ExecutorError> {
+        let mut params = self.args.clone().unwrap_or_default();
+
+        if let (Some(session), Some(session_id)) = (&self.session, session_id) {
+            let session_id = quote_arg(session_id)?;
+            params.extend(
+                session
+                    .resume_args
+                    .iter()
+                    .map(|arg| arg.replace(SESSION_ID_PLACEHOLDER, &session_id)),
+            );
+        }
+
+        if self.prompt_input == PromptInput::Argument {
+            let prompt = quote_arg(prompt)?;
+            if params.iter().any(|p| p.contains(PROMPT_PLACEHOLDER)) {
+                params = params
+                    .into_iter()
+                    .map(|p| p.replace(PROMPT_PLACEHOLDER, &prompt))
+                    .collect();
+            } else {
+                params.push(prompt);
+            }
+        }
+
+        let builder = CommandBuilder::new(self.command.clone()).params(params);
+        Ok(apply_overrides(builder, &self.cmd).build_initial())
+    }
+
+    async fn spawn_command(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: Option<&str>,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let (shell_cmd, shell_arg) = get_shell_command();
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        let cli_command = self.build_command(&combined_prompt, session_id)?;
+
+        let mut command = Command::new(shell_cmd);
+        command
+            .kill_on_drop(true)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .current_dir(current_dir)
+            .arg(shell_arg)
+            .arg(&cli_command)
+            .envs(&self.cmd.injected_env)
+            .envs(self.env.clone().unwrap_or_default());
+
+        let mut child = group_spawn(&mut command)?;
+
+        // Close stdin either way so the agent never waits for more input
+        if let Some(mut stdin) = child.inner().stdin.take() {
+            if self.prompt_input == PromptInput::Stdin {
+                stdin.write_all(combined_prompt.as_bytes()).await?;
+            }
+            stdin.shutdown().await?;
+        }
+
+        Ok(child.into())
+    }
+}
+
+fn quote_arg(arg: &str) -> Result<String, ExecutorError> {
+    shlex::try_quote(arg)
+        .map(|quoted| quoted.into_owned())
+        .map_err(|e| ExecutorError::Io(std::io::Error::other(format!("Invalid argument: {e}"))))
+}
+
+#[async_trait]
+impl StandardCodingAgentExecutor for GenericCli {
+    async fn spawn(&self, current_dir: &Path, prompt: &str) -> Result<SpawnedChild, ExecutorError> {
+        self.spawn_command(current_dir, prompt, None).await
+    }
+
+    async fn spawn_follow_up(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: &str,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        if self.session.is_none() {
+            return Err(ExecutorError::FollowUpNotSupported(
+                "no session resume configured for this agent".to_string(),
+            ));
+        }
+        self.spawn_command(current_dir, prompt, Some(session_id))
+            .await
+    }
+
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
+        let entry_index_provider = EntryIndexProvider::start_from(&msg_store);
+        normalize_stderr_logs(msg_store.clone(), entry_index_provider.clone());
+
+        let mut processor = GenericCliLogProcessor::new(self, worktree_path);
+        let mut plain_text = matches!(self.output, OutputParser::PlainText).then(|| {
+            PlainTextLogProcessor::builder()
+                .normalized_entry_producer(Box::new(|content: String| NormalizedEntry {
+                    timestamp: None,
+                    entry_type: NormalizedEntryType::AssistantMessage,
+                    content,
+                    metadata: None,
+                }))
+                .transform_lines(Box::new(|lines| {
+                    lines.iter_mut().for_each(|line| {
+                        *line = strip_ansi_escapes::strip_str(&line);
+                    })
+                }))
+                .index_provider(entry_index_provider.clone())
+                .build()
+        });
+
+        tokio::spawn(async move {
+            let mut stdout_lines = msg_store.stdout_timed_lines();
+            let mut session_id_pushed = false;
+
+            while let Some(Ok(TimedOutput { at, text: line })) = stdout_lines.next().await {
+                if !session_id_pushed && let Some(session_id) = processor.extract_session_id(&line)
+                {
+                    msg_store.push_session_id(session_id);
+                    session_id_pushed = true;
+                }
+
+                let patches = match plain_text.as_mut() {
+                    Some(plain_text) => plain_text.process(line + "\n"),
+                    None => processor.process_line(&line, &entry_index_provider),
+                };
+                for patch in patches {
+                    msg_store.push_patch_at(patch, at);
+                }
+            }
+        });
+    }
+
+    fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
+        configured_mcp_config_path(self.mcp_config_path.as_deref())
+    }
+
+    async fn check_availability(&self) -> bool {
+        self.cmd
+            .program(&self.command)
+            .is_some_and(|program| resolve_executable_path(&program).is_some())
+    }
+}
+
+enum CompiledRule {
+    Json(JsonLineRule),
+    Regex(Regex, EntryMapping),
+}
+
+/// Applies the declarative output mapping of a [`GenericCli`] profile to stdout lines.
+struct GenericCliLogProcessor {
+    rules: Vec<CompiledRule>,
+    unmatched_lines: UnmatchedLines,
+    session_extractor: Option<SessionExtractor>,
+    worktree_path: String,
+    /// Open tool calls by `tool_call_id`, completed by `tool_result` rules
+    tool_calls: HashMap<String, (usize, NormalizedEntry)>,
+}
+
+enum SessionExtractor {
+    JsonPath(String),
+    Regex(Regex),
+}
+
+impl GenericCliLogProcessor {
+    fn new(config: &GenericCli, worktree_path: &Path) -> Self {
+        let rules = match &config.output {
+            OutputParser::PlainText => vec![],
+            OutputParser::JsonLines { rules } => {
+                rules.iter().cloned().map(CompiledRule::Json).collect()
+            }
+            OutputParser::Regex { rules } => rules
+                .iter()
+                .filter_map(|rule| match Regex::new(&rule.pattern) {
+                    Ok(regex) => Some(CompiledRule::Regex(regex, rule.mapping.clone())),
+                    Err(e) => {
+                        tracing::warn!("Ignoring invalid output rule '{}': {}", rule.pattern, e);
+                        None
+                    }
+                })
+                .collect(),
+        };
+
+        let session_extractor =
+            config
+                .session
+                .as_ref()
+                .and_then(|session| match &session.extractor {
+                    SessionIdExtractor::JsonPath(path) => {
+                        Some(SessionExtractor::JsonPath(path.clone()))
+                    }
+                    SessionIdExtractor::Regex(pattern) => match Regex::new(pattern) {
+                        Ok(regex) => Some(SessionExtractor::Regex(regex)),
+                        Err(e) => {
+                            tracing::warn!(
+                                "Ignoring invalid session id regex '{}': {}",
+                                pattern,
+                                e
+                            );
+                            None
+                        }
+                    },
+                });
+
+        Self {
+            rules,
+            unmatched_lines: config.unmatched_lines,
+            session_extractor,
+            worktree_path: worktree_path.to_string_lossy().to_string(),
+            tool_calls: HashMap::new(),
+        }
+    }
+
+    fn extract_session_id(&self, line: &str) -> Option<String> {
+        let session_id = match self.session_extractor.as_ref()? {
+            SessionExtractor::JsonPath(path) => {
+                let value = serde_json::from_str::<Value>(line.trim()).ok()?;
+                json_path(&value, path).and_then(value_to_string)
+            }
+            SessionExtractor::Regex(regex) => {
+                let caps = regex.captures(line)?;
+                caps.name("session_id")
+                    .or_else(|| caps.get(1))
+                    .map(|m| m.as_str().to_string())
+            }
+        }?;
+        let session_id = session_id.trim().to_string();
+        (!session_id.is_empty()).then_some(session_id)
+    }
+
+    fn process_line(&mut self, line: &str, entry_index: &EntryIndexProvider) -> Vec<Patch> {
+        let line = strip_ansi_escapes::strip_str(line);
+        if line.trim().is_empty() {
+            return vec![];
+        }
+
+        let json = serde_json::from_str::<Value>(line.trim()).ok();
+        let mut matched = None;
+        for rule in &self.rules {
+            match rule {
+                CompiledRule::Json(rule) => {
+                    let Some(value) = json.as_ref() else {
+                        continue;
+                    };
+                    let applies = rule
+                        .when
+                        .iter()
+                        .all(|(path, expected)| json_path(value, path) == Some(expected));
+                    if applies {
+                        let lookup =
+                            |field: &str| json_path(value, field).and_then(value_to_string);
+                        matched = Some(self.render_mapping(&rule.mapping, &line, &lookup));
+                        break;
+                    }
+                }
+                CompiledRule::Regex(regex, mapping) => {
+                    if let Some(caps) = regex.captures(&line) {
+                        let lookup = |field: &str| capture(&caps, field);
+                        matched = Some(self.render_mapping(mapping, &line, &lookup));
+                        break;
+                    }
+                }
+            }
+        }
+
+        match matched {
+            Some(rendered) => self.apply(rendered, entry_index),
+            None => {
+                let entry_type = match self.unmatched_lines {
+                    UnmatchedLines::Drop => return vec![],
+                    UnmatchedLines::AssistantMessage => NormalizedEntryType::AssistantMessage,
+                    UnmatchedLines::SystemMessage => NormalizedEntryType::SystemMessage,
+                };
+                let entry = NormalizedEntry {
+                    timestamp: None,
+                    entry_type,
+                    content: line,
+                    metadata: None,
+                };
+                vec![ConversationPatch::add_normalized_entry(
+                    entry_index.next(),
+                    entry,
+                )]
+            }
+        }
+    }
+
+    fn render_mapping(
+        &self,
+        mapping: &EntryMapping,
+        line: &str,
+        lookup: &dyn Fn(&str) -> Option<String>,
+    ) -> RenderedEntry {
+        let render = |template: &Option<String>| {
+            template
+                .as_deref()
+                .map(|template| render_template(template, lookup))
+                .filter(|rendered| !rendered.is_empty())
+        };
+
+        RenderedEntry {
+            entry_type: mapping.entry_type,
+            content: render(&mapping.content).unwrap_or_else(|| line.to_string()),
+            tool_name: render(&mapping.tool_name),
+            action: mapping.action.unwrap_or_default(),
+            target: render(&mapping.target),
+            unified_diff: render(&mapping.unified_diff),
+            tool_call_id: render(&mapping.tool_call_id),
+            failed: render(&mapping.failed)
+                .is_some_and(|failed| matches!(failed.trim(), "true" | "1")),
+        }
+    }
+
+    fn apply(&mut self, rendered: RenderedEntry, entry_index: &EntryIndexProvider) -> Vec<Patch> {
+        let entry_type = match rendered.entry_type {
+            MappedEntryType::UserMessage => NormalizedEntryType::UserMessage,
+            MappedEntryType::AssistantMessage => NormalizedEntryType::AssistantMessage,
+            MappedEntryType::Thinking => NormalizedEntryType::Thinking,
+            MappedEntryType::SystemMessage => NormalizedEntryType::SystemMessage,
+            MappedEntryType::ErrorMessage => NormalizedEntryType::ErrorMessage,
+            MappedEntryType::ToolUse => {
+                let tool_name = rendered
+                    .tool_name
+                    .clone()
+                    .unwrap_or_else(|| "tool".to_string());
+                NormalizedEntryType::ToolUse {
+                    action_type: self.action_type(&rendered, &tool_name),
+                    tool_name,
+                    status: if rendered.tool_call_id.is_some() {
+                        ToolStatus::Created
+                    } else {
+                        ToolStatus::Success
+                    },
+                }
+            }
+            MappedEntryType::ToolResult => return self.complete_tool_call(rendered),
+        };
+
+        let index = entry_index.next();
+        let entry = NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: rendered.content,
+            metadata: None,
+        };
+        if let Some(tool_call_id) = rendered.tool_call_id
+            && matches!(rendered.entry_type, MappedEntryType::ToolUse)
+        {
+            self.tool_calls.insert(tool_call_id, (index, entry.clone()));
+        }
+        vec![ConversationPatch::add_normalized_entry(index, entry)]
+    }
+
+    fn action_type(&self, rendered: &RenderedEntry, tool_name: &str) -> ActionType {
+        let target = rendered.target.clone().unwrap_or_default();
+        match rendered.action {
+            MappedAction::FileRead => ActionType::FileRead {
+                path: make_path_relative(&target, &self.worktree_path),
+            },
+            MappedAction::FileEdit => ActionType::FileEdit {
+                path: make_path_relative(&target, &self.worktree_path),
+                changes: rendered
+                    .unified_diff
+                    .clone()
+                    .map(|unified_diff| FileChange::Edit {
+                        unified_diff,
+                        has_line_numbers: false,
+                    })
+                    .into_iter()
+                    .collect(),
+            },
+            MappedAction::CommandRun => ActionType::CommandRun {
+                command: target,
+                result: None,
+            },
+            MappedAction::Search => ActionType::Search { query: target },
+            MappedAction::WebFetch => ActionType::WebFetch { url: target },
+            MappedAction::Tool => ActionType::Tool {
+                tool_name: tool_name.to_string(),
+                arguments: rendered.target.as_deref().map(|target| {
+                    serde_json::from_str(target).unwrap_or(Value::String(target.to_string()))
+                }),
+                result: None,
+            },
+            MappedAction::Other => ActionType::Other {
+                description: rendered.content.clone(),
+            },
+        }
+    }
+
+    fn complete_tool_call(&mut self, rendered: RenderedEntry) -> Vec<Patch> {
+        let Some((index, entry)) = rendered
+            .tool_call_id
+            .as_ref()
+            .and_then(|id| self.tool_calls.remove(id))
+        else {
+            return vec![];
+        };
+
+        let NormalizedEntryType::ToolUse {
+            tool_name,
+            action_type,
+            ..
+        } = entry.entry_type
+        else {
+            return vec![];
+        };
+
+        let output = rendered.content;
+        let action_type = match action_type {
+            ActionType::CommandRun { command, .. } => ActionType::CommandRun {
+                command,
+                result: Some(CommandRunResult {
+                    exit_status: Some(CommandExitStatus::Success {
+                        success: !rendered.failed,
+                    }),
+                    output: Some(output),
+                }),
+            },
+            ActionType::Tool {
+                tool_name,
+                arguments,
+                ..
+            } => ActionType::Tool {
+                tool_name,
+                arguments,
+                result: Some(ToolResult {
+                    r#type: ToolResultValueType::Markdown,
+                    value: Value::String(output),
+                }),
+            },
+            other => other,
+        };
+
+        let entry = NormalizedEntry {
+            entry_type: NormalizedEntryType::ToolUse {
+                tool_name,
+                action_type,
+                status: if rendered.failed {
+                    ToolStatus::Failed
+                } else {
+                    ToolStatus::Success
+                },
+            },
+            ..entry
+        };
+        vec![ConversationPatch::replace(index, entry)]
+    }
+}
+
+struct RenderedEntry {
+    entry_type: MappedEntryType,
+    content: String,
+    tool_name: Option<String>,
+    action: MappedAction,
+    target: Option<String>,
+    unified_diff: Option<String>,
+    tool_call_id: Option<String>,
+    failed: bool,
+}
+
+/// Resolve a dotted path such as `message.content.0.text`
+fn json_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .filter(|segment| !segment.is_empty())
+        .try_fold(value, |current, segment| match current {
+            Value::Array(items) => segment
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| items.get(index)),
+            Value::Object(map) => map.get(segment),
+            _ => None,
+        })
+}
+
+fn value_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+fn capture(caps: &Captures, field: &str) -> Option<String> {
+    let group = match field.parse::<usize>() {
+        Ok(index) => caps.get(index),
+        Err(_) => caps.name(field),
+    };
+    group.map(|m| m.as_str().to_string())
+}
+
+/// Replace `{field}` placeholders using `lookup`; unknown fields render as empty strings
+fn render_template(template: &str, lookup: &dyn Fn(&str) -> Option<String>) -> String {
+    TEMPLATE_FIELD
+        .replace_all(template, |caps: &Captures| {
+            lookup(caps[1].trim()).unwrap_or_default()
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logs::utils::patch::extract_normalized_entry_from_patch;
+
+    fn agent(config: Value) -> GenericCli {
+        serde_json::from_value(config).unwrap()
+    }
+
+    fn entries(patches: &[Patch]) -> Vec<(usize, NormalizedEntry)> {
+        patches
+            .iter()
+            .filter_map(extract_normalized_entry_from_patch)
+            .collect()
+    }
+
+    #[test]
+    fn test_json_lines_mapping() {
+        let cli = agent(serde_json::json!({
+            "command": "my-agent",
+            "output": {
+                "format": "json_lines",
+                "rules": [
+                    { "when": { "type": "text" }, "entry_type": "assistant_message", "content": "{text}" },
+                    {
+                        "when": { "type": "tool", "tool.name": "shell" },
+                        "entry_type": "tool_use",
+                        "tool_name": "bash",
+                        "action": "command_run",
+                        "target": "{tool.input.cmd}",
+                        "content": "`{tool.input.cmd}`",
+                        "tool_call_id": "{id}"
+                    },
+                    {
+                        "when": { "type": "tool_result" },
+                        "entry_type": "tool_result",
+                        "tool_call_id": "{id}",
+                        "content": "{output}",
+                        "failed": "{is_error}"
+                    }
+                ]
+            },
+            "session": { "extractor": { "json_path": "session.id" }, "resume_args": ["--resume", "{session_id}"] }
+        }));
+        let mut processor = GenericCliLogProcessor::new(&cli, Path::new("/tmp/wt"));
+        let index = EntryIndexProvider::test_new();
+
+        assert_eq!(
+            processor.extract_session_id(r#"{"type":"init","session":{"id":"abc"}}"#),
+            Some("abc".to_string())
+        );
+        assert!(
+            processor
+                .process_line(r#"{"type":"init","session":{"id":"abc"}}"#, &index)
+                .is_empty()
+        );
+
+        let text = entries(&processor.process_line(r#"{"type":"text","text":"Hello"}"#, &index));
+        assert_eq!(text.len(), 1);
+        assert!(matches!(
+            text[0].1.entry_type,
+            NormalizedEntryType::AssistantMessage
+        ));
+        assert_eq!(text[0].1.content, "Hello");
+
+        let tool = entries(&processor.process_line(
+            r#"{"type":"tool","id":"t1","tool":{"name":"shell","input":{"cmd":"ls"}}}"#,
+            &index,
+        ));
+        assert_eq!(tool[0].0, 1);
+        assert_eq!(tool[0].1.content, "`ls`");
+        match &tool[0].1.entry_type {
+            NormalizedEntryType::ToolUse {
+                tool_name,
+                action_type: ActionType::CommandRun { command, .. },
+                status: ToolStatus::Created,
+            } => {
+                assert_eq!(tool_name, "bash");
+                assert_eq!(command, "ls");
+            }
+            other => panic!("unexpected entry {other:?}"),
+        }
+
+        let result = entries(&processor.process_line(
+            r#"{"type":"tool_result","id":"t1","output":"a.txt","is_error":false}"#,
+            &index,
+        ));
+        assert_eq!(result[0].0, 1);
+        match &result[0].1.entry_type {
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::CommandRun { result, .. },
+                status: ToolStatus::Success,
+                ..
+            } => {
+                assert_eq!(
+                    result.as_ref().and_then(|r| r.output.as_deref()),
+                    Some("a.txt")
+                );
+            }
+            other => panic!("unexpected entry {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_regex_mapping_and_unmatched_lines() {
+        let cli = agent(serde_json::json!({
+            "command": "my-agent",
+            "output": {
+                "format": "regex",
+                "rules": [
+                    { "pattern": r"^\[read\] (?P<path>.+)$", "entry_type": "tool_use", "tool_name": "read", "action": "file_read", "target": "{path}" },
+                    { "pattern": r"^ERROR: (.*)$", "entry_type": "error_message", "content": "{1}" }
+                ]
+            },
+            "unmatched_lines": "assistant_message",
+            "session": { "extractor": { "regex": r"session: (\S+)" }, "resume_args": ["--continue={session_id}"] }
+        }));
+        let mut processor = GenericCliLogProcessor::new(&cli, Path::new("/tmp/wt"));
+        let index = EntryIndexProvider::test_new();
+
+        assert_eq!(
+            processor.extract_session_id("started session: s-42"),
+            Some("s-42".to_string())
+        );
+
+        let read = entries(&processor.process_line("[read] /tmp/wt/src/main.rs", &index));
+        match &read[0].1.entry_type {
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::FileRead { path },
+                status: ToolStatus::Success,
+                ..
+            } => assert_eq!(path, "src/main.rs"),
+            other => panic!("unexpected entry {other:?}"),
+        }
+
+        let error = entries(&processor.process_line("ERROR: boom", &index));
+        assert!(matches!(
+            error[0].1.entry_type,
+            NormalizedEntryType::ErrorMessage
+        ));
+        assert_eq!(error[0].1.content, "boom");
+
+        let prose = entries(&processor.process_line("Thinking about it", &index));
+        assert!(matches!(
+            prose[0].1.entry_type,
+            NormalizedEntryType::AssistantMessage
+        ));
+    }
+
+    #[test]
+    fn test_build_command() {
+        let cli = agent(serde_json::json!({
+            "command": "my-agent",
+            "args": ["run", "-p", "{prompt}"],
+            "prompt_input": "argument",
+            "session": { "extractor": { "json_path": "session_id" }, "resume_args": ["--resume", "{session_id}"] },
+            "additional_params": ["--verbose"]
+        }));
+
+        let initial = cli.build_command("fix it's bug", None).unwrap();
+        assert_eq!(
+            shlex::split(&initial).unwrap(),
+            vec!["my-agent", "run", "-p", "fix it's bug", "--verbose"]
+        );
+        assert_eq!(
+            cli.build_command("go", Some("s1")).unwrap(),
+            "my-agent run -p go --resume s1 --verbose"
+        );
+
+        let stdin_cli = agent(serde_json::json!({ "command": "my-agent" }));
+        assert_eq!(
+            stdin_cli.build_command("go", Some("s1")).unwrap(),
+            "my-agent"
+        );
+    }
+}
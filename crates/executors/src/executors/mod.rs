@@ -1,4 +1,4 @@
-use std::{path::Path, sync::Arc};
+use std::{collections::HashMap, path::Path, sync::Arc};
 
 use async_trait::async_trait;
 use command_group::AsyncGroupChild;
@@ -14,11 +14,14 @@ use workspace_utils::msg_store::MsgStore;
 
 use crate::{
     approvals::ExecutorApprovalService,
+    command::CmdOverrides,
     executors::{
-        amp::Amp, claude::ClaudeCode, codex::Codex, copilot::Copilot, cursor::CursorAgent,
-        gemini::Gemini, opencode::Opencode, qwen::QwenCode,
+        acp::Acp, amp::Amp, claude::ClaudeCode, codex::Codex, copilot::Copilot,
+        cursor::CursorAgent, gemini::Gemini, generic_cli::GenericCli, opencode::Opencode,
+        qwen::QwenCode,
     },
     mcp_config::McpConfig,
+    steering::SteeringSender,
 };
 
 pub mod acp;
@@ -28,6 +31,7 @@ pub mod codex;
 pub mod copilot;
 pub mod cursor;
 pub mod gemini;
+pub mod generic_cli;
 pub mod opencode;
 pub mod qwen;
 
@@ -83,6 +87,8 @@ pub enum CodingAgent {
     CursorAgent,
     QwenCode,
     Copilot,
+    Acp,
+    GenericCli,
 }
 
 impl CodingAgent {
@@ -135,9 +141,90 @@ impl CodingAgent {
             Self::Codex(_) => vec![BaseAgentCapability::SessionFork],
             Self::Gemini(_) => vec![BaseAgentCapability::SessionFork],
             Self::QwenCode(_) => vec![BaseAgentCapability::SessionFork],
+            Self::Acp(_) => vec![BaseAgentCapability::SessionFork],
+            Self::GenericCli(cli) => {
+                if cli.session.is_some() {
+                    vec![BaseAgentCapability::SessionFork]
+                } else {
+                    vec![]
+                }
+            }
             Self::Opencode(_) | Self::CursorAgent(_) | Self::Copilot(_) => vec![],
         }
     }
+
+    pub fn cmd_overrides(&self) -> &CmdOverrides {
+        match self {
+            Self::ClaudeCode(agent) => &agent.cmd,
+            Self::Amp(agent) => &agent.cmd,
+            Self::Gemini(agent) => &agent.cmd,
+            Self::Codex(agent) => &agent.cmd,
+            Self::Opencode(agent) => &agent.cmd,
+            Self::CursorAgent(agent) => &agent.cmd,
+            Self::QwenCode(agent) => &agent.cmd,
+            Self::Copilot(agent) => &agent.cmd,
+            Self::Acp(agent) => &agent.cmd,
+            Self::GenericCli(agent) => &agent.cmd,
+        }
+    }
+
+    fn cmd_overrides_mut(&mut self) -> &mut CmdOverrides {
+        match self {
+            Self::ClaudeCode(agent) => &mut agent.cmd,
+            Self::Amp(agent) => &mut agent.cmd,
+            Self::Gemini(agent) => &mut agent.cmd,
+            Self::Codex(agent) => &mut agent.cmd,
+            Self::Opencode(agent) => &mut agent.cmd,
+            Self::CursorAgent(agent) => &mut agent.cmd,
+            Self::QwenCode(agent) => &mut agent.cmd,
+            Self::Copilot(agent) => &mut agent.cmd,
+            Self::Acp(agent) => &mut agent.cmd,
+            Self::GenericCli(agent) => &mut agent.cmd,
+        }
+    }
+
+    /// Set environment variables passed to the agent process on spawn
+    pub fn use_env(&mut self, env: HashMap<String, String>) {
+        self.cmd_overrides_mut().injected_env = env;
+    }
+
+    pub fn append_prompt(&self) -> &AppendPrompt {
+        match self {
+            Self::ClaudeCode(agent) => &agent.append_prompt,
+            Self::Amp(agent) => &agent.append_prompt,
+            Self::Gemini(agent) => &agent.append_prompt,
+            Self::Codex(agent) => &agent.append_prompt,
+            Self::Opencode(agent) => &agent.append_prompt,
+            Self::CursorAgent(agent) => &agent.append_prompt,
+            Self::QwenCode(agent) => &agent.append_prompt,
+            Self::Copilot(agent) => &agent.append_prompt,
+            Self::Acp(agent) => &agent.append_prompt,
+            Self::GenericCli(agent) => &agent.append_prompt,
+        }
+    }
+
+    fn append_prompt_mut(&mut self) -> &mut AppendPrompt {
+        match self {
+            Self::ClaudeCode(agent) => &mut agent.append_prompt,
+            Self::Amp(agent) => &mut agent.append_prompt,
+            Self::Gemini(agent) => &mut agent.append_prompt,
+            Self::Codex(agent) => &mut agent.append_prompt,
+            Self::Opencode(agent) => &mut agent.append_prompt,
+            Self::CursorAgent(agent) => &mut agent.append_prompt,
+            Self::QwenCode(agent) => &mut agent.append_prompt,
+            Self::Copilot(agent) => &mut agent.append_prompt,
+            Self::Acp(agent) => &mut agent.append_prompt,
+            Self::GenericCli(agent) => &mut agent.append_prompt,
+        }
+    }
+
+    /// Fill in the template variables of the configured append prompt
+    pub fn use_prompt_vars(&mut self, vars: &serde_json::Value) {
+        let append_prompt = self.append_prompt_mut();
+        if let Some(value) = &append_prompt.0 {
+            append_prompt.0 = Some(workspace_utils::template::render(value, vars));
+        }
+    }
 }
 
 #[async_trait]
@@ -173,6 +260,8 @@ pub type ExecutorExitSignal = tokio::sync::oneshot::Receiver<()>;
 pub struct SpawnedChild {
     pub child: AsyncGroupChild,
     pub exit_signal: Option<ExecutorExitSignal>,
+    /// Present when the agent accepts user messages while it is running
+    pub steering: Option<SteeringSender>,
 }
 
 impl From<AsyncGroupChild> for SpawnedChild {
@@ -180,6 +269,7 @@ impl From<AsyncGroupChild> for SpawnedChild {
         Self {
             child,
             exit_signal: None,
+            steering: None,
         }
     }
 }
@@ -7,7 +7,6 @@ use std::{
 };
 
 use async_trait::async_trait;
-use command_group::AsyncCommandGroup;
 use fork_stream::StreamExt as _;
 use futures::{StreamExt, future::ready, stream::BoxStream};
 use lazy_static::lazy_static;
@@ -16,10 +15,14 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tokio::{io::AsyncWriteExt, process::Command};
 use ts_rs::TS;
-use workspace_utils::{msg_store::MsgStore, path::make_path_relative, shell::get_shell_command};
+use workspace_utils::{
+    msg_store::{MsgStore, TimedOutput},
+    path::make_path_relative,
+    shell::get_shell_command,
+};
 
 use crate::{
-    command::{CmdOverrides, CommandBuilder, apply_overrides},
+    command::{CmdOverrides, CommandBuilder, apply_overrides, group_spawn},
     executors::{
         AppendPrompt, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
         opencode::share_bridge::Bridge as ShareBridge,
@@ -147,9 +150,10 @@ impl StandardCodingAgentExecutor for Opencode {
             .arg(opencode_command)
             .env("NODE_NO_WARNINGS", "1")
             .env("OPENCODE_AUTO_SHARE", "1")
-            .env("OPENCODE_API", bridge.base_url.clone());
+            .env("OPENCODE_API", bridge.base_url.clone())
+            .envs(&self.cmd.injected_env);
 
-        let mut child = match command.group_spawn() {
+        let mut child = match group_spawn(&mut command) {
             Ok(c) => c,
             Err(e) => {
                 // If opencode fails to start, shut down the bridge to free the port
@@ -214,9 +218,10 @@ impl StandardCodingAgentExecutor for Opencode {
             .arg(&opencode_command)
             .env("NODE_NO_WARNINGS", "1")
             .env("OPENCODE_AUTO_SHARE", "1")
-            .env("OPENCODE_API", bridge.base_url.clone());
+            .env("OPENCODE_API", bridge.base_url.clone())
+            .envs(&self.cmd.injected_env);
 
-        let mut child = match command.group_spawn() {
+        let mut child = match group_spawn(&mut command) {
             Ok(c) => c,
             Err(e) => {
                 bridge.shutdown().await;
@@ -263,9 +268,12 @@ impl StandardCodingAgentExecutor for Opencode {
         let entry_index_counter = EntryIndexProvider::start_from(&msg_store);
 
         let stderr_lines = msg_store
-            .stderr_lines_stream()
+            .stderr_timed_lines()
             .filter_map(|res| ready(res.ok()))
-            .map(|line| strip_ansi_escapes::strip_str(&line))
+            .map(|line| TimedOutput {
+                text: strip_ansi_escapes::strip_str(&line.text),
+                ..line
+            })
             .fork();
 
         // Log line: INFO  2025-08-05T10:17:26 +1ms service=session id=ses_786439b6dffe4bLqNBS4fGd7mJ
@@ -273,7 +281,9 @@ impl StandardCodingAgentExecutor for Opencode {
         let log_lines = stderr_lines
             .clone()
             .filter(|line| {
-                ready(OPENCODE_LOG_REGEX.is_match(line) || LogUtils::is_error_line(line))
+                ready(
+                    OPENCODE_LOG_REGEX.is_match(&line.text) || LogUtils::is_error_line(&line.text),
+                )
             })
             .boxed();
 
@@ -288,10 +298,18 @@ impl StandardCodingAgentExecutor for Opencode {
 
         // Also parse share events from stdout
         let share_events = msg_store
-            .stdout_lines_stream()
+            .stdout_timed_lines()
             .filter_map(|res| ready(res.ok()))
-            .filter(|line| ready(line.starts_with(Opencode::SHARE_PREFIX)))
-            .map(|line| line[Opencode::SHARE_PREFIX.len()..].to_string())
+            .filter_map(|line| {
+                ready(
+                    line.text
+                        .strip_prefix(Opencode::SHARE_PREFIX)
+                        .map(|event| TimedOutput {
+                            at: line.at,
+                            text: event.to_string(),
+                        }),
+                )
+            })
             .boxed();
         tokio::spawn(Self::process_share_events(
             share_events,
@@ -316,12 +334,16 @@ impl StandardCodingAgentExecutor for Opencode {
 impl Opencode {
     const SHARE_PREFIX: &'static str = "[oc-share] ";
     async fn process_opencode_log_lines(
-        mut log_lines: BoxStream<'_, String>,
+        mut log_lines: BoxStream<'_, TimedOutput>,
         msg_store: Arc<MsgStore>,
         entry_index_counter: EntryIndexProvider,
         _worktree_path: PathBuf,
     ) {
-        while let Some(line) = log_lines.next().await {
+        while let Some(TimedOutput {
+            at: source_at,
+            text: line,
+        }) = log_lines.next().await
+        {
             if line.starts_with("ERROR") || LogUtils::is_error_line(&line) {
                 let entry = NormalizedEntry {
                     timestamp: None,
@@ -335,7 +357,7 @@ impl Opencode {
                     entry_index_counter.next(),
                     entry,
                 );
-                msg_store.push_patch(patch);
+                msg_store.push_patch_at(patch, source_at);
             }
         }
     }
@@ -344,7 +366,7 @@ impl Opencode {
 impl Opencode {
     /// Parse share events and emit normalized patches
     async fn process_share_events(
-        mut lines: BoxStream<'_, String>,
+        mut lines: BoxStream<'_, TimedOutput>,
         worktree_path: PathBuf,
         entry_index_counter: EntryIndexProvider,
         msg_store: Arc<MsgStore>,
@@ -437,7 +459,11 @@ impl Opencode {
             }
         };
 
-        while let Some(line) = lines.next().await {
+        while let Some(TimedOutput {
+            at: source_at,
+            text: line,
+        }) = lines.next().await
+        {
             let Ok(env) = serde_json::from_str::<ShareSyncEnvelope>(&line) else {
                 continue;
             };
@@ -483,7 +509,10 @@ impl Opencode {
                                         content,
                                         metadata: None,
                                     };
-                                    msg_store.push_patch(ConversationPatch::replace(idx, entry));
+                                    msg_store.push_patch_at(
+                                        ConversationPatch::replace(idx, entry),
+                                        source_at,
+                                    );
                                 }
                                 HmEntry::Vacant(v) => {
                                     let idx = entry_index_counter.next();
@@ -494,9 +523,10 @@ impl Opencode {
                                         content,
                                         metadata: None,
                                     };
-                                    msg_store.push_patch(ConversationPatch::add_normalized_entry(
-                                        idx, entry,
-                                    ));
+                                    msg_store.push_patch_at(
+                                        ConversationPatch::add_normalized_entry(idx, entry),
+                                        source_at,
+                                    );
                                 }
                             }
                         }
@@ -575,7 +605,7 @@ impl Opencode {
                             } else {
                                 ConversationPatch::replace(idx, entry)
                             };
-                            msg_store.push_patch(patch);
+                            msg_store.push_patch_at(patch, source_at);
                         }
                         None => {
                             // Role unknown; accumulate but don't emit yet
@@ -799,7 +829,7 @@ impl Opencode {
                     };
 
                     let patch = upsert_by_part(entry, id);
-                    msg_store.push_patch(patch);
+                    msg_store.push_patch_at(patch, source_at);
                 }
                 Err(_) => {}
             }
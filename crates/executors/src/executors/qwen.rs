@@ -41,7 +41,8 @@ impl StandardCodingAgentExecutor for QwenCode {
     async fn spawn(&self, current_dir: &Path, prompt: &str) -> Result<SpawnedChild, ExecutorError> {
         let qwen_command = self.build_command_builder().build_initial();
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
-        let harness = AcpAgentHarness::with_session_namespace("qwen_sessions");
+        let harness = AcpAgentHarness::with_session_namespace("qwen_sessions")
+            .with_env(self.cmd.injected_env.clone());
         harness
             .spawn_with_command(current_dir, combined_prompt, qwen_command)
             .await
@@ -55,7 +56,8 @@ impl StandardCodingAgentExecutor for QwenCode {
     ) -> Result<SpawnedChild, ExecutorError> {
         let qwen_command = self.build_command_builder().build_follow_up(&[]);
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
-        let harness = AcpAgentHarness::with_session_namespace("qwen_sessions");
+        let harness = AcpAgentHarness::with_session_namespace("qwen_sessions")
+            .with_env(self.cmd.injected_env.clone());
         harness
             .spawn_follow_up_with_command(current_dir, combined_prompt, session_id, qwen_command)
             .await
@@ -6,3 +6,4 @@ pub mod logs;
 pub mod mcp_config;
 pub mod profile;
 pub mod stdout_dup;
+pub mod steering;
@@ -7,6 +7,10 @@ pub mod plain_text_processor;
 pub mod stderr_processor;
 pub mod utils;
 
+/// Version of the log normalizers. Bump it when a normalizer's output changes so stored
+/// conversations can be found and re-normalized.
+pub const NORMALIZER_VERSION: i64 = 1;
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 #[ts(export)]
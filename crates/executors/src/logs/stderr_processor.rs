@@ -11,7 +11,7 @@
 use std::{sync::Arc, time::Duration};
 
 use futures::StreamExt;
-use workspace_utils::msg_store::MsgStore;
+use workspace_utils::msg_store::{MsgStore, TimedOutput};
 
 use super::{NormalizedEntry, NormalizedEntryType, plain_text_processor::PlainTextLogProcessor};
 use crate::logs::utils::EntryIndexProvider;
@@ -35,7 +35,7 @@ use crate::logs::utils::EntryIndexProvider;
 /// * `entry_index_provider` - provider of incremental entry indices for patch ordering.
 pub fn normalize_stderr_logs(msg_store: Arc<MsgStore>, entry_index_provider: EntryIndexProvider) {
     tokio::spawn(async move {
-        let mut stderr = msg_store.stderr_chunked_stream();
+        let mut stderr = msg_store.stderr_timed_chunks();
 
         // Create a processor with time-based emission for stderr
         let mut processor = PlainTextLogProcessor::builder()
@@ -49,9 +49,9 @@ pub fn normalize_stderr_logs(msg_store: Arc<MsgStore>, entry_index_provider: Ent
             .index_provider(entry_index_provider)
             .build();
 
-        while let Some(Ok(chunk)) = stderr.next().await {
+        while let Some(Ok(TimedOutput { at, text: chunk })) = stderr.next().await {
             for patch in processor.process(chunk) {
-                msg_store.push_patch(patch);
+                msg_store.push_patch_at(patch, at);
             }
         }
     });
@@ -1,6 +1,6 @@
 use json_patch::Patch;
 use serde::{Deserialize, Serialize};
-use serde_json::{from_value, json, to_value};
+use serde_json::{Value, from_value, json, to_value};
 use ts_rs::TS;
 use workspace_utils::diff::Diff;
 
@@ -121,6 +121,46 @@ impl ConversationPatch {
         }]))
         .unwrap()
     }
+
+    /// Create an ADD patch for a stored conversation entry at the given index
+    pub fn add_entry(entry_index: usize, entry: Value) -> Patch {
+        from_value(json!([{
+            "op": PatchOperation::Add,
+            "path": format!("/entries/{entry_index}"),
+            "value": entry,
+        }]))
+        .unwrap()
+    }
+
+    /// Create a single patch that adds stored conversation entries in order
+    pub fn add_entries(entries: Vec<Value>) -> Patch {
+        let ops: Vec<Value> = entries
+            .into_iter()
+            .enumerate()
+            .map(|(entry_index, value)| {
+                json!({
+                    "op": PatchOperation::Add,
+                    "path": format!("/entries/{entry_index}"),
+                    "value": value,
+                })
+            })
+            .collect();
+        from_value(Value::Array(ops)).unwrap()
+    }
+}
+
+/// Apply a conversation's patches in order and return the resulting entries
+pub fn conversation_entries<'a>(
+    patches: impl IntoIterator<Item = &'a Patch>,
+) -> Result<Vec<Value>, json_patch::PatchError> {
+    let mut doc = json!({ "entries": [] });
+    for patch in patches {
+        json_patch::patch(&mut doc, patch)?;
+    }
+    Ok(match doc["entries"].take() {
+        Value::Array(entries) => entries,
+        _ => Vec::new(),
+    })
 }
 
 /// Extract the entry index and `NormalizedEntry` from a JsonPatch if it contains one
@@ -2,12 +2,13 @@
 //!
 //! These helpers abstract over JSON vs TOML formats used by different agents.
 
-use std::{collections::HashMap, sync::LazyLock};
+use std::{collections::HashMap, path::PathBuf, sync::LazyLock};
 
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use tokio::fs;
 use ts_rs::TS;
+use workspace_utils::path::expand_tilde;
 
 use crate::executors::{CodingAgent, ExecutorError};
 
@@ -87,6 +88,12 @@ pub async fn write_agent_config(
 
 type ServerMap = Map<String, Value>;
 
+/// The MCP config path set in a profile, for executors without a fixed config location
+pub fn configured_mcp_config_path(path: Option<&str>) -> Option<PathBuf> {
+    path.filter(|path| !path.trim().is_empty())
+        .map(expand_tilde)
+}
+
 fn is_http_server(s: &Map<String, Value>) -> bool {
     matches!(s.get("type").and_then(Value::as_str), Some("http"))
 }
@@ -293,7 +300,10 @@ impl CodingAgent {
         use Adapter::*;
 
         let adapter = match self {
-            CodingAgent::ClaudeCode(_) | CodingAgent::Amp(_) => Passthrough,
+            CodingAgent::ClaudeCode(_)
+            | CodingAgent::Amp(_)
+            | CodingAgent::Acp(_)
+            | CodingAgent::GenericCli(_) => Passthrough,
             CodingAgent::QwenCode(_) | CodingAgent::Gemini(_) => Gemini,
             CodingAgent::CursorAgent(_) => Cursor,
             CodingAgent::Codex(_) => Codex,
@@ -0,0 +1,100 @@
+//! Real-time steering: delivering user messages to an agent while its process is still running.
+//!
+//! Executors that keep an input channel open (stream-json stdin, JSON-RPC, ACP) return a
+//! [`SteeringSender`] in their `SpawnedChild`. Once a message is delivered the executor writes a
+//! [`SteeringLogLine`] into the process log, which its normalizer turns into a `UserMessage` entry,
+//! so the message is shown in the conversation and survives log replay.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::logs::{NormalizedEntry, NormalizedEntryType};
+
+#[derive(Debug, Error)]
+pub enum SteeringError {
+    #[error("agent is no longer accepting input")]
+    Closed,
+    #[error("failed to deliver message to agent: {0}")]
+    DeliveryFailed(String),
+}
+
+/// A user message waiting to be delivered to a running agent
+#[derive(Debug)]
+pub struct SteeringMessage {
+    pub content: String,
+    reply: oneshot::Sender<Result<(), SteeringError>>,
+}
+
+impl SteeringMessage {
+    /// Report back whether the message reached the agent
+    pub fn complete(self, result: Result<(), SteeringError>) {
+        let _ = self.reply.send(result);
+    }
+}
+
+/// Handle used by the container to send messages to a running agent
+#[derive(Debug, Clone)]
+pub struct SteeringSender(mpsc::UnboundedSender<SteeringMessage>);
+
+impl SteeringSender {
+    /// Send a message and wait until the executor has delivered it (or failed to)
+    pub async fn send(&self, content: impl Into<String>) -> Result<(), SteeringError> {
+        let (reply, reply_rx) = oneshot::channel();
+        self.0
+            .send(SteeringMessage {
+                content: content.into(),
+                reply,
+            })
+            .map_err(|_| SteeringError::Closed)?;
+        reply_rx.await.unwrap_or(Err(SteeringError::Closed))
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.0.is_closed()
+    }
+}
+
+/// Executor side of the steering channel. It closes once the container drops the sender.
+pub type SteeringReceiver = mpsc::UnboundedReceiver<SteeringMessage>;
+
+pub fn channel() -> (SteeringSender, SteeringReceiver) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    (SteeringSender(tx), rx)
+}
+
+/// Log line recording a delivered steering message
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SteeringLogLine {
+    pub steering_message: String,
+}
+
+impl SteeringLogLine {
+    pub fn new(content: impl Into<String>) -> Self {
+        Self {
+            steering_message: content.into(),
+        }
+    }
+
+    pub fn raw(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if !line.starts_with('{') {
+            return None;
+        }
+        serde_json::from_str(line).ok()
+    }
+
+    pub fn to_normalized_entry(&self) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::UserMessage,
+            content: self.steering_message.clone(),
+            metadata: None,
+        }
+    }
+}
@@ -0,0 +1,213 @@
+#![cfg(unix)]
+
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::LazyLock,
+    time::Duration,
+};
+
+use executors::executors::{
+    BaseAgentCapability, CodingAgent, SpawnedChild, StandardCodingAgentExecutor,
+    acp::{AcpEvent, SessionManager},
+};
+use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+fn stub_agent_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/stub_acp_agent.py")
+}
+
+fn stub_agent(namespace: &str) -> CodingAgent {
+    stub_agent_with_turn_delay(namespace, 0.0)
+}
+
+fn stub_agent_with_turn_delay(namespace: &str, turn_delay_secs: f64) -> CodingAgent {
+    serde_json::from_value(serde_json::json!({
+        "ACP": {
+            "command": "python3",
+            "args": [stub_agent_path().to_string_lossy()],
+            "env": {
+                "STUB_ACP_PREFIX": "stub",
+                "STUB_ACP_TURN_DELAY": turn_delay_secs.to_string(),
+            },
+            "session_namespace": namespace,
+        }
+    }))
+    .expect("valid ACP profile")
+}
+
+/// Sessions are persisted under the home directory, so every test in this binary shares a
+/// temporary one instead of writing into the real `~/.vibe-kanban`
+static HOME: LazyLock<TempDir> = LazyLock::new(|| {
+    let home = TempDir::new().unwrap();
+    // SAFETY: set once, and every test initialises it before touching the environment
+    unsafe { std::env::set_var("HOME", home.path()) };
+    home
+});
+
+fn isolated_home() -> &'static Path {
+    HOME.path()
+}
+
+fn session_dir(namespace: &str) -> PathBuf {
+    let mut dir = isolated_home().join(".vibe-kanban");
+    if cfg!(debug_assertions) {
+        dir = dir.join("dev");
+    }
+    dir.join(namespace)
+}
+
+/// Collect the ACP events of one turn, stopping the agent once the harness signals completion
+/// (as the container does) and reading its stdout until the harness closes it.
+async fn collect_turn(spawned: &mut SpawnedChild) -> Vec<AcpEvent> {
+    let stdout = spawned.child.inner().stdout.take().expect("child stdout");
+    let reader = tokio::spawn(async move {
+        let mut lines = BufReader::new(stdout).lines();
+        let mut events = Vec::new();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if let Ok(event) = AcpEvent::from_str(&line) {
+                events.push(event);
+            }
+        }
+        events
+    });
+
+    let exit_signal = spawned.exit_signal.take().expect("ACP exit signal");
+    tokio::time::timeout(Duration::from_secs(30), exit_signal)
+        .await
+        .expect("ACP turn timed out")
+        .expect("exit signal sender dropped");
+    let _ = spawned.child.kill().await;
+
+    tokio::time::timeout(Duration::from_secs(10), reader)
+        .await
+        .expect("ACP stdout was not closed")
+        .unwrap()
+}
+
+fn session_id(events: &[AcpEvent]) -> String {
+    events
+        .iter()
+        .find_map(|event| match event {
+            AcpEvent::SessionStart(id) => Some(id.clone()),
+            _ => None,
+        })
+        .expect("session start event")
+}
+
+fn assistant_text(events: &[AcpEvent]) -> String {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            AcpEvent::Message(agent_client_protocol::ContentBlock::Text(text)) => {
+                Some(text.text.clone())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn acp_executor_spawns_follows_up_and_forks_sessions() {
+    isolated_home();
+    let namespace = format!("acp_test_{}", uuid::Uuid::new_v4().simple());
+    let worktree = TempDir::new().unwrap();
+    let agent = stub_agent(&namespace);
+
+    assert!(agent.check_availability().await);
+    assert!(
+        agent
+            .capabilities()
+            .contains(&BaseAgentCapability::SessionFork)
+    );
+
+    // Initial turn: the stub echoes the prompt and the env var reaches the process
+    let mut initial = agent.spawn(worktree.path(), "hello").await.unwrap();
+    let initial_events = collect_turn(&mut initial).await;
+    let initial_session = session_id(&initial_events);
+    assert_eq!(assistant_text(&initial_events), "stub: hello");
+    assert!(
+        initial_events
+            .iter()
+            .any(|event| matches!(event, AcpEvent::Done(reason) if reason.contains("end_turn")))
+    );
+
+    // Follow-up: a new session is forked from the previous one and its history replayed
+    let mut follow_up = agent
+        .spawn_follow_up(worktree.path(), "and again", &initial_session)
+        .await
+        .unwrap();
+    let follow_up_events = collect_turn(&mut follow_up).await;
+    let forked_session = session_id(&follow_up_events);
+    assert_ne!(forked_session, initial_session);
+
+    let reply = assistant_text(&follow_up_events);
+    assert!(reply.starts_with("stub: RESUME CONTEXT"));
+    assert!(reply.contains("stub: hello"));
+    assert!(reply.contains("and again"));
+
+    // The original session is left untouched while the fork carries both turns
+    let sessions = SessionManager::new(namespace.clone()).unwrap();
+    let original_history = sessions.read_session_raw(&initial_session).unwrap();
+    let forked_history = sessions.read_session_raw(&forked_session).unwrap();
+    assert!(!original_history.contains("and again"));
+    assert!(forked_history.starts_with(&original_history));
+    assert!(forked_history.contains("and again"));
+
+    let _ = std::fs::remove_dir_all(session_dir(&namespace));
+}
+
+#[tokio::test]
+async fn acp_executor_accepts_steering_messages_mid_turn() {
+    isolated_home();
+    let namespace = format!("acp_test_{}", uuid::Uuid::new_v4().simple());
+    let worktree = TempDir::new().unwrap();
+    let agent = stub_agent_with_turn_delay(&namespace, 1.0);
+
+    let mut spawned = agent.spawn(worktree.path(), "hello").await.unwrap();
+    let steering = spawned
+        .steering
+        .clone()
+        .expect("ACP agents accept steering");
+
+    // Delivered while the first turn is still running, then sent as the next prompt
+    steering.send("also check the tests").await.unwrap();
+
+    let events = collect_turn(&mut spawned).await;
+    assert_eq!(
+        assistant_text(&events),
+        "stub: hellostub: also check the tests"
+    );
+    assert_eq!(
+        events
+            .iter()
+            .filter(|event| matches!(event, AcpEvent::Done(_)))
+            .count(),
+        2
+    );
+
+    // Once the agent has finished, further messages are rejected so callers can queue them
+    assert!(steering.send("too late").await.is_err());
+
+    let _ = std::fs::remove_dir_all(session_dir(&namespace));
+}
+
+#[tokio::test]
+async fn acp_executor_reports_missing_command_as_unavailable() {
+    let home = isolated_home();
+    let agent: CodingAgent = serde_json::from_value(serde_json::json!({
+        "ACP": {
+            "command": "definitely-not-an-acp-agent --acp",
+            "mcp_config_path": "~/.my-agent/settings.json",
+        }
+    }))
+    .unwrap();
+
+    assert!(!agent.check_availability().await);
+    assert!(agent.supports_mcp());
+    assert_eq!(
+        agent.default_mcp_config_path(),
+        Some(home.join(".my-agent/settings.json"))
+    );
+}
@@ -0,0 +1,79 @@
+#!/usr/bin/env python3
+"""Minimal Agent Client Protocol agent used by the ACP executor tests.
+
+Speaks newline-delimited JSON-RPC over stdio and answers every prompt with a
+single agent message chunk: "<STUB_ACP_PREFIX>: <prompt text>". Setting
+STUB_ACP_TURN_DELAY (seconds) keeps each turn running for that long.
+"""
+
+import json
+import os
+import sys
+import time
+
+
+def send(message):
+    sys.stdout.write(json.dumps(message) + "\n")
+    sys.stdout.flush()
+
+
+def respond(request_id, result):
+    send({"jsonrpc": "2.0", "id": request_id, "result": result})
+
+
+def main():
+    prefix = os.environ.get("STUB_ACP_PREFIX", "echo")
+    turn_delay = float(os.environ.get("STUB_ACP_TURN_DELAY", "0"))
+    sessions = 0
+
+    for line in sys.stdin:
+        line = line.strip()
+        if not line:
+            continue
+
+        message = json.loads(line)
+        method = message.get("method")
+        params = message.get("params") or {}
+        request_id = message.get("id")
+
+        if method == "initialize":
+            respond(
+                request_id,
+                {"protocolVersion": 1, "agentCapabilities": {}, "authMethods": []},
+            )
+        elif method == "session/new":
+            sessions += 1
+            respond(request_id, {"sessionId": f"stub-session-{sessions}"})
+        elif method == "session/prompt":
+            text = "".join(
+                block.get("text", "")
+                for block in params.get("prompt", [])
+                if block.get("type") == "text"
+            )
+            time.sleep(turn_delay)
+            send(
+                {
+                    "jsonrpc": "2.0",
+                    "method": "session/update",
+                    "params": {
+                        "sessionId": params.get("sessionId"),
+                        "update": {
+                            "sessionUpdate": "agent_message_chunk",
+                            "content": {"type": "text", "text": f"{prefix}: {text}"},
+                        },
+                    },
+                }
+            )
+            respond(request_id, {"stopReason": "end_turn"})
+        elif request_id is not None:
+            send(
+                {
+                    "jsonrpc": "2.0",
+                    "id": request_id,
+                    "error": {"code": -32601, "message": "Method not found"},
+                }
+            )
+
+
+if __name__ == "__main__":
+    main()
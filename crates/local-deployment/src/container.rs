@@ -8,10 +8,12 @@ use std::{
 
 use anyhow::anyhow;
 use async_trait::async_trait;
+use chrono::Utc;
 use command_group::AsyncGroupChild;
 use db::{
     DBService,
     models::{
+        audit_event::AuditActor,
         draft::{Draft, DraftType},
         execution_process::{
             ExecutionContext, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
@@ -26,8 +28,9 @@ use db::{
 };
 use deployment::DeploymentError;
 use executors::{
-    actions::{Executable, ExecutorAction},
+    actions::{Executable, ExecutorAction, PromptContext},
     approvals::{ExecutorApprovalService, NoopExecutorApprovalService},
+    command::{CGROUP_PROCS_ENV, ResourceLimits},
     executors::BaseCodingAgent,
     logs::{
         NormalizedEntryType,
@@ -36,6 +39,7 @@ use executors::{
             patch::{escape_json_pointer_segment, extract_normalized_entry_from_patch},
         },
     },
+    steering::SteeringSender,
 };
 use futures::{FutureExt, StreamExt, TryStreamExt, stream::select};
 use serde_json::json;
@@ -45,32 +49,44 @@ use services::services::{
     config::Config,
     container::{ContainerError, ContainerRef, ContainerService},
     diff_stream::{self, DiffStreamHandle},
+    drafts::DraftsService,
     git::{Commit, DiffTarget, GitService},
     image::ImageService,
     notification::NotificationService,
+    project_env::ProjectEnvService,
+    retention::RetentionService,
     worktree_manager::WorktreeManager,
 };
 use tokio::{sync::RwLock, task::JoinHandle};
 use tokio_util::io::ReaderStream;
+use tracing::Instrument;
 use utils::{
+    attempt_env::{PROJECT_ID_ENV, TASK_ATTEMPT_ID_ENV, TASK_ID_ENV},
     log_msg::LogMsg,
     msg_store::MsgStore,
+    redact::Redactor,
+    telemetry,
     text::{git_branch_id, short_uuid},
 };
 use uuid::Uuid;
 
-use crate::command;
+use crate::{
+    command,
+    limits::{ResourceGuard, limits_for_run_reason},
+};
 
 #[derive(Clone)]
 pub struct LocalContainerService {
     db: DBService,
     child_store: Arc<RwLock<HashMap<Uuid, Arc<RwLock<AsyncGroupChild>>>>>,
     msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
+    steering: Arc<RwLock<HashMap<Uuid, SteeringSender>>>,
     config: Arc<RwLock<Config>>,
     git: GitService,
     image_service: ImageService,
     analytics: Option<AnalyticsContext>,
     approvals: Approvals,
+    project_env: ProjectEnvService,
 }
 
 impl LocalContainerService {
@@ -82,18 +98,22 @@ impl LocalContainerService {
         image_service: ImageService,
         analytics: Option<AnalyticsContext>,
         approvals: Approvals,
+        project_env: ProjectEnvService,
     ) -> Self {
         let child_store = Arc::new(RwLock::new(HashMap::new()));
+        let steering = Arc::new(RwLock::new(HashMap::new()));
 
         LocalContainerService {
             db,
             child_store,
             msg_stores,
+            steering,
             config,
             git,
             image_service,
             analytics,
             approvals,
+            project_env,
         }
     }
 
@@ -128,8 +148,21 @@ impl LocalContainerService {
     }
 
     /// Finalize task execution by updating status to InReview and sending notifications
+    #[tracing::instrument(
+        name = "task_attempt.finalize",
+        skip_all,
+        fields(task_attempt.id = %ctx.task_attempt.id)
+    )]
     async fn finalize_task(db: &DBService, config: &Arc<RwLock<Config>>, ctx: &ExecutionContext) {
-        if let Err(e) = Task::update_status(&db.pool, ctx.task.id, TaskStatus::InReview).await {
+        if let Err(e) = Task::transition_status(
+            &db.pool,
+            ctx.task.id,
+            TaskStatus::InReview,
+            AuditActor::Agent,
+            Some(ctx.task_attempt.id),
+        )
+        .await
+        {
             tracing::error!("Failed to update task status to InReview: {e}");
         }
         let notify_cfg = config.read().await.notifications.clone();
@@ -269,6 +302,7 @@ impl LocalContainerService {
 
     pub async fn spawn_worktree_cleanup(&self) {
         let db = self.db.clone();
+        let config = self.config.clone();
         let mut cleanup_interval = tokio::time::interval(tokio::time::Duration::from_secs(1800)); // 30 minutes
         self.cleanup_orphaned_worktrees().await;
         tokio::spawn(async move {
@@ -285,6 +319,10 @@ impl LocalContainerService {
                     .unwrap_or_else(|e| {
                         tracing::error!("Failed to clean up expired worktree attempts: {}", e)
                     });
+                let retention = config.read().await.retention.clone();
+                if let Err(e) = RetentionService::run(&db.pool, &retention, false).await {
+                    tracing::error!("Failed to apply log retention policy: {}", e);
+                }
             }
         });
     }
@@ -295,10 +333,12 @@ impl LocalContainerService {
         &self,
         exec_id: &Uuid,
         exit_signal: Option<tokio::sync::oneshot::Receiver<()>>,
+        resource_guard: Option<ResourceGuard>,
     ) -> JoinHandle<()> {
         let exec_id = *exec_id;
         let child_store = self.child_store.clone();
         let msg_stores = self.msg_stores.clone();
+        let steering = self.steering.clone();
         let db = self.db.clone();
         let config = self.config.clone();
         let container = self.clone();
@@ -306,12 +346,27 @@ impl LocalContainerService {
 
         let mut process_exit_rx = self.spawn_os_exit_watcher(exec_id);
 
-        tokio::spawn(async move {
+        // Lasts for the lifetime of the process, parenting its git and tool call spans
+        let span = tracing::info_span!(
+            "execution_process",
+            execution_process.id = %exec_id,
+            run_reason = tracing::field::Empty,
+            executor = tracing::field::Empty,
+            status = tracing::field::Empty,
+        );
+
+        let monitor = async move {
             let mut exit_signal_future = exit_signal
                 .map(|rx| rx.map(|_| ()).boxed()) // wait for signal
                 .unwrap_or_else(|| std::future::pending::<()>().boxed()); // no signal, stall forever
 
+            let timeout_future = match resource_guard.as_ref().and_then(ResourceGuard::timeout) {
+                Some(timeout) => tokio::time::sleep(timeout).boxed(),
+                None => std::future::pending::<()>().boxed(),
+            };
+
             let status_result: std::io::Result<std::process::ExitStatus>;
+            let mut limit_exceeded: Option<String> = None;
 
             // Wait for process to exit, or exit signal from executor
             tokio::select! {
@@ -332,6 +387,23 @@ impl LocalContainerService {
                 exit_status_result = &mut process_exit_rx => {
                     status_result = exit_status_result.unwrap_or_else(|e| Err(std::io::Error::other(e)));
                 }
+                // Wall-clock limit from the executor profile
+                _ = timeout_future => {
+                    if let Some(child_lock) = child_store.read().await.get(&exec_id).cloned() {
+                        let mut child = child_lock.write().await;
+                        if let Err(err) = command::kill_process_group(&mut child).await {
+                            tracing::error!("Failed to kill process group after timeout: {} {}", exec_id, err);
+                        }
+                    }
+                    limit_exceeded = resource_guard.as_ref().map(ResourceGuard::timeout_message);
+                    status_result = Err(std::io::Error::other("wall-clock limit exceeded"));
+                }
+            }
+
+            if limit_exceeded.is_none() {
+                limit_exceeded = resource_guard
+                    .as_ref()
+                    .and_then(ResourceGuard::memory_exceeded_message);
             }
 
             let (exit_code, status) = match status_result {
@@ -346,6 +418,16 @@ impl LocalContainerService {
                 }
                 Err(_) => (None, ExecutionProcessStatus::Failed),
             };
+            let (exit_code, status) = match &limit_exceeded {
+                Some(message) => {
+                    tracing::warn!("Execution {} stopped: {}", exec_id, message);
+                    if let Some(msg_store) = msg_stores.read().await.get(&exec_id) {
+                        msg_store.push_stderr(format!("{message}\n"));
+                    }
+                    (None, ExecutionProcessStatus::LimitExceeded)
+                }
+                None => (exit_code, status),
+            };
 
             if !ExecutionProcess::was_stopped(&db.pool, exec_id).await
                 && let Err(e) =
@@ -353,6 +435,9 @@ impl LocalContainerService {
             {
                 tracing::error!("Failed to update execution process completion: {}", e);
             }
+            if let Ok(Some(process)) = ExecutionProcess::find_by_id(&db.pool, exec_id).await {
+                Self::record_process_finished(&process);
+            }
 
             if let Ok(ctx) = ExecutionProcess::load_context(&db.pool, exec_id).await {
                 // Update executor session summary if available
@@ -452,23 +537,70 @@ impl LocalContainerService {
                 }
             }
 
-            // Cleanup msg store
-            if let Some(msg_arc) = msg_stores.write().await.remove(&exec_id) {
-                msg_arc.push_finished();
-                tokio::time::sleep(Duration::from_millis(50)).await; // Wait for the finish message to propogate
-                match Arc::try_unwrap(msg_arc) {
-                    Ok(inner) => drop(inner),
-                    Err(arc) => tracing::error!(
-                        "There are still {} strong Arcs to MsgStore for {}",
-                        Arc::strong_count(&arc),
-                        exec_id
-                    ),
+            // Finish the msg store, unless stopping the process already did
+            container.finish_msg_store(exec_id).await;
+
+            // Cleanup child handle and close the steering channel
+            child_store.write().await.remove(&exec_id);
+            steering.write().await.remove(&exec_id);
+        };
+        tokio::spawn(monitor.instrument(span))
+    }
+
+    /// Mark an execution's msg store finished, then store its conversation and drop the store
+    /// in the background, as waiting for the normalizers to settle takes a while. Shared by the
+    /// exit monitor and `stop_execution`: whichever finishes the store persists it, and the
+    /// store stays readable until the conversation is stored.
+    async fn finish_msg_store(&self, exec_id: Uuid) {
+        let Some(store) = self.msg_stores.read().await.get(&exec_id).cloned() else {
+            return;
+        };
+        if !store.push_finished() {
+            return;
+        }
+
+        let container = self.clone();
+        tokio::spawn(
+            async move {
+                container.persist_conversation(&exec_id, &store).await;
+                drop(store);
+                if let Some(msg_arc) = container.msg_stores.write().await.remove(&exec_id) {
+                    match Arc::try_unwrap(msg_arc) {
+                        Ok(inner) => drop(inner),
+                        Err(arc) => tracing::error!(
+                            "There are still {} strong Arcs to MsgStore for {}",
+                            Arc::strong_count(&arc),
+                            exec_id
+                        ),
+                    }
                 }
             }
+            // Keeps the process span, which parents the tool call spans of the conversation
+            .instrument(tracing::Span::current()),
+        );
+    }
 
-            // Cleanup child handle
-            child_store.write().await.remove(&exec_id);
-        })
+    /// Export the outcome of a finished process on its span and as metrics
+    fn record_process_finished(process: &ExecutionProcess) {
+        let run_reason = process.run_reason.to_string();
+        let status = process.status.to_string();
+        let executor = process
+            .executor_action()
+            .ok()
+            .and_then(ExecutorAction::base_executor)
+            .map(|executor| executor.to_string());
+
+        let span = tracing::Span::current();
+        span.record("run_reason", run_reason.as_str());
+        span.record("status", status.as_str());
+        if let Some(executor) = &executor {
+            span.record("executor", executor.as_str());
+        }
+
+        let duration = (process.completed_at.unwrap_or_else(Utc::now) - process.started_at)
+            .to_std()
+            .unwrap_or_default();
+        telemetry::process_finished(&run_reason, executor.as_deref(), &status, duration);
     }
 
     pub fn spawn_os_exit_watcher(
@@ -513,19 +645,26 @@ impl LocalContainerService {
         format!("{}-{}", short_uuid(attempt_id), task_title_id)
     }
 
-    async fn track_child_msgs_in_store(&self, id: Uuid, child: &mut AsyncGroupChild) {
+    async fn track_child_msgs_in_store(
+        &self,
+        id: Uuid,
+        child: &mut AsyncGroupChild,
+        redactor: Redactor,
+    ) {
         let store = Arc::new(MsgStore::new());
 
         let out = child.inner().stdout.take().expect("no stdout");
         let err = child.inner().stderr.take().expect("no stderr");
 
-        // Map stdout bytes -> LogMsg::Stdout
-        let out = ReaderStream::new(out)
-            .map_ok(|chunk| LogMsg::Stdout(String::from_utf8_lossy(&chunk).into_owned()));
+        // Map stdout bytes -> LogMsg::Stdout, hiding secrets before anything is stored
+        let out =
+            ReaderStream::new(out).map_ok(|chunk| String::from_utf8_lossy(&chunk).into_owned());
+        let out = redactor.clone().redact_stream(out).map_ok(LogMsg::Stdout);
 
         // Map stderr bytes -> LogMsg::Stderr
-        let err = ReaderStream::new(err)
-            .map_ok(|chunk| LogMsg::Stderr(String::from_utf8_lossy(&chunk).into_owned()));
+        let err =
+            ReaderStream::new(err).map_ok(|chunk| String::from_utf8_lossy(&chunk).into_owned());
+        let err = redactor.redact_stream(err).map_ok(LogMsg::Stderr);
 
         // If you have a JSON Patch source, map it to LogMsg::JsonPatch too, then select all three.
 
@@ -792,11 +931,36 @@ impl ContainerService for LocalContainerService {
         }
     }
 
+    /// Limits from the profile the action runs with, or the attempt's latest profile for scripts
+    async fn resource_limits_for(
+        &self,
+        task_attempt: &TaskAttempt,
+        execution_process: &ExecutionProcess,
+        executor_action: &ExecutorAction,
+    ) -> Option<ResourceLimits> {
+        let profile_id = match executor_action.executor_profile_id() {
+            Some(profile_id) => profile_id,
+            None => ExecutionProcess::latest_executor_profile_for_attempt(
+                &self.db.pool,
+                task_attempt.id,
+            )
+            .await
+            .ok()?,
+        };
+        let limits = ExecutorConfigs::get_cached()
+            .get_coding_agent(&profile_id)?
+            .cmd_overrides()
+            .limits
+            .clone()?;
+        Some(limits_for_run_reason(&execution_process.run_reason, limits))
+    }
+
     async fn start_execution_inner(
         &self,
         task_attempt: &TaskAttempt,
         execution_process: &ExecutionProcess,
         executor_action: &ExecutorAction,
+        prompt_context: &PromptContext,
     ) -> Result<(), ContainerError> {
         // Get the worktree path
         let container_ref = task_attempt
@@ -817,19 +981,60 @@ impl ContainerService for LocalContainerService {
                 _ => Arc::new(NoopExecutorApprovalService {}),
             };
 
+        // Project env vars and secrets are passed to the process and redacted from its output
+        let project_id = task_attempt
+            .parent_task(&self.db.pool)
+            .await?
+            .ok_or(ContainerError::Other(anyhow!(
+                "Task not found for task attempt"
+            )))?
+            .project_id;
+        let mut env = self.project_env.env_for_project(project_id).await;
+        let redactor = self.project_env.redactor_for_project(project_id).await;
+
+        // Lets the MCP task server default to this attempt's scope
+        env.insert(PROJECT_ID_ENV.to_string(), project_id.to_string());
+        env.insert(TASK_ID_ENV.to_string(), task_attempt.task_id.to_string());
+        env.insert(TASK_ATTEMPT_ID_ENV.to_string(), task_attempt.id.to_string());
+
+        // The cgroup has to exist before the spawn so the child can join it before it execs
+        let resource_guard = self
+            .resource_limits_for(task_attempt, execution_process, executor_action)
+            .await
+            .map(|limits| ResourceGuard::create(execution_process.id, limits));
+        if let Some(procs) = resource_guard
+            .as_ref()
+            .and_then(ResourceGuard::cgroup_procs)
+        {
+            env.insert(
+                CGROUP_PROCS_ENV.to_string(),
+                procs.to_string_lossy().into_owned(),
+            );
+        }
+
         // Create the child and stream, add to execution tracker
         let mut spawned = executor_action
-            .spawn(&current_dir, approvals_service)
+            .spawn(&current_dir, approvals_service, &env, prompt_context)
             .await?;
 
-        self.track_child_msgs_in_store(execution_process.id, &mut spawned.child)
+        self.track_child_msgs_in_store(execution_process.id, &mut spawned.child, redactor)
             .await;
 
+        if let Some(steering) = spawned.steering {
+            self.steering
+                .write()
+                .await
+                .insert(execution_process.id, steering);
+        }
+
         self.add_child_to_store(execution_process.id, spawned.child)
             .await;
 
-        // Spawn unified exit monitor: watches OS exit and optional executor signal
-        let _hn = self.spawn_exit_monitor(&execution_process.id, spawned.exit_signal);
+        telemetry::process_started(&execution_process.run_reason.to_string());
+
+        // Spawn unified exit monitor: watches OS exit, optional executor signal and limits
+        let _hn =
+            self.spawn_exit_monitor(&execution_process.id, spawned.exit_signal, resource_guard);
 
         Ok(())
     }
@@ -867,11 +1072,10 @@ impl ContainerService for LocalContainerService {
             }
         }
         self.remove_child_from_store(&execution_process.id).await;
+        self.steering.write().await.remove(&execution_process.id);
 
-        // Mark the process finished in the MsgStore
-        if let Some(msg) = self.msg_stores.write().await.remove(&execution_process.id) {
-            msg.push_finished();
-        }
+        // Mark the process finished in the MsgStore and store its conversation
+        self.finish_msg_store(execution_process.id).await;
 
         // Update task status to InReview when execution is stopped
         if let Ok(ctx) = ExecutionProcess::load_context(&self.db.pool, execution_process.id).await
@@ -879,8 +1083,14 @@ impl ContainerService for LocalContainerService {
                 ctx.execution_process.run_reason,
                 ExecutionProcessRunReason::DevServer
             )
-            && let Err(e) =
-                Task::update_status(&self.db.pool, ctx.task.id, TaskStatus::InReview).await
+            && let Err(e) = Task::transition_status(
+                &self.db.pool,
+                ctx.task.id,
+                TaskStatus::InReview,
+                AuditActor::System,
+                Some(ctx.task_attempt.id),
+            )
+            .await
         {
             tracing::error!("Failed to update task status to InReview: {e}");
         }
@@ -950,6 +1160,36 @@ impl ContainerService for LocalContainerService {
         Ok(Box::pin(wrapper))
     }
 
+    async fn steer_execution(
+        &self,
+        execution_process: &ExecutionProcess,
+        message: &str,
+    ) -> Result<bool, ContainerError> {
+        let Some(steering) = self
+            .steering
+            .read()
+            .await
+            .get(&execution_process.id)
+            .cloned()
+        else {
+            return Ok(false);
+        };
+
+        match steering.send(message).await {
+            Ok(()) => Ok(true),
+            Err(e) => {
+                tracing::debug!(
+                    "Could not steer execution process {}: {}",
+                    execution_process.id,
+                    e
+                );
+                // The agent stopped accepting input, later messages go straight to the queue
+                self.steering.write().await.remove(&execution_process.id);
+                Ok(false)
+            }
+        }
+    }
+
     async fn try_commit_changes(&self, ctx: &ExecutionContext) -> Result<bool, ContainerError> {
         if !matches!(
             ctx.execution_process.run_reason,
@@ -1262,6 +1502,7 @@ impl LocalContainerService {
         // Clear the draft to reflect that it has been consumed
         let _ =
             Draft::clear_after_send(&self.db.pool, ctx.task_attempt.id, DraftType::FollowUp).await;
+        DraftsService::record_queue_depth(&self.db.pool).await;
 
         Ok(())
     }
@@ -1286,6 +1527,128 @@ fn truncate_to_char_boundary(content: &str, max_len: usize) -> &str {
 
 #[cfg(test)]
 mod tests {
+    use command_group::AsyncCommandGroup;
+    use db::models::{
+        execution_process_conversation::ExecutionProcessConversation, project::CreateProject,
+    };
+    use executors::logs::NormalizedEntry;
+
+    use super::*;
+
+    /// A container over an in-memory database, with a running process started by `sleep`
+    async fn running_process() -> (LocalContainerService, ExecutionProcess) {
+        let db = DBService::new_in_memory().await.unwrap();
+        let (project_id, task_id, attempt_id, execution_id) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+        Project::create(
+            &db.pool,
+            &CreateProject::from_name_repo_path("p".to_string(), project_id.to_string()),
+            project_id,
+        )
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO tasks (id, project_id, title) VALUES (?, ?, 't')")
+            .bind(task_id)
+            .bind(project_id)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO task_attempts (id, task_id) VALUES (?, ?)")
+            .bind(attempt_id)
+            .bind(task_id)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO execution_processes (id, task_attempt_id, run_reason, executor_action, status)
+             VALUES (?, ?, 'codingagent', '{}', 'running')",
+        )
+        .bind(execution_id)
+        .bind(attempt_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+        let process = ExecutionProcess::find_by_id(&db.pool, execution_id)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let msg_stores = Arc::new(RwLock::new(HashMap::new()));
+        let container = LocalContainerService::new(
+            db.clone(),
+            msg_stores.clone(),
+            Arc::new(RwLock::new(Config::default())),
+            GitService::new(),
+            ImageService::new(db.pool.clone()).unwrap(),
+            None,
+            Approvals::new(msg_stores),
+            ProjectEnvService::new().unwrap(),
+        );
+        let child = tokio::process::Command::new("sleep")
+            .arg("30")
+            .group_spawn()
+            .unwrap();
+        container.add_child_to_store(execution_id, child).await;
+        (container, process)
+    }
+
+    #[tokio::test]
+    async fn stopped_process_gets_a_stored_conversation() {
+        let (container, process) = running_process().await;
+        let store = Arc::new(MsgStore::new());
+        store.push_patch(ConversationPatch::add_normalized_entry(
+            0,
+            NormalizedEntry {
+                timestamp: None,
+                entry_type: NormalizedEntryType::AssistantMessage,
+                content: "done".to_string(),
+                metadata: None,
+            },
+        ));
+        container
+            .msg_stores
+            .write()
+            .await
+            .insert(process.id, store.clone());
+
+        container
+            .stop_execution(&process, ExecutionProcessStatus::Killed)
+            .await
+            .unwrap();
+        // The store stays readable until its conversation is stored
+        assert!(container.msg_stores.read().await.contains_key(&process.id));
+        drop(store);
+
+        let conversation = tokio::time::timeout(Duration::from_secs(30), async {
+            loop {
+                if let Some(conversation) = ExecutionProcessConversation::find_by_execution_id(
+                    &container.db.pool,
+                    process.id,
+                )
+                .await
+                .unwrap()
+                {
+                    return conversation;
+                }
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("conversation of the stopped process was not stored");
+        assert_eq!(conversation.entry_count, 1);
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while container.msg_stores.read().await.contains_key(&process.id) {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("msg store was not dropped");
+    }
 
     #[test]
     fn test_truncate_to_char_boundary() {
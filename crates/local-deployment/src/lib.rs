@@ -11,11 +11,13 @@ use services::services::{
     config::{Config, load_config_from_file, save_config_to_file},
     container::ContainerService,
     drafts::DraftsService,
-    events::EventService,
+    events::{EventService, TaskBatches},
     file_search_cache::FileSearchCache,
     filesystem::FilesystemService,
     git::GitService,
     image::ImageService,
+    project_env::ProjectEnvService,
+    scheduler::ScheduleService,
 };
 use tokio::sync::RwLock;
 use utils::{assets::config_path, msg_store::MsgStore};
@@ -24,6 +26,7 @@ use uuid::Uuid;
 use crate::container::LocalContainerService;
 mod command;
 pub mod container;
+mod limits;
 
 #[derive(Clone)]
 pub struct LocalDeployment {
@@ -41,6 +44,7 @@ pub struct LocalDeployment {
     file_search_cache: Arc<FileSearchCache>,
     approvals: Approvals,
     drafts: DraftsService,
+    project_env: ProjectEnvService,
 }
 
 #[async_trait]
@@ -79,14 +83,17 @@ impl Deployment for LocalDeployment {
         let filesystem = FilesystemService::new();
 
         // Create shared components for EventService
-        let events_msg_store = Arc::new(MsgStore::new());
+        // Event subscribers only need recent history, so keep it bounded in memory
+        let events_msg_store = Arc::new(MsgStore::bounded(10 * 1024 * 1024));
         let events_entry_count = Arc::new(RwLock::new(0));
+        let task_batches = TaskBatches::default();
 
         // Create DB with event hooks
         let db = {
             let hook = EventService::create_hook(
                 events_msg_store.clone(),
                 events_entry_count.clone(),
+                task_batches.clone(),
                 DBService::new().await?, // Temporary DB service for the hook
             );
             DBService::new_with_after_connect(hook).await?
@@ -104,6 +111,7 @@ impl Deployment for LocalDeployment {
         }
 
         let approvals = Approvals::new(msg_stores.clone());
+        let project_env = ProjectEnvService::new()?;
 
         // We need to make analytics accessible to the ContainerService
         // TODO: Handle this more gracefully
@@ -119,10 +127,16 @@ impl Deployment for LocalDeployment {
             image.clone(),
             analytics_ctx,
             approvals.clone(),
+            project_env.clone(),
         );
         container.spawn_worktree_cleanup().await;
 
-        let events = EventService::new(db.clone(), events_msg_store, events_entry_count);
+        let events = EventService::new(
+            db.clone(),
+            events_msg_store,
+            events_entry_count,
+            task_batches,
+        );
         let drafts = DraftsService::new(db.clone(), image.clone());
         let file_search_cache = Arc::new(FileSearchCache::new());
 
@@ -141,6 +155,7 @@ impl Deployment for LocalDeployment {
             file_search_cache,
             approvals,
             drafts,
+            project_env,
         })
     }
 
@@ -202,4 +217,12 @@ impl Deployment for LocalDeployment {
     fn drafts(&self) -> &DraftsService {
         &self.drafts
     }
+
+    fn project_env(&self) -> &ProjectEnvService {
+        &self.project_env
+    }
+
+    async fn spawn_schedule_service(&self) -> tokio::task::JoinHandle<()> {
+        ScheduleService::spawn(self.db.clone(), self.container.clone(), self.git.clone()).await
+    }
 }
@@ -0,0 +1,273 @@
+//! Resource limits for execution processes.
+//!
+//! Memory and CPU limits go through a cgroup v2 group next to (or under) the server's own cgroup
+//! when that part of the hierarchy is writable, and are skipped otherwise. The group is created
+//! before the process is spawned and the child joins it before it execs (see
+//! [`executors::command::group_spawn`]), so everything it starts is limited from the start.
+//! Rlimits aren't used as a fallback: an address space limit breaks runtimes that reserve large
+//! mappings up front (Node, the JVM, Go) and going over it couldn't be reported as a limit being
+//! exceeded. The wall-clock limit is enforced by the exit monitor.
+
+use std::time::Duration;
+
+use db::models::execution_process::ExecutionProcessRunReason;
+use executors::command::ResourceLimits;
+use uuid::Uuid;
+
+/// The limits that apply to a process started for `run_reason`. Dev servers run until they're
+/// stopped, so the wall-clock limit doesn't apply to them.
+pub fn limits_for_run_reason(
+    run_reason: &ExecutionProcessRunReason,
+    mut limits: ResourceLimits,
+) -> ResourceLimits {
+    if matches!(run_reason, ExecutionProcessRunReason::DevServer) {
+        limits.timeout_secs = None;
+    }
+    limits
+}
+
+pub struct ResourceGuard {
+    limits: ResourceLimits,
+    #[cfg(target_os = "linux")]
+    cgroup: Option<std::path::PathBuf>,
+}
+
+impl ResourceGuard {
+    /// Set up `limits` for an execution process that is about to be spawned
+    pub fn create(exec_id: Uuid, limits: ResourceLimits) -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            let cgroup = linux::create(exec_id, &limits);
+            Self { limits, cgroup }
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = exec_id;
+            if limits.cpus.is_some() || limits.memory_mb.is_some() {
+                tracing::warn!("CPU and memory limits are only supported on Linux");
+            }
+            Self { limits }
+        }
+    }
+
+    /// The `cgroup.procs` file the spawned process has to join, if there is a cgroup. It goes
+    /// into the process's env as [`executors::command::CGROUP_PROCS_ENV`].
+    pub fn cgroup_procs(&self) -> Option<std::path::PathBuf> {
+        #[cfg(target_os = "linux")]
+        {
+            self.cgroup
+                .as_ref()
+                .map(|cgroup| cgroup.join("cgroup.procs"))
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            None
+        }
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.limits.timeout_secs.map(Duration::from_secs)
+    }
+
+    pub fn timeout_message(&self) -> String {
+        format!(
+            "Process exceeded its wall-clock limit of {}s and was stopped",
+            self.limits.timeout_secs.unwrap_or_default()
+        )
+    }
+
+    /// Set when the kernel killed a process in the group for going over the memory limit
+    pub fn memory_exceeded_message(&self) -> Option<String> {
+        #[cfg(target_os = "linux")]
+        if let Some(cgroup) = &self.cgroup
+            && linux::oom_killed(cgroup)
+        {
+            return Some(format!(
+                "Process exceeded its memory limit of {} MiB and was killed",
+                self.limits.memory_mb.unwrap_or_default()
+            ));
+        }
+        None
+    }
+}
+
+impl Drop for ResourceGuard {
+    fn drop(&mut self) {
+        #[cfg(target_os = "linux")]
+        if let Some(cgroup) = self.cgroup.take() {
+            linux::remove(&cgroup);
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::{
+        fs, io,
+        path::{Path, PathBuf},
+    };
+
+    use executors::command::ResourceLimits;
+    use uuid::Uuid;
+
+    const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+    const CPU_PERIOD_US: u64 = 100_000;
+
+    pub fn create(exec_id: Uuid, limits: &ResourceLimits) -> Option<PathBuf> {
+        if limits.cpus.is_none() && limits.memory_mb.is_none() {
+            return None;
+        }
+
+        match create_cgroup(exec_id, limits) {
+            Ok(cgroup) => Some(cgroup),
+            Err(e) => {
+                tracing::warn!(
+                    "CPU and memory limits need a writable cgroup v2 hierarchy, skipping: {}",
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    /// Try the server's own cgroup, then its parent (e.g. a delegated systemd slice)
+    fn create_cgroup(exec_id: Uuid, limits: &ResourceLimits) -> io::Result<PathBuf> {
+        let own = own_cgroup().ok_or_else(|| io::Error::other("cgroup v2 is not mounted"))?;
+        let mut controllers = Vec::new();
+        if limits.memory_mb.is_some() {
+            controllers.push("memory");
+        }
+        if limits.cpus.is_some() {
+            controllers.push("cpu");
+        }
+
+        let mut last_err = io::Error::other("no writable cgroup found");
+        for parent in [Some(own.as_path()), own.parent()].into_iter().flatten() {
+            if !parent.starts_with(CGROUP_ROOT) {
+                continue;
+            }
+            match create_child(parent, exec_id, &controllers) {
+                Ok(dir) => {
+                    if let Err(e) = write_limits(&dir, limits) {
+                        remove(&dir);
+                        last_err = e;
+                        continue;
+                    }
+                    return Ok(dir);
+                }
+                Err(e) => last_err = e,
+            }
+        }
+        Err(last_err)
+    }
+
+    fn create_child(parent: &Path, exec_id: Uuid, controllers: &[&str]) -> io::Result<PathBuf> {
+        let enabled = fs::read_to_string(parent.join("cgroup.subtree_control"))?;
+        let missing: Vec<String> = controllers
+            .iter()
+            .filter(|c| !enabled.split_whitespace().any(|e| e == **c))
+            .map(|c| format!("+{c}"))
+            .collect();
+        if !missing.is_empty() {
+            fs::write(parent.join("cgroup.subtree_control"), missing.join(" "))?;
+        }
+
+        let dir = parent.join(format!("vibe-kanban-{exec_id}"));
+        fs::create_dir(&dir)?;
+        Ok(dir)
+    }
+
+    fn write_limits(dir: &Path, limits: &ResourceLimits) -> io::Result<()> {
+        if let Some(memory_mb) = limits.memory_mb {
+            fs::write(
+                dir.join("memory.max"),
+                (memory_mb * 1024 * 1024).to_string(),
+            )?;
+            // Without this the group can keep growing into swap instead of being OOM killed
+            let _ = fs::write(dir.join("memory.swap.max"), "0");
+        }
+        if let Some(cpus) = limits.cpus {
+            let quota = ((cpus * CPU_PERIOD_US as f64) as u64).max(1_000);
+            fs::write(dir.join("cpu.max"), format!("{quota} {CPU_PERIOD_US}"))?;
+        }
+        Ok(())
+    }
+
+    fn own_cgroup() -> Option<PathBuf> {
+        let content = fs::read_to_string("/proc/self/cgroup").ok()?;
+        let relative = content.lines().find_map(|line| line.strip_prefix("0::"))?;
+        Some(Path::new(CGROUP_ROOT).join(relative.trim_start_matches('/')))
+    }
+
+    pub fn oom_killed(cgroup: &Path) -> bool {
+        fs::read_to_string(cgroup.join("memory.events"))
+            .ok()
+            .and_then(|events| {
+                events.lines().find_map(|line| {
+                    line.strip_prefix("oom_kill ")
+                        .and_then(|n| n.trim().parse::<u64>().ok())
+                })
+            })
+            .is_some_and(|count| count > 0)
+    }
+
+    pub fn remove(cgroup: &Path) {
+        // Anything left behind (e.g. a daemonised child) has to go before the group can be removed
+        let _ = fs::write(cgroup.join("cgroup.kill"), "1");
+        if let Err(e) = fs::remove_dir(cgroup) {
+            tracing::debug!("Failed to remove cgroup {:?}: {}", cgroup, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> ResourceLimits {
+        ResourceLimits {
+            cpus: None,
+            memory_mb: Some(512),
+            timeout_secs: Some(60),
+        }
+    }
+
+    #[test]
+    fn dev_servers_are_exempt_from_the_wall_clock_limit() {
+        let dev_server = limits_for_run_reason(&ExecutionProcessRunReason::DevServer, limits());
+        assert_eq!(dev_server.timeout_secs, None);
+        assert_eq!(dev_server.memory_mb, Some(512));
+
+        for run_reason in [
+            ExecutionProcessRunReason::CodingAgent,
+            ExecutionProcessRunReason::SetupScript,
+            ExecutionProcessRunReason::CleanupScript,
+        ] {
+            assert_eq!(limits_for_run_reason(&run_reason, limits()), limits());
+        }
+    }
+
+    #[test]
+    fn guard_reports_the_configured_timeout() {
+        let guard = ResourceGuard::create(
+            Uuid::new_v4(),
+            ResourceLimits {
+                memory_mb: None,
+                ..limits()
+            },
+        );
+        assert_eq!(guard.timeout(), Some(Duration::from_secs(60)));
+        assert_eq!(
+            guard.timeout_message(),
+            "Process exceeded its wall-clock limit of 60s and was stopped"
+        );
+        // No cgroup was created, so nothing can have been OOM killed
+        assert_eq!(guard.cgroup_procs(), None);
+        assert_eq!(guard.memory_exceeded_message(), None);
+
+        let guard = ResourceGuard::create(Uuid::new_v4(), ResourceLimits::default());
+        assert_eq!(guard.timeout(), None);
+    }
+}
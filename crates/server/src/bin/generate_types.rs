@@ -29,12 +29,27 @@ fn generate_types_content() -> String {
         db::models::tag::CreateTag::decl(),
         db::models::tag::UpdateTag::decl(),
         server::routes::tags::TagSearchParams::decl(),
+        server::routes::templates::PreviewTemplateRequest::decl(),
+        server::routes::templates::TemplatePreview::decl(),
+        services::services::prompt_template::TemplateVariable::decl(),
         db::models::task::TaskStatus::decl(),
+        db::models::task::TaskPriority::decl(),
         db::models::task::Task::decl(),
+        db::models::task::TaskFields::decl(),
         db::models::task::TaskWithAttemptStatus::decl(),
         db::models::task::TaskRelationships::decl(),
         db::models::task::CreateTask::decl(),
+        db::models::task::TaskFilter::decl(),
+        db::models::task::UpdateTaskFields::decl(),
         db::models::task::UpdateTask::decl(),
+        db::models::label::Label::decl(),
+        db::models::label::CreateLabel::decl(),
+        db::models::label::UpdateLabel::decl(),
+        db::models::custom_field::CustomFieldType::decl(),
+        db::models::custom_field::CustomField::decl(),
+        db::models::custom_field::CreateCustomField::decl(),
+        db::models::custom_field::UpdateCustomField::decl(),
+        db::models::custom_field::TaskCustomFieldValue::decl(),
         db::models::image::Image::decl(),
         db::models::image::CreateImage::decl(),
         utils::response::ApiResponse::<()>::decl(),
@@ -47,9 +62,34 @@ fn generate_types_content() -> String {
         services::services::drafts::DraftResponse::decl(),
         services::services::drafts::UpdateFollowUpDraftRequest::decl(),
         services::services::drafts::UpdateRetryFollowUpDraftRequest::decl(),
+        services::services::drafts::SteerRequest::decl(),
+        services::services::drafts::SteerResponse::decl(),
+        services::services::project_env::ProjectEnvVar::decl(),
+        services::services::project_env::ProjectEnvVarInput::decl(),
+        services::services::project_env::UpdateProjectEnv::decl(),
+        db::models::project_instructions::ProjectInstructions::decl(),
+        db::models::project_instructions::UpdateProjectInstructions::decl(),
+        db::models::project_instructions::ProjectInstructionVersion::decl(),
+        db::models::task_schedule::ScheduleCatchUp::decl(),
+        db::models::task_schedule::ScheduleRunStatus::decl(),
+        db::models::task_schedule::TaskSchedule::decl(),
+        db::models::task_schedule::CreateTaskSchedule::decl(),
+        db::models::task_schedule::UpdateTaskSchedule::decl(),
+        db::models::task_schedule::TaskScheduleRun::decl(),
+        db::models::issue_tracker::IssueTrackerKind::decl(),
+        db::models::issue_tracker::IssueTracker::decl(),
+        db::models::issue_tracker::CreateIssueTracker::decl(),
+        db::models::issue_tracker::UpdateIssueTracker::decl(),
+        db::models::issue_tracker::TaskIssueLink::decl(),
+        services::services::issue_sync::SyncSummary::decl(),
         server::routes::task_attempts::ChangeTargetBranchRequest::decl(),
         server::routes::task_attempts::ChangeTargetBranchResponse::decl(),
         server::routes::tasks::CreateAndStartTaskRequest::decl(),
+        server::routes::tasks::BulkTaskIdsRequest::decl(),
+        server::routes::tasks::BulkUpdateTaskStatusRequest::decl(),
+        server::routes::tasks::BulkMoveTasksRequest::decl(),
+        server::routes::tasks::BulkStartTasksRequest::decl(),
+        server::routes::tasks::BulkTaskResult::decl(),
         server::routes::task_attempts::CreateGitHubPrRequest::decl(),
         server::routes::images::ImageResponse::decl(),
         services::services::github_service::GitHubServiceError::decl(),
@@ -62,6 +102,7 @@ fn generate_types_content() -> String {
         services::services::config::SoundFile::decl(),
         services::services::config::UiLanguage::decl(),
         services::services::config::ShowcaseState::decl(),
+        services::services::config::RetentionConfig::decl(),
         services::services::auth::DeviceFlowStartResponse::decl(),
         server::routes::auth::DevicePollStatus::decl(),
         server::routes::auth::CheckTokenResponse::decl(),
@@ -70,6 +111,7 @@ fn generate_types_content() -> String {
         utils::diff::DiffChangeKind::decl(),
         services::services::github_service::RepositoryInfo::decl(),
         executors::command::CommandBuilder::decl(),
+        executors::command::ResourceLimits::decl(),
         executors::profile::ExecutorProfileId::decl(),
         executors::profile::ExecutorConfig::decl(),
         executors::executors::BaseAgentCapability::decl(),
@@ -87,6 +129,18 @@ fn generate_types_content() -> String {
         executors::executors::copilot::Copilot::decl(),
         executors::executors::opencode::Opencode::decl(),
         executors::executors::qwen::QwenCode::decl(),
+        executors::executors::acp::Acp::decl(),
+        executors::executors::generic_cli::GenericCli::decl(),
+        executors::executors::generic_cli::PromptInput::decl(),
+        executors::executors::generic_cli::OutputParser::decl(),
+        executors::executors::generic_cli::JsonLineRule::decl(),
+        executors::executors::generic_cli::RegexRule::decl(),
+        executors::executors::generic_cli::EntryMapping::decl(),
+        executors::executors::generic_cli::MappedEntryType::decl(),
+        executors::executors::generic_cli::MappedAction::decl(),
+        executors::executors::generic_cli::UnmatchedLines::decl(),
+        executors::executors::generic_cli::SessionResume::decl(),
+        executors::executors::generic_cli::SessionIdExtractor::decl(),
         executors::executors::AppendPrompt::decl(),
         executors::actions::coding_agent_initial::CodingAgentInitialRequest::decl(),
         executors::actions::coding_agent_follow_up::CodingAgentFollowUpRequest::decl(),
@@ -101,11 +155,20 @@ fn generate_types_content() -> String {
         db::models::execution_process::ExecutionProcess::decl(),
         db::models::execution_process::ExecutionProcessStatus::decl(),
         db::models::execution_process::ExecutionProcessRunReason::decl(),
+        db::models::execution_process_logs::ExecutionProcessLogPage::decl(),
+        db::models::execution_process_logs::ExecutionProcessLogPrune::decl(),
+        db::models::execution_process_logs::LogPruneReason::decl(),
+        services::services::retention::RetentionReport::decl(),
+        services::services::retention::RetentionCandidate::decl(),
         db::models::merge::Merge::decl(),
         db::models::merge::DirectMerge::decl(),
         db::models::merge::PrMerge::decl(),
         db::models::merge::MergeStatus::decl(),
         db::models::merge::PullRequestInfo::decl(),
+        db::models::audit_event::AuditEvent::decl(),
+        db::models::audit_event::AuditActor::decl(),
+        db::models::audit_event::AuditAction::decl(),
+        db::models::audit_event::AuditQuery::decl(),
         db::models::draft::Draft::decl(),
         db::models::draft::DraftType::decl(),
         executors::logs::CommandExitStatus::decl(),
@@ -195,6 +258,14 @@ fn generate_schemas() -> Result<HashMap<&'static str, String>, serde_json::Error
             "copilot",
             generate_json_schema::<executors::executors::copilot::Copilot>()?,
         ),
+        (
+            "acp",
+            generate_json_schema::<executors::executors::acp::Acp>()?,
+        ),
+        (
+            "generic_cli",
+            generate_json_schema::<executors::executors::generic_cli::GenericCli>()?,
+        ),
     ]);
     println!(
         "✅ JSON schemas generated. {} schemas created.",
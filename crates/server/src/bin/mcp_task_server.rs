@@ -52,13 +52,13 @@ fn main() -> anyhow::Result<()> {
                 url
             };
 
-            let service = TaskServer::new(&base_url)
-                .serve(stdio())
-                .await
-                .map_err(|e| {
-                    tracing::error!("serving error: {:?}", e);
-                    e
-                })?;
+            // Agents started by vibe-kanban get tools scoped to their own attempt
+            let server = TaskServer::new(&base_url).with_detected_scope().await;
+
+            let service = server.serve(stdio()).await.map_err(|e| {
+                tracing::error!("serving error: {:?}", e);
+                e
+            })?;
 
             service.waiting().await?;
             Ok(())
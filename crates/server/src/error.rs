@@ -5,7 +5,8 @@ use axum::{
     response::{IntoResponse, Response},
 };
 use db::models::{
-    execution_process::ExecutionProcessError, project::ProjectError, task_attempt::TaskAttemptError,
+    execution_process::ExecutionProcessError, execution_process_logs::ExecutionProcessLogsError,
+    project::ProjectError, task::TaskFieldsError, task_attempt::TaskAttemptError,
 };
 use deployment::DeploymentError;
 use executors::executors::ExecutorError;
@@ -13,10 +14,12 @@ use git2::Error as Git2Error;
 use services::services::{
     auth::AuthError, config::ConfigError, container::ContainerError, drafts::DraftsServiceError,
     git::GitServiceError, github_service::GitHubServiceError, image::ImageError,
+    issue_sync::IssueSyncError, project_env::ProjectEnvError,
+    project_instructions::ProjectInstructionsError, scheduler::ScheduleError,
     worktree_manager::WorktreeError,
 };
 use thiserror::Error;
-use utils::response::ApiResponse;
+use utils::{response::ApiResponse, template::TemplateError};
 
 #[derive(Debug, Error, ts_rs::TS)]
 #[ts(type = "string")]
@@ -28,6 +31,8 @@ pub enum ApiError {
     #[error(transparent)]
     ExecutionProcess(#[from] ExecutionProcessError),
     #[error(transparent)]
+    ExecutionProcessLogs(#[from] ExecutionProcessLogsError),
+    #[error(transparent)]
     GitService(#[from] GitServiceError),
     #[error(transparent)]
     GitHubService(#[from] GitHubServiceError),
@@ -49,12 +54,26 @@ pub enum ApiError {
     Image(#[from] ImageError),
     #[error(transparent)]
     Drafts(#[from] DraftsServiceError),
+    #[error(transparent)]
+    ProjectEnv(#[from] ProjectEnvError),
+    #[error(transparent)]
+    ProjectInstructions(#[from] ProjectInstructionsError),
+    #[error(transparent)]
+    IssueSync(#[from] IssueSyncError),
+    #[error(transparent)]
+    Schedule(#[from] ScheduleError),
+    #[error(transparent)]
+    TaskFields(#[from] TaskFieldsError),
+    #[error(transparent)]
+    Template(#[from] TemplateError),
     #[error("Multipart error: {0}")]
     Multipart(#[from] MultipartError),
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
     #[error("Conflict: {0}")]
     Conflict(String),
+    #[error("Bad request: {0}")]
+    BadRequest(String),
 }
 
 impl From<Git2Error> for ApiError {
@@ -74,6 +93,10 @@ impl IntoResponse for ApiError {
                 }
                 _ => (StatusCode::INTERNAL_SERVER_ERROR, "ExecutionProcessError"),
             },
+            ApiError::ExecutionProcessLogs(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "ExecutionProcessLogsError",
+            ),
             // Promote certain GitService errors to conflict status with concise messages
             ApiError::GitService(git_err) => match git_err {
                 services::services::git::GitServiceError::MergeConflicts(_) => {
@@ -111,9 +134,47 @@ impl IntoResponse for ApiError {
                     (StatusCode::INTERNAL_SERVER_ERROR, "ExecutionProcessError")
                 }
             },
+            ApiError::ProjectEnv(env_err) => match env_err {
+                ProjectEnvError::Validation(_) => (StatusCode::BAD_REQUEST, "ProjectEnvError"),
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "ProjectEnvError"),
+            },
+            ApiError::ProjectInstructions(instructions_err) => match instructions_err {
+                ProjectInstructionsError::Database(_) => {
+                    (StatusCode::INTERNAL_SERVER_ERROR, "DatabaseError")
+                }
+                _ => (StatusCode::BAD_REQUEST, "ProjectInstructionsError"),
+            },
+            ApiError::IssueSync(sync_err) => match sync_err {
+                IssueSyncError::InvalidTracker(_) | IssueSyncError::NoGitHubToken => {
+                    (StatusCode::BAD_REQUEST, "IssueSyncError")
+                }
+                IssueSyncError::ProjectNotFound => (StatusCode::NOT_FOUND, "IssueSyncError"),
+                // The tracker failed rather than us
+                IssueSyncError::GitHub(_) | IssueSyncError::Http(_) => {
+                    (StatusCode::BAD_GATEWAY, "IssueSyncError")
+                }
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "IssueSyncError"),
+            },
+            ApiError::Schedule(schedule_err) => match schedule_err {
+                ScheduleError::Cron(_) | ScheduleError::NeverRuns(_) => {
+                    (StatusCode::BAD_REQUEST, "ScheduleError")
+                }
+                ScheduleError::TemplateNotFound | ScheduleError::ProjectNotFound => {
+                    (StatusCode::NOT_FOUND, "ScheduleError")
+                }
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "ScheduleError"),
+            },
+            ApiError::TaskFields(fields_err) => match fields_err {
+                TaskFieldsError::Database(_) => {
+                    (StatusCode::INTERNAL_SERVER_ERROR, "TaskFieldsError")
+                }
+                _ => (StatusCode::BAD_REQUEST, "TaskFieldsError"),
+            },
+            ApiError::Template(_) => (StatusCode::BAD_REQUEST, "TemplateError"),
             ApiError::Io(_) => (StatusCode::INTERNAL_SERVER_ERROR, "IoError"),
             ApiError::Multipart(_) => (StatusCode::BAD_REQUEST, "MultipartError"),
             ApiError::Conflict(_) => (StatusCode::CONFLICT, "ConflictError"),
+            ApiError::BadRequest(_) => (StatusCode::BAD_REQUEST, "BadRequest"),
         };
 
         let error_message = match &self {
@@ -138,6 +199,32 @@ impl IntoResponse for ApiError {
             },
             ApiError::Multipart(_) => "Failed to upload file. Please ensure the file is valid and try again.".to_string(),
             ApiError::Conflict(msg) => msg.clone(),
+            ApiError::BadRequest(msg) => msg.clone(),
+            ApiError::ProjectEnv(ProjectEnvError::Validation(msg)) => msg.clone(),
+            ApiError::ProjectInstructions(
+                err @ (ProjectInstructionsError::Template(_)
+                | ProjectInstructionsError::InvalidSyncPath(_)),
+            ) => err.to_string(),
+            ApiError::IssueSync(
+                err @ (IssueSyncError::InvalidTracker(_)
+                | IssueSyncError::NoGitHubToken
+                | IssueSyncError::ProjectNotFound
+                | IssueSyncError::GitHub(_)
+                | IssueSyncError::Http(_)),
+            ) => err.to_string(),
+            ApiError::Schedule(
+                err @ (ScheduleError::Cron(_)
+                | ScheduleError::NeverRuns(_)
+                | ScheduleError::TemplateNotFound
+                | ScheduleError::ProjectNotFound),
+            ) => err.to_string(),
+            ApiError::TaskFields(
+                err @ (TaskFieldsError::UnknownLabel(_)
+                | TaskFieldsError::UnknownCustomField(_)
+                | TaskFieldsError::InvalidValue { .. }
+                | TaskFieldsError::InvalidOptions),
+            ) => err.to_string(),
+            ApiError::Template(err) => err.to_string(),
             ApiError::Drafts(drafts_err) => match drafts_err {
                 DraftsServiceError::Conflict(msg) => msg.clone(),
                 DraftsServiceError::Database(_) => format!("{}: {}", error_type, drafts_err),
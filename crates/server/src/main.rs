@@ -1,15 +1,20 @@
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
 use anyhow::{self, Error as AnyhowError};
+use db::models::execution_process_logs::ExecutionProcessLogs;
 use deployment::{Deployment, DeploymentError};
 use server::{DeploymentImpl, routes};
+use services::services::config::load_config_from_file;
 use sqlx::Error as SqlxError;
 use strip_ansi_escapes::strip;
 use thiserror::Error;
 use tracing_subscriber::{EnvFilter, prelude::*};
 use utils::{
-    assets::asset_dir,
+    assets::{asset_dir, config_path},
     browser::open_browser,
     port_file::write_port_file,
     sentry::{self as sentry_utils, SentrySource, sentry_layer},
+    telemetry,
 };
 
 #[derive(Debug, Error)]
@@ -33,11 +38,23 @@ async fn main() -> Result<(), VibeKanbanError> {
         "warn,server={level},services={level},db={level},executors={level},deployment={level},local_deployment={level},utils={level}",
         level = log_level
     );
-    let env_filter = EnvFilter::try_new(filter_string).expect("Failed to create tracing filter");
+    let env_filter = EnvFilter::try_new(&filter_string).expect("Failed to create tracing filter");
+
+    // OpenTelemetry export is configured before the deployment loads, so read the setting early
+    let otlp_endpoint = load_config_from_file(&config_path()).await.otlp_endpoint;
+    let otel = telemetry::init_once(otlp_endpoint.as_deref());
+    let otel_filter = EnvFilter::try_new(&filter_string).expect("Failed to create tracing filter");
+
     tracing_subscriber::registry()
         .with(tracing_subscriber::fmt::layer().with_filter(env_filter))
         .with(sentry_layer())
+        .with(telemetry::otel_layer().map(|layer| layer.with_filter(otel_filter)))
         .init();
+    match otel {
+        Ok(true) => tracing::info!("Exporting traces and metrics over OTLP"),
+        Ok(false) => {}
+        Err(e) => tracing::warn!("Failed to start OpenTelemetry export: {}", e),
+    }
 
     // Create asset directory if it doesn't exist
     if !asset_dir().exists() {
@@ -48,7 +65,20 @@ async fn main() -> Result<(), VibeKanbanError> {
     deployment.update_sentry_scope().await?;
     deployment.cleanup_orphan_executions().await?;
     deployment.backfill_before_head_commits().await?;
+
+    // Compress log chunks left open by the last run and logs from the old single-row format
+    let deployment_for_logs = deployment.clone();
+    tokio::spawn(async move {
+        match ExecutionProcessLogs::seal_open_chunks(&deployment_for_logs.db().pool).await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!("Compressed {} open log chunks", count),
+            Err(e) => tracing::warn!("Failed to compress open log chunks: {}", e),
+        }
+    });
+
     deployment.spawn_pr_monitor_service().await;
+    deployment.spawn_schedule_service().await;
+    deployment.spawn_issue_sync_service().await;
     deployment
         .track_if_analytics_allowed("session_start", serde_json::json!({}))
         .await;
@@ -65,8 +95,6 @@ async fn main() -> Result<(), VibeKanbanError> {
         }
     });
 
-    let app_router = routes::router(deployment);
-
     let port = std::env::var("BACKEND_PORT")
         .or_else(|_| std::env::var("PORT"))
         .ok()
@@ -83,7 +111,8 @@ async fn main() -> Result<(), VibeKanbanError> {
 
     let host = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
     let listener = tokio::net::TcpListener::bind(format!("{host}:{port}")).await?;
-    let actual_port = listener.local_addr()?.port(); // get → 53427 (example)
+    let local_addr = listener.local_addr()?;
+    let actual_port = local_addr.port(); // get → 53427 (example)
 
     // Write port file for discovery if prod, warn on fail
     if let Err(e) = write_port_file(actual_port).await {
@@ -92,6 +121,15 @@ async fn main() -> Result<(), VibeKanbanError> {
 
     tracing::info!("Server running on http://{host}:{actual_port}");
 
+    // The MCP endpoint calls back into the API, over loopback when bound to all interfaces
+    let backend_ip = if local_addr.ip().is_unspecified() {
+        IpAddr::V4(Ipv4Addr::LOCALHOST)
+    } else {
+        local_addr.ip()
+    };
+    let backend_url = format!("http://{}", SocketAddr::new(backend_ip, actual_port));
+    let app_router = routes::router(deployment, &backend_url);
+
     if !cfg!(debug_assertions) {
         tracing::info!("Opening browser...");
         tokio::spawn(async move {
@@ -106,5 +144,6 @@ async fn main() -> Result<(), VibeKanbanError> {
     }
 
     axum::serve(listener, app_router).await?;
+    telemetry::shutdown();
     Ok(())
 }
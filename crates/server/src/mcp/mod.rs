@@ -1 +1,3 @@
+pub mod resources;
+pub mod scope;
 pub mod task_server;
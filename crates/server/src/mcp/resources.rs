@@ -0,0 +1,441 @@
+//! MCP resources of the task server and their `resources/updated` notifications, which are
+//! fed by the backend's `/api/events` stream.
+
+use std::{
+    collections::HashSet,
+    fmt,
+    sync::{Arc, Mutex, Weak},
+    time::Duration,
+};
+
+use rmcp::{
+    Peer, RoleServer, ServiceError,
+    model::{
+        AnnotateAble, RawResourceTemplate, ResourceTemplate, ResourceUpdatedNotificationParam,
+    },
+};
+use serde_json::Value;
+use services::services::events::types::EV_RESYNC_REQUIRED;
+use tokio::task::JoinHandle;
+use utils::log_msg::EV_JSON_PATCH;
+use uuid::Uuid;
+
+pub const JSON_MIME_TYPE: &str = "application/json";
+
+/// How long to wait before reconnecting to the backend's event stream after it failed
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+/// A resource the task server can read, identified by a `vibe://` URI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VibeResource {
+    /// `vibe://project/{id}/tasks`
+    ProjectTasks(Uuid),
+    /// `vibe://attempt/{id}/diff`
+    AttemptDiff(Uuid),
+    /// `vibe://attempt/{id}/log`
+    AttemptLog(Uuid),
+}
+
+impl VibeResource {
+    pub fn parse(uri: &str) -> Option<Self> {
+        let rest = uri.strip_prefix("vibe://")?;
+        let mut segments = rest.split('/');
+        let (kind, id, leaf) = (segments.next()?, segments.next()?, segments.next()?);
+        if segments.next().is_some() {
+            return None;
+        }
+        let id = Uuid::parse_str(id).ok()?;
+        match (kind, leaf) {
+            ("project", "tasks") => Some(Self::ProjectTasks(id)),
+            ("attempt", "diff") => Some(Self::AttemptDiff(id)),
+            ("attempt", "log") => Some(Self::AttemptLog(id)),
+            _ => None,
+        }
+    }
+
+    pub fn uri(&self) -> String {
+        match self {
+            Self::ProjectTasks(id) => format!("vibe://project/{id}/tasks"),
+            Self::AttemptDiff(id) => format!("vibe://attempt/{id}/diff"),
+            Self::AttemptLog(id) => format!("vibe://attempt/{id}/log"),
+        }
+    }
+}
+
+pub fn resource_templates() -> Vec<ResourceTemplate> {
+    [
+        (
+            "vibe://project/{project_id}/tasks",
+            "project-tasks",
+            "The tasks of a project with their status and latest attempt state",
+        ),
+        (
+            "vibe://attempt/{attempt_id}/diff",
+            "attempt-diff",
+            "The changes a task attempt made to its worktree, with a unified patch per file",
+        ),
+        (
+            "vibe://attempt/{attempt_id}/log",
+            "attempt-log",
+            "The end of the conversation of the attempt's latest coding agent run",
+        ),
+    ]
+    .into_iter()
+    .map(|(uri_template, name, description)| {
+        RawResourceTemplate {
+            uri_template: uri_template.to_string(),
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            mime_type: Some(JSON_MIME_TYPE.to_string()),
+        }
+        .no_annotation()
+    })
+    .collect()
+}
+
+/// What a backend event may have changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Changed {
+    Resource(VibeResource),
+    /// A task was removed; its project isn't part of the event
+    AnyProjectTasks,
+    /// Events were missed, so anything may have changed
+    Everything,
+}
+
+impl Changed {
+    fn covers(&self, resource: &VibeResource) -> bool {
+        match self {
+            Self::Resource(changed) => changed == resource,
+            Self::AnyProjectTasks => matches!(resource, VibeResource::ProjectTasks(_)),
+            Self::Everything => true,
+        }
+    }
+}
+
+/// Resources touched by a `json_patch` event of the backend's event stream
+fn changed_by_patch(data: &str) -> Vec<Changed> {
+    let Ok(ops) = serde_json::from_str::<Vec<Value>>(data) else {
+        return Vec::new();
+    };
+
+    let uuid_field = |value: Option<&Value>, field: &str| {
+        value
+            .and_then(|value| value.get(field))
+            .and_then(Value::as_str)
+            .and_then(|id| Uuid::parse_str(id).ok())
+    };
+
+    let mut changed = Vec::new();
+    for op in &ops {
+        let Some(path) = op.get("path").and_then(Value::as_str) else {
+            continue;
+        };
+        let value = op.get("value");
+        if path.starts_with("/tasks/") {
+            match uuid_field(value, "project_id") {
+                Some(project_id) => {
+                    changed.push(Changed::Resource(VibeResource::ProjectTasks(project_id)))
+                }
+                None => changed.push(Changed::AnyProjectTasks),
+            }
+        } else if path.starts_with("/execution_processes/") {
+            // Logs and worktree changes aren't events of their own, so attempts are reported
+            // as changed when one of their processes starts, finishes or changes status
+            if let Some(attempt_id) = uuid_field(value, "task_attempt_id") {
+                changed.push(Changed::Resource(VibeResource::AttemptLog(attempt_id)));
+                changed.push(Changed::Resource(VibeResource::AttemptDiff(attempt_id)));
+            }
+        } else if path.starts_with("/task_attempts/")
+            && let Some(attempt_id) = uuid_field(value, "id")
+        {
+            changed.push(Changed::Resource(VibeResource::AttemptDiff(attempt_id)));
+        }
+    }
+    changed
+}
+
+#[derive(Default)]
+struct SubscriptionState {
+    uris: HashSet<String>,
+    peer: Option<Peer<RoleServer>>,
+    watcher: Option<JoinHandle<()>>,
+}
+
+impl Drop for SubscriptionState {
+    fn drop(&mut self) {
+        // The session (and with it every clone of its server) is gone
+        if let Some(watcher) = self.watcher.take() {
+            watcher.abort();
+        }
+    }
+}
+
+/// Why following the backend's event stream stopped
+enum StreamEnd {
+    /// The backend closed the stream
+    Ended,
+    /// The backend could not resume, so it has to be followed again for live events only
+    Resync,
+    /// The client can't be notified anymore
+    ClientGone,
+}
+
+/// Resources the client subscribed to. The backend's event stream is followed while there
+/// is at least one subscription and the client is still connected. The watcher only holds a
+/// weak reference, so it is aborted once the session's server is dropped.
+#[derive(Clone, Default)]
+pub struct Subscriptions {
+    state: Arc<Mutex<SubscriptionState>>,
+}
+
+impl fmt::Debug for Subscriptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock().unwrap();
+        f.debug_struct("Subscriptions")
+            .field("uris", &state.uris)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Subscriptions {
+    pub fn subscribe(
+        &self,
+        uri: String,
+        peer: Peer<RoleServer>,
+        client: reqwest::Client,
+        events_url: String,
+    ) {
+        let mut state = self.state.lock().unwrap();
+        state.uris.insert(uri);
+        state.peer = Some(peer);
+        if state.watcher.is_none() {
+            let subscriptions = Arc::downgrade(&self.state);
+            state.watcher = Some(tokio::spawn(async move {
+                Self::watch_events(subscriptions, client, events_url).await
+            }));
+        }
+    }
+
+    pub fn unsubscribe(&self, uri: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.uris.remove(uri);
+        if state.uris.is_empty()
+            && let Some(watcher) = state.watcher.take()
+        {
+            watcher.abort();
+        }
+    }
+
+    async fn watch_events(
+        state: Weak<Mutex<SubscriptionState>>,
+        client: reqwest::Client,
+        events_url: String,
+    ) {
+        let mut last_seq = None;
+        loop {
+            match Self::follow_events(&state, &client, &events_url, &mut last_seq).await {
+                Ok(StreamEnd::Resync) => continue,
+                Ok(StreamEnd::ClientGone) => {
+                    tracing::debug!("[MCP] The client disconnected, no longer following events");
+                    if let Some(state) = state.upgrade() {
+                        let mut state = state.lock().unwrap();
+                        state.peer = None;
+                        state.watcher = None;
+                    }
+                    return;
+                }
+                Ok(StreamEnd::Ended) => tracing::debug!("[MCP] The backend event stream ended"),
+                Err(e) => tracing::warn!("[MCP] Lost the backend event stream: {}", e),
+            }
+            tokio::time::sleep(RECONNECT_DELAY).await;
+        }
+    }
+
+    /// Follow the event stream until it ends, resuming after `last_seq` if set. Returns early
+    /// when the backend could not resume or the client is gone.
+    async fn follow_events(
+        state: &Weak<Mutex<SubscriptionState>>,
+        client: &reqwest::Client,
+        events_url: &str,
+        last_seq: &mut Option<u64>,
+    ) -> Result<StreamEnd, reqwest::Error> {
+        let request = match *last_seq {
+            Some(seq) => client
+                .get(events_url)
+                .header("last-event-id", seq.to_string()),
+            None => client.get(format!("{events_url}?live=true")),
+        };
+        let mut response = request.send().await?.error_for_status()?;
+
+        let mut parser = SseParser::default();
+        while let Some(chunk) = response.chunk().await? {
+            for event in parser.feed(&chunk) {
+                if let Some(seq) = event.id.as_deref().and_then(|id| id.parse().ok()) {
+                    *last_seq = Some(seq);
+                }
+                let changed = match event.event.as_str() {
+                    EV_JSON_PATCH => changed_by_patch(&event.data),
+                    EV_RESYNC_REQUIRED => vec![Changed::Everything],
+                    _ => continue,
+                };
+                let Some(state) = state.upgrade() else {
+                    return Ok(StreamEnd::ClientGone);
+                };
+                if !(Self { state }).notify(&changed).await {
+                    return Ok(StreamEnd::ClientGone);
+                }
+                if event.event == EV_RESYNC_REQUIRED {
+                    // The replay that follows would repeat old events
+                    *last_seq = None;
+                    return Ok(StreamEnd::Resync);
+                }
+            }
+        }
+        Ok(StreamEnd::Ended)
+    }
+
+    /// Notify the client about subscribed resources that may have changed. Returns false once
+    /// the client's transport is closed.
+    async fn notify(&self, changed: &[Changed]) -> bool {
+        if changed.is_empty() {
+            return true;
+        }
+        let (peer, uris) = {
+            let state = self.state.lock().unwrap();
+            let uris: Vec<String> = state
+                .uris
+                .iter()
+                .filter(|uri| {
+                    VibeResource::parse(uri)
+                        .is_some_and(|resource| changed.iter().any(|c| c.covers(&resource)))
+                })
+                .cloned()
+                .collect();
+            (state.peer.clone(), uris)
+        };
+        let Some(peer) = peer else {
+            return true;
+        };
+        for uri in uris {
+            match peer
+                .notify_resource_updated(ResourceUpdatedNotificationParam { uri: uri.clone() })
+                .await
+            {
+                Ok(()) => {}
+                Err(ServiceError::TransportClosed | ServiceError::TransportSend(_)) => {
+                    return false;
+                }
+                Err(e) => tracing::debug!("[MCP] Failed to notify about {}: {}", uri, e),
+            }
+        }
+        true
+    }
+}
+
+#[derive(Debug, Default)]
+struct SseEvent {
+    event: String,
+    data: String,
+    id: Option<String>,
+}
+
+/// Incremental parser for a `text/event-stream` body
+#[derive(Default)]
+struct SseParser {
+    buffer: Vec<u8>,
+    current: SseEvent,
+    has_data: bool,
+}
+
+impl SseParser {
+    fn feed(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\n', '\r']);
+
+            if line.is_empty() {
+                let event = std::mem::take(&mut self.current);
+                if std::mem::take(&mut self.has_data) || !event.event.is_empty() {
+                    events.push(event);
+                }
+                continue;
+            }
+            if line.starts_with(':') {
+                continue;
+            }
+            let (field, value) = line.split_once(':').unwrap_or((line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "event" => self.current.event = value.to_string(),
+                "data" => {
+                    if self.has_data {
+                        self.current.data.push('\n');
+                    }
+                    self.current.data.push_str(value);
+                    self.has_data = true;
+                }
+                "id" => self.current.id = Some(value.to_string()),
+                _ => {}
+            }
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_resource_uris() {
+        let id = Uuid::new_v4();
+        for resource in [
+            VibeResource::ProjectTasks(id),
+            VibeResource::AttemptDiff(id),
+            VibeResource::AttemptLog(id),
+        ] {
+            assert_eq!(VibeResource::parse(&resource.uri()), Some(resource));
+        }
+        assert_eq!(
+            VibeResource::parse(&format!("vibe://project/{id}/diff")),
+            None
+        );
+        assert_eq!(VibeResource::parse("vibe://attempt/not-a-uuid/log"), None);
+    }
+
+    #[test]
+    fn maps_patches_to_resources() {
+        let (project_id, attempt_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let data = serde_json::json!([
+            {"op": "replace", "path": "/tasks/1", "value": {"project_id": project_id}},
+            {"op": "remove", "path": "/tasks/2"},
+            {"op": "add", "path": "/execution_processes/3", "value": {"task_attempt_id": attempt_id}},
+        ])
+        .to_string();
+        assert_eq!(
+            changed_by_patch(&data),
+            vec![
+                Changed::Resource(VibeResource::ProjectTasks(project_id)),
+                Changed::AnyProjectTasks,
+                Changed::Resource(VibeResource::AttemptLog(attempt_id)),
+                Changed::Resource(VibeResource::AttemptDiff(attempt_id)),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_events_split_across_chunks() {
+        let mut parser = SseParser::default();
+        assert!(parser.feed(b"event: json_patch\nid: 7\nda").is_empty());
+        let events = parser.feed(b"ta: [1]\n\n: keep-alive\n\nevent: resync_required\ndata: \n\n");
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event, "json_patch");
+        assert_eq!(events[0].id.as_deref(), Some("7"));
+        assert_eq!(events[0].data, "[1]");
+        assert_eq!(events[1].event, "resync_required");
+    }
+}
@@ -0,0 +1,87 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use utils::attempt_env::{PROJECT_ID_ENV, TASK_ATTEMPT_ID_ENV, TASK_ID_ENV};
+use uuid::Uuid;
+
+/// The project, task and attempt the MCP server works for, when an agent launched by vibe-kanban
+/// started it. Tools default to these ids.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, schemars::JsonSchema)]
+pub struct McpScope {
+    #[schemars(description = "The project of the current task")]
+    pub project_id: Option<Uuid>,
+    #[schemars(description = "The task the agent is working on")]
+    pub task_id: Option<Uuid>,
+    #[schemars(description = "The task attempt the agent is running in")]
+    pub attempt_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContainerInfoResponse {
+    data: Option<ContainerInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContainerInfo {
+    attempt_id: Uuid,
+    task_id: Uuid,
+    project_id: Uuid,
+}
+
+impl McpScope {
+    pub fn is_empty(&self) -> bool {
+        self.project_id.is_none() && self.task_id.is_none() && self.attempt_id.is_none()
+    }
+
+    /// Scope from the environment vibe-kanban sets on the processes of a task attempt
+    pub fn from_env() -> Self {
+        let id = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|value| Uuid::parse_str(value.trim()).ok())
+        };
+        Self {
+            project_id: id(PROJECT_ID_ENV),
+            task_id: id(TASK_ID_ENV),
+            attempt_id: id(TASK_ATTEMPT_ID_ENV),
+        }
+    }
+
+    /// Scope from the environment, or else from the attempt whose worktree contains the
+    /// current directory. Some agents don't pass their environment on to MCP servers, but
+    /// they do start them inside the worktree.
+    pub async fn detect(client: &reqwest::Client, base_url: &str) -> Self {
+        let scope = Self::from_env();
+        if !scope.is_empty() {
+            return scope;
+        }
+        let Ok(current_dir) = std::env::current_dir() else {
+            return scope;
+        };
+        for dir in current_dir.ancestors() {
+            if let Some(scope) = Self::for_worktree(client, base_url, dir).await {
+                return scope;
+            }
+        }
+        scope
+    }
+
+    async fn for_worktree(client: &reqwest::Client, base_url: &str, dir: &Path) -> Option<Self> {
+        let url = format!("{}/api/containers/info", base_url.trim_end_matches('/'));
+        let response = client
+            .get(&url)
+            .query(&[("ref", dir.to_string_lossy())])
+            .send()
+            .await
+            .ok()?;
+        if !response.status().is_success() {
+            return None;
+        }
+        let info = response.json::<ContainerInfoResponse>().await.ok()?.data?;
+        Some(Self {
+            project_id: Some(info.project_id),
+            task_id: Some(info.task_id),
+            attempt_id: Some(info.attempt_id),
+        })
+    }
+}
@@ -1,29 +1,48 @@
-use std::{future::Future, path::PathBuf, str::FromStr};
+use std::{collections::BTreeMap, future::Future, path::PathBuf, str::FromStr};
 
 use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
     project::Project,
-    task::{CreateTask, Task, TaskStatus, TaskWithAttemptStatus, UpdateTask},
+    task::{CreateTask, Task, TaskPriority, TaskStatus, TaskWithAttemptStatus, UpdateTask},
     task_attempt::TaskAttempt,
 };
 use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
 use rmcp::{
-    ErrorData, ServerHandler,
+    ErrorData, RoleServer, ServerHandler,
     handler::server::tool::{Parameters, ToolRouter},
     model::{
-        CallToolResult, Content, Implementation, ProtocolVersion, ServerCapabilities, ServerInfo,
+        AnnotateAble, CallToolResult, Implementation, ListResourceTemplatesResult,
+        ListResourcesResult, PaginatedRequestParam, ProtocolVersion, RawResource,
+        ReadResourceRequestParam, ReadResourceResult, ResourceContents, ServerCapabilities,
+        ServerInfo, SubscribeRequestParam, UnsubscribeRequestParam,
     },
-    schemars, tool, tool_handler, tool_router,
+    schemars,
+    service::RequestContext,
+    tool, tool_handler, tool_router,
 };
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_json;
+use utils::diff::{Diff, compute_line_change_counts, create_unified_diff};
 use uuid::Uuid;
 
-use crate::routes::task_attempts::CreateTaskAttemptBody;
+use super::{
+    resources::{self, JSON_MIME_TYPE, Subscriptions, VibeResource},
+    scope::McpScope,
+};
+use crate::{
+    middleware::ACTOR_HEADER,
+    routes::task_attempts::{
+        CreateFollowUpAttempt, CreateGitHubPrRequest, CreateTaskAttemptBody,
+        RebaseTaskAttemptRequest,
+    },
+};
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct CreateTaskRequest {
-    #[schemars(description = "The ID of the project to create the task in. This is required!")]
-    pub project_id: Uuid,
+    #[schemars(
+        description = "The ID of the project to create the task in. Defaults to the current project"
+    )]
+    pub project_id: Option<Uuid>,
     #[schemars(description = "The title of the task")]
     pub title: String,
     #[schemars(description = "Optional description of the task")]
@@ -78,12 +97,26 @@ pub struct ListProjectsResponse {
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct ListTasksRequest {
-    #[schemars(description = "The ID of the project to list tasks from")]
-    pub project_id: Uuid,
+    #[schemars(
+        description = "The ID of the project to list tasks from. Defaults to the current project"
+    )]
+    pub project_id: Option<Uuid>,
     #[schemars(
         description = "Optional status filter: 'todo', 'inprogress', 'inreview', 'done', 'cancelled'"
     )]
     pub status: Option<String>,
+    #[schemars(description = "Optional priority filter: 'low', 'medium', 'high', 'urgent'")]
+    pub priority: Option<String>,
+    #[schemars(description = "Optional assignee filter, matched ignoring case")]
+    pub assignee: Option<String>,
+    #[schemars(description = "Only list tasks with the label of this name")]
+    pub label: Option<String>,
+    #[schemars(description = "Only list tasks due on or before this date (YYYY-MM-DD)")]
+    pub due_before: Option<String>,
+    #[schemars(description = "Only list tasks with a value for the custom field of this name")]
+    pub custom_field: Option<String>,
+    #[schemars(description = "Value the custom field must have, used with custom_field")]
+    pub custom_value: Option<String>,
     #[schemars(description = "Maximum number of tasks to return (default: 50)")]
     pub limit: Option<i32>,
 }
@@ -96,6 +129,16 @@ pub struct TaskSummary {
     pub title: String,
     #[schemars(description = "Current status of the task")]
     pub status: String,
+    #[schemars(description = "Priority of the task: 'low', 'medium', 'high', 'urgent'")]
+    pub priority: Option<String>,
+    #[schemars(description = "Who owns the task")]
+    pub assignee: Option<String>,
+    #[schemars(description = "When the task is due (YYYY-MM-DD)")]
+    pub due_date: Option<String>,
+    #[schemars(description = "Names of the task's labels")]
+    pub labels: Vec<String>,
+    #[schemars(description = "The task's custom field values, by field name")]
+    pub custom_fields: BTreeMap<String, serde_json::Value>,
     #[schemars(description = "When the task was created")]
     pub created_at: String,
     #[schemars(description = "When the task was last updated")]
@@ -114,8 +157,23 @@ impl TaskSummary {
             id: task.id.to_string(),
             title: task.title.to_string(),
             status: task.status.to_string(),
-            created_at: task.created_at.to_rfc3339(),
-            updated_at: task.updated_at.to_rfc3339(),
+            priority: task.fields.priority.map(|priority| priority.to_string()),
+            assignee: task.fields.assignee,
+            due_date: task.fields.due_date.map(|date| date.to_string()),
+            labels: task
+                .fields
+                .labels
+                .into_iter()
+                .map(|label| label.name)
+                .collect(),
+            custom_fields: task
+                .fields
+                .custom_fields
+                .into_iter()
+                .map(|field| (field.name, field.value))
+                .collect(),
+            created_at: task.task.created_at.to_rfc3339(),
+            updated_at: task.task.updated_at.to_rfc3339(),
             has_in_progress_attempt: Some(task.has_in_progress_attempt),
             has_merged_attempt: Some(task.has_merged_attempt),
             last_attempt_failed: Some(task.last_attempt_failed),
@@ -169,9 +227,23 @@ pub struct ListTasksResponse {
     pub applied_filters: ListTasksFilters,
 }
 
+/// Contents of the `vibe://project/{id}/tasks` resource
+#[derive(Debug, Serialize)]
+pub struct ProjectTasksResource {
+    pub project_id: String,
+    pub count: usize,
+    pub tasks: Vec<TaskSummary>,
+}
+
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 pub struct ListTasksFilters {
     pub status: Option<String>,
+    pub priority: Option<String>,
+    pub assignee: Option<String>,
+    pub label: Option<String>,
+    pub due_before: Option<String>,
+    pub custom_field: Option<String>,
+    pub custom_value: Option<String>,
     pub limit: i32,
 }
 
@@ -225,8 +297,8 @@ pub struct DeleteTaskResponse {
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct GetTaskRequest {
-    #[schemars(description = "The ID of the task to retrieve")]
-    pub task_id: Uuid,
+    #[schemars(description = "The ID of the task to retrieve. Defaults to the current task")]
+    pub task_id: Option<Uuid>,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -234,43 +306,315 @@ pub struct GetTaskResponse {
     pub task: TaskDetails,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListTaskAttemptsRequest {
+    #[schemars(
+        description = "The ID of the task to list attempts for. Defaults to the current task"
+    )]
+    pub task_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct TaskAttemptSummary {
+    #[schemars(description = "The unique identifier of the attempt")]
+    pub id: String,
+    #[schemars(description = "The task the attempt works on")]
+    pub task_id: String,
+    #[schemars(description = "The git branch the attempt works on")]
+    pub branch: String,
+    #[schemars(description = "The branch the attempt is merged or rebased onto")]
+    pub target_branch: String,
+    #[schemars(description = "The coding agent running the attempt")]
+    pub executor: String,
+    #[schemars(description = "Whether the attempt's worktree has been cleaned up")]
+    pub worktree_deleted: bool,
+    #[schemars(description = "When the attempt was created")]
+    pub created_at: String,
+    #[schemars(description = "When the attempt was last updated")]
+    pub updated_at: String,
+}
+
+impl TaskAttemptSummary {
+    fn from_attempt(attempt: TaskAttempt) -> Self {
+        Self {
+            id: attempt.id.to_string(),
+            task_id: attempt.task_id.to_string(),
+            branch: attempt.branch,
+            target_branch: attempt.target_branch,
+            executor: attempt.executor,
+            worktree_deleted: attempt.worktree_deleted,
+            created_at: attempt.created_at.to_rfc3339(),
+            updated_at: attempt.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ListTaskAttemptsResponse {
+    pub attempts: Vec<TaskAttemptSummary>,
+    pub count: usize,
+    pub task_id: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ScopedAttemptRequest {
+    #[schemars(description = "The ID of the task attempt. Defaults to the current attempt")]
+    pub attempt_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct TaskAttemptRequest {
+    #[schemars(description = "The ID of the task attempt")]
+    pub attempt_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ExecutionProcessSummary {
+    #[schemars(description = "The unique identifier of the execution process")]
+    pub id: String,
+    #[schemars(
+        description = "Why the process ran: 'setupscript', 'cleanupscript', 'codingagent' or 'devserver'"
+    )]
+    pub run_reason: String,
+    #[schemars(
+        description = "Current status: 'running', 'completed', 'failed', 'killed' or 'limitexceeded' (stopped for going over a resource limit)"
+    )]
+    pub status: String,
+    #[schemars(description = "Exit code, once the process finished")]
+    pub exit_code: Option<i64>,
+    #[schemars(description = "When the process started")]
+    pub started_at: String,
+    #[schemars(description = "When the process finished")]
+    pub completed_at: Option<String>,
+}
+
+impl ExecutionProcessSummary {
+    fn from_process(process: ExecutionProcess) -> Self {
+        Self {
+            id: process.id.to_string(),
+            run_reason: process.run_reason.to_string(),
+            status: process.status.to_string(),
+            exit_code: process.exit_code,
+            started_at: process.started_at.to_rfc3339(),
+            completed_at: process.completed_at.map(|at| at.to_rfc3339()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ListExecutionProcessesResponse {
+    pub processes: Vec<ExecutionProcessSummary>,
+    pub count: usize,
+    pub attempt_id: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetAttemptDiffRequest {
+    #[schemars(description = "The ID of the task attempt. Defaults to the current attempt")]
+    pub attempt_id: Option<Uuid>,
+    #[schemars(
+        description = "Include a unified diff of each file (default: true). Set to false for line counts only"
+    )]
+    pub include_patch: Option<bool>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct FileDiffSummary {
+    #[schemars(description = "Path of the file, the new path for renames")]
+    pub path: String,
+    #[schemars(description = "Previous path, for renamed or copied files")]
+    pub old_path: Option<String>,
+    #[schemars(
+        description = "Kind of change: 'added', 'deleted', 'modified', 'renamed', 'copied' or 'permissionChange'"
+    )]
+    pub change: String,
+    pub additions: usize,
+    pub deletions: usize,
+    #[schemars(description = "Unified diff of the file, missing when it was too large")]
+    pub patch: Option<String>,
+}
+
+impl FileDiffSummary {
+    fn from_diff(diff: Diff, include_patch: bool) -> Self {
+        let path = diff
+            .new_path
+            .clone()
+            .or_else(|| diff.old_path.clone())
+            .unwrap_or_default();
+        let old = diff.old_content.as_deref().unwrap_or_default();
+        let new = diff.new_content.as_deref().unwrap_or_default();
+        let (additions, deletions) = match (diff.additions, diff.deletions) {
+            (Some(additions), Some(deletions)) => (additions, deletions),
+            _ => compute_line_change_counts(old, new),
+        };
+        let patch = (include_patch && !diff.content_omitted && old != new)
+            .then(|| create_unified_diff(&path, old, new));
+        let change = serde_json::to_value(&diff.change)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default();
+        Self {
+            old_path: diff.old_path.filter(|old_path| *old_path != path),
+            path,
+            change,
+            additions,
+            deletions,
+            patch,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct GetAttemptDiffResponse {
+    pub attempt_id: String,
+    pub files: Vec<FileDiffSummary>,
+    pub count: usize,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetConversationRequest {
+    #[schemars(description = "The ID of the task attempt. Defaults to the current attempt")]
+    pub attempt_id: Option<Uuid>,
+    #[schemars(
+        description = "Optional execution process to read, defaults to the attempt's latest coding agent run"
+    )]
+    pub execution_process_id: Option<Uuid>,
+    #[schemars(description = "Number of entries to return from the end (default: 50)")]
+    pub tail: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ConversationTail {
+    total: usize,
+    entries: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct GetConversationResponse {
+    pub attempt_id: String,
+    pub execution_process_id: String,
+    #[schemars(description = "Number of entries in the whole conversation")]
+    pub total: usize,
+    #[schemars(
+        description = "The last entries of the normalized conversation, oldest first. Each has a `type` and a `content`"
+    )]
+    pub entries: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SendFollowUpRequest {
+    #[schemars(description = "The ID of the task attempt to continue")]
+    pub attempt_id: Uuid,
+    #[schemars(description = "The follow-up instructions for the coding agent")]
+    pub prompt: String,
+    #[schemars(description = "Optional executor variant, defaults to the one used last")]
+    pub variant: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct SendFollowUpResponse {
+    pub attempt_id: String,
+    pub execution_process_id: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct RebaseAttemptRequest {
+    #[schemars(description = "The ID of the task attempt to rebase")]
+    pub attempt_id: Uuid,
+    #[schemars(
+        description = "Optional branch to rebase onto, which becomes the attempt's target branch. Defaults to the current target branch"
+    )]
+    pub new_base_branch: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CreatePullRequestRequest {
+    #[schemars(description = "The ID of the task attempt to open a pull request for")]
+    pub attempt_id: Uuid,
+    #[schemars(description = "The pull request title")]
+    pub title: String,
+    #[schemars(description = "Optional pull request description")]
+    pub body: Option<String>,
+    #[schemars(description = "Optional base branch, defaults to the attempt's target branch")]
+    pub target_branch: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct CreatePullRequestResponse {
+    pub attempt_id: String,
+    pub pr_url: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct TaskAttemptActionResponse {
+    pub attempt_id: String,
+    #[schemars(description = "What was done")]
+    pub message: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct TaskServer {
     client: reqwest::Client,
     base_url: String,
+    scope: McpScope,
+    subscriptions: Subscriptions,
     tool_router: ToolRouter<TaskServer>,
 }
 
 impl TaskServer {
     pub fn new(base_url: &str) -> Self {
+        // Changes made through the tools are attributed to MCP in the audit log
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(
+            ACTOR_HEADER,
+            reqwest::header::HeaderValue::from_static("mcp"),
+        );
+        let client = reqwest::Client::builder()
+            .default_headers(headers)
+            .build()
+            .unwrap_or_default();
         Self {
-            client: reqwest::Client::new(),
+            client,
             base_url: base_url.to_string(),
+            scope: McpScope::default(),
+            subscriptions: Subscriptions::default(),
             tool_router: Self::tool_router(),
         }
     }
+
+    /// Default tool arguments to the given project, task and attempt
+    pub fn with_scope(mut self, scope: McpScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    /// Scope the server to the attempt of the agent that started it, if any
+    pub async fn with_detected_scope(self) -> Self {
+        let scope = McpScope::detect(&self.client, &self.base_url).await;
+        self.with_scope(scope)
+    }
 }
 
 #[derive(Debug, Deserialize)]
 struct ApiResponseEnvelope<T> {
     success: bool,
     data: Option<T>,
+    error_data: Option<serde_json::Value>,
     message: Option<String>,
 }
 
 impl TaskServer {
     fn success<T: Serialize>(data: &T) -> Result<CallToolResult, ErrorData> {
-        Ok(CallToolResult::success(vec![Content::text(
-            serde_json::to_string_pretty(data)
-                .unwrap_or_else(|_| "Failed to serialize response".to_string()),
-        )]))
+        match serde_json::to_value(data) {
+            Ok(value) => Ok(CallToolResult::structured(value)),
+            Err(e) => Self::err("Failed to serialize response", Some(&e.to_string())),
+        }
     }
 
     fn err_value(v: serde_json::Value) -> Result<CallToolResult, ErrorData> {
-        Ok(CallToolResult::error(vec![Content::text(
-            serde_json::to_string_pretty(&v)
-                .unwrap_or_else(|_| "Failed to serialize error".to_string()),
-        )]))
+        Ok(CallToolResult::structured_error(v))
     }
 
     fn err<S: Into<String>>(msg: S, details: Option<S>) -> Result<CallToolResult, ErrorData> {
@@ -285,30 +629,68 @@ impl TaskServer {
         &self,
         rb: reqwest::RequestBuilder,
     ) -> Result<T, CallToolResult> {
+        self.send_envelope(rb)
+            .await?
+            .data
+            .ok_or_else(|| Self::err("VK API response missing data field", None).unwrap())
+    }
+
+    /// Send a request whose response carries no data
+    async fn send_ok(&self, rb: reqwest::RequestBuilder) -> Result<(), CallToolResult> {
+        self.send_envelope::<serde_json::Value>(rb)
+            .await
+            .map(|_| ())
+    }
+
+    async fn send_envelope<T: DeserializeOwned>(
+        &self,
+        rb: reqwest::RequestBuilder,
+    ) -> Result<ApiResponseEnvelope<T>, CallToolResult> {
         let resp = rb
             .send()
             .await
             .map_err(|e| Self::err("Failed to connect to VK API", Some(&e.to_string())).unwrap())?;
 
-        if !resp.status().is_success() {
-            let status = resp.status();
+        let status = resp.status();
+        let body = resp.text().await.map_err(|e| {
+            Self::err("Failed to read VK API response", Some(&e.to_string())).unwrap()
+        })?;
+
+        if !status.is_success() {
+            // Error responses usually still carry a message in the envelope
+            let message = serde_json::from_str::<ApiResponseEnvelope<serde_json::Value>>(&body)
+                .ok()
+                .and_then(|envelope| envelope.message);
             return Err(
-                Self::err(format!("VK API returned error status: {}", status), None).unwrap(),
+                Self::err(format!("VK API returned error status: {}", status), message).unwrap(),
             );
         }
 
-        let api_response = resp.json::<ApiResponseEnvelope<T>>().await.map_err(|e| {
+        let api_response = serde_json::from_str::<ApiResponseEnvelope<T>>(&body).map_err(|e| {
             Self::err("Failed to parse VK API response", Some(&e.to_string())).unwrap()
         })?;
 
         if !api_response.success {
             let msg = api_response.message.as_deref().unwrap_or("Unknown error");
-            return Err(Self::err("VK API returned error", Some(msg)).unwrap());
+            let mut v = serde_json::json!({"success": false, "error": "VK API returned error", "details": msg});
+            if let Some(error_data) = api_response.error_data {
+                v["error_data"] = error_data;
+            }
+            return Err(Self::err_value(v).unwrap());
         }
 
-        api_response
-            .data
-            .ok_or_else(|| Self::err("VK API response missing data field", None).unwrap())
+        Ok(api_response)
+    }
+
+    /// `id`, or the scope's id when the agent left it out
+    fn scoped(id: Option<Uuid>, default: Option<Uuid>, name: &str) -> Result<Uuid, CallToolResult> {
+        id.or(default).ok_or_else(|| {
+            Self::err(
+                format!("`{name}` is required: this MCP server isn't scoped to one"),
+                None,
+            )
+            .unwrap()
+        })
     }
 
     fn url(&self, path: &str) -> String {
@@ -323,7 +705,7 @@ impl TaskServer {
 #[tool_router]
 impl TaskServer {
     #[tool(
-        description = "Create a new task/ticket in a project. Always pass the `project_id` of the project you want to create the task in - it is required!"
+        description = "Create a new task/ticket in a project. Defaults to the current project; tasks created there are linked to the current attempt as subtasks."
     )]
     async fn create_task(
         &self,
@@ -333,19 +715,17 @@ impl TaskServer {
             description,
         }): Parameters<CreateTaskRequest>,
     ) -> Result<CallToolResult, ErrorData> {
+        let project_id = match Self::scoped(project_id, self.scope.project_id, "project_id") {
+            Ok(id) => id,
+            Err(e) => return Ok(e),
+        };
+        let mut payload = CreateTask::from_title_description(project_id, title, description);
+        if self.scope.project_id == Some(project_id) {
+            payload.parent_task_attempt = self.scope.attempt_id;
+        }
+
         let url = self.url("/api/tasks");
-        let task: Task = match self
-            .send_json(
-                self.client
-                    .post(&url)
-                    .json(&CreateTask::from_title_description(
-                        project_id,
-                        title,
-                        description,
-                    )),
-            )
-            .await
-        {
+        let task: Task = match self.send_json(self.client.post(&url).json(&payload)).await {
             Ok(t) => t,
             Err(e) => return Ok(e),
         };
@@ -355,6 +735,13 @@ impl TaskServer {
         })
     }
 
+    #[tool(
+        description = "Get the project, task and attempt you are working in. Tools default to these ids when they are left out."
+    )]
+    async fn get_context(&self) -> Result<CallToolResult, ErrorData> {
+        TaskServer::success(&self.scope)
+    }
+
     #[tool(description = "List all the available projects")]
     async fn list_projects(&self) -> Result<CallToolResult, ErrorData> {
         let url = self.url("/api/projects");
@@ -377,16 +764,26 @@ impl TaskServer {
     }
 
     #[tool(
-        description = "List all the task/tickets in a project with optional filtering and execution status. `project_id` is required!"
+        description = "List all the task/tickets in a project with optional filtering and execution status. Defaults to the current project."
     )]
     async fn list_tasks(
         &self,
         Parameters(ListTasksRequest {
             project_id,
             status,
+            priority,
+            assignee,
+            label,
+            due_before,
+            custom_field,
+            custom_value,
             limit,
         }): Parameters<ListTasksRequest>,
     ) -> Result<CallToolResult, ErrorData> {
+        let project_id = match Self::scoped(project_id, self.scope.project_id, "project_id") {
+            Ok(id) => id,
+            Err(e) => return Ok(e),
+        };
         let status_filter = if let Some(ref status_str) = status {
             match TaskStatus::from_str(status_str) {
                 Ok(s) => Some(s),
@@ -400,13 +797,37 @@ impl TaskServer {
         } else {
             None
         };
+        if let Some(priority_str) = &priority
+            && TaskPriority::from_str(priority_str).is_err()
+        {
+            return Self::err(
+                "Invalid priority filter. Valid values: 'low', 'medium', 'high', 'urgent'"
+                    .to_string(),
+                Some(priority_str.to_string()),
+            );
+        }
+
+        // The server filters by the task fields, the status is filtered here
+        let field_filters: Vec<(&str, &str)> = [
+            ("priority", &priority),
+            ("assignee", &assignee),
+            ("label", &label),
+            ("due_before", &due_before),
+            ("custom_field", &custom_field),
+            ("custom_value", &custom_value),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.as_deref().map(|value| (name, value)))
+        .collect();
 
         let url = self.url(&format!("/api/tasks?project_id={}", project_id));
-        let all_tasks: Vec<TaskWithAttemptStatus> =
-            match self.send_json(self.client.get(&url)).await {
-                Ok(t) => t,
-                Err(e) => return Ok(e),
-            };
+        let all_tasks: Vec<TaskWithAttemptStatus> = match self
+            .send_json(self.client.get(&url).query(&field_filters))
+            .await
+        {
+            Ok(t) => t,
+            Err(e) => return Ok(e),
+        };
 
         let task_limit = limit.unwrap_or(50).max(0) as usize;
         let filtered = all_tasks.into_iter().filter(|t| {
@@ -429,6 +850,12 @@ impl TaskServer {
             project_id: project_id.to_string(),
             applied_filters: ListTasksFilters {
                 status: status.clone(),
+                priority,
+                assignee,
+                label,
+                due_before,
+                custom_field,
+                custom_value,
                 limit: task_limit as i32,
             },
         };
@@ -554,10 +981,7 @@ impl TaskServer {
         Parameters(DeleteTaskRequest { task_id }): Parameters<DeleteTaskRequest>,
     ) -> Result<CallToolResult, ErrorData> {
         let url = self.url(&format!("/api/tasks/{}", task_id));
-        if let Err(e) = self
-            .send_json::<serde_json::Value>(self.client.delete(&url))
-            .await
-        {
+        if let Err(e) = self.send_ok(self.client.delete(&url)).await {
             return Ok(e);
         }
 
@@ -569,12 +993,16 @@ impl TaskServer {
     }
 
     #[tool(
-        description = "Get detailed information (like task description) about a specific task/ticket. You can use `list_tasks` to find the `task_ids` of all tasks in a project. `project_id` and `task_id` are required!"
+        description = "Get detailed information (like task description) about a specific task/ticket. You can use `list_tasks` to find the `task_ids` of all tasks in a project. Defaults to the current task."
     )]
     async fn get_task(
         &self,
         Parameters(GetTaskRequest { task_id }): Parameters<GetTaskRequest>,
     ) -> Result<CallToolResult, ErrorData> {
+        let task_id = match Self::scoped(task_id, self.scope.task_id, "task_id") {
+            Ok(id) => id,
+            Err(e) => return Ok(e),
+        };
         let url = self.url(&format!("/api/tasks/{}", task_id));
         let task: Task = match self.send_json(self.client.get(&url)).await {
             Ok(t) => t,
@@ -586,6 +1014,375 @@ impl TaskServer {
 
         TaskServer::success(&response)
     }
+
+    #[tool(
+        description = "List the attempts of a task, newest first. Defaults to the current task."
+    )]
+    async fn list_task_attempts(
+        &self,
+        Parameters(ListTaskAttemptsRequest { task_id }): Parameters<ListTaskAttemptsRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let task_id = match Self::scoped(task_id, self.scope.task_id, "task_id") {
+            Ok(id) => id,
+            Err(e) => return Ok(e),
+        };
+        let url = self.url(&format!("/api/task-attempts?task_id={}", task_id));
+        let attempts: Vec<TaskAttempt> = match self.send_json(self.client.get(&url)).await {
+            Ok(attempts) => attempts,
+            Err(e) => return Ok(e),
+        };
+
+        let attempts: Vec<TaskAttemptSummary> = attempts
+            .into_iter()
+            .map(TaskAttemptSummary::from_attempt)
+            .collect();
+        let response = ListTaskAttemptsResponse {
+            count: attempts.len(),
+            attempts,
+            task_id: task_id.to_string(),
+        };
+
+        TaskServer::success(&response)
+    }
+
+    #[tool(
+        description = "List the execution processes of a task attempt (setup script, coding agent runs, cleanup script, dev server) with their status. Defaults to the current attempt."
+    )]
+    async fn list_execution_processes(
+        &self,
+        Parameters(ScopedAttemptRequest { attempt_id }): Parameters<ScopedAttemptRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let attempt_id = match Self::scoped(attempt_id, self.scope.attempt_id, "attempt_id") {
+            Ok(id) => id,
+            Err(e) => return Ok(e),
+        };
+        let processes = match self.execution_processes(attempt_id).await {
+            Ok(processes) => processes,
+            Err(e) => return Ok(e),
+        };
+
+        let processes: Vec<ExecutionProcessSummary> = processes
+            .into_iter()
+            .map(ExecutionProcessSummary::from_process)
+            .collect();
+        let response = ListExecutionProcessesResponse {
+            count: processes.len(),
+            processes,
+            attempt_id: attempt_id.to_string(),
+        };
+
+        TaskServer::success(&response)
+    }
+
+    #[tool(
+        description = "Get the changes a task attempt made to its worktree, compared to the branch it started from. Defaults to the current attempt."
+    )]
+    async fn get_attempt_diff(
+        &self,
+        Parameters(GetAttemptDiffRequest {
+            attempt_id,
+            include_patch,
+        }): Parameters<GetAttemptDiffRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let attempt_id = match Self::scoped(attempt_id, self.scope.attempt_id, "attempt_id") {
+            Ok(id) => id,
+            Err(e) => return Ok(e),
+        };
+        match self
+            .attempt_diff(attempt_id, include_patch.unwrap_or(true))
+            .await
+        {
+            Ok(response) => TaskServer::success(&response),
+            Err(e) => Ok(e),
+        }
+    }
+
+    #[tool(
+        description = "Read the end of a coding agent's conversation in a task attempt: messages, tool calls and their results. Defaults to the current attempt."
+    )]
+    async fn get_attempt_conversation(
+        &self,
+        Parameters(GetConversationRequest {
+            attempt_id,
+            execution_process_id,
+            tail,
+        }): Parameters<GetConversationRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let attempt_id = match Self::scoped(attempt_id, self.scope.attempt_id, "attempt_id") {
+            Ok(id) => id,
+            Err(e) => return Ok(e),
+        };
+        match self
+            .attempt_conversation(attempt_id, execution_process_id, tail)
+            .await
+        {
+            Ok(response) => TaskServer::success(&response),
+            Err(e) => Ok(e),
+        }
+    }
+
+    #[tool(
+        description = "Send follow-up instructions to the coding agent of a task attempt, continuing its session. `attempt_id` and `prompt` are required!"
+    )]
+    async fn send_follow_up(
+        &self,
+        Parameters(SendFollowUpRequest {
+            attempt_id,
+            prompt,
+            variant,
+        }): Parameters<SendFollowUpRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if prompt.trim().is_empty() {
+            return Self::err("Prompt must not be empty.".to_string(), None::<String>);
+        }
+
+        let payload = CreateFollowUpAttempt {
+            prompt,
+            variant: variant.filter(|v| !v.trim().is_empty()),
+            image_ids: None,
+            retry_process_id: None,
+            force_when_dirty: None,
+            perform_git_reset: None,
+        };
+        let url = self.url(&format!("/api/task-attempts/{}/follow-up", attempt_id));
+        let process: ExecutionProcess =
+            match self.send_json(self.client.post(&url).json(&payload)).await {
+                Ok(process) => process,
+                Err(e) => return Ok(e),
+            };
+
+        let response = SendFollowUpResponse {
+            attempt_id: attempt_id.to_string(),
+            execution_process_id: process.id.to_string(),
+        };
+
+        TaskServer::success(&response)
+    }
+
+    #[tool(
+        description = "Stop every running execution process of a task attempt. `attempt_id` is required!"
+    )]
+    async fn stop_task_attempt(
+        &self,
+        Parameters(TaskAttemptRequest { attempt_id }): Parameters<TaskAttemptRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!("/api/task-attempts/{}/stop", attempt_id));
+        if let Err(e) = self.send_ok(self.client.post(&url)).await {
+            return Ok(e);
+        }
+
+        TaskServer::success(&TaskAttemptActionResponse {
+            attempt_id: attempt_id.to_string(),
+            message: "Stopped the attempt's running processes".to_string(),
+        })
+    }
+
+    #[tool(
+        description = "Rebase a task attempt's branch onto its target branch, or onto `new_base_branch`. Fails with the conflicting files if the rebase has conflicts. `attempt_id` is required!"
+    )]
+    async fn rebase_task_attempt(
+        &self,
+        Parameters(RebaseAttemptRequest {
+            attempt_id,
+            new_base_branch,
+        }): Parameters<RebaseAttemptRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let payload = RebaseTaskAttemptRequest {
+            old_base_branch: None,
+            new_base_branch: new_base_branch.filter(|b| !b.trim().is_empty()),
+        };
+        let url = self.url(&format!("/api/task-attempts/{}/rebase", attempt_id));
+        if let Err(e) = self.send_ok(self.client.post(&url).json(&payload)).await {
+            return Ok(e);
+        }
+
+        TaskServer::success(&TaskAttemptActionResponse {
+            attempt_id: attempt_id.to_string(),
+            message: "Rebased the attempt's branch".to_string(),
+        })
+    }
+
+    #[tool(
+        description = "Merge a task attempt's changes into its target branch and mark the task done. `attempt_id` is required!"
+    )]
+    async fn merge_task_attempt(
+        &self,
+        Parameters(TaskAttemptRequest { attempt_id }): Parameters<TaskAttemptRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!("/api/task-attempts/{}/merge", attempt_id));
+        if let Err(e) = self.send_ok(self.client.post(&url)).await {
+            return Ok(e);
+        }
+
+        TaskServer::success(&TaskAttemptActionResponse {
+            attempt_id: attempt_id.to_string(),
+            message: "Merged the attempt into its target branch".to_string(),
+        })
+    }
+
+    #[tool(
+        description = "Push a task attempt's branch to GitHub and open a pull request. `attempt_id` and `title` are required!"
+    )]
+    async fn create_pull_request(
+        &self,
+        Parameters(CreatePullRequestRequest {
+            attempt_id,
+            title,
+            body,
+            target_branch,
+        }): Parameters<CreatePullRequestRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let payload = CreateGitHubPrRequest {
+            title,
+            body,
+            target_branch: target_branch.filter(|b| !b.trim().is_empty()),
+        };
+        let url = self.url(&format!("/api/task-attempts/{}/pr", attempt_id));
+        let pr_url: String = match self.send_json(self.client.post(&url).json(&payload)).await {
+            Ok(pr_url) => pr_url,
+            Err(e) => return Ok(e),
+        };
+
+        TaskServer::success(&CreatePullRequestResponse {
+            attempt_id: attempt_id.to_string(),
+            pr_url,
+        })
+    }
+}
+
+impl TaskServer {
+    async fn execution_processes(
+        &self,
+        attempt_id: Uuid,
+    ) -> Result<Vec<ExecutionProcess>, CallToolResult> {
+        let url = self.url(&format!(
+            "/api/execution-processes?task_attempt_id={}",
+            attempt_id
+        ));
+        self.send_json(self.client.get(&url)).await
+    }
+
+    async fn attempt_diff(
+        &self,
+        attempt_id: Uuid,
+        include_patch: bool,
+    ) -> Result<GetAttemptDiffResponse, CallToolResult> {
+        let url = self.url(&format!(
+            "/api/task-attempts/{}/diff?stats_only={}",
+            attempt_id, !include_patch
+        ));
+        let diffs: Vec<Diff> = self.send_json(self.client.get(&url)).await?;
+
+        let files: Vec<FileDiffSummary> = diffs
+            .into_iter()
+            .map(|diff| FileDiffSummary::from_diff(diff, include_patch))
+            .collect();
+        Ok(GetAttemptDiffResponse {
+            attempt_id: attempt_id.to_string(),
+            count: files.len(),
+            additions: files.iter().map(|file| file.additions).sum(),
+            deletions: files.iter().map(|file| file.deletions).sum(),
+            files,
+        })
+    }
+
+    /// The end of a process's conversation, by default the attempt's latest coding agent run
+    async fn attempt_conversation(
+        &self,
+        attempt_id: Uuid,
+        execution_process_id: Option<Uuid>,
+        tail: Option<usize>,
+    ) -> Result<GetConversationResponse, CallToolResult> {
+        let execution_process_id = match execution_process_id {
+            Some(id) => id,
+            None => self
+                .execution_processes(attempt_id)
+                .await?
+                .into_iter()
+                .filter(|p| p.run_reason == ExecutionProcessRunReason::CodingAgent)
+                .max_by_key(|p| p.created_at)
+                .map(|process| process.id)
+                .ok_or_else(|| {
+                    Self::err(
+                        "The attempt has no coding agent runs yet.".to_string(),
+                        None::<String>,
+                    )
+                    .unwrap()
+                })?,
+        };
+
+        let mut url = self.url(&format!(
+            "/api/execution-processes/{}/conversation",
+            execution_process_id
+        ));
+        if let Some(tail) = tail {
+            url.push_str(&format!("?tail={}", tail));
+        }
+        let conversation: ConversationTail = self.send_json(self.client.get(&url)).await?;
+
+        Ok(GetConversationResponse {
+            attempt_id: attempt_id.to_string(),
+            execution_process_id: execution_process_id.to_string(),
+            total: conversation.total,
+            entries: conversation.entries,
+        })
+    }
+
+    async fn read_vibe_resource(
+        &self,
+        resource: VibeResource,
+    ) -> Result<serde_json::Value, CallToolResult> {
+        let value = match resource {
+            VibeResource::ProjectTasks(project_id) => {
+                let url = self.url(&format!("/api/tasks?project_id={}", project_id));
+                let tasks: Vec<TaskWithAttemptStatus> =
+                    self.send_json(self.client.get(&url)).await?;
+                let tasks: Vec<TaskSummary> = tasks
+                    .into_iter()
+                    .map(TaskSummary::from_task_with_status)
+                    .collect();
+                serde_json::to_value(ProjectTasksResource {
+                    project_id: project_id.to_string(),
+                    count: tasks.len(),
+                    tasks,
+                })
+            }
+            VibeResource::AttemptDiff(attempt_id) => {
+                serde_json::to_value(self.attempt_diff(attempt_id, true).await?)
+            }
+            VibeResource::AttemptLog(attempt_id) => {
+                serde_json::to_value(self.attempt_conversation(attempt_id, None, None).await?)
+            }
+        };
+        value.map_err(|e| Self::err("Failed to serialize response", Some(&e.to_string())).unwrap())
+    }
+}
+
+/// Error of a resource request, keeping the details a tool call would have returned
+fn resource_error(uri: &str, result: CallToolResult) -> ErrorData {
+    ErrorData::internal_error(
+        format!("Failed to read resource {}", uri),
+        result.structured_content,
+    )
+}
+
+impl TaskServer {
+    fn instructions(&self) -> String {
+        let mut instructions = "A task and project management server. If you need to create or update tickets or tasks then use these tools. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project. TOOLS: 'get_context', 'list_projects', 'list_tasks', 'create_task', 'start_task_attempt', 'get_task', 'update_task', 'delete_task', 'list_task_attempts', 'list_execution_processes', 'get_attempt_diff', 'get_attempt_conversation', 'send_follow_up', 'stop_task_attempt', 'rebase_task_attempt', 'merge_task_attempt', 'create_pull_request'. Use `list_task_attempts` to find the `attempt_id`s of a task. Make sure to pass `project_id`, `task_id` or `attempt_id` where required. You can use list tools to get the available ids. RESOURCES: 'vibe://project/{project_id}/tasks', 'vibe://attempt/{attempt_id}/diff', 'vibe://attempt/{attempt_id}/log'. Subscribe to them to be notified when a task or attempt changes instead of polling.".to_string();
+        if self.scope.is_empty() {
+            instructions.push_str(" Most tools need the `project_id` of the project you are working on; call `list_projects` to find it.");
+        } else {
+            let id =
+                |id: Option<Uuid>| id.map_or_else(|| "unknown".to_string(), |id| id.to_string());
+            instructions.push_str(&format!(
+                " You are working in project {}, on task {}, in task attempt {}. Tools default to these ids when you leave them out, and tasks you create in this project become subtasks of your attempt.",
+                id(self.scope.project_id),
+                id(self.scope.task_id),
+                id(self.scope.attempt_id),
+            ));
+        }
+        instructions
+    }
 }
 
 #[tool_handler]
@@ -595,12 +1392,108 @@ impl ServerHandler for TaskServer {
             protocol_version: ProtocolVersion::V_2025_03_26,
             capabilities: ServerCapabilities::builder()
                 .enable_tools()
+                .enable_resources()
+                .enable_resources_subscribe()
                 .build(),
             server_info: Implementation {
                 name: "vibe-kanban".to_string(),
                 version: "1.0.0".to_string(),
             },
-            instructions: Some("A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. This should be provided to you. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project`. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'start_task_attempt', 'get_task', 'update_task', 'delete_task'. Make sure to pass `project_id` or `task_id` where required. You can use list tools to get the available ids.".to_string()),
+            instructions: Some(self.instructions()),
         }
     }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, ErrorData> {
+        let url = self.url("/api/projects");
+        let projects: Vec<Project> = self
+            .send_json(self.client.get(&url))
+            .await
+            .map_err(|e| resource_error("vibe://project", e))?;
+
+        let resources = projects
+            .into_iter()
+            .map(|project| {
+                RawResource {
+                    description: Some(format!("Tasks of the project {}", project.name)),
+                    mime_type: Some(JSON_MIME_TYPE.to_string()),
+                    ..RawResource::new(
+                        VibeResource::ProjectTasks(project.id).uri(),
+                        format!("{} tasks", project.name),
+                    )
+                }
+                .no_annotation()
+            })
+            .collect();
+        Ok(ListResourcesResult {
+            resources,
+            next_cursor: None,
+        })
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, ErrorData> {
+        Ok(ListResourceTemplatesResult {
+            resource_templates: resources::resource_templates(),
+            next_cursor: None,
+        })
+    }
+
+    async fn read_resource(
+        &self,
+        ReadResourceRequestParam { uri }: ReadResourceRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, ErrorData> {
+        let resource = VibeResource::parse(&uri).ok_or_else(|| {
+            ErrorData::resource_not_found(format!("Unknown resource {uri}"), None)
+        })?;
+        let value = self
+            .read_vibe_resource(resource)
+            .await
+            .map_err(|e| resource_error(&uri, e))?;
+
+        Ok(ReadResourceResult {
+            contents: vec![ResourceContents::TextResourceContents {
+                text: serde_json::to_string_pretty(&value)
+                    .map_err(|e| ErrorData::internal_error(e.to_string(), None))?,
+                mime_type: Some(JSON_MIME_TYPE.to_string()),
+                uri,
+            }],
+        })
+    }
+
+    async fn subscribe(
+        &self,
+        SubscribeRequestParam { uri }: SubscribeRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<(), ErrorData> {
+        if VibeResource::parse(&uri).is_none() {
+            return Err(ErrorData::resource_not_found(
+                format!("Unknown resource {uri}"),
+                None,
+            ));
+        }
+        self.subscriptions.subscribe(
+            uri,
+            context.peer,
+            self.client.clone(),
+            self.url("/api/events"),
+        );
+        Ok(())
+    }
+
+    async fn unsubscribe(
+        &self,
+        UnsubscribeRequestParam { uri }: UnsubscribeRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), ErrorData> {
+        self.subscriptions.unsubscribe(&uri);
+        Ok(())
+    }
 }
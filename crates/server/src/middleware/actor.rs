@@ -0,0 +1,27 @@
+use axum::{extract::FromRequestParts, http::request::Parts};
+use db::models::audit_event::AuditActor;
+
+/// Header API clients set to say who they act for. The MCP server sends `mcp`.
+pub const ACTOR_HEADER: &str = "x-vk-actor";
+
+/// Who made a request, for the audit log. Requests without a recognised actor header come
+/// from the user.
+#[derive(Debug, Clone, Copy)]
+pub struct RequestActor(pub AuditActor);
+
+impl<S: Send + Sync> FromRequestParts<S> for RequestActor {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let actor = match parts
+            .headers
+            .get(ACTOR_HEADER)
+            .and_then(|value| value.to_str().ok())
+        {
+            Some("mcp") => AuditActor::Mcp,
+            Some("agent") => AuditActor::Agent,
+            _ => AuditActor::User,
+        };
+        Ok(Self(actor))
+    }
+}
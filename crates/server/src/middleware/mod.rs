@@ -1,3 +1,5 @@
+pub mod actor;
 pub mod model_loaders;
 
+pub use actor::*;
 pub use model_loaders::*;
@@ -5,8 +5,9 @@ use axum::{
     response::Response,
 };
 use db::models::{
-    execution_process::ExecutionProcess, project::Project, tag::Tag, task::Task,
-    task_attempt::TaskAttempt,
+    custom_field::CustomField, execution_process::ExecutionProcess, issue_tracker::IssueTracker,
+    label::Label, project::Project, tag::Tag, task::Task, task_attempt::TaskAttempt,
+    task_schedule::TaskSchedule,
 };
 use deployment::Deployment;
 use uuid::Uuid;
@@ -203,3 +204,91 @@ pub async fn load_tag_middleware(
     // Continue with the next middleware/handler
     Ok(next.run(request).await)
 }
+
+pub async fn load_schedule_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(schedule_id): Path<Uuid>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let schedule = match TaskSchedule::find_by_id(&deployment.db().pool, schedule_id).await {
+        Ok(Some(schedule)) => schedule,
+        Ok(None) => {
+            tracing::warn!("Task schedule {} not found", schedule_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch task schedule {}: {}", schedule_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    request.extensions_mut().insert(schedule);
+    Ok(next.run(request).await)
+}
+
+pub async fn load_issue_tracker_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(tracker_id): Path<Uuid>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let tracker = match IssueTracker::find_by_id(&deployment.db().pool, tracker_id).await {
+        Ok(Some(tracker)) => tracker,
+        Ok(None) => {
+            tracing::warn!("Issue tracker {} not found", tracker_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch issue tracker {}: {}", tracker_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    request.extensions_mut().insert(tracker);
+    Ok(next.run(request).await)
+}
+
+pub async fn load_label_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(label_id): Path<Uuid>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let label = match Label::find_by_id(&deployment.db().pool, label_id).await {
+        Ok(Some(label)) => label,
+        Ok(None) => {
+            tracing::warn!("Label {} not found", label_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch label {}: {}", label_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    request.extensions_mut().insert(label);
+    Ok(next.run(request).await)
+}
+
+pub async fn load_custom_field_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(field_id): Path<Uuid>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let field = match CustomField::find_by_id(&deployment.db().pool, field_id).await {
+        Ok(Some(field)) => field,
+        Ok(None) => {
+            tracing::warn!("Custom field {} not found", field_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch custom field {}: {}", field_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    request.extensions_mut().insert(field);
+    Ok(next.run(request).await)
+}
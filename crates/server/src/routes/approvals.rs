@@ -4,7 +4,10 @@ use axum::{
     http::StatusCode,
     routing::{get, post},
 };
-use db::models::execution_process::ExecutionProcess;
+use db::models::{
+    audit_event::{AuditAction, AuditEvent, CreateAuditEvent},
+    execution_process::ExecutionProcess,
+};
 use deployment::Deployment;
 use services::services::container::ContainerService;
 use utils::approvals::{
@@ -12,7 +15,7 @@ use utils::approvals::{
     EXIT_PLAN_MODE_TOOL_NAME,
 };
 
-use crate::DeploymentImpl;
+use crate::{DeploymentImpl, middleware::RequestActor};
 
 pub async fn create_approval(
     State(deployment): State<DeploymentImpl>,
@@ -58,6 +61,7 @@ pub async fn get_approval_status(
 
 pub async fn respond_to_approval(
     State(deployment): State<DeploymentImpl>,
+    RequestActor(actor): RequestActor,
     Path(id): Path<String>,
     Json(request): Json<ApprovalResponse>,
 ) -> Result<Json<ApprovalStatus>, StatusCode> {
@@ -77,15 +81,30 @@ pub async fn respond_to_approval(
                 )
                 .await;
 
+            let ctx =
+                ExecutionProcess::load_context(&deployment.db().pool, context.execution_process_id)
+                    .await
+                    .ok();
+            if let Some(ctx) = &ctx {
+                AuditEvent::record(
+                    &deployment.db().pool,
+                    CreateAuditEvent::new(actor, AuditAction::ApprovalResponded)
+                        .task(ctx.task.project_id, ctx.task.id)
+                        .attempt(ctx.task_attempt.id)
+                        .details(serde_json::json!({
+                            "approval_id": id,
+                            "tool_name": context.tool_name,
+                            "status": status,
+                        })),
+                )
+                .await;
+            }
+
             if matches!(status, ApprovalStatus::Approved)
                 && context.tool_name == EXIT_PLAN_MODE_TOOL_NAME
                 // If exiting plan mode, automatically start a new execution process with different
                 // permissions
-                && let Ok(ctx) = ExecutionProcess::load_context(
-                    &deployment.db().pool,
-                    context.execution_process_id,
-                )
-                .await
+                && let Some(ctx) = ctx
                 && let Err(e) = deployment.container().exit_plan_mode_tool(ctx).await
             {
                 tracing::error!("failed to exit plan mode: {:?}", e);
@@ -0,0 +1,83 @@
+use axum::{
+    Router,
+    extract::{
+        Query, State,
+        ws::{WebSocket, WebSocketUpgrade},
+    },
+    response::{IntoResponse, Json as ResponseJson},
+    routing::get,
+};
+use db::models::audit_event::{AuditEvent, AuditQuery};
+use deployment::Deployment;
+use futures_util::{SinkExt, StreamExt, TryStreamExt};
+use serde::Deserialize;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Audit log entries across all projects, newest first
+pub async fn list_audit_events(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<AuditQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<AuditEvent>>>, ApiError> {
+    let events = AuditEvent::find(&deployment.db().pool, &query).await?;
+    Ok(ResponseJson(ApiResponse::success(events)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ActivityQuery {
+    pub project_id: Option<Uuid>,
+    /// Resume a stream after this sequence number instead of starting from a snapshot
+    pub after: Option<u64>,
+}
+
+pub async fn stream_activity_ws(
+    ws: WebSocketUpgrade,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ActivityQuery>,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| async move {
+        if let Err(e) = handle_activity_ws(socket, deployment, query).await {
+            tracing::warn!("activity WS closed: {}", e);
+        }
+    })
+}
+
+async fn handle_activity_ws(
+    socket: WebSocket,
+    deployment: DeploymentImpl,
+    query: ActivityQuery,
+) -> anyhow::Result<()> {
+    let mut stream = deployment
+        .events()
+        .stream_activity_raw(query.project_id, query.after)
+        .await?
+        .map_ok(|msg| msg.to_ws_message());
+
+    let (mut sender, mut receiver) = socket.split();
+
+    // Drain (and ignore) any client->server messages so pings/pongs work
+    tokio::spawn(async move { while let Some(Ok(_)) = receiver.next().await {} });
+
+    while let Some(item) = stream.next().await {
+        match item {
+            Ok(msg) => {
+                if sender.send(msg).await.is_err() {
+                    break; // client disconnected
+                }
+            }
+            Err(e) => {
+                tracing::error!("stream error: {}", e);
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/audit", get(list_audit_events))
+        .route("/audit/stream/ws", get(stream_activity_ws))
+}
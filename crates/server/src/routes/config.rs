@@ -8,6 +8,7 @@ use axum::{
     response::{Json as ResponseJson, Response},
     routing::{get, put},
 };
+use db::models::audit_event::{AuditAction, AuditEvent, CreateAuditEvent};
 use deployment::{Deployment, DeploymentError};
 use executors::{
     executors::{BaseAgentCapability, BaseCodingAgent, StandardCodingAgentExecutor},
@@ -16,12 +17,15 @@ use executors::{
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use services::services::config::{Config, ConfigError, SoundFile, save_config_to_file};
+use services::services::{
+    config::{Config, ConfigError, SoundFile, save_config_to_file},
+    prompt_template,
+};
 use tokio::fs;
 use ts_rs::TS;
 use utils::{assets::config_path, response::ApiResponse};
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{DeploymentImpl, error::ApiError, middleware::RequestActor};
 
 pub fn router() -> Router<DeploymentImpl> {
     Router::new()
@@ -98,6 +102,7 @@ async fn get_user_system_info(
 
 async fn update_config(
     State(deployment): State<DeploymentImpl>,
+    RequestActor(actor): RequestActor,
     Json(new_config): Json<Config>,
 ) -> ResponseJson<ApiResponse<Config>> {
     let config_path = config_path();
@@ -121,12 +126,36 @@ async fn update_config(
             // Track config events when fields transition from false → true and run side effects
             handle_config_events(&deployment, &old_config, &new_config).await;
 
+            let changed = changed_config_fields(&old_config, &new_config);
+            if !changed.is_empty() {
+                AuditEvent::record(
+                    &deployment.db().pool,
+                    CreateAuditEvent::new(actor, AuditAction::ConfigUpdated)
+                        .details(serde_json::json!({ "fields": changed })),
+                )
+                .await;
+            }
+
             ResponseJson(ApiResponse::success(new_config))
         }
         Err(e) => ResponseJson(ApiResponse::error(&format!("Failed to save config: {}", e))),
     }
 }
 
+/// Names of the top level config fields that differ. Values are left out of the audit log as
+/// they can hold credentials.
+fn changed_config_fields(old: &Config, new: &Config) -> Vec<String> {
+    let (Ok(Value::Object(old)), Ok(Value::Object(new))) =
+        (serde_json::to_value(old), serde_json::to_value(new))
+    else {
+        return Vec::new();
+    };
+    new.iter()
+        .filter(|(key, value)| old.get(*key) != Some(*value))
+        .map(|(key, _)| key.clone())
+        .collect()
+}
+
 /// Track config events when fields transition from false → true
 async fn track_config_events(deployment: &DeploymentImpl, old: &Config, new: &Config) {
     let events = [
@@ -256,7 +285,8 @@ async fn get_mcp_servers(
 }
 
 async fn update_mcp_servers(
-    State(_deployment): State<DeploymentImpl>,
+    State(deployment): State<DeploymentImpl>,
+    RequestActor(actor): RequestActor,
     Query(query): Query<McpServerQuery>,
     Json(payload): Json<UpdateMcpServersBody>,
 ) -> Result<ResponseJson<ApiResponse<String>>, ApiError> {
@@ -284,8 +314,19 @@ async fn update_mcp_servers(
     };
 
     let mcpc = agent.get_mcp_config();
+    let mut server_names: Vec<String> = payload.servers.keys().cloned().collect();
+    server_names.sort();
     match update_mcp_servers_in_config(&config_path, &mcpc, payload.servers).await {
-        Ok(message) => Ok(ResponseJson(ApiResponse::success(message))),
+        Ok(message) => {
+            AuditEvent::record(
+                &deployment.db().pool,
+                CreateAuditEvent::new(actor, AuditAction::McpServersUpdated).details(
+                    serde_json::json!({ "executor": query.executor, "servers": server_names }),
+                ),
+            )
+            .await;
+            Ok(ResponseJson(ApiResponse::success(message)))
+        }
         Err(e) => Ok(ResponseJson(ApiResponse::error(&format!(
             "Failed to update MCP servers: {}",
             e
@@ -410,18 +451,36 @@ async fn get_profiles(
 }
 
 async fn update_profiles(
-    State(_deployment): State<DeploymentImpl>,
+    State(deployment): State<DeploymentImpl>,
+    RequestActor(actor): RequestActor,
     body: String,
 ) -> ResponseJson<ApiResponse<String>> {
     // Try to parse as ExecutorProfileConfigs format
     match serde_json::from_str::<ExecutorConfigs>(&body) {
         Ok(executor_profiles) => {
+            // Append prompts are templates, reject ones with unknown variables
+            for (executor, config) in &executor_profiles.executors {
+                for (variant, agent) in &config.configurations {
+                    if let Some(append_prompt) = agent.append_prompt().get()
+                        && let Err(e) = prompt_template::validate(&append_prompt)
+                    {
+                        return ResponseJson(ApiResponse::error(&format!(
+                            "Invalid append_prompt for {executor}:{variant}: {e}"
+                        )));
+                    }
+                }
+            }
             // Save the profiles to file
             match executor_profiles.save_overrides() {
                 Ok(_) => {
                     tracing::info!("Executor profiles saved successfully");
                     // Reload the cached profiles
                     ExecutorConfigs::reload();
+                    AuditEvent::record(
+                        &deployment.db().pool,
+                        CreateAuditEvent::new(actor, AuditAction::ProfilesUpdated),
+                    )
+                    .await;
                     ResponseJson(ApiResponse::success(
                         "Executor profiles updated successfully".to_string(),
                     ))
@@ -17,6 +17,8 @@ use crate::DeploymentImpl;
 #[derive(Debug, Deserialize)]
 pub struct DraftsQuery {
     pub project_id: Uuid,
+    /// Resume a stream after this sequence number instead of starting from a snapshot
+    pub after: Option<u64>,
 }
 
 pub async fn stream_project_drafts_ws(
@@ -25,7 +27,9 @@ pub async fn stream_project_drafts_ws(
     Query(query): Query<DraftsQuery>,
 ) -> impl IntoResponse {
     ws.on_upgrade(move |socket| async move {
-        if let Err(e) = handle_project_drafts_ws(socket, deployment, query.project_id).await {
+        if let Err(e) =
+            handle_project_drafts_ws(socket, deployment, query.project_id, query.after).await
+        {
             tracing::warn!("drafts WS closed: {}", e);
         }
     })
@@ -35,12 +39,13 @@ async fn handle_project_drafts_ws(
     socket: WebSocket,
     deployment: DeploymentImpl,
     project_id: Uuid,
+    after: Option<u64>,
 ) -> anyhow::Result<()> {
     let mut stream = deployment
         .events()
-        .stream_drafts_for_project_raw(project_id)
+        .stream_drafts_for_project_raw(project_id, after)
         .await?
-        .map_ok(|msg| msg.to_ws_message_unchecked());
+        .map_ok(|msg| msg.to_ws_message());
 
     let (mut sender, mut receiver) = socket.split();
     tokio::spawn(async move { while let Some(Ok(_)) = receiver.next().await {} });
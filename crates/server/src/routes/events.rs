@@ -1,6 +1,7 @@
 use axum::{
     BoxError, Router,
-    extract::State,
+    extract::{Query, State},
+    http::HeaderMap,
     response::{
         Sse,
         sse::{Event, KeepAlive},
@@ -9,15 +10,37 @@ use axum::{
 };
 use deployment::Deployment;
 use futures_util::TryStreamExt;
+use serde::Deserialize;
 
 use crate::DeploymentImpl;
 
+#[derive(Debug, Deserialize)]
+pub struct EventsQuery {
+    /// Resume after this sequence number, for clients that can't set `Last-Event-ID`
+    pub after: Option<u64>,
+    /// Skip the replay buffer and only send events that happen from now on
+    #[serde(default)]
+    pub live: bool,
+}
+
 pub async fn events(
     State(deployment): State<DeploymentImpl>,
+    Query(query): Query<EventsQuery>,
+    headers: HeaderMap,
 ) -> Result<Sse<impl futures_util::Stream<Item = Result<Event, BoxError>>>, axum::http::StatusCode>
 {
+    // EventSource sends the id of the last event it saw when it reconnects
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok());
+
+    let after = last_event_id
+        .or(query.after)
+        .or_else(|| query.live.then(|| deployment.events().latest_seq()));
+
     // Ask the container service for a combined "history + live" stream
-    let stream = deployment.stream_events().await;
+    let stream = deployment.stream_events(after).await;
     Ok(Sse::new(stream.map_err(|e| -> BoxError { e.into() })).keep_alive(KeepAlive::default()))
 }
 
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use anyhow;
 use axum::{
     Extension, Router,
@@ -5,18 +7,23 @@ use axum::{
         Path, Query, State,
         ws::{WebSocket, WebSocketUpgrade},
     },
+    http::header,
     middleware::from_fn_with_state,
-    response::{IntoResponse, Json as ResponseJson},
+    response::{IntoResponse, Json as ResponseJson, Response},
     routing::{get, post},
 };
-use db::models::execution_process::{
-    ExecutionProcess, ExecutionProcessError, ExecutionProcessStatus,
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessError, ExecutionProcessStatus},
+    execution_process_logs::{ExecutionProcessLogPage, ExecutionProcessLogs},
 };
 use deployment::Deployment;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
-use serde::Deserialize;
-use services::services::container::ContainerService;
-use utils::{log_msg::LogMsg, response::ApiResponse};
+use serde::{Deserialize, Serialize};
+use services::services::{
+    container::ContainerService,
+    conversation_export::{ConversationExport, ExportFormat},
+};
+use utils::{log_msg::LogMsg, response::ApiResponse, text::short_uuid};
 use uuid::Uuid;
 
 use crate::{DeploymentImpl, error::ApiError, middleware::load_execution_process_middleware};
@@ -27,6 +34,8 @@ pub struct ExecutionProcessQuery {
     /// If true, include soft-deleted (dropped) processes in results/stream
     #[serde(default)]
     pub show_soft_deleted: Option<bool>,
+    /// Resume a stream after this sequence number instead of starting from a snapshot
+    pub after: Option<u64>,
 }
 
 pub async fn get_execution_processes(
@@ -51,6 +60,86 @@ pub async fn get_execution_process_by_id(
     Ok(ResponseJson(ApiResponse::success(execution_process)))
 }
 
+const DEFAULT_RAW_LOGS_LIMIT: i64 = 500;
+const MAX_RAW_LOGS_LIMIT: i64 = 5000;
+
+#[derive(Debug, Deserialize)]
+pub struct RawLogsQuery {
+    /// Index of the first entry to return
+    pub offset: Option<i64>,
+    pub limit: Option<i64>,
+    /// Return the last `tail` entries instead of a window starting at `offset`
+    pub tail: Option<i64>,
+}
+
+pub async fn get_raw_logs(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<RawLogsQuery>,
+) -> Result<ResponseJson<ApiResponse<ExecutionProcessLogPage>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let page = match query.tail {
+        Some(tail) => {
+            ExecutionProcessLogs::read_tail(
+                pool,
+                execution_process.id,
+                tail.clamp(0, MAX_RAW_LOGS_LIMIT),
+            )
+            .await?
+        }
+        None => {
+            let limit = query
+                .limit
+                .unwrap_or(DEFAULT_RAW_LOGS_LIMIT)
+                .clamp(0, MAX_RAW_LOGS_LIMIT);
+            ExecutionProcessLogs::read_range(
+                pool,
+                execution_process.id,
+                query.offset.unwrap_or(0),
+                limit,
+            )
+            .await?
+        }
+    };
+
+    Ok(ResponseJson(ApiResponse::success(page)))
+}
+
+const DEFAULT_CONVERSATION_TAIL: usize = 50;
+
+#[derive(Debug, Deserialize)]
+pub struct ConversationQuery {
+    /// Return only the last `tail` entries, 50 by default
+    pub tail: Option<usize>,
+}
+
+/// The end of a process's normalized conversation
+#[derive(Debug, Serialize)]
+pub struct ConversationTail {
+    /// Number of entries in the whole conversation
+    pub total: usize,
+    pub entries: Vec<serde_json::Value>,
+}
+
+pub async fn get_conversation(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ConversationQuery>,
+) -> Result<ResponseJson<ApiResponse<ConversationTail>>, ApiError> {
+    let mut entries = deployment
+        .container()
+        .load_conversation(&execution_process.id)
+        .await
+        .unwrap_or_default();
+    let total = entries.len();
+    let tail = query.tail.unwrap_or(DEFAULT_CONVERSATION_TAIL).min(total);
+    let entries = entries.split_off(total - tail);
+    Ok(ResponseJson(ApiResponse::success(ConversationTail {
+        total,
+        entries,
+    })))
+}
+
 pub async fn stream_raw_logs_ws(
     ws: WebSocketUpgrade,
     State(deployment): State<DeploymentImpl>,
@@ -180,6 +269,47 @@ async fn handle_normalized_logs_ws(
     Ok(())
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ReplayQuery {
+    /// Playback speed, 2.0 replays twice as fast as the original run
+    #[serde(default = "default_replay_speed")]
+    pub speed: f64,
+    /// Longest pause between two entries, so idle periods don't stall the replay
+    pub max_gap_secs: Option<f64>,
+}
+
+fn default_replay_speed() -> f64 {
+    1.0
+}
+
+/// Stream a process's conversation entries with their original relative timing
+pub async fn stream_replay_ws(
+    ws: WebSocketUpgrade,
+    State(deployment): State<DeploymentImpl>,
+    Path(exec_id): Path<Uuid>,
+    Query(query): Query<ReplayQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let max_gap = query
+        .max_gap_secs
+        .filter(|secs| secs.is_finite() && *secs >= 0.0)
+        .map(Duration::from_secs_f64);
+    let stream = deployment
+        .container()
+        .replay_conversation(&exec_id, query.speed, max_gap)
+        .await
+        .ok_or_else(|| {
+            ApiError::ExecutionProcess(ExecutionProcessError::ExecutionProcessNotFound)
+        })?;
+
+    let stream = stream.err_into::<anyhow::Error>().into_stream();
+
+    Ok(ws.on_upgrade(move |socket| async move {
+        if let Err(e) = handle_normalized_logs_ws(socket, stream).await {
+            tracing::warn!("replay WS closed: {}", e);
+        }
+    }))
+}
+
 pub async fn stop_execution_process(
     Extension(execution_process): Extension<ExecutionProcess>,
     State(deployment): State<DeploymentImpl>,
@@ -192,6 +322,75 @@ pub async fn stop_execution_process(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+/// Render an export as a file download
+pub fn export_response(
+    export: &ConversationExport,
+    format: ExportFormat,
+    file_stem: &str,
+) -> Result<Response, ApiError> {
+    let body = export.render(format).map_err(std::io::Error::from)?;
+    let disposition = format!(
+        "attachment; filename=\"{}.{}\"",
+        file_stem,
+        format.extension()
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// Export the conversation of a single execution process
+pub async fn export_execution_process(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response, ApiError> {
+    let ctx = ExecutionProcess::load_context(&deployment.db().pool, execution_process.id).await?;
+    let file_stem = format!("process-{}", short_uuid(&execution_process.id));
+    let export = ConversationExport::collect(
+        deployment.container(),
+        ctx.task.title,
+        vec![execution_process],
+    )
+    .await;
+
+    export_response(&export, query.format, &file_stem)
+}
+
+/// Re-normalize a finished process from its raw logs, replacing the stored conversation
+pub async fn renormalize_execution_process(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<bool>>, ApiError> {
+    let renormalized = deployment
+        .container()
+        .renormalize_execution(&execution_process.id)
+        .await?;
+
+    Ok(ResponseJson(ApiResponse::success(renormalized)))
+}
+
+/// Re-normalize every stored conversation produced by an older normalizer version
+pub async fn renormalize_outdated_execution_processes(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<usize>>, ApiError> {
+    let renormalized = deployment.container().renormalize_outdated().await?;
+    tracing::info!("Re-normalized {} stored conversations", renormalized);
+
+    Ok(ResponseJson(ApiResponse::success(renormalized)))
+}
+
 pub async fn stream_execution_processes_ws(
     ws: WebSocketUpgrade,
     State(deployment): State<DeploymentImpl>,
@@ -203,6 +402,7 @@ pub async fn stream_execution_processes_ws(
             deployment,
             query.task_attempt_id,
             query.show_soft_deleted.unwrap_or(false),
+            query.after,
         )
         .await
         {
@@ -216,13 +416,14 @@ async fn handle_execution_processes_ws(
     deployment: DeploymentImpl,
     task_attempt_id: uuid::Uuid,
     show_soft_deleted: bool,
+    after: Option<u64>,
 ) -> anyhow::Result<()> {
     // Get the raw stream and convert LogMsg to WebSocket messages
     let mut stream = deployment
         .events()
-        .stream_execution_processes_for_attempt_raw(task_attempt_id, show_soft_deleted)
+        .stream_execution_processes_for_attempt_raw(task_attempt_id, show_soft_deleted, after)
         .await?
-        .map_ok(|msg| msg.to_ws_message_unchecked());
+        .map_ok(|msg| msg.to_ws_message());
 
     // Split socket into sender and receiver
     let (mut sender, mut receiver) = socket.split();
@@ -251,8 +452,13 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let task_attempt_id_router = Router::new()
         .route("/", get(get_execution_process_by_id))
         .route("/stop", post(stop_execution_process))
+        .route("/renormalize", post(renormalize_execution_process))
+        .route("/export", get(export_execution_process))
+        .route("/raw-logs", get(get_raw_logs))
+        .route("/conversation", get(get_conversation))
         .route("/raw-logs/ws", get(stream_raw_logs_ws))
         .route("/normalized-logs/ws", get(stream_normalized_logs_ws))
+        .route("/replay/ws", get(stream_replay_ws))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_execution_process_middleware,
@@ -261,6 +467,10 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let task_attempts_router = Router::new()
         .route("/", get(get_execution_processes))
         .route("/stream/ws", get(stream_execution_processes_ws))
+        .route(
+            "/renormalize-outdated",
+            post(renormalize_outdated_execution_processes),
+        )
         .nest("/{id}", task_attempt_id_router);
 
     Router::new().nest("/execution-processes", task_attempts_router)
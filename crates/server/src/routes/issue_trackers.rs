@@ -0,0 +1,134 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::{
+    issue_tracker::{CreateIssueTracker, IssueTracker, TaskIssueLink, UpdateIssueTracker},
+    project::Project,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::issue_sync::{self, IssueSyncError, SyncSummary};
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_issue_tracker_middleware};
+
+#[derive(Debug, Deserialize)]
+pub struct IssueTrackerQuery {
+    pub project_id: Option<Uuid>,
+}
+
+pub async fn get_issue_trackers(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<IssueTrackerQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<IssueTracker>>>, ApiError> {
+    let trackers = IssueTracker::find_all(&deployment.db().pool, query.project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(trackers)))
+}
+
+pub async fn get_issue_tracker(
+    Extension(tracker): Extension<IssueTracker>,
+) -> Result<ResponseJson<ApiResponse<IssueTracker>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(tracker)))
+}
+
+pub async fn create_issue_tracker(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateIssueTracker>,
+) -> Result<ResponseJson<ApiResponse<IssueTracker>>, ApiError> {
+    let pool = &deployment.db().pool;
+    issue_sync::validate(payload.kind, payload.url.as_deref())?;
+    if Project::find_by_id(pool, payload.project_id)
+        .await?
+        .is_none()
+    {
+        return Err(IssueSyncError::ProjectNotFound.into());
+    }
+    let tracker = IssueTracker::create(pool, &payload, Uuid::new_v4()).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "issue_tracker_created",
+            serde_json::json!({
+                "tracker_id": tracker.id.to_string(),
+                "project_id": tracker.project_id.to_string(),
+                "kind": tracker.kind,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(tracker)))
+}
+
+pub async fn update_issue_tracker(
+    Extension(tracker): Extension<IssueTracker>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateIssueTracker>,
+) -> Result<ResponseJson<ApiResponse<IssueTracker>>, ApiError> {
+    issue_sync::validate(tracker.kind, payload.url.as_deref())?;
+    let tracker = IssueTracker::update(&deployment.db().pool, tracker.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(tracker)))
+}
+
+pub async fn delete_issue_tracker(
+    Extension(tracker): Extension<IssueTracker>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = IssueTracker::delete(&deployment.db().pool, tracker.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+/// Sync the tracker now rather than waiting for the background sync
+pub async fn sync_issue_tracker(
+    Extension(tracker): Extension<IssueTracker>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<SyncSummary>>, ApiError> {
+    let github_token = deployment.config().read().await.github.token();
+    let summary = issue_sync::sync_tracker(
+        &deployment.db().pool,
+        &tracker,
+        github_token,
+        deployment.git(),
+        deployment.project_env(),
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(summary)))
+}
+
+pub async fn get_issue_links(
+    Extension(tracker): Extension<IssueTracker>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskIssueLink>>>, ApiError> {
+    let links = TaskIssueLink::find_by_tracker_id(&deployment.db().pool, tracker.id).await?;
+    Ok(ResponseJson(ApiResponse::success(links)))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let tracker_router = Router::new()
+        .route(
+            "/",
+            get(get_issue_tracker)
+                .put(update_issue_tracker)
+                .delete(delete_issue_tracker),
+        )
+        .route("/sync", post(sync_issue_tracker))
+        .route("/links", get(get_issue_links))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_issue_tracker_middleware,
+        ));
+
+    let inner = Router::new()
+        .route("/", get(get_issue_trackers).post(create_issue_tracker))
+        .nest("/{tracker_id}", tracker_router);
+
+    Router::new().nest("/issue-trackers", inner)
+}
@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+use axum::{
+    Router,
+    extract::{Request, State},
+    http::{StatusCode, header},
+    middleware::{Next, from_fn_with_state},
+    response::{IntoResponse, Response},
+};
+use rmcp::transport::streamable_http_server::{
+    StreamableHttpServerConfig, StreamableHttpService, session::local::LocalSessionManager,
+};
+use sha2::{Digest, Sha256};
+
+use crate::mcp::task_server::TaskServer;
+
+/// Bearer token clients of `/mcp` must send. The endpoint is open when this is unset.
+pub const MCP_TOKEN_ENV: &str = "VIBE_MCP_TOKEN";
+
+/// The MCP task server over Streamable HTTP. Each session gets its own `TaskServer`, which
+/// calls the API at `backend_url` like the stdio binary does.
+pub fn router(backend_url: &str) -> Router {
+    let backend_url = backend_url.to_string();
+    let service = StreamableHttpService::new(
+        move || Ok(TaskServer::new(&backend_url)),
+        Arc::new(LocalSessionManager::default()),
+        StreamableHttpServerConfig::default(),
+    );
+
+    // Only the token's digest is kept, so comparing what clients send takes the same time
+    // however much of it matches
+    let token = std::env::var(MCP_TOKEN_ENV)
+        .ok()
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+        .map(|token| Sha256::digest(token.as_bytes()));
+    if token.is_none() {
+        tracing::debug!(
+            "{} is not set, /mcp accepts requests without a token",
+            MCP_TOKEN_ENV
+        );
+    }
+
+    Router::new()
+        .nest_service("/mcp", service)
+        .layer(from_fn_with_state(token, require_bearer_token))
+}
+
+type TokenDigest = sha2::digest::Output<Sha256>;
+
+async fn require_bearer_token(
+    State(token): State<Option<TokenDigest>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let Some(token) = token else {
+        return next.run(req).await;
+    };
+    let authorized = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|sent| Sha256::digest(sent.as_bytes()) == token);
+    if !authorized {
+        return (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+        )
+            .into_response();
+    }
+    next.run(req).await
+}
@@ -0,0 +1,251 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use axum::{Router, extract::State, http::header, response::IntoResponse, routing::get};
+use db::models::{
+    execution_process::ExecutionProcess,
+    task::{Task, TaskStatus},
+};
+use deployment::Deployment;
+use executors::actions::ExecutorAction;
+use services::services::{
+    pr_monitor,
+    worktree_manager::{WorktreeManager, WorktreeUsage},
+};
+
+use crate::{DeploymentImpl, error::ApiError};
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Walking every worktree is slow, so disk usage is reused between scrapes for this long
+const WORKTREE_USAGE_TTL: Duration = Duration::from_secs(60);
+
+static WORKTREE_USAGE: Mutex<Option<(Instant, WorktreeUsage)>> = Mutex::new(None);
+
+/// Prometheus text exposition of the server's state
+pub async fn get_metrics(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<impl IntoResponse, ApiError> {
+    let pool = &deployment.db().pool;
+    let mut out = MetricsWriter::default();
+
+    let task_counts = Task::count_by_status(pool).await?;
+    out.header(
+        "vibe_kanban_tasks",
+        "gauge",
+        "Tasks across all projects, by status",
+    );
+    for status in [
+        TaskStatus::Todo,
+        TaskStatus::InProgress,
+        TaskStatus::InReview,
+        TaskStatus::Done,
+        TaskStatus::Cancelled,
+    ] {
+        let count = task_counts
+            .iter()
+            .find(|(s, _)| *s == status)
+            .map_or(0, |(_, count)| *count);
+        out.sample(
+            "vibe_kanban_tasks",
+            &[("status", &status.to_string())],
+            count,
+        );
+    }
+
+    let mut running: BTreeMap<(String, String), u64> = BTreeMap::new();
+    for process in ExecutionProcess::find_running(pool).await? {
+        let executor = process
+            .executor_action()
+            .ok()
+            .and_then(ExecutorAction::base_executor)
+            .map(|executor| executor.to_string())
+            .unwrap_or_default();
+        *running
+            .entry((process.run_reason.to_string(), executor))
+            .or_default() += 1;
+    }
+    out.header(
+        "vibe_kanban_execution_processes_running",
+        "gauge",
+        "Running execution processes, by run reason and executor",
+    );
+    for ((run_reason, executor), count) in &running {
+        out.sample(
+            "vibe_kanban_execution_processes_running",
+            &[("run_reason", run_reason), ("executor", executor)],
+            count,
+        );
+    }
+
+    out.header(
+        "vibe_kanban_approvals_pending",
+        "gauge",
+        "Tool calls waiting for an approval response",
+    );
+    out.sample(
+        "vibe_kanban_approvals_pending",
+        &[],
+        deployment.approvals().pending().await.len(),
+    );
+
+    let (store_count, store_bytes, spilled_bytes) = {
+        let stores = deployment.msg_stores().read().await;
+        let bytes: usize = stores.values().map(|store| store.history_bytes()).sum();
+        let spilled: u64 = stores.values().map(|store| store.spilled_bytes()).sum();
+        (stores.len(), bytes, spilled)
+    };
+    out.header(
+        "vibe_kanban_msg_stores",
+        "gauge",
+        "In-memory log stores of execution processes",
+    );
+    out.sample("vibe_kanban_msg_stores", &[], store_count);
+    out.header(
+        "vibe_kanban_msg_store_bytes",
+        "gauge",
+        "Approximate memory held by in-memory log history",
+    );
+    out.sample("vibe_kanban_msg_store_bytes", &[], store_bytes);
+    out.header(
+        "vibe_kanban_msg_store_spilled_bytes",
+        "gauge",
+        "Log history spilled to disk to stay within the memory budget",
+    );
+    out.sample("vibe_kanban_msg_store_spilled_bytes", &[], spilled_bytes);
+
+    let cache = deployment.file_search_cache().stats();
+    out.header(
+        "vibe_kanban_file_search_cache_lookups_total",
+        "counter",
+        "File search cache lookups, by result",
+    );
+    out.sample(
+        "vibe_kanban_file_search_cache_lookups_total",
+        &[("result", "hit")],
+        cache.hits,
+    );
+    out.sample(
+        "vibe_kanban_file_search_cache_lookups_total",
+        &[("result", "miss")],
+        cache.misses,
+    );
+    let lookups = cache.hits + cache.misses;
+    let hit_rate = if lookups == 0 {
+        0.0
+    } else {
+        cache.hits as f64 / lookups as f64
+    };
+    out.header(
+        "vibe_kanban_file_search_cache_hit_ratio",
+        "gauge",
+        "Share of file search cache lookups served from the cache",
+    );
+    out.sample("vibe_kanban_file_search_cache_hit_ratio", &[], hit_rate);
+
+    if let Some(usage) = worktree_usage().await {
+        out.header(
+            "vibe_kanban_worktrees",
+            "gauge",
+            "Task attempt worktrees on disk",
+        );
+        out.sample("vibe_kanban_worktrees", &[], usage.count);
+        out.header(
+            "vibe_kanban_worktree_bytes",
+            "gauge",
+            "Disk space used by task attempt worktrees",
+        );
+        out.sample("vibe_kanban_worktree_bytes", &[], usage.bytes);
+    }
+
+    out.header(
+        "vibe_kanban_pr_monitor_poll_errors_total",
+        "counter",
+        "Failed pull request status checks",
+    );
+    out.sample(
+        "vibe_kanban_pr_monitor_poll_errors_total",
+        &[],
+        pr_monitor::poll_error_count(),
+    );
+
+    Ok(([(header::CONTENT_TYPE, CONTENT_TYPE)], out.0))
+}
+
+async fn worktree_usage() -> Option<WorktreeUsage> {
+    if let Some((at, usage)) = *WORKTREE_USAGE.lock().unwrap()
+        && at.elapsed() < WORKTREE_USAGE_TTL
+    {
+        return Some(usage);
+    }
+    match WorktreeManager::usage().await {
+        Ok(usage) => {
+            *WORKTREE_USAGE.lock().unwrap() = Some((Instant::now(), usage));
+            Some(usage)
+        }
+        Err(e) => {
+            tracing::warn!("Failed to measure worktree disk usage: {}", e);
+            None
+        }
+    }
+}
+
+#[derive(Default)]
+struct MetricsWriter(String);
+
+impl MetricsWriter {
+    fn header(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.0, "# HELP {name} {help}");
+        let _ = writeln!(self.0, "# TYPE {name} {kind}");
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: impl std::fmt::Display) {
+        self.0.push_str(name);
+        if !labels.is_empty() {
+            self.0.push('{');
+            for (i, (key, value)) in labels.iter().enumerate() {
+                if i > 0 {
+                    self.0.push(',');
+                }
+                let _ = write!(self.0, "{key}=\"{}\"", escape_label(value));
+            }
+            self.0.push('}');
+        }
+        let _ = writeln!(self.0, " {value}");
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    Router::new().route("/metrics", get(get_metrics))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_text_exposition_format() {
+        let mut out = MetricsWriter::default();
+        out.header("vibe_kanban_tasks", "gauge", "Tasks by status");
+        out.sample("vibe_kanban_tasks", &[("status", "in\"review")], 3);
+        out.sample("vibe_kanban_worktrees", &[], 0.5);
+        assert_eq!(
+            out.0,
+            "# HELP vibe_kanban_tasks Tasks by status\n\
+             # TYPE vibe_kanban_tasks gauge\n\
+             vibe_kanban_tasks{status=\"in\\\"review\"} 3\n\
+             vibe_kanban_worktrees 0.5\n"
+        );
+    }
+}
@@ -7,6 +7,7 @@ use axum::{
 use crate::DeploymentImpl;
 
 pub mod approvals;
+pub mod audit;
 pub mod auth;
 pub mod config;
 pub mod containers;
@@ -18,12 +19,23 @@ pub mod execution_processes;
 pub mod frontend;
 pub mod health;
 pub mod images;
+pub mod issue_trackers;
+pub mod mcp;
+pub mod metrics;
 pub mod projects;
+pub mod retention;
+pub mod schedules;
 pub mod tags;
 pub mod task_attempts;
+pub mod task_fields;
 pub mod tasks;
+pub mod templates;
+
+/// The app's routes. `backend_url` is where the server can reach its own API.
+pub fn router(deployment: DeploymentImpl, backend_url: &str) -> IntoMakeService<Router> {
+    // Scraped at the root, where Prometheus looks by default
+    let metrics_routes = metrics::router(&deployment).with_state(deployment.clone());
 
-pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
     // Create routers with different middleware layers
     let base_routes = Router::new()
         .route("/health", get(health::health_check))
@@ -32,13 +44,19 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .merge(projects::router(&deployment))
         .merge(drafts::router(&deployment))
         .merge(tasks::router(&deployment))
+        .merge(task_fields::router(&deployment))
         .merge(task_attempts::router(&deployment))
         .merge(execution_processes::router(&deployment))
         .merge(tags::router(&deployment))
+        .merge(templates::router(&deployment))
+        .merge(retention::router(&deployment))
+        .merge(schedules::router(&deployment))
+        .merge(issue_trackers::router(&deployment))
         .merge(auth::router(&deployment))
         .merge(filesystem::router())
         .merge(events::router(&deployment))
         .merge(approvals::router())
+        .merge(audit::router(&deployment))
         .nest("/images", images::routes())
         .layer(from_fn_with_state(
             deployment.clone(),
@@ -49,6 +67,8 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
     Router::new()
         .route("/", get(frontend::serve_frontend_root))
         .route("/{*path}", get(frontend::serve_frontend))
+        .merge(metrics_routes)
+        .merge(mcp::router(backend_url))
         .nest("/api", base_routes)
         .into_make_service()
 }
@@ -8,8 +8,12 @@ use axum::{
     response::Json as ResponseJson,
     routing::{get, post},
 };
-use db::models::project::{
-    CreateProject, Project, ProjectError, SearchMatchType, SearchResult, UpdateProject,
+use db::models::{
+    audit_event::{AuditAction, AuditEvent, CreateAuditEvent},
+    project::{CreateProject, Project, ProjectError, SearchMatchType, SearchResult, UpdateProject},
+    project_instructions::{
+        ProjectInstructionVersion, ProjectInstructions, UpdateProjectInstructions,
+    },
 };
 use deployment::Deployment;
 use ignore::WalkBuilder;
@@ -17,11 +21,17 @@ use services::services::{
     file_ranker::FileRanker,
     file_search_cache::{CacheError, SearchMode, SearchQuery},
     git::GitBranch,
+    project_env::{ProjectEnvVar, UpdateProjectEnv},
+    project_instructions,
 };
 use utils::{path::expand_tilde, response::ApiResponse};
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError, middleware::load_project_middleware};
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{RequestActor, load_project_middleware},
+};
 
 pub async fn get_projects(
     State(deployment): State<DeploymentImpl>,
@@ -172,6 +182,7 @@ pub async fn create_project(
 pub async fn update_project(
     Extension(existing_project): Extension<Project>,
     State(deployment): State<DeploymentImpl>,
+    RequestActor(actor): RequestActor,
     Json(payload): Json<UpdateProject>,
 ) -> Result<ResponseJson<ApiResponse<Project>>, StatusCode> {
     // Destructure payload to handle field updates.
@@ -185,6 +196,7 @@ pub async fn update_project(
         cleanup_script,
         copy_files,
     } = payload;
+    let before = existing_project.clone();
     // If git_repo_path is being changed, check if the new path is already used by another project
     let git_repo_path = if let Some(new_git_repo_path) = git_repo_path.map(|s| expand_tilde(&s))
         && new_git_repo_path != existing_project.git_repo_path
@@ -223,7 +235,35 @@ pub async fn update_project(
     )
     .await
     {
-        Ok(project) => Ok(ResponseJson(ApiResponse::success(project))),
+        Ok(project) => {
+            let changed: Vec<&str> = [
+                ("name", before.name != project.name),
+                (
+                    "git_repo_path",
+                    before.git_repo_path != project.git_repo_path,
+                ),
+                ("setup_script", before.setup_script != project.setup_script),
+                ("dev_script", before.dev_script != project.dev_script),
+                (
+                    "cleanup_script",
+                    before.cleanup_script != project.cleanup_script,
+                ),
+                ("copy_files", before.copy_files != project.copy_files),
+            ]
+            .into_iter()
+            .filter_map(|(field, changed)| changed.then_some(field))
+            .collect();
+            if !changed.is_empty() {
+                AuditEvent::record(
+                    &deployment.db().pool,
+                    CreateAuditEvent::new(actor, AuditAction::ProjectUpdated)
+                        .project(project.id)
+                        .details(serde_json::json!({ "fields": changed })),
+                )
+                .await;
+            }
+            Ok(ResponseJson(ApiResponse::success(project)))
+        }
         Err(e) => {
             tracing::error!("Failed to update project: {}", e);
             Err(StatusCode::INTERNAL_SERVER_ERROR)
@@ -234,12 +274,28 @@ pub async fn update_project(
 pub async fn delete_project(
     Extension(project): Extension<Project>,
     State(deployment): State<DeploymentImpl>,
+    RequestActor(actor): RequestActor,
 ) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
     match Project::delete(&deployment.db().pool, project.id).await {
         Ok(rows_affected) => {
             if rows_affected == 0 {
                 Err(StatusCode::NOT_FOUND)
             } else {
+                if let Err(e) = deployment.project_env().remove_project(project.id).await {
+                    tracing::error!("Failed to remove env vars for deleted project: {}", e);
+                }
+
+                AuditEvent::record(
+                    &deployment.db().pool,
+                    CreateAuditEvent::new(actor, AuditAction::ProjectDeleted)
+                        .project(project.id)
+                        .details(serde_json::json!({
+                            "name": project.name,
+                            "git_repo_path": project.git_repo_path,
+                        })),
+                )
+                .await;
+
                 deployment
                     .track_if_analytics_allowed(
                         "project_deleted",
@@ -259,6 +315,103 @@ pub async fn delete_project(
     }
 }
 
+pub async fn get_project_env(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectEnvVar>>>, ApiError> {
+    let vars = deployment.project_env().list(project.id).await;
+    Ok(ResponseJson(ApiResponse::success(vars)))
+}
+
+pub async fn update_project_env(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    RequestActor(actor): RequestActor,
+    Json(payload): Json<UpdateProjectEnv>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectEnvVar>>>, ApiError> {
+    let vars = deployment
+        .project_env()
+        .update(project.id, payload.vars)
+        .await?;
+
+    AuditEvent::record(
+        &deployment.db().pool,
+        CreateAuditEvent::new(actor, AuditAction::ProjectUpdated)
+            .project(project.id)
+            .details(serde_json::json!({
+                "fields": ["env"],
+                "env_keys": vars.iter().map(|v| v.key.as_str()).collect::<Vec<_>>(),
+            })),
+    )
+    .await;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_env_updated",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "var_count": vars.len(),
+                "secret_count": vars.iter().filter(|v| v.secret).count(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(vars)))
+}
+
+pub async fn get_project_instructions(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectInstructions>>, ApiError> {
+    let instructions =
+        ProjectInstructions::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(instructions)))
+}
+
+pub async fn update_project_instructions(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    RequestActor(actor): RequestActor,
+    Json(payload): Json<UpdateProjectInstructions>,
+) -> Result<ResponseJson<ApiResponse<ProjectInstructions>>, ApiError> {
+    project_instructions::validate(&payload)?;
+    let instructions =
+        ProjectInstructions::upsert(&deployment.db().pool, project.id, &payload).await?;
+
+    AuditEvent::record(
+        &deployment.db().pool,
+        CreateAuditEvent::new(actor, AuditAction::ProjectUpdated)
+            .project(project.id)
+            .details(serde_json::json!({
+                "fields": ["instructions"],
+                "sync_path": instructions.sync_path,
+            })),
+    )
+    .await;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_instructions_updated",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "synced": instructions.sync_path.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(instructions)))
+}
+
+/// Instruction texts attempts of the project were given, newest first
+pub async fn get_project_instruction_versions(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectInstructionVersion>>>, ApiError> {
+    let versions =
+        ProjectInstructionVersion::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(versions)))
+}
+
 #[derive(serde::Deserialize)]
 pub struct OpenEditorRequest {
     editor_type: Option<String>,
@@ -498,6 +651,15 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             get(get_project).put(update_project).delete(delete_project),
         )
         .route("/branches", get(get_project_branches))
+        .route("/env", get(get_project_env).put(update_project_env))
+        .route(
+            "/instructions",
+            get(get_project_instructions).put(update_project_instructions),
+        )
+        .route(
+            "/instructions/versions",
+            get(get_project_instruction_versions),
+        )
         .route("/search", get(search_project_files))
         .route("/open-editor", post(open_project_in_editor))
         .layer(from_fn_with_state(
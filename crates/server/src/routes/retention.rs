@@ -0,0 +1,35 @@
+use axum::{
+    Router,
+    extract::State,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use deployment::Deployment;
+use services::services::retention::{RetentionReport, RetentionService};
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Dry run of the log retention policy: what the next periodic run would remove
+pub async fn get_retention_report(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<RetentionReport>>, ApiError> {
+    let retention = deployment.config().read().await.retention.clone();
+    let report = RetentionService::plan(&deployment.db().pool, &retention).await?;
+    Ok(ResponseJson(ApiResponse::success(report)))
+}
+
+/// Apply the log retention policy now instead of waiting for the periodic run
+pub async fn run_retention(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<RetentionReport>>, ApiError> {
+    let retention = deployment.config().read().await.retention.clone();
+    let report = RetentionService::run(&deployment.db().pool, &retention, false).await?;
+    Ok(ResponseJson(ApiResponse::success(report)))
+}
+
+pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/retention/report", get(get_retention_report))
+        .route("/retention/run", post(run_retention))
+}
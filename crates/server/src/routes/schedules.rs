@@ -0,0 +1,186 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use chrono::Utc;
+use db::models::{
+    project::Project,
+    tag::Tag,
+    task_schedule::{CreateTaskSchedule, TaskSchedule, TaskScheduleRun, UpdateTaskSchedule},
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::scheduler::{self, ScheduleError};
+use sqlx::SqlitePool;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_schedule_middleware};
+
+/// Runs returned by the history endpoint unless a limit is given
+const DEFAULT_RUN_HISTORY: i64 = 50;
+
+#[derive(Debug, Deserialize)]
+pub struct ScheduleQuery {
+    pub project_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RunHistoryQuery {
+    pub limit: Option<i64>,
+}
+
+/// Check the template and project exist, returning the first run time of `cron`
+async fn validate(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    tag_id: Uuid,
+    cron: &str,
+) -> Result<chrono::DateTime<Utc>, ApiError> {
+    let next_run_at = scheduler::next_run_after(cron, Utc::now())?;
+    if Tag::find_by_id(pool, tag_id).await?.is_none() {
+        return Err(ScheduleError::TemplateNotFound.into());
+    }
+    if Project::find_by_id(pool, project_id).await?.is_none() {
+        return Err(ScheduleError::ProjectNotFound.into());
+    }
+    Ok(next_run_at)
+}
+
+pub async fn get_schedules(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ScheduleQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskSchedule>>>, ApiError> {
+    let schedules = TaskSchedule::find_all(&deployment.db().pool, query.project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(schedules)))
+}
+
+pub async fn get_schedule(
+    Extension(schedule): Extension<TaskSchedule>,
+) -> Result<ResponseJson<ApiResponse<TaskSchedule>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+pub async fn create_schedule(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateTaskSchedule>,
+) -> Result<ResponseJson<ApiResponse<TaskSchedule>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let next_run_at = validate(pool, payload.project_id, payload.tag_id, &payload.cron).await?;
+    let schedule = TaskSchedule::create(pool, &payload, Uuid::new_v4(), next_run_at).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_schedule_created",
+            serde_json::json!({
+                "schedule_id": schedule.id.to_string(),
+                "project_id": schedule.project_id.to_string(),
+                "starts_attempt": schedule.executor_profile_id.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+pub async fn update_schedule(
+    Extension(schedule): Extension<TaskSchedule>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateTaskSchedule>,
+) -> Result<ResponseJson<ApiResponse<TaskSchedule>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let next_run_at = validate(pool, schedule.project_id, payload.tag_id, &payload.cron).await?;
+    let schedule = TaskSchedule::update(pool, schedule.id, &payload, next_run_at).await?;
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+pub async fn delete_schedule(
+    Extension(schedule): Extension<TaskSchedule>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = TaskSchedule::delete(&deployment.db().pool, schedule.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+pub async fn pause_schedule(
+    Extension(schedule): Extension<TaskSchedule>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<TaskSchedule>>, ApiError> {
+    let schedule = TaskSchedule::set_paused(&deployment.db().pool, schedule.id, true, None).await?;
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+/// Resume a paused schedule. Times missed while it was paused don't run.
+pub async fn resume_schedule(
+    Extension(schedule): Extension<TaskSchedule>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<TaskSchedule>>, ApiError> {
+    let next_run_at = scheduler::next_run_after(&schedule.cron, Utc::now())?;
+    let schedule =
+        TaskSchedule::set_paused(&deployment.db().pool, schedule.id, false, Some(next_run_at))
+            .await?;
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+/// Run the schedule now, outside of its cron times
+pub async fn run_schedule_now(
+    Extension(schedule): Extension<TaskSchedule>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<TaskScheduleRun>>, ApiError> {
+    let run = scheduler::run_schedule(
+        &deployment.db().pool,
+        deployment.container(),
+        deployment.git(),
+        &schedule,
+        Utc::now(),
+    )
+    .await?
+    .ok_or_else(|| ApiError::Conflict("The schedule already ran at this time".to_string()))?;
+    Ok(ResponseJson(ApiResponse::success(run)))
+}
+
+/// Times the schedule ran or skipped, newest first
+pub async fn get_schedule_runs(
+    Extension(schedule): Extension<TaskSchedule>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<RunHistoryQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskScheduleRun>>>, ApiError> {
+    let runs = TaskScheduleRun::find_by_schedule_id(
+        &deployment.db().pool,
+        schedule.id,
+        query.limit.unwrap_or(DEFAULT_RUN_HISTORY),
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(runs)))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let schedule_router = Router::new()
+        .route(
+            "/",
+            get(get_schedule)
+                .put(update_schedule)
+                .delete(delete_schedule),
+        )
+        .route("/pause", post(pause_schedule))
+        .route("/resume", post(resume_schedule))
+        .route("/run", post(run_schedule_now))
+        .route("/runs", get(get_schedule_runs))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_schedule_middleware,
+        ));
+
+    let inner = Router::new()
+        .route("/", get(get_schedules).post(create_schedule))
+        .nest("/{schedule_id}", schedule_router);
+
+    Router::new().nest("/schedules", inner)
+}
@@ -8,6 +8,7 @@ use axum::{
 use db::models::tag::{CreateTag, Tag, UpdateTag};
 use deployment::Deployment;
 use serde::Deserialize;
+use services::services::prompt_template;
 use ts_rs::TS;
 use utils::response::ApiResponse;
 
@@ -44,6 +45,7 @@ pub async fn create_tag(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateTag>,
 ) -> Result<ResponseJson<ApiResponse<Tag>>, ApiError> {
+    prompt_template::validate(&payload.content)?;
     let tag = Tag::create(&deployment.db().pool, &payload).await?;
 
     deployment
@@ -64,6 +66,9 @@ pub async fn update_tag(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<UpdateTag>,
 ) -> Result<ResponseJson<ApiResponse<Tag>>, ApiError> {
+    if let Some(content) = &payload.content {
+        prompt_template::validate(content)?;
+    }
     let updated_tag = Tag::update(&deployment.db().pool, tag.id, &payload).await?;
 
     deployment
@@ -1,6 +1,8 @@
 pub mod drafts;
 pub mod util;
 
+use std::sync::{Arc, atomic::AtomicUsize};
+
 use axum::{
     Extension, Json, Router,
     extract::{
@@ -9,14 +11,16 @@ use axum::{
     },
     http::StatusCode,
     middleware::from_fn_with_state,
-    response::{IntoResponse, Json as ResponseJson},
+    response::{IntoResponse, Json as ResponseJson, Response},
     routing::{get, post},
 };
 use db::models::{
+    audit_event::{AuditAction, AuditEvent, CreateAuditEvent},
     draft::{Draft, DraftType},
     execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
     merge::{Merge, MergeStatus, PrMerge, PullRequestInfo},
     project::{Project, ProjectError},
+    project_instructions::ProjectInstructionVersion,
     task::{Task, TaskRelationships, TaskStatus},
     task_attempt::{CreateTaskAttempt, TaskAttempt, TaskAttemptError},
 };
@@ -33,19 +37,24 @@ use git2::BranchType;
 use serde::{Deserialize, Serialize};
 use services::services::{
     container::ContainerService,
-    git::{ConflictOp, WorktreeResetOptions},
+    conversation_export::ConversationExport,
+    diff_stream::apply_stream_omit_policy,
+    git::{ConflictOp, DiffTarget, WorktreeResetOptions},
     github_service::{CreatePrRequest, GitHubService, GitHubServiceError},
 };
 use sqlx::Error as SqlxError;
 use ts_rs::TS;
-use utils::response::ApiResponse;
+use utils::{diff::Diff, response::ApiResponse, text::short_uuid};
 use uuid::Uuid;
 
 use crate::{
     DeploymentImpl,
     error::ApiError,
-    middleware::load_task_attempt_middleware,
-    routes::task_attempts::util::{ensure_worktree_path, handle_images_for_prompt},
+    middleware::{RequestActor, load_task_attempt_middleware},
+    routes::{
+        execution_processes::{ExportQuery, export_response},
+        task_attempts::util::{ensure_worktree_path, handle_images_for_prompt},
+    },
 };
 
 #[derive(Debug, Deserialize, Serialize, TS)]
@@ -191,7 +200,7 @@ pub async fn create_task_attempt(
     Ok(ResponseJson(ApiResponse::success(task_attempt)))
 }
 
-#[derive(Debug, Deserialize, TS)]
+#[derive(Debug, Deserialize, Serialize, TS)]
 pub struct CreateFollowUpAttempt {
     pub prompt: String,
     pub variant: Option<String>,
@@ -494,6 +503,45 @@ pub async fn stream_task_attempt_diff_ws(
     })
 }
 
+/// The attempt's changes against its base commit, as a single snapshot. Contents are omitted
+/// once the response grows too large, keeping the line counts.
+pub async fn get_task_attempt_diff(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Query(params): Query<DiffStreamQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<Diff>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let task = task_attempt
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::TaskAttempt(TaskAttemptError::TaskNotFound))?;
+    let ctx = TaskAttempt::load_context(pool, task_attempt.id, task.id, task.project_id).await?;
+    let worktree_path = ensure_worktree_path(&deployment, &task_attempt).await?;
+
+    let base_commit = deployment.git().get_base_commit(
+        &ctx.project.git_repo_path,
+        &task_attempt.branch,
+        &task_attempt.target_branch,
+    )?;
+    let diffs = deployment.git().get_diffs(
+        DiffTarget::Worktree {
+            worktree_path: &worktree_path,
+            base_commit: &base_commit,
+        },
+        None,
+    )?;
+
+    let sent_bytes = Arc::new(AtomicUsize::new(0));
+    let diffs = diffs
+        .into_iter()
+        .map(|mut diff| {
+            apply_stream_omit_policy(&mut diff, &sent_bytes, params.stats_only);
+            diff
+        })
+        .collect();
+    Ok(ResponseJson(ApiResponse::success(diffs)))
+}
+
 async fn handle_task_attempt_diff_ws(
     socket: WebSocket,
     deployment: DeploymentImpl,
@@ -605,6 +653,7 @@ pub async fn compare_commit_to_head(
 pub async fn merge_task_attempt(
     Extension(task_attempt): Extension<TaskAttempt>,
     State(deployment): State<DeploymentImpl>,
+    RequestActor(actor): RequestActor,
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
     let pool = &deployment.db().pool;
 
@@ -646,7 +695,25 @@ pub async fn merge_task_attempt(
         &merge_commit_id,
     )
     .await?;
-    Task::update_status(pool, ctx.task.id, TaskStatus::Done).await?;
+    AuditEvent::record(
+        pool,
+        CreateAuditEvent::new(actor, AuditAction::AttemptMerged)
+            .task(ctx.project.id, ctx.task.id)
+            .attempt(task_attempt.id)
+            .details(serde_json::json!({
+                "target_branch": ctx.task_attempt.target_branch,
+                "merge_commit": merge_commit_id,
+            })),
+    )
+    .await;
+    Task::transition_status(
+        pool,
+        ctx.task.id,
+        TaskStatus::Done,
+        actor,
+        Some(task_attempt.id),
+    )
+    .await?;
 
     deployment
         .track_if_analytics_allowed(
@@ -665,6 +732,7 @@ pub async fn merge_task_attempt(
 pub async fn push_task_attempt_branch(
     Extension(task_attempt): Extension<TaskAttempt>,
     State(deployment): State<DeploymentImpl>,
+    RequestActor(actor): RequestActor,
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
     let github_config = deployment.config().read().await.github.clone();
     let Some(github_token) = github_config.token() else {
@@ -679,12 +747,24 @@ pub async fn push_task_attempt_branch(
     deployment
         .git()
         .push_to_github(&ws_path, &task_attempt.branch, &github_token)?;
+
+    if let Some(task) = task_attempt.parent_task(&deployment.db().pool).await? {
+        AuditEvent::record(
+            &deployment.db().pool,
+            CreateAuditEvent::new(actor, AuditAction::BranchPushed)
+                .task(task.project_id, task.id)
+                .attempt(task_attempt.id)
+                .details(serde_json::json!({ "branch": task_attempt.branch })),
+        )
+        .await;
+    }
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
 pub async fn create_github_pr(
     Extension(task_attempt): Extension<TaskAttempt>,
     State(deployment): State<DeploymentImpl>,
+    RequestActor(actor): RequestActor,
     Json(request): Json<CreateGitHubPrRequest>,
 ) -> Result<ResponseJson<ApiResponse<String, GitHubServiceError>>, ApiError> {
     let github_config = deployment.config().read().await.github.clone();
@@ -782,6 +862,18 @@ pub async fn create_github_pr(
             {
                 tracing::error!("Failed to update task attempt PR status: {}", e);
             }
+            AuditEvent::record(
+                pool,
+                CreateAuditEvent::new(actor, AuditAction::PrCreated)
+                    .task(project.id, task.id)
+                    .attempt(task_attempt.id)
+                    .details(serde_json::json!({
+                        "number": pr_info.number,
+                        "url": pr_info.url,
+                        "base_branch": norm_target_branch_name,
+                    })),
+            )
+            .await;
 
             // Auto-open PR in browser
             if let Err(e) = utils::browser::open_browser(&pr_info.url).await {
@@ -1318,6 +1410,16 @@ pub async fn start_dev_server(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+/// The project instructions the attempt's agents were given, if any
+pub async fn get_task_attempt_instructions(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectInstructionVersion>>>, ApiError> {
+    let version =
+        ProjectInstructionVersion::find_for_attempt(&deployment.db().pool, task_attempt.id).await?;
+    Ok(ResponseJson(ApiResponse::success(version)))
+}
+
 pub async fn get_task_attempt_children(
     Extension(task_attempt): Extension<TaskAttempt>,
     State(deployment): State<DeploymentImpl>,
@@ -1377,6 +1479,7 @@ pub struct AttachPrResponse {
 pub async fn attach_existing_pr(
     Extension(task_attempt): Extension<TaskAttempt>,
     State(deployment): State<DeploymentImpl>,
+    RequestActor(actor): RequestActor,
 ) -> Result<ResponseJson<ApiResponse<AttachPrResponse>>, ApiError> {
     let pool = &deployment.db().pool;
 
@@ -1427,6 +1530,18 @@ pub async fn attach_existing_pr(
             &pr_info.url,
         )
         .await?;
+        AuditEvent::record(
+            pool,
+            CreateAuditEvent::new(actor, AuditAction::PrAttached)
+                .task(project.id, task.id)
+                .attempt(task_attempt.id)
+                .details(serde_json::json!({
+                    "number": pr_info.number,
+                    "url": pr_info.url,
+                    "status": pr_info.status,
+                })),
+        )
+        .await;
 
         // Update status if not open
         if !matches!(pr_info.status, MergeStatus::Open) {
@@ -1441,7 +1556,14 @@ pub async fn attach_existing_pr(
 
         // If PR is merged, mark task as done
         if matches!(pr_info.status, MergeStatus::Merged) {
-            Task::update_status(pool, task.id, TaskStatus::Done).await?;
+            Task::transition_status(
+                pool,
+                task.id,
+                TaskStatus::Done,
+                actor,
+                Some(task_attempt.id),
+            )
+            .await?;
         }
 
         Ok(ResponseJson(ApiResponse::success(AttachPrResponse {
@@ -1460,6 +1582,27 @@ pub async fn attach_existing_pr(
     }
 }
 
+/// Export the conversation of every process in the attempt's current history
+pub async fn export_task_attempt(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response, ApiError> {
+    let pool = &deployment.db().pool;
+    let task = task_attempt
+        .parent_task(pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    let processes = ExecutionProcess::find_by_task_attempt_id(pool, task_attempt.id, false).await?;
+    let export = ConversationExport::collect(deployment.container(), task.title, processes).await;
+
+    export_response(
+        &export,
+        query.format,
+        &format!("attempt-{}", short_uuid(&task_attempt.id)),
+    )
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let task_attempt_id_router = Router::new()
         .route("/", get(get_task_attempt))
@@ -1471,11 +1614,13 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
                 .delete(drafts::delete_draft),
         )
         .route("/draft/queue", post(drafts::set_draft_queue))
+        .route("/steer", post(drafts::steer))
         .route("/replace-process", post(replace_process))
         .route("/commit-info", get(get_commit_info))
         .route("/commit-compare", get(compare_commit_to_head))
         .route("/start-dev-server", post(start_dev_server))
         .route("/branch-status", get(get_task_attempt_branch_status))
+        .route("/diff", get(get_task_attempt_diff))
         .route("/diff/ws", get(stream_task_attempt_diff_ws))
         .route("/merge", post(merge_task_attempt))
         .route("/push", post(push_task_attempt_branch))
@@ -1486,7 +1631,9 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/open-editor", post(open_task_attempt_in_editor))
         .route("/delete-file", post(delete_task_attempt_file))
         .route("/children", get(get_task_attempt_children))
+        .route("/instructions", get(get_task_attempt_instructions))
         .route("/stop", post(stop_task_attempt_execution))
+        .route("/export", get(export_task_attempt))
         .route("/change-target-branch", post(change_target_branch))
         .layer(from_fn_with_state(
             deployment.clone(),
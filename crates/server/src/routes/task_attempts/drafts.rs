@@ -6,7 +6,8 @@ use db::models::{
 use deployment::Deployment;
 use serde::Deserialize;
 use services::services::drafts::{
-    DraftResponse, SetQueueRequest, UpdateFollowUpDraftRequest, UpdateRetryFollowUpDraftRequest,
+    DraftResponse, SetQueueRequest, SteerRequest, SteerResponse, UpdateFollowUpDraftRequest,
+    UpdateRetryFollowUpDraftRequest,
 };
 use utils::response::ApiResponse;
 
@@ -145,3 +146,22 @@ pub async fn set_draft_queue(
         .await?;
     Ok(ResponseJson(ApiResponse::success(resp)))
 }
+
+#[axum::debug_handler]
+pub async fn steer(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SteerRequest>,
+) -> Result<ResponseJson<ApiResponse<SteerResponse>>, ApiError> {
+    if payload.message.trim().is_empty() {
+        return Err(ApiError::TaskAttempt(TaskAttemptError::ValidationError(
+            "Message cannot be empty".to_string(),
+        )));
+    }
+
+    let service = deployment.drafts();
+    let resp = service
+        .steer_or_queue(deployment.container(), &task_attempt, &payload)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(resp)))
+}
@@ -0,0 +1,216 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, put},
+};
+use db::models::{
+    custom_field::{CreateCustomField, CustomField, UpdateCustomField},
+    label::{CreateLabel, Label, UpdateLabel},
+    project::Project,
+    task::Task,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{load_custom_field_middleware, load_label_middleware},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct ProjectQuery {
+    pub project_id: Uuid,
+}
+
+fn validate_name(name: &str) -> Result<(), ApiError> {
+    if name.trim().is_empty() {
+        return Err(ApiError::BadRequest("Name must not be empty".to_string()));
+    }
+    Ok(())
+}
+
+async fn ensure_project(pool: &SqlitePool, project_id: Uuid) -> Result<(), ApiError> {
+    match Project::find_by_id(pool, project_id).await? {
+        Some(_) => Ok(()),
+        None => Err(ApiError::BadRequest("Project not found".to_string())),
+    }
+}
+
+/// Names are unique within a project, ignoring case
+fn name_taken(err: sqlx::Error, name: &str) -> ApiError {
+    match &err {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => ApiError::Conflict(
+            format!("The project already has one named '{}'", name.trim()),
+        ),
+        _ => err.into(),
+    }
+}
+
+/// Tasks whose labels or custom field values are about to change, so they can be sent to task
+/// streams as one batch
+async fn find_tasks(pool: &SqlitePool, task_ids: Vec<Uuid>) -> Result<Vec<Task>, ApiError> {
+    let mut tasks = Vec::with_capacity(task_ids.len());
+    for task_id in task_ids {
+        if let Some(task) = Task::find_by_id(pool, task_id).await? {
+            tasks.push(task);
+        }
+    }
+    Ok(tasks)
+}
+
+pub async fn get_labels(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ProjectQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<Label>>>, ApiError> {
+    let labels = Label::find_by_project_id(&deployment.db().pool, query.project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(labels)))
+}
+
+pub async fn create_label(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateLabel>,
+) -> Result<ResponseJson<ApiResponse<Label>>, ApiError> {
+    let pool = &deployment.db().pool;
+    validate_name(&payload.name)?;
+    ensure_project(pool, payload.project_id).await?;
+    let label = Label::create(pool, &payload, Uuid::new_v4())
+        .await
+        .map_err(|e| name_taken(e, &payload.name))?;
+    Ok(ResponseJson(ApiResponse::success(label)))
+}
+
+pub async fn update_label(
+    Extension(label): Extension<Label>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateLabel>,
+) -> Result<ResponseJson<ApiResponse<Label>>, ApiError> {
+    let pool = &deployment.db().pool;
+    validate_name(&payload.name)?;
+    let tasks = find_tasks(pool, Label::find_task_ids(pool, label.id).await?).await?;
+    let batch = deployment.events().begin_task_batch(&tasks);
+    let label = Label::update(pool, label.id, &payload)
+        .await
+        .map_err(|e| name_taken(e, &payload.name))?;
+    batch.finish().await;
+    Ok(ResponseJson(ApiResponse::success(label)))
+}
+
+/// Delete the label, removing it from its tasks
+pub async fn delete_label(
+    Extension(label): Extension<Label>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let tasks = find_tasks(pool, Label::find_task_ids(pool, label.id).await?).await?;
+    let batch = deployment.events().begin_task_batch(&tasks);
+    let rows_affected = Label::delete(pool, label.id).await?;
+    batch.finish().await;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+pub async fn get_custom_fields(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ProjectQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<CustomField>>>, ApiError> {
+    let fields = CustomField::find_by_project_id(&deployment.db().pool, query.project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(fields)))
+}
+
+pub async fn create_custom_field(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateCustomField>,
+) -> Result<ResponseJson<ApiResponse<CustomField>>, ApiError> {
+    let pool = &deployment.db().pool;
+    validate_name(&payload.name)?;
+    payload.field_type.validate_options(&payload.options)?;
+    ensure_project(pool, payload.project_id).await?;
+    let field = CustomField::create(pool, &payload, Uuid::new_v4())
+        .await
+        .map_err(|e| name_taken(e, &payload.name))?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "custom_field_created",
+            serde_json::json!({
+                "field_id": field.id.to_string(),
+                "field_type": field.field_type,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(field)))
+}
+
+/// Rename the field or change its options. Task values that are no longer an option are
+/// cleared.
+pub async fn update_custom_field(
+    Extension(field): Extension<CustomField>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateCustomField>,
+) -> Result<ResponseJson<ApiResponse<CustomField>>, ApiError> {
+    let pool = &deployment.db().pool;
+    validate_name(&payload.name)?;
+    field.field_type.validate_options(&payload.options)?;
+    let tasks = find_tasks(pool, CustomField::find_task_ids(pool, field.id).await?).await?;
+    let batch = deployment.events().begin_task_batch(&tasks);
+    let field = CustomField::update(pool, field.id, &payload)
+        .await
+        .map_err(|e| name_taken(e, &payload.name))?;
+    batch.finish().await;
+    Ok(ResponseJson(ApiResponse::success(field)))
+}
+
+/// Delete the field along with every task's value for it
+pub async fn delete_custom_field(
+    Extension(field): Extension<CustomField>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let tasks = find_tasks(pool, CustomField::find_task_ids(pool, field.id).await?).await?;
+    let batch = deployment.events().begin_task_batch(&tasks);
+    let rows_affected = CustomField::delete(pool, field.id).await?;
+    batch.finish().await;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let label_router = Router::new()
+        .route("/", put(update_label).delete(delete_label))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_label_middleware,
+        ));
+
+    let custom_field_router = Router::new()
+        .route("/", put(update_custom_field).delete(delete_custom_field))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_custom_field_middleware,
+        ));
+
+    let labels = Router::new()
+        .route("/", get(get_labels).post(create_label))
+        .nest("/{label_id}", label_router);
+
+    let custom_fields = Router::new()
+        .route("/", get(get_custom_fields).post(create_custom_field))
+        .nest("/{field_id}", custom_field_router);
+
+    Router::new()
+        .nest("/labels", labels)
+        .nest("/custom-fields", custom_fields)
+}
@@ -1,4 +1,7 @@
-use std::path::PathBuf;
+use std::{
+    collections::{HashMap, HashSet, hash_map},
+    path::{Path, PathBuf},
+};
 
 use anyhow;
 use axum::{
@@ -10,11 +13,16 @@ use axum::{
     http::StatusCode,
     middleware::from_fn_with_state,
     response::{IntoResponse, Json as ResponseJson},
-    routing::{get, post},
+    routing::{get, post, put},
 };
 use db::models::{
+    audit_event::{AuditAction, AuditEvent, CreateAuditEvent},
     image::TaskImage,
-    task::{CreateTask, Task, TaskWithAttemptStatus, UpdateTask},
+    project::Project,
+    task::{
+        CreateTask, Task, TaskFields, TaskFilter, TaskStatus, TaskWithAttemptStatus, UpdateTask,
+        UpdateTaskFields,
+    },
     task_attempt::{CreateTaskAttempt, TaskAttempt},
 };
 use deployment::Deployment;
@@ -24,25 +32,40 @@ use serde::{Deserialize, Serialize};
 use services::services::container::{
     ContainerService, WorktreeCleanupData, cleanup_worktrees_direct,
 };
-use sqlx::Error as SqlxError;
+use sqlx::{Error as SqlxError, SqlitePool};
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError, middleware::load_task_middleware};
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{RequestActor, load_task_middleware},
+};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TaskQuery {
     pub project_id: Uuid,
+    /// Resume a stream after this sequence number instead of starting from a snapshot
+    pub after: Option<u64>,
 }
 
 pub async fn get_tasks(
     State(deployment): State<DeploymentImpl>,
     Query(query): Query<TaskQuery>,
+    Query(filter): Query<TaskFilter>,
 ) -> Result<ResponseJson<ApiResponse<Vec<TaskWithAttemptStatus>>>, ApiError> {
-    let tasks =
-        Task::find_by_project_id_with_attempt_status(&deployment.db().pool, query.project_id)
-            .await?;
+    if filter.custom_value.is_some() && filter.custom_field.is_none() {
+        return Err(ApiError::BadRequest(
+            "custom_value needs a custom_field to compare with".to_string(),
+        ));
+    }
+    let tasks = Task::find_by_project_id_with_attempt_status(
+        &deployment.db().pool,
+        query.project_id,
+        &filter,
+    )
+    .await?;
 
     Ok(ResponseJson(ApiResponse::success(tasks)))
 }
@@ -53,7 +76,7 @@ pub async fn stream_tasks_ws(
     Query(query): Query<TaskQuery>,
 ) -> impl IntoResponse {
     ws.on_upgrade(move |socket| async move {
-        if let Err(e) = handle_tasks_ws(socket, deployment, query.project_id).await {
+        if let Err(e) = handle_tasks_ws(socket, deployment, query.project_id, query.after).await {
             tracing::warn!("tasks WS closed: {}", e);
         }
     })
@@ -63,13 +86,14 @@ async fn handle_tasks_ws(
     socket: WebSocket,
     deployment: DeploymentImpl,
     project_id: Uuid,
+    after: Option<u64>,
 ) -> anyhow::Result<()> {
     // Get the raw stream and convert LogMsg to WebSocket messages
     let mut stream = deployment
         .events()
-        .stream_tasks_raw(project_id)
+        .stream_tasks_raw(project_id, after)
         .await?
-        .map_ok(|msg| msg.to_ws_message_unchecked());
+        .map_ok(|msg| msg.to_ws_message());
 
     // Split socket into sender and receiver
     let (mut sender, mut receiver) = socket.split();
@@ -203,6 +227,7 @@ pub async fn create_task_and_start(
     tracing::info!("Started execution process {}", execution_process.id);
     Ok(ResponseJson(ApiResponse::success(TaskWithAttemptStatus {
         task,
+        fields: TaskFields::default(),
         has_in_progress_attempt: true,
         has_merged_attempt: false,
         last_attempt_failed: false,
@@ -213,6 +238,7 @@ pub async fn create_task_and_start(
 pub async fn update_task(
     Extension(existing_task): Extension<Task>,
     State(deployment): State<DeploymentImpl>,
+    RequestActor(actor): RequestActor,
     Json(payload): Json<UpdateTask>,
 ) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
     // Use existing values if not provided in update
@@ -222,6 +248,7 @@ pub async fn update_task(
         Some(s) => Some(s),                     // Non-empty string = update description
         None => existing_task.description,      // Field omitted = keep existing
     };
+    let previous_status = existing_task.status.clone();
     let status = payload.status.unwrap_or(existing_task.status);
     let parent_task_attempt = payload
         .parent_task_attempt
@@ -238,6 +265,16 @@ pub async fn update_task(
     )
     .await?;
 
+    if task.status != previous_status {
+        AuditEvent::record(
+            &deployment.db().pool,
+            CreateAuditEvent::new(actor, AuditAction::TaskStatusChanged)
+                .task(task.project_id, task.id)
+                .details(serde_json::json!({ "from": previous_status, "to": task.status })),
+        )
+        .await;
+    }
+
     if let Some(image_ids) = &payload.image_ids {
         TaskImage::delete_by_task_id(&deployment.db().pool, task.id).await?;
         TaskImage::associate_many_dedup(&deployment.db().pool, task.id, image_ids).await?;
@@ -246,9 +283,24 @@ pub async fn update_task(
     Ok(ResponseJson(ApiResponse::success(task)))
 }
 
+/// Replace the task's priority, assignee, due date, labels and custom field values
+pub async fn update_task_fields(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateTaskFields>,
+) -> Result<ResponseJson<ApiResponse<TaskFields>>, ApiError> {
+    // Label and custom field rows don't reach the stream on their own, the batch sends the
+    // task once they're all written
+    let batch = deployment.events().begin_task_batch([&task]);
+    let fields = Task::update_fields(&deployment.db().pool, &task, &payload).await?;
+    batch.finish().await;
+    Ok(ResponseJson(ApiResponse::success(fields)))
+}
+
 pub async fn delete_task(
     Extension(task): Extension<Task>,
     State(deployment): State<DeploymentImpl>,
+    RequestActor(actor): RequestActor,
 ) -> Result<(StatusCode, ResponseJson<ApiResponse<()>>), ApiError> {
     // Validate no running execution processes
     if deployment
@@ -273,19 +325,7 @@ pub async fn delete_task(
         .await?
         .ok_or_else(|| ApiError::Database(SqlxError::RowNotFound))?;
 
-    let cleanup_data: Vec<WorktreeCleanupData> = attempts
-        .iter()
-        .filter_map(|attempt| {
-            attempt
-                .container_ref
-                .as_ref()
-                .map(|worktree_path| WorktreeCleanupData {
-                    attempt_id: attempt.id,
-                    worktree_path: PathBuf::from(worktree_path),
-                    git_repo_path: Some(project.git_repo_path.clone()),
-                })
-        })
-        .collect();
+    let cleanup_data = worktree_cleanup_data(&attempts, &project.git_repo_path);
 
     // Use a transaction to ensure atomicity: either all operations succeed or all are rolled back
     let mut tx = deployment.db().pool.begin().await?;
@@ -308,6 +348,17 @@ pub async fn delete_task(
     // Commit the transaction - if this fails, all changes are rolled back
     tx.commit().await?;
 
+    AuditEvent::record(
+        &deployment.db().pool,
+        CreateAuditEvent::new(actor, AuditAction::TaskDeleted)
+            .task(task.project_id, task.id)
+            .details(serde_json::json!({
+                "title": task.title,
+                "attempt_count": attempts.len(),
+            })),
+    )
+    .await;
+
     if total_children_affected > 0 {
         tracing::info!(
             "Nullified {} child task references before deleting task {}",
@@ -354,15 +405,444 @@ pub async fn delete_task(
     Ok((StatusCode::ACCEPTED, ResponseJson(ApiResponse::success(()))))
 }
 
+fn worktree_cleanup_data(
+    attempts: &[TaskAttempt],
+    git_repo_path: &Path,
+) -> Vec<WorktreeCleanupData> {
+    attempts
+        .iter()
+        .filter_map(|attempt| {
+            attempt
+                .container_ref
+                .as_ref()
+                .map(|worktree_path| WorktreeCleanupData {
+                    attempt_id: attempt.id,
+                    worktree_path: PathBuf::from(worktree_path),
+                    git_repo_path: Some(git_repo_path.to_path_buf()),
+                })
+        })
+        .collect()
+}
+
+/// Most tasks a bulk request may name
+const MAX_BULK_TASKS: usize = 500;
+
+#[derive(Debug, Deserialize, TS)]
+pub struct BulkTaskIdsRequest {
+    pub task_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct BulkUpdateTaskStatusRequest {
+    pub task_ids: Vec<Uuid>,
+    pub status: TaskStatus,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct BulkMoveTasksRequest {
+    pub task_ids: Vec<Uuid>,
+    pub project_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct BulkStartTasksRequest {
+    pub task_ids: Vec<Uuid>,
+    pub executor_profile_id: ExecutorProfileId,
+    /// Defaults to the current branch of each task's repository
+    pub base_branch: Option<String>,
+}
+
+/// Outcome of a bulk operation for one of its tasks
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct BulkTaskResult {
+    pub task_id: Uuid,
+    pub success: bool,
+    pub error: Option<String>,
+    /// The attempt started on the task, for bulk starts
+    pub task_attempt_id: Option<Uuid>,
+}
+
+impl BulkTaskResult {
+    fn ok(task_id: Uuid) -> Self {
+        Self {
+            task_id,
+            success: true,
+            error: None,
+            task_attempt_id: None,
+        }
+    }
+
+    fn failed(task_id: Uuid, error: impl ToString) -> Self {
+        Self {
+            task_id,
+            success: false,
+            error: Some(error.to_string()),
+            task_attempt_id: None,
+        }
+    }
+}
+
+/// The tasks of a bulk request, without duplicates, and failures for the ids that don't exist
+async fn load_bulk_tasks(
+    pool: &SqlitePool,
+    task_ids: &[Uuid],
+) -> Result<(Vec<Task>, Vec<BulkTaskResult>), ApiError> {
+    if task_ids.len() > MAX_BULK_TASKS {
+        return Err(ApiError::BadRequest(format!(
+            "At most {MAX_BULK_TASKS} tasks can be changed at once"
+        )));
+    }
+    let mut seen = HashSet::new();
+    let mut tasks = Vec::new();
+    let mut missing = Vec::new();
+    for &task_id in task_ids {
+        if !seen.insert(task_id) {
+            continue;
+        }
+        match Task::find_by_id(pool, task_id).await? {
+            Some(task) => tasks.push(task),
+            None => missing.push(BulkTaskResult::failed(task_id, "Task not found")),
+        }
+    }
+    Ok((tasks, missing))
+}
+
+/// Results in the order their tasks were requested
+fn in_request_order(task_ids: &[Uuid], results: Vec<BulkTaskResult>) -> Vec<BulkTaskResult> {
+    let mut by_id: HashMap<Uuid, BulkTaskResult> = results
+        .into_iter()
+        .map(|result| (result.task_id, result))
+        .collect();
+    task_ids.iter().filter_map(|id| by_id.remove(id)).collect()
+}
+
+/// Set the status of the tasks in one transaction
+pub async fn bulk_update_task_status(
+    State(deployment): State<DeploymentImpl>,
+    RequestActor(actor): RequestActor,
+    Json(payload): Json<BulkUpdateTaskStatusRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<BulkTaskResult>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let (tasks, mut results) = load_bulk_tasks(pool, &payload.task_ids).await?;
+    let batch = deployment.events().begin_task_batch(&tasks);
+
+    let mut tx = pool.begin().await?;
+    for task in &tasks {
+        Task::set_status(&mut *tx, task.id, &payload.status).await?;
+    }
+    tx.commit().await?;
+    batch.finish().await;
+
+    for task in tasks {
+        if task.status != payload.status {
+            AuditEvent::record(
+                pool,
+                CreateAuditEvent::new(actor, AuditAction::TaskStatusChanged)
+                    .task(task.project_id, task.id)
+                    .details(serde_json::json!({ "from": task.status, "to": payload.status })),
+            )
+            .await;
+        }
+        results.push(BulkTaskResult::ok(task.id));
+    }
+
+    Ok(ResponseJson(ApiResponse::success(in_request_order(
+        &payload.task_ids,
+        results,
+    ))))
+}
+
+/// Delete the tasks without running processes in one transaction
+pub async fn bulk_delete_tasks(
+    State(deployment): State<DeploymentImpl>,
+    RequestActor(actor): RequestActor,
+    Json(payload): Json<BulkTaskIdsRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<BulkTaskResult>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let (tasks, mut results) = load_bulk_tasks(pool, &payload.task_ids).await?;
+    let batch = deployment.events().begin_task_batch(&tasks);
+
+    let mut deletable = Vec::new();
+    let mut repo_paths = HashMap::new();
+    for task in tasks {
+        if deployment
+            .container()
+            .has_running_processes(task.id)
+            .await?
+        {
+            results.push(BulkTaskResult::failed(
+                task.id,
+                "Task has running execution processes. Stop them first.",
+            ));
+            continue;
+        }
+        if let hash_map::Entry::Vacant(entry) = repo_paths.entry(task.project_id) {
+            let project = task
+                .parent_project(pool)
+                .await?
+                .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+            entry.insert(project.git_repo_path);
+        }
+        let attempts = TaskAttempt::fetch_all(pool, Some(task.id)).await?;
+        deletable.push((task, attempts));
+    }
+
+    let mut tx = pool.begin().await?;
+    for (task, attempts) in &deletable {
+        for attempt in attempts {
+            Task::nullify_children_by_attempt_id(&mut *tx, attempt.id).await?;
+        }
+        Task::delete(&mut *tx, task.id).await?;
+    }
+    tx.commit().await?;
+    batch.finish().await;
+
+    let mut cleanup_data = Vec::new();
+    for (task, attempts) in deletable {
+        AuditEvent::record(
+            pool,
+            CreateAuditEvent::new(actor, AuditAction::TaskDeleted)
+                .task(task.project_id, task.id)
+                .details(serde_json::json!({
+                    "title": task.title,
+                    "attempt_count": attempts.len(),
+                })),
+        )
+        .await;
+        cleanup_data.extend(worktree_cleanup_data(
+            &attempts,
+            &repo_paths[&task.project_id],
+        ));
+        results.push(BulkTaskResult::ok(task.id));
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "tasks_bulk_deleted",
+            serde_json::json!({
+                "task_count": results.iter().filter(|result| result.success).count(),
+            }),
+        )
+        .await;
+
+    tokio::spawn(async move {
+        tracing::info!(
+            "Starting background cleanup of {} worktrees of bulk deleted tasks",
+            cleanup_data.len()
+        );
+        if let Err(e) = cleanup_worktrees_direct(&cleanup_data).await {
+            tracing::error!(
+                "Background worktree cleanup of bulk deleted tasks failed: {}",
+                e
+            );
+        }
+    });
+
+    Ok(ResponseJson(ApiResponse::success(in_request_order(
+        &payload.task_ids,
+        results,
+    ))))
+}
+
+/// Move the tasks to another project in one transaction. Tasks with attempts can't move, as
+/// their branches and worktrees belong to their project's repository.
+pub async fn bulk_move_tasks(
+    State(deployment): State<DeploymentImpl>,
+    RequestActor(actor): RequestActor,
+    Json(payload): Json<BulkMoveTasksRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<BulkTaskResult>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if Project::find_by_id(pool, payload.project_id)
+        .await?
+        .is_none()
+    {
+        return Err(ApiError::BadRequest("Target project not found".to_string()));
+    }
+    let (tasks, mut results) = load_bulk_tasks(pool, &payload.task_ids).await?;
+    let batch = deployment.events().begin_task_batch(&tasks);
+
+    let mut movable = Vec::new();
+    for task in tasks {
+        if task.project_id == payload.project_id {
+            results.push(BulkTaskResult::ok(task.id));
+        } else if !TaskAttempt::fetch_all(pool, Some(task.id))
+            .await?
+            .is_empty()
+        {
+            results.push(BulkTaskResult::failed(
+                task.id,
+                "Task has attempts, which belong to its project's repository",
+            ));
+        } else {
+            movable.push(task);
+        }
+    }
+
+    let mut tx = pool.begin().await?;
+    for task in &movable {
+        Task::set_project(&mut *tx, task.id, payload.project_id).await?;
+    }
+    tx.commit().await?;
+    batch.finish().await;
+
+    for task in movable {
+        AuditEvent::record(
+            pool,
+            CreateAuditEvent::new(actor, AuditAction::TaskMoved)
+                .task(payload.project_id, task.id)
+                .details(serde_json::json!({
+                    "from": task.project_id,
+                    "to": payload.project_id,
+                })),
+        )
+        .await;
+        results.push(BulkTaskResult::ok(task.id));
+    }
+
+    Ok(ResponseJson(ApiResponse::success(in_request_order(
+        &payload.task_ids,
+        results,
+    ))))
+}
+
+/// Start an attempt on each task that isn't already running one. Each start is independent, so
+/// one failing doesn't stop the others.
+pub async fn bulk_start_tasks(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<BulkStartTasksRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<BulkTaskResult>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let (tasks, mut results) = load_bulk_tasks(pool, &payload.task_ids).await?;
+    let batch = deployment.events().begin_task_batch(&tasks);
+
+    let mut base_branches: HashMap<Uuid, String> = HashMap::new();
+    for task in tasks {
+        if deployment
+            .container()
+            .has_running_processes(task.id)
+            .await?
+        {
+            results.push(BulkTaskResult::failed(
+                task.id,
+                "Task already has a running attempt. Stop it first.",
+            ));
+            continue;
+        }
+
+        let result = async {
+            let base_branch = match &payload.base_branch {
+                Some(branch) => branch.clone(),
+                None => match base_branches.get(&task.project_id) {
+                    Some(branch) => branch.clone(),
+                    None => {
+                        let project = task
+                            .parent_project(pool)
+                            .await?
+                            .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+                        let branch = deployment
+                            .git()
+                            .get_current_branch(&project.git_repo_path)?;
+                        base_branches.insert(task.project_id, branch.clone());
+                        branch
+                    }
+                },
+            };
+
+            let attempt_id = Uuid::new_v4();
+            let branch = deployment
+                .container()
+                .git_branch_from_task_attempt(&attempt_id, &task.title)
+                .await;
+            let task_attempt = TaskAttempt::create(
+                pool,
+                &CreateTaskAttempt {
+                    executor: payload.executor_profile_id.executor,
+                    base_branch,
+                    branch,
+                },
+                attempt_id,
+                task.id,
+            )
+            .await?;
+            deployment
+                .container()
+                .start_attempt(&task_attempt, payload.executor_profile_id.clone())
+                .await?;
+            Ok::<_, ApiError>(task_attempt.id)
+        }
+        .await;
+
+        results.push(match result {
+            Ok(task_attempt_id) => BulkTaskResult {
+                task_attempt_id: Some(task_attempt_id),
+                ..BulkTaskResult::ok(task.id)
+            },
+            Err(e) => {
+                tracing::warn!("Failed to start an attempt on task {}: {}", task.id, e);
+                BulkTaskResult::failed(task.id, e)
+            }
+        });
+    }
+    batch.finish().await;
+
+    deployment
+        .track_if_analytics_allowed(
+            "tasks_bulk_started",
+            serde_json::json!({
+                "executor": &payload.executor_profile_id.executor,
+                "variant": &payload.executor_profile_id.variant,
+                "task_count": results.iter().filter(|result| result.success).count(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(in_request_order(
+        &payload.task_ids,
+        results,
+    ))))
+}
+
+/// Stop the running processes of all attempts of the tasks
+pub async fn bulk_stop_tasks(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<BulkTaskIdsRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<BulkTaskResult>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let (tasks, mut results) = load_bulk_tasks(pool, &payload.task_ids).await?;
+    let batch = deployment.events().begin_task_batch(&tasks);
+
+    for task in tasks {
+        let attempts = TaskAttempt::fetch_all(pool, Some(task.id)).await?;
+        results.push(
+            match deployment.container().stop_task_processes(&attempts).await {
+                Ok(()) => BulkTaskResult::ok(task.id),
+                Err(e) => BulkTaskResult::failed(task.id, e),
+            },
+        );
+    }
+    batch.finish().await;
+
+    Ok(ResponseJson(ApiResponse::success(in_request_order(
+        &payload.task_ids,
+        results,
+    ))))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let task_id_router = Router::new()
         .route("/", get(get_task).put(update_task).delete(delete_task))
+        .route("/fields", put(update_task_fields))
         .layer(from_fn_with_state(deployment.clone(), load_task_middleware));
 
     let inner = Router::new()
         .route("/", get(get_tasks).post(create_task))
         .route("/stream/ws", get(stream_tasks_ws))
         .route("/create-and-start", post(create_task_and_start))
+        .route("/bulk/status", post(bulk_update_task_status))
+        .route("/bulk/delete", post(bulk_delete_tasks))
+        .route("/bulk/move", post(bulk_move_tasks))
+        .route("/bulk/start", post(bulk_start_tasks))
+        .route("/bulk/stop", post(bulk_stop_tasks))
         .nest("/{task_id}", task_id_router);
 
     // mount under /projects/:project_id/tasks
@@ -0,0 +1,94 @@
+use axum::{
+    Json, Router,
+    extract::State,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::task_attempt::TaskAttempt;
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::prompt_template::{self, TEMPLATE_VARIABLES, TemplateVariable};
+use ts_rs::TS;
+use utils::{response::ApiResponse, template::Template};
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct PreviewTemplateRequest {
+    pub template: String,
+    /// Attempt to render against. Without one the template is only checked.
+    pub task_attempt_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct TemplatePreview {
+    /// The rendered template, when it parses and an attempt was given
+    pub rendered: Option<String>,
+    pub variables: Vec<String>,
+    pub unknown_variables: Vec<String>,
+    /// Why the template doesn't parse
+    pub error: Option<String>,
+}
+
+/// Variables available to prompt templates
+pub async fn list_template_variables() -> ResponseJson<ApiResponse<Vec<TemplateVariable>>> {
+    ResponseJson(ApiResponse::success(TEMPLATE_VARIABLES.to_vec()))
+}
+
+pub async fn preview_template(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<PreviewTemplateRequest>,
+) -> Result<ResponseJson<ApiResponse<TemplatePreview>>, ApiError> {
+    let template = match Template::parse(&payload.template) {
+        Ok(template) => template,
+        Err(e) => {
+            return Ok(ResponseJson(ApiResponse::success(TemplatePreview {
+                rendered: None,
+                variables: Vec::new(),
+                unknown_variables: Vec::new(),
+                error: Some(e.to_string()),
+            })));
+        }
+    };
+
+    let variables = template.variables();
+    let unknown_variables = variables
+        .iter()
+        .filter(|name| !TEMPLATE_VARIABLES.iter().any(|v| v.name == name.as_str()))
+        .cloned()
+        .collect();
+
+    let rendered = match payload.task_attempt_id {
+        Some(attempt_id) => {
+            let pool = &deployment.db().pool;
+            let attempt = TaskAttempt::find_by_id(pool, attempt_id)
+                .await?
+                .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+            let vars = prompt_template::context_for_attempt(
+                pool,
+                deployment.git(),
+                &attempt,
+                &[&payload.template],
+            )
+            .await?;
+            Some(template.render(&vars))
+        }
+        None => None,
+    };
+
+    Ok(ResponseJson(ApiResponse::success(TemplatePreview {
+        rendered,
+        variables,
+        unknown_variables,
+        error: None,
+    })))
+}
+
+pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let inner = Router::new()
+        .route("/variables", get(list_template_variables))
+        .route("/preview", post(preview_template));
+
+    Router::new().nest("/templates", inner)
+}
@@ -5,6 +5,7 @@ use std::{collections::HashMap, sync::Arc, time::Duration as StdDuration};
 use chrono::{DateTime, Utc};
 use dashmap::DashMap;
 use db::models::{
+    audit_event::AuditActor,
     execution_process::ExecutionProcess,
     executor_session::ExecutorSession,
     task::{Task, TaskStatus},
@@ -27,6 +28,7 @@ use utils::{
     },
     log_msg::LogMsg,
     msg_store::MsgStore,
+    telemetry,
 };
 use uuid::Uuid;
 
@@ -191,6 +193,7 @@ impl Approvals {
     ) -> Result<(ApprovalStatus, ToolContext), ApprovalError> {
         if let Some((_, p)) = self.pending.remove(id) {
             self.completed.insert(id.to_string(), req.status.clone());
+            record_approval_wait(&req.status, p.requested_at);
             let _ = p.response_tx.send(req.status.clone());
 
             if let Some(store) = self.msg_store_by_id(&p.execution_process_id).await {
@@ -222,7 +225,14 @@ impl Approvals {
             ) && let Ok(ctx) =
                 ExecutionProcess::load_context(pool, tool_ctx.execution_process_id).await
                 && ctx.task.status == TaskStatus::InReview
-                && let Err(e) = Task::update_status(pool, ctx.task.id, TaskStatus::InProgress).await
+                && let Err(e) = Task::transition_status(
+                    pool,
+                    ctx.task.id,
+                    TaskStatus::InProgress,
+                    AuditActor::User,
+                    Some(ctx.task_attempt.id),
+                )
+                .await
             {
                 tracing::warn!(
                     "Failed to update task status to InProgress after approval response: {}",
@@ -300,6 +310,7 @@ impl Approvals {
             completed.insert(id.clone(), status.clone());
 
             if is_timeout && let Some((_, pending_approval)) = pending.remove(&id) {
+                record_approval_wait(&status, pending_approval.requested_at);
                 if pending_approval.response_tx.send(status.clone()).is_err() {
                     tracing::debug!("approval '{}' timeout notification receiver dropped", id);
                 }
@@ -340,10 +351,29 @@ impl Approvals {
     }
 }
 
+/// Export how long a tool call waited for its approval outcome
+fn record_approval_wait(status: &ApprovalStatus, requested_at: DateTime<Utc>) {
+    let outcome = match status {
+        ApprovalStatus::Pending => "pending",
+        ApprovalStatus::Approved => "approved",
+        ApprovalStatus::Denied { .. } => "denied",
+        ApprovalStatus::TimedOut => "timed_out",
+    };
+    let wait = (Utc::now() - requested_at).to_std().unwrap_or_default();
+    telemetry::approval_resolved(outcome, wait);
+}
+
 pub(crate) async fn ensure_task_in_review(pool: &SqlitePool, execution_process_id: Uuid) {
     if let Ok(ctx) = ExecutionProcess::load_context(pool, execution_process_id).await
         && ctx.task.status == TaskStatus::InProgress
-        && let Err(e) = Task::update_status(pool, ctx.task.id, TaskStatus::InReview).await
+        && let Err(e) = Task::transition_status(
+            pool,
+            ctx.task.id,
+            TaskStatus::InReview,
+            AuditActor::Agent,
+            Some(ctx.task_attempt.id),
+        )
+        .await
     {
         tracing::warn!(
             "Failed to update task status to InReview for approval request: {}",
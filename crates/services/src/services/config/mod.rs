@@ -23,6 +23,7 @@ pub type EditorType = versions::v7::EditorType;
 pub type GitHubConfig = versions::v7::GitHubConfig;
 pub type UiLanguage = versions::v7::UiLanguage;
 pub type ShowcaseState = versions::v7::ShowcaseState;
+pub type RetentionConfig = versions::v7::RetentionConfig;
 
 /// Will always return config, trying old schemas or eventually returning default
 pub async fn load_config_from_file(config_path: &PathBuf) -> Config {
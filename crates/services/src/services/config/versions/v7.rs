@@ -17,6 +17,20 @@ pub struct ShowcaseState {
     pub seen_features: Vec<String>,
 }
 
+/// How long raw execution logs are kept. Everything is kept by default.
+#[derive(Clone, Debug, Serialize, Deserialize, TS, Default)]
+pub struct RetentionConfig {
+    /// Delete raw logs of finished processes older than this many days
+    #[serde(default)]
+    pub log_retention_days: Option<u32>,
+    /// Delete raw logs of processes dropped from the attempt history
+    #[serde(default)]
+    pub drop_logs_of_dropped_processes: bool,
+    /// Cap on stored raw logs per project, oldest logs are deleted first
+    #[serde(default)]
+    pub max_log_storage_mb_per_project: Option<u64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS, EnumString)]
 #[ts(use_ts_enum)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
@@ -49,6 +63,12 @@ pub struct Config {
     pub git_branch_prefix: String,
     #[serde(default)]
     pub showcases: ShowcaseState,
+    #[serde(default)]
+    pub retention: RetentionConfig,
+    /// OpenTelemetry collector to export traces and metrics to, e.g. `http://localhost:4318`.
+    /// `OTEL_EXPORTER_OTLP_ENDPOINT` takes precedence. Applied on restart.
+    #[serde(default)]
+    pub otlp_endpoint: Option<String>,
 }
 
 impl Config {
@@ -99,6 +119,8 @@ impl Config {
             language: old_config.language,
             git_branch_prefix: default_git_branch_prefix(),
             showcases: ShowcaseState::default(),
+            retention: RetentionConfig::default(),
+            otlp_endpoint: None,
         })
     }
 }
@@ -144,6 +166,8 @@ impl Default for Config {
             language: UiLanguage::default(),
             git_branch_prefix: default_git_branch_prefix(),
             showcases: ShowcaseState::default(),
+            retention: RetentionConfig::default(),
+            otlp_endpoint: None,
         }
     }
 }
@@ -2,18 +2,22 @@ use std::{
     collections::HashMap,
     path::{Path, PathBuf},
     sync::Arc,
+    time::{Duration, SystemTime},
 };
 
 use anyhow::{Error as AnyhowError, anyhow};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use db::{
     DBService,
     models::{
+        audit_event::AuditActor,
         execution_process::{
             CreateExecutionProcess, ExecutionContext, ExecutionProcess, ExecutionProcessRunReason,
             ExecutionProcessStatus,
         },
-        execution_process_logs::ExecutionProcessLogs,
+        execution_process_conversation::ExecutionProcessConversation,
+        execution_process_logs::{ExecutionProcessLogs, ExecutionProcessLogsError},
         executor_session::{CreateExecutorSession, ExecutorSession},
         task::{Task, TaskStatus},
         task_attempt::{TaskAttempt, TaskAttemptError},
@@ -21,21 +25,29 @@ use db::{
 };
 use executors::{
     actions::{
-        ExecutorAction, ExecutorActionType,
+        ExecutorAction, ExecutorActionType, PromptContext,
         coding_agent_follow_up::CodingAgentFollowUpRequest,
         coding_agent_initial::CodingAgentInitialRequest,
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
     },
     executors::{ExecutorError, StandardCodingAgentExecutor},
+    logs::{
+        NORMALIZER_VERSION,
+        utils::{ConversationPatch, patch::conversation_entries},
+    },
     profile::{ExecutorConfigs, ExecutorProfileId, to_default_variant},
 };
 use futures::{StreamExt, future};
 use sqlx::Error as SqlxError;
 use thiserror::Error;
-use tokio::{sync::RwLock, task::JoinHandle};
+use tokio::{
+    sync::{RwLock, broadcast},
+    task::JoinHandle,
+};
 use utils::{
-    log_msg::LogMsg,
+    log_msg::{LogMsg, TimedLogMsg},
     msg_store::MsgStore,
+    telemetry::{self, ToolCall},
     text::{git_branch_id, short_uuid},
 };
 use uuid::Uuid;
@@ -43,6 +55,7 @@ use uuid::Uuid;
 use crate::services::{
     git::{GitService, GitServiceError},
     image::ImageService,
+    project_instructions, prompt_template,
     worktree_manager::{WorktreeError, WorktreeManager},
 };
 pub type ContainerRef = String;
@@ -81,6 +94,93 @@ pub async fn cleanup_worktrees_direct(data: &[WorktreeCleanupData]) -> Result<()
     Ok(())
 }
 
+/// How long the normalizers may stay quiet before a finished process's conversation is complete
+const NORMALIZER_IDLE: Duration = Duration::from_millis(250);
+const NORMALIZER_SETTLE_TIMEOUT: Duration = Duration::from_secs(10);
+/// How many outdated conversations are loaded at a time when re-normalizing them
+const RENORMALIZE_BATCH_SIZE: i64 = 50;
+
+/// When a normalized conversation entry was captured, if it was recorded
+fn entry_timestamp(entry: &serde_json::Value) -> Option<DateTime<Utc>> {
+    let timestamp = entry.get("content")?.get("timestamp")?.as_str()?;
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|at| at.with_timezone(&Utc))
+}
+
+/// Export the tool calls of a finished process's conversation under the current span. A call
+/// lasts until the next entry with a timestamp.
+fn record_tool_calls(entries: &[serde_json::Value], executor: Option<&str>) {
+    let parent = tracing::Span::current();
+    let timestamps: Vec<Option<DateTime<Utc>>> = entries.iter().map(entry_timestamp).collect();
+    for (index, entry) in entries.iter().enumerate() {
+        let Some(entry_type) = entry.get("content").and_then(|c| c.get("entry_type")) else {
+            continue;
+        };
+        if entry_type.get("type").and_then(|t| t.as_str()) != Some("tool_use") {
+            continue;
+        }
+        let tool_name = entry_type
+            .get("tool_name")
+            .and_then(|t| t.as_str())
+            .unwrap_or("unknown");
+        let status = entry_type
+            .get("status")
+            .and_then(|s| s.get("status"))
+            .and_then(|s| s.as_str())
+            .unwrap_or("unknown");
+        let started = timestamps[index];
+        let ended = timestamps[index + 1..].iter().flatten().next().copied();
+        telemetry::record_tool_call(
+            &parent,
+            executor,
+            ToolCall {
+                tool_name,
+                status,
+                started: started.map(SystemTime::from),
+                ended: ended.or(started).map(SystemTime::from),
+            },
+        );
+    }
+}
+
+/// Wait for the normalizers of a finished process to stop producing patches, then apply them.
+/// Returns None when there is no conversation or the history no longer holds all of it.
+async fn settled_conversation_entries(store: &MsgStore) -> Option<Vec<serde_json::Value>> {
+    let mut rx = store.get_receiver();
+    let _ = tokio::time::timeout(NORMALIZER_SETTLE_TIMEOUT, async {
+        while let Ok(received) = tokio::time::timeout(NORMALIZER_IDLE, rx.recv()).await {
+            if matches!(received, Err(broadcast::error::RecvError::Closed)) {
+                break;
+            }
+        }
+    })
+    .await;
+
+    history_conversation_entries(&store.get_history())
+}
+
+/// Apply the conversation patches in a store's history
+fn history_conversation_entries(history: &[LogMsg]) -> Option<Vec<serde_json::Value>> {
+    let patches: Vec<_> = history
+        .iter()
+        .filter_map(|msg| match msg {
+            LogMsg::JsonPatch(patch) => Some(patch),
+            _ => None,
+        })
+        .collect();
+    if patches.is_empty() {
+        return None;
+    }
+    match conversation_entries(patches) {
+        Ok(entries) => Some(entries),
+        Err(e) => {
+            tracing::warn!("Conversation patches could not be applied: {}", e);
+            None
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum ContainerError {
     #[error(transparent)]
@@ -98,6 +198,8 @@ pub enum ContainerError {
     #[error(transparent)]
     TaskAttemptError(#[from] TaskAttemptError),
     #[error(transparent)]
+    ExecutionProcessLogs(#[from] ExecutionProcessLogsError),
+    #[error(transparent)]
     Other(#[from] AnyhowError), // Catches any unclassified errors
 }
 
@@ -196,6 +298,7 @@ pub trait ContainerService {
         task_attempt: &TaskAttempt,
         execution_process: &ExecutionProcess,
         executor_action: &ExecutorAction,
+        prompt_context: &PromptContext,
     ) -> Result<(), ContainerError>;
 
     async fn stop_execution(
@@ -204,6 +307,14 @@ pub trait ContainerService {
         status: ExecutionProcessStatus,
     ) -> Result<(), ContainerError>;
 
+    /// Deliver a message to a running execution process.
+    /// Returns false when the executor does not accept input while running.
+    async fn steer_execution(
+        &self,
+        execution_process: &ExecutionProcess,
+        message: &str,
+    ) -> Result<bool, ContainerError>;
+
     async fn try_commit_changes(&self, ctx: &ExecutionContext) -> Result<bool, ContainerError>;
 
     async fn copy_project_files(
@@ -258,20 +369,11 @@ pub trait ContainerService {
             );
         } else {
             // Fallback: load from DB and create direct stream
-            let logs_record =
-                match ExecutionProcessLogs::find_by_execution_id(&self.db().pool, *id).await {
-                    Ok(Some(record)) => record,
-                    Ok(None) => return None, // No logs exist
-                    Err(e) => {
-                        tracing::error!("Failed to fetch logs for execution {}: {}", id, e);
-                        return None;
-                    }
-                };
-
-            let messages = match logs_record.parse_logs() {
-                Ok(msgs) => msgs,
+            let messages = match ExecutionProcessLogs::load_all(&self.db().pool, *id).await {
+                Ok(Some(msgs)) => msgs,
+                Ok(None) => return None, // No logs exist
                 Err(e) => {
-                    tracing::error!("Failed to parse logs for execution {}: {}", id, e);
+                    tracing::error!("Failed to load logs for execution {}: {}", id, e);
                     return None;
                 }
             };
@@ -280,6 +382,7 @@ pub trait ContainerService {
             let stream = futures::stream::iter(
                 messages
                     .into_iter()
+                    .map(|timed| timed.msg)
                     .filter(|m| matches!(m, LogMsg::Stdout(_) | LogMsg::Stderr(_)))
                     .chain(std::iter::once(LogMsg::Finished))
                     .map(Ok::<_, std::io::Error>),
@@ -296,7 +399,7 @@ pub trait ContainerService {
     ) -> Option<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>> {
         // First try in-memory store (existing behavior)
         if let Some(store) = self.get_msg_store_by_id(id).await {
-            Some(
+            return Some(
                 store
                     .history_plus_stream() // BoxStream<Result<LogMsg, io::Error>>
                     .filter(|msg| future::ready(matches!(msg, Ok(LogMsg::JsonPatch(..)))))
@@ -304,115 +407,293 @@ pub trait ContainerService {
                         Ok::<_, std::io::Error>(LogMsg::Finished)
                     }))
                     .boxed(),
-            )
-        } else {
-            // Fallback: load from DB and normalize
-            let logs_record =
-                match ExecutionProcessLogs::find_by_execution_id(&self.db().pool, *id).await {
-                    Ok(Some(record)) => record,
-                    Ok(None) => return None, // No logs exist
-                    Err(e) => {
-                        tracing::error!("Failed to fetch logs for execution {}: {}", id, e);
-                        return None;
-                    }
-                };
+            );
+        }
 
-            let raw_messages = match logs_record.parse_logs() {
-                Ok(msgs) => msgs,
-                Err(e) => {
-                    tracing::error!("Failed to parse logs for execution {}: {}", id, e);
-                    return None;
+        // Finished processes serve the conversation stored when they exited
+        match ExecutionProcessConversation::find_by_execution_id(&self.db().pool, *id).await {
+            Ok(Some(conversation)) => match conversation.entries() {
+                Ok(entries) => {
+                    let messages = vec![
+                        Ok(LogMsg::JsonPatch(ConversationPatch::add_entries(entries))),
+                        Ok(LogMsg::Finished),
+                    ];
+                    return Some(futures::stream::iter(messages).boxed());
                 }
-            };
-
-            // Create temporary store and populate
-            let temp_store = Arc::new(MsgStore::new());
-            for msg in raw_messages {
-                if matches!(msg, LogMsg::Stdout(_) | LogMsg::Stderr(_)) {
-                    temp_store.push(msg);
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to decode stored conversation for execution {}: {}",
+                        id,
+                        e
+                    );
                 }
+            },
+            Ok(None) => {}
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to fetch stored conversation for execution {}: {}",
+                    id,
+                    e
+                );
             }
-            temp_store.push_finished();
+        }
 
-            let process = match ExecutionProcess::find_by_id(&self.db().pool, *id).await {
-                Ok(Some(process)) => process,
-                Ok(None) => {
-                    tracing::error!("No execution process found for ID: {}", id);
-                    return None;
-                }
-                Err(e) => {
-                    tracing::error!("Failed to fetch execution process {}: {}", id, e);
-                    return None;
-                }
-            };
+        // Fallback: normalize the raw logs from the DB
+        let temp_store = self.normalize_from_db(id).await?;
+        Some(
+            temp_store
+                .history_plus_stream()
+                .filter(|msg| future::ready(matches!(msg, Ok(LogMsg::JsonPatch(..)))))
+                .chain(futures::stream::once(async {
+                    Ok::<_, std::io::Error>(LogMsg::Finished)
+                }))
+                .boxed(),
+        )
+    }
 
-            // Get the task attempt to determine correct directory
-            let task_attempt = match process.parent_task_attempt(&self.db().pool).await {
-                Ok(Some(task_attempt)) => task_attempt,
-                Ok(None) => {
-                    tracing::error!("No task attempt found for ID: {}", process.task_attempt_id);
-                    return None;
-                }
-                Err(e) => {
-                    tracing::error!(
-                        "Failed to fetch task attempt {}: {}",
-                        process.task_attempt_id,
-                        e
-                    );
-                    return None;
+    /// Normalized conversation entries of a process: live from its store while it runs,
+    /// otherwise the stored conversation or a fresh normalization of its raw logs
+    async fn load_conversation(&self, id: &Uuid) -> Option<Vec<serde_json::Value>> {
+        if let Some(store) = self.get_msg_store_by_id(id).await {
+            return history_conversation_entries(&store.get_history());
+        }
+
+        match ExecutionProcessConversation::find_by_execution_id(&self.db().pool, *id).await {
+            Ok(Some(conversation)) => match conversation.entries() {
+                Ok(entries) => return Some(entries),
+                Err(e) => tracing::warn!(
+                    "Failed to decode stored conversation for execution {}: {}",
+                    id,
+                    e
+                ),
+            },
+            Ok(None) => {}
+            Err(e) => tracing::warn!(
+                "Failed to fetch stored conversation for execution {}: {}",
+                id,
+                e
+            ),
+        }
+
+        let store = self.normalize_from_db(id).await?;
+        settled_conversation_entries(&store).await
+    }
+
+    /// Replay a process's conversation with its original timing: each entry is added after the
+    /// time that passed since the previous one, divided by `speed` and capped at `max_gap`.
+    /// Entries without a timestamp are added straight away.
+    async fn replay_conversation(
+        &self,
+        id: &Uuid,
+        speed: f64,
+        max_gap: Option<Duration>,
+    ) -> Option<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>> {
+        let entries = self.load_conversation(id).await?;
+        let speed = if speed.is_finite() && speed > 0.0 {
+            speed
+        } else {
+            1.0
+        };
+
+        let mut previous: Option<DateTime<Utc>> = None;
+        let mut timeline = Vec::with_capacity(entries.len());
+        for (index, entry) in entries.into_iter().enumerate() {
+            let at = entry_timestamp(&entry);
+            let mut delay = match (previous, at) {
+                (Some(previous), Some(at)) => {
+                    (at - previous).to_std().unwrap_or_default().div_f64(speed)
                 }
+                _ => Duration::ZERO,
             };
+            if let Some(max_gap) = max_gap {
+                delay = delay.min(max_gap);
+            }
+            previous = at.or(previous);
+            timeline.push((delay, ConversationPatch::add_entry(index, entry)));
+        }
 
-            if let Err(err) = self.ensure_container_exists(&task_attempt).await {
-                tracing::warn!(
-                    "Failed to recreate worktree before log normalization for task attempt {}: {}",
-                    task_attempt.id,
-                    err
-                );
+        Some(
+            futures::stream::iter(timeline)
+                .then(|(delay, patch)| async move {
+                    tokio::time::sleep(delay).await;
+                    Ok::<_, std::io::Error>(LogMsg::JsonPatch(patch))
+                })
+                .chain(futures::stream::once(async {
+                    Ok::<_, std::io::Error>(LogMsg::Finished)
+                }))
+                .boxed(),
+        )
+    }
+
+    /// Run the executor's normalizer over the raw logs stored for a process, recreating the
+    /// attempt's worktree first if it was cleaned up
+    async fn normalize_from_db(&self, id: &Uuid) -> Option<Arc<MsgStore>> {
+        self.normalize_stored_logs(id, true).await
+    }
+
+    /// Run the executor's normalizer over the raw logs stored for a process. The normalizer is
+    /// given the attempt's recorded worktree path, which is only recreated if
+    /// `recreate_worktree` is set.
+    async fn normalize_stored_logs(
+        &self,
+        id: &Uuid,
+        recreate_worktree: bool,
+    ) -> Option<Arc<MsgStore>> {
+        let raw_messages = match ExecutionProcessLogs::load_all(&self.db().pool, *id).await {
+            Ok(Some(msgs)) => msgs,
+            Ok(None) => return None, // No logs exist
+            Err(e) => {
+                tracing::error!("Failed to load logs for execution {}: {}", id, e);
+                return None;
             }
+        };
 
-            let current_dir = self.task_attempt_to_current_dir(&task_attempt);
+        // Create temporary store and populate
+        let temp_store = Arc::new(MsgStore::new());
+        for timed in raw_messages {
+            if matches!(timed.msg, LogMsg::Stdout(_) | LogMsg::Stderr(_)) {
+                temp_store.push_timed(timed);
+            }
+        }
+        temp_store.push_finished();
 
-            let executor_action = if let Ok(executor_action) = process.executor_action() {
-                executor_action
-            } else {
+        let process = match ExecutionProcess::find_by_id(&self.db().pool, *id).await {
+            Ok(Some(process)) => process,
+            Ok(None) => {
+                tracing::error!("No execution process found for ID: {}", id);
+                return None;
+            }
+            Err(e) => {
+                tracing::error!("Failed to fetch execution process {}: {}", id, e);
+                return None;
+            }
+        };
+
+        // Get the task attempt to determine correct directory
+        let task_attempt = match process.parent_task_attempt(&self.db().pool).await {
+            Ok(Some(task_attempt)) => task_attempt,
+            Ok(None) => {
+                tracing::error!("No task attempt found for ID: {}", process.task_attempt_id);
+                return None;
+            }
+            Err(e) => {
                 tracing::error!(
-                    "Failed to parse executor action: {:?}",
+                    "Failed to fetch task attempt {}: {}",
+                    process.task_attempt_id,
+                    e
+                );
+                return None;
+            }
+        };
+
+        if recreate_worktree && let Err(err) = self.ensure_container_exists(&task_attempt).await {
+            tracing::warn!(
+                "Failed to recreate worktree before log normalization for task attempt {}: {}",
+                task_attempt.id,
+                err
+            );
+        }
+
+        let current_dir = self.task_attempt_to_current_dir(&task_attempt);
+
+        let executor_action = if let Ok(executor_action) = process.executor_action() {
+            executor_action
+        } else {
+            tracing::error!(
+                "Failed to parse executor action: {:?}",
+                process.executor_action()
+            );
+            return None;
+        };
+
+        // Spawn normalizer on populated store
+        match executor_action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                let executor = ExecutorConfigs::get_cached()
+                    .get_coding_agent_or_default(&request.executor_profile_id);
+                executor.normalize_logs(temp_store.clone(), &current_dir);
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                let executor = ExecutorConfigs::get_cached()
+                    .get_coding_agent_or_default(&request.executor_profile_id);
+                executor.normalize_logs(temp_store.clone(), &current_dir);
+            }
+            _ => {
+                tracing::debug!(
+                    "Executor action doesn't support log normalization: {:?}",
                     process.executor_action()
                 );
                 return None;
-            };
+            }
+        }
+        Some(temp_store)
+    }
 
-            // Spawn normalizer on populated store
-            match executor_action.typ() {
-                ExecutorActionType::CodingAgentInitialRequest(request) => {
-                    let executor = ExecutorConfigs::get_cached()
-                        .get_coding_agent_or_default(&request.executor_profile_id);
-                    executor.normalize_logs(temp_store.clone(), &current_dir);
-                }
-                ExecutorActionType::CodingAgentFollowUpRequest(request) => {
-                    let executor = ExecutorConfigs::get_cached()
-                        .get_coding_agent_or_default(&request.executor_profile_id);
-                    executor.normalize_logs(temp_store.clone(), &current_dir);
-                }
-                _ => {
-                    tracing::debug!(
-                        "Executor action doesn't support log normalization: {:?}",
-                        process.executor_action()
-                    );
-                    return None;
+    /// Store the normalized conversation of a finished process so it is served as it rendered
+    /// at the time, even after the executor's log parser changes
+    async fn persist_conversation(&self, id: &Uuid, store: &MsgStore) {
+        let Some(entries) = settled_conversation_entries(store).await else {
+            return;
+        };
+        let executor = match ExecutionProcess::find_by_id(&self.db().pool, *id).await {
+            Ok(Some(process)) => process
+                .executor_action()
+                .ok()
+                .and_then(ExecutorAction::base_executor)
+                .map(|executor| executor.to_string()),
+            _ => None,
+        };
+        record_tool_calls(&entries, executor.as_deref());
+
+        if let Err(e) =
+            ExecutionProcessConversation::upsert(&self.db().pool, *id, NORMALIZER_VERSION, &entries)
+                .await
+        {
+            tracing::error!("Failed to store conversation for execution {}: {}", id, e);
+        }
+    }
+
+    /// Re-run normalization for a finished process and replace its stored conversation.
+    /// Returns false when the process is still running or has nothing to normalize. The
+    /// attempt's worktree is left as it is.
+    async fn renormalize_execution(&self, id: &Uuid) -> Result<bool, ContainerError> {
+        if self.get_msg_store_by_id(id).await.is_some() {
+            return Ok(false);
+        }
+        let Some(store) = self.normalize_stored_logs(id, false).await else {
+            return Ok(false);
+        };
+        let Some(entries) = settled_conversation_entries(&store).await else {
+            return Ok(false);
+        };
+        ExecutionProcessConversation::upsert(&self.db().pool, *id, NORMALIZER_VERSION, &entries)
+            .await?;
+        Ok(true)
+    }
+
+    /// Re-normalize every stored conversation produced by an older normalizer version
+    async fn renormalize_outdated(&self) -> Result<usize, ContainerError> {
+        let mut renormalized = 0;
+        let mut after = None;
+        loop {
+            let outdated = ExecutionProcessConversation::find_outdated(
+                &self.db().pool,
+                NORMALIZER_VERSION,
+                after,
+                RENORMALIZE_BATCH_SIZE,
+            )
+            .await?;
+            let Some(&last) = outdated.last() else {
+                break;
+            };
+            after = Some(last);
+            for id in outdated {
+                if self.renormalize_execution(&id).await? {
+                    renormalized += 1;
                 }
             }
-            Some(
-                temp_store
-                    .history_plus_stream()
-                    .filter(|msg| future::ready(matches!(msg, Ok(LogMsg::JsonPatch(..)))))
-                    .chain(futures::stream::once(async {
-                        Ok::<_, std::io::Error>(LogMsg::Finished)
-                    }))
-                    .boxed(),
-            )
         }
+        Ok(renormalized)
     }
 
     fn spawn_stream_raw_logs_to_db(&self, execution_id: &Uuid) -> JoinHandle<()> {
@@ -428,13 +709,14 @@ pub trait ContainerService {
             };
 
             if let Some(store) = store {
-                let mut stream = store.history_plus_stream();
+                let mut stream = store.history_plus_stream_timed();
 
-                while let Some(Ok(msg)) = stream.next().await {
-                    match &msg {
+                while let Some(Ok(timed)) = stream.next().await {
+                    match &timed.msg {
                         LogMsg::Stdout(_) | LogMsg::Stderr(_) => {
-                            // Serialize this individual message as a JSONL line
-                            match serde_json::to_string(&msg) {
+                            // Serialize this individual message, with its capture time, as a
+                            // JSONL line
+                            match serde_json::to_string(&timed) {
                                 Ok(jsonl_line) => {
                                     let jsonl_line_with_newline = format!("{jsonl_line}\n");
 
@@ -489,6 +771,11 @@ pub trait ContainerService {
         })
     }
 
+    #[tracing::instrument(
+        name = "task_attempt.start",
+        skip(self, task_attempt),
+        fields(task_attempt.id = %task_attempt.id)
+    )]
     async fn start_attempt(
         &self,
         task_attempt: &TaskAttempt,
@@ -568,6 +855,47 @@ pub trait ContainerService {
         Ok(execution_process)
     }
 
+    /// Collect what goes into the prompt of a coding agent action besides the prompt itself:
+    /// the project's instructions, rendered when they were pinned to the attempt, and the
+    /// variables of the profile's append prompt. The prompt is what the user wrote and is sent
+    /// as is.
+    async fn prepare_prompt(
+        &self,
+        task_attempt: &TaskAttempt,
+        executor_action: &ExecutorAction,
+    ) -> Result<PromptContext, SqlxError> {
+        if !matches!(
+            executor_action.typ(),
+            ExecutorActionType::CodingAgentInitialRequest(_)
+                | ExecutorActionType::CodingAgentFollowUpRequest(_)
+        ) {
+            return Ok(PromptContext::default());
+        }
+        let append_prompt = executor_action
+            .executor_profile_id()
+            .and_then(|profile| ExecutorConfigs::get_cached().get_coding_agent(&profile))
+            .and_then(|agent| agent.append_prompt().get());
+
+        let instructions =
+            project_instructions::for_attempt(&self.db().pool, self.git(), task_attempt)
+                .await?
+                .map(|version| version.content);
+        let vars = match append_prompt.as_deref() {
+            Some(append_prompt) => {
+                prompt_template::context_for_attempt(
+                    &self.db().pool,
+                    self.git(),
+                    task_attempt,
+                    &[append_prompt],
+                )
+                .await?
+            }
+            None => serde_json::Value::Null,
+        };
+
+        Ok(PromptContext { vars, instructions })
+    }
+
     async fn start_execution(
         &self,
         task_attempt: &TaskAttempt,
@@ -582,7 +910,14 @@ pub trait ContainerService {
         if task.status != TaskStatus::InProgress
             && run_reason != &ExecutionProcessRunReason::DevServer
         {
-            Task::update_status(&self.db().pool, task.id, TaskStatus::InProgress).await?;
+            Task::transition_status(
+                &self.db().pool,
+                task.id,
+                TaskStatus::InProgress,
+                AuditActor::System,
+                Some(task_attempt.id),
+            )
+            .await?;
         }
         // Create new execution process record
         // Capture current HEAD as the "before" commit for this execution
@@ -594,6 +929,8 @@ pub trait ContainerService {
                 None
             }
         };
+        let prompt_context = self.prepare_prompt(task_attempt, executor_action).await?;
+
         let create_execution_process = CreateExecutionProcess {
             task_attempt_id: task_attempt.id,
             executor_action: executor_action.clone(),
@@ -634,7 +971,12 @@ pub trait ContainerService {
         }
 
         if let Err(start_error) = self
-            .start_execution_inner(task_attempt, &execution_process, executor_action)
+            .start_execution_inner(
+                task_attempt,
+                &execution_process,
+                executor_action,
+                &prompt_context,
+            )
             .await
         {
             // Mark process as failed
@@ -654,7 +996,9 @@ pub trait ContainerService {
             }
 
             // Persist the error to process stderr logs
-            let log_message = LogMsg::Stderr(format!("Failed to start execution: {start_error}"));
+            let log_message = TimedLogMsg::now(LogMsg::Stderr(format!(
+                "Failed to start execution: {start_error}"
+            )));
             if let Ok(json_line) = serde_json::to_string(&log_message)
                 && let Err(err) = ExecutionProcessLogs::append_log_line(
                     &self.db().pool,
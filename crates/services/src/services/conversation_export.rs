@@ -0,0 +1,814 @@
+//! Export of normalized conversations as Markdown, self-contained HTML or JSONL.
+
+use std::fmt::Write as _;
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    execution_process_logs::ExecutionProcessLogs,
+};
+use executors::{
+    actions::ExecutorActionType,
+    logs::{
+        ActionType, CommandExitStatus, FileChange, NormalizedEntry, NormalizedEntryType,
+        ToolResultValueType, ToolStatus,
+    },
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+use crate::services::container::ContainerService;
+
+/// Version of the JSONL record schema, bumped on incompatible changes
+pub const EXPORT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Markdown,
+    Html,
+    Jsonl,
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "text/markdown; charset=utf-8",
+            ExportFormat::Html => "text/html; charset=utf-8",
+            ExportFormat::Jsonl => "application/x-ndjson",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Html => "html",
+            ExportFormat::Jsonl => "jsonl",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ConversationItem {
+    Entry(NormalizedEntry),
+    Stdout(String),
+    Stderr(String),
+}
+
+impl ConversationItem {
+    /// Parse a conversation entry as stored in the `/entries` document
+    fn from_value(value: &Value) -> Option<Self> {
+        let content = value.get("content")?;
+        match value.get("type")?.as_str()? {
+            "NORMALIZED_ENTRY" => serde_json::from_value(content.clone())
+                .ok()
+                .map(ConversationItem::Entry),
+            "STDOUT" => Some(ConversationItem::Stdout(content.as_str()?.to_string())),
+            "STDERR" => Some(ConversationItem::Stderr(content.as_str()?.to_string())),
+            _ => None,
+        }
+    }
+
+    /// Append to the previous item when both are output of the same stream
+    fn push_merged(items: &mut Vec<Self>, item: Self) {
+        match (items.last_mut(), &item) {
+            (Some(ConversationItem::Stdout(prev)), ConversationItem::Stdout(next))
+            | (Some(ConversationItem::Stderr(prev)), ConversationItem::Stderr(next)) => {
+                prev.push_str(next);
+                return;
+            }
+            _ => {}
+        }
+        items.push(item);
+    }
+}
+
+pub struct ExportedProcess {
+    pub process: ExecutionProcess,
+    pub executor: Option<String>,
+    pub items: Vec<ConversationItem>,
+}
+
+pub struct ConversationExport {
+    pub title: String,
+    pub exported_at: DateTime<Utc>,
+    pub processes: Vec<ExportedProcess>,
+}
+
+impl ConversationExport {
+    /// Gather the conversation of each process. Processes without a normalized conversation,
+    /// such as scripts, are exported with their raw output.
+    pub async fn collect<C>(container: &C, title: String, processes: Vec<ExecutionProcess>) -> Self
+    where
+        C: ContainerService + Sync + ?Sized,
+    {
+        let mut exported = Vec::with_capacity(processes.len());
+        for process in processes {
+            let action = process.executor_action().ok();
+            let executor = action
+                .and_then(|action| action.base_executor())
+                .map(|executor| executor.to_string());
+            let prompt = action.and_then(|action| match action.typ() {
+                ExecutorActionType::CodingAgentInitialRequest(request) => {
+                    Some(request.prompt.clone())
+                }
+                ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                    Some(request.prompt.clone())
+                }
+                _ => None,
+            });
+
+            let mut items = Vec::new();
+            if let Some(prompt) = &prompt {
+                items.push(ConversationItem::Entry(NormalizedEntry {
+                    timestamp: None,
+                    entry_type: NormalizedEntryType::UserMessage,
+                    content: prompt.clone(),
+                    metadata: None,
+                }));
+            }
+
+            match container.load_conversation(&process.id).await {
+                Some(entries) => {
+                    for item in entries.iter().filter_map(ConversationItem::from_value) {
+                        // The prompt is already the first message, agents often echo it back
+                        if let (ConversationItem::Entry(entry), Some(prompt)) = (&item, &prompt)
+                            && matches!(entry.entry_type, NormalizedEntryType::UserMessage)
+                            && entry.content.trim() == prompt.trim()
+                        {
+                            continue;
+                        }
+                        ConversationItem::push_merged(&mut items, item);
+                    }
+                }
+                None => {
+                    let messages = ExecutionProcessLogs::load_all(&container.db().pool, process.id)
+                        .await
+                        .unwrap_or_else(|e| {
+                            tracing::warn!("Failed to load logs for export {}: {}", process.id, e);
+                            None
+                        })
+                        .unwrap_or_default();
+                    for timed in messages {
+                        let item = match timed.msg {
+                            LogMsg::Stdout(out) => ConversationItem::Stdout(out),
+                            LogMsg::Stderr(err) => ConversationItem::Stderr(err),
+                            _ => continue,
+                        };
+                        ConversationItem::push_merged(&mut items, item);
+                    }
+                }
+            }
+
+            exported.push(ExportedProcess {
+                process,
+                executor,
+                items,
+            });
+        }
+
+        Self {
+            title,
+            exported_at: Utc::now(),
+            processes: exported,
+        }
+    }
+
+    pub fn render(&self, format: ExportFormat) -> Result<String, serde_json::Error> {
+        match format {
+            ExportFormat::Markdown => Ok(self.to_markdown()),
+            ExportFormat::Html => Ok(self.to_html()),
+            ExportFormat::Jsonl => self.to_jsonl(),
+        }
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# {}\n", self.title);
+        let _ = writeln!(out, "_Exported {}_\n", self.exported_at.to_rfc3339());
+
+        for (i, exported) in self.processes.iter().enumerate() {
+            let _ = writeln!(out, "## {}. {}\n", i + 1, process_heading(exported));
+            let _ = writeln!(out, "_{}_\n", process_times(&exported.process));
+
+            for item in &exported.items {
+                match item {
+                    ConversationItem::Entry(entry) => markdown_entry(&mut out, entry),
+                    ConversationItem::Stdout(text) => {
+                        let _ = writeln!(out, "### Output\n\n{}", code_block("text", text));
+                    }
+                    ConversationItem::Stderr(text) => {
+                        let _ = writeln!(out, "### Error output\n\n{}", code_block("text", text));
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    fn to_html(&self) -> String {
+        let mut body = String::new();
+        let _ = writeln!(body, "<h1>{}</h1>", escape_html(&self.title));
+        let _ = writeln!(
+            body,
+            "<p class=\"meta\">Exported {}</p>",
+            escape_html(&self.exported_at.to_rfc3339())
+        );
+
+        for (i, exported) in self.processes.iter().enumerate() {
+            let _ = writeln!(
+                body,
+                "<section><h2>{}. {}</h2><p class=\"meta\">{}</p>",
+                i + 1,
+                escape_html(&process_heading(exported)),
+                escape_html(&process_times(&exported.process))
+            );
+            for item in &exported.items {
+                match item {
+                    ConversationItem::Entry(entry) => html_entry(&mut body, entry),
+                    ConversationItem::Stdout(text) => {
+                        let _ = writeln!(
+                            body,
+                            "<div class=\"entry output\"><div class=\"role\">Output</div>{}</div>",
+                            html_pre(text, None)
+                        );
+                    }
+                    ConversationItem::Stderr(text) => {
+                        let _ = writeln!(
+                            body,
+                            "<div class=\"entry error\"><div class=\"role\">Error output</div>{}</div>",
+                            html_pre(text, None)
+                        );
+                    }
+                }
+            }
+            body.push_str("</section>\n");
+        }
+
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+            escape_html(&self.title),
+            HTML_STYLE,
+            body
+        )
+    }
+
+    fn to_jsonl(&self) -> Result<String, serde_json::Error> {
+        let mut out = String::new();
+        let mut push = |record: &JsonlRecord| -> Result<(), serde_json::Error> {
+            out.push_str(&serde_json::to_string(record)?);
+            out.push('\n');
+            Ok(())
+        };
+
+        push(&JsonlRecord::Export {
+            schema_version: EXPORT_SCHEMA_VERSION,
+            title: &self.title,
+            exported_at: self.exported_at,
+        })?;
+        for exported in &self.processes {
+            let process = &exported.process;
+            push(&JsonlRecord::Process {
+                execution_process_id: process.id,
+                task_attempt_id: process.task_attempt_id,
+                run_reason: &process.run_reason,
+                executor: exported.executor.as_deref(),
+                status: &process.status,
+                exit_code: process.exit_code,
+                started_at: process.started_at,
+                completed_at: process.completed_at,
+            })?;
+
+            for (index, item) in exported.items.iter().enumerate() {
+                let record = match item {
+                    ConversationItem::Entry(entry) => {
+                        let Some(kind) = entry_kind(&entry.entry_type) else {
+                            continue;
+                        };
+                        let (tool, denied_tool) = match &entry.entry_type {
+                            NormalizedEntryType::ToolUse {
+                                tool_name,
+                                action_type,
+                                status,
+                            } => (
+                                Some(JsonlTool {
+                                    name: tool_name,
+                                    action: action_type,
+                                    status,
+                                }),
+                                None,
+                            ),
+                            NormalizedEntryType::UserFeedback { denied_tool } => {
+                                (None, Some(denied_tool.as_str()))
+                            }
+                            _ => (None, None),
+                        };
+                        JsonlRecord::Entry {
+                            execution_process_id: process.id,
+                            index,
+                            kind,
+                            timestamp: entry.timestamp.as_deref(),
+                            content: &entry.content,
+                            tool,
+                            denied_tool,
+                        }
+                    }
+                    ConversationItem::Stdout(text) => JsonlRecord::Entry {
+                        execution_process_id: process.id,
+                        index,
+                        kind: "stdout",
+                        timestamp: None,
+                        content: text,
+                        tool: None,
+                        denied_tool: None,
+                    },
+                    ConversationItem::Stderr(text) => JsonlRecord::Entry {
+                        execution_process_id: process.id,
+                        index,
+                        kind: "stderr",
+                        timestamp: None,
+                        content: text,
+                        tool: None,
+                        denied_tool: None,
+                    },
+                };
+                push(&record)?;
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// One line of the JSONL export
+#[derive(Serialize)]
+#[serde(tag = "record", rename_all = "snake_case")]
+enum JsonlRecord<'a> {
+    Export {
+        schema_version: u32,
+        title: &'a str,
+        exported_at: DateTime<Utc>,
+    },
+    Process {
+        execution_process_id: Uuid,
+        task_attempt_id: Uuid,
+        run_reason: &'a ExecutionProcessRunReason,
+        executor: Option<&'a str>,
+        status: &'a ExecutionProcessStatus,
+        exit_code: Option<i64>,
+        started_at: DateTime<Utc>,
+        completed_at: Option<DateTime<Utc>>,
+    },
+    Entry {
+        execution_process_id: Uuid,
+        index: usize,
+        kind: &'static str,
+        timestamp: Option<&'a str>,
+        content: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        tool: Option<JsonlTool<'a>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        denied_tool: Option<&'a str>,
+    },
+}
+
+#[derive(Serialize)]
+struct JsonlTool<'a> {
+    name: &'a str,
+    action: &'a ActionType,
+    status: &'a ToolStatus,
+}
+
+/// Entries that only drive the live UI are left out
+fn entry_kind(entry_type: &NormalizedEntryType) -> Option<&'static str> {
+    Some(match entry_type {
+        NormalizedEntryType::UserMessage => "user_message",
+        NormalizedEntryType::UserFeedback { .. } => "user_feedback",
+        NormalizedEntryType::AssistantMessage => "assistant_message",
+        NormalizedEntryType::ToolUse { .. } => "tool_use",
+        NormalizedEntryType::SystemMessage => "system_message",
+        NormalizedEntryType::ErrorMessage => "error_message",
+        NormalizedEntryType::Thinking => "thinking",
+        NormalizedEntryType::Loading | NormalizedEntryType::NextAction { .. } => return None,
+    })
+}
+
+fn entry_label(entry_type: &NormalizedEntryType) -> &'static str {
+    match entry_type {
+        NormalizedEntryType::UserMessage => "User",
+        NormalizedEntryType::UserFeedback { .. } => "User feedback",
+        NormalizedEntryType::AssistantMessage => "Assistant",
+        NormalizedEntryType::ToolUse { .. } => "Tool",
+        NormalizedEntryType::SystemMessage => "System",
+        NormalizedEntryType::ErrorMessage => "Error",
+        NormalizedEntryType::Thinking => "Thinking",
+        NormalizedEntryType::Loading | NormalizedEntryType::NextAction { .. } => "",
+    }
+}
+
+fn process_heading(exported: &ExportedProcess) -> String {
+    let reason = match exported.process.run_reason {
+        ExecutionProcessRunReason::SetupScript => "Setup script",
+        ExecutionProcessRunReason::CleanupScript => "Cleanup script",
+        ExecutionProcessRunReason::CodingAgent => "Coding agent",
+        ExecutionProcessRunReason::DevServer => "Dev server",
+    };
+    let status = serde_json::to_value(&exported.process.status)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default();
+    match &exported.executor {
+        Some(executor) => format!("{reason} ({executor}), {status}"),
+        None => format!("{reason}, {status}"),
+    }
+}
+
+fn process_times(process: &ExecutionProcess) -> String {
+    match process.completed_at {
+        Some(completed_at) => format!(
+            "Started {}, finished {}",
+            process.started_at.to_rfc3339(),
+            completed_at.to_rfc3339()
+        ),
+        None => format!("Started {}", process.started_at.to_rfc3339()),
+    }
+}
+
+fn tool_status_label(status: &ToolStatus) -> String {
+    match status {
+        ToolStatus::Created => "started".to_string(),
+        ToolStatus::Success => "succeeded".to_string(),
+        ToolStatus::Failed => "failed".to_string(),
+        ToolStatus::Denied {
+            reason: Some(reason),
+        } => format!("denied: {reason}"),
+        ToolStatus::Denied { reason: None } => "denied".to_string(),
+        ToolStatus::PendingApproval { .. } => "awaiting approval".to_string(),
+        ToolStatus::TimedOut => "approval timed out".to_string(),
+    }
+}
+
+fn exit_status_label(status: &CommandExitStatus) -> String {
+    match status {
+        CommandExitStatus::ExitCode { code } => format!("Exit code {code}"),
+        CommandExitStatus::Success { success: true } => "Succeeded".to_string(),
+        CommandExitStatus::Success { success: false } => "Failed".to_string(),
+    }
+}
+
+/// Fenced code block that survives backticks inside the content
+fn code_block(lang: &str, content: &str) -> String {
+    let mut longest = 0;
+    let mut run = 0;
+    for c in content.chars() {
+        run = if c == '`' { run + 1 } else { 0 };
+        longest = longest.max(run);
+    }
+    let fence = "`".repeat((longest + 1).max(3));
+    format!(
+        "{fence}{lang}\n{}\n{fence}\n",
+        content.trim_end_matches('\n')
+    )
+}
+
+fn markdown_entry(out: &mut String, entry: &NormalizedEntry) {
+    match &entry.entry_type {
+        NormalizedEntryType::Loading | NormalizedEntryType::NextAction { .. } => {}
+        NormalizedEntryType::ToolUse {
+            tool_name,
+            action_type,
+            status,
+        } => {
+            let _ = writeln!(
+                out,
+                "### Tool: {} ({})\n",
+                tool_name,
+                tool_status_label(status)
+            );
+            if !entry.content.trim().is_empty() {
+                let _ = writeln!(out, "{}\n", entry.content.trim());
+            }
+            markdown_action(out, action_type);
+        }
+        NormalizedEntryType::UserFeedback { denied_tool } => {
+            let _ = writeln!(
+                out,
+                "### User feedback (denied `{}`)\n\n{}\n",
+                denied_tool,
+                entry.content.trim()
+            );
+        }
+        NormalizedEntryType::Thinking => {
+            let quoted: Vec<String> = entry
+                .content
+                .trim()
+                .lines()
+                .map(|line| format!("> {line}"))
+                .collect();
+            let _ = writeln!(out, "### Thinking\n\n{}\n", quoted.join("\n"));
+        }
+        entry_type => {
+            let _ = writeln!(
+                out,
+                "### {}\n\n{}\n",
+                entry_label(entry_type),
+                entry.content.trim()
+            );
+        }
+    }
+}
+
+fn markdown_action(out: &mut String, action: &ActionType) {
+    match action {
+        ActionType::FileRead { path } => {
+            let _ = writeln!(out, "Read `{path}`\n");
+        }
+        ActionType::FileEdit { path, changes } => {
+            let _ = writeln!(out, "Changed `{path}`\n");
+            for change in changes {
+                match change {
+                    FileChange::Edit { unified_diff, .. } => {
+                        out.push_str(&code_block("diff", unified_diff));
+                        out.push('\n');
+                    }
+                    FileChange::Write { content } => {
+                        let _ = writeln!(out, "Wrote file:\n\n{}", code_block("", content));
+                    }
+                    FileChange::Delete => {
+                        let _ = writeln!(out, "Deleted file\n");
+                    }
+                    FileChange::Rename { new_path } => {
+                        let _ = writeln!(out, "Renamed to `{new_path}`\n");
+                    }
+                }
+            }
+        }
+        ActionType::CommandRun { command, result } => {
+            out.push_str(&code_block("sh", &format!("$ {command}")));
+            out.push('\n');
+            if let Some(result) = result {
+                if let Some(output) = result.output.as_deref().filter(|o| !o.trim().is_empty()) {
+                    out.push_str(&code_block("text", output));
+                    out.push('\n');
+                }
+                if let Some(exit_status) = &result.exit_status {
+                    let _ = writeln!(out, "{}\n", exit_status_label(exit_status));
+                }
+            }
+        }
+        ActionType::Search { query } => {
+            let _ = writeln!(out, "Searched for `{query}`\n");
+        }
+        ActionType::WebFetch { url } => {
+            let _ = writeln!(out, "Fetched <{url}>\n");
+        }
+        ActionType::Tool {
+            arguments, result, ..
+        } => {
+            if let Some(arguments) = arguments {
+                let json = serde_json::to_string_pretty(arguments).unwrap_or_default();
+                out.push_str(&code_block("json", &json));
+                out.push('\n');
+            }
+            if let Some(result) = result {
+                match result.r#type {
+                    ToolResultValueType::Markdown => {
+                        let text = result
+                            .value
+                            .as_str()
+                            .map(str::to_string)
+                            .unwrap_or_else(|| result.value.to_string());
+                        let _ = writeln!(out, "{}\n", text.trim());
+                    }
+                    ToolResultValueType::Json => {
+                        let json = serde_json::to_string_pretty(&result.value).unwrap_or_default();
+                        out.push_str(&code_block("json", &json));
+                        out.push('\n');
+                    }
+                }
+            }
+        }
+        ActionType::TaskCreate { description } => {
+            let _ = writeln!(out, "Created task: {description}\n");
+        }
+        ActionType::PlanPresentation { plan } => {
+            let _ = writeln!(out, "{}\n", plan.trim());
+        }
+        ActionType::TodoManagement { todos, .. } => {
+            for todo in todos {
+                let mark = if todo.status == "completed" { "x" } else { " " };
+                let _ = writeln!(out, "- [{mark}] {}", todo.content);
+            }
+            out.push('\n');
+        }
+        ActionType::Other { description } => {
+            let _ = writeln!(out, "{description}\n");
+        }
+    }
+}
+
+const HTML_STYLE: &str = "body{font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',sans-serif;max-width:960px;margin:2rem auto;padding:0 1rem;color:#1f2328;line-height:1.5}\
+h1{font-size:1.6rem}h2{font-size:1.2rem;margin-top:2.5rem;border-bottom:1px solid #d0d7de;padding-bottom:.3rem}\
+.meta{color:#656d76;font-size:.85rem}\
+.entry{margin:1rem 0;padding:.75rem 1rem;border-radius:6px;background:#f6f8fa}\
+.entry.user{background:#ddf4ff}.entry.error{background:#ffebe9}.entry.thinking{color:#656d76;font-style:italic}\
+.role{font-weight:600;font-size:.8rem;text-transform:uppercase;color:#656d76;margin-bottom:.35rem}\
+.text{white-space:pre-wrap}\
+pre{background:#fff;border:1px solid #d0d7de;border-radius:6px;padding:.6rem;overflow-x:auto;font-size:.8rem}\
+.add{color:#116329;background:#dafbe1;display:block}.del{color:#82071e;background:#ffebe9;display:block}.hunk{color:#0550ae;display:block}";
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Preformatted block, highlighting added and removed lines of diffs
+fn html_pre(text: &str, lang: Option<&str>) -> String {
+    let body = if lang == Some("diff") {
+        text.lines()
+            .map(|line| {
+                let class = if line.starts_with("+++") || line.starts_with("---") {
+                    None
+                } else if line.starts_with('+') {
+                    Some("add")
+                } else if line.starts_with('-') {
+                    Some("del")
+                } else if line.starts_with("@@") {
+                    Some("hunk")
+                } else {
+                    None
+                };
+                match class {
+                    Some(class) => format!("<span class=\"{class}\">{}</span>", escape_html(line)),
+                    None => format!("{}\n", escape_html(line)),
+                }
+            })
+            .collect()
+    } else {
+        escape_html(text.trim_end_matches('\n'))
+    };
+    format!("<pre>{body}</pre>")
+}
+
+fn html_entry(out: &mut String, entry: &NormalizedEntry) {
+    let class = match &entry.entry_type {
+        NormalizedEntryType::Loading | NormalizedEntryType::NextAction { .. } => return,
+        NormalizedEntryType::UserMessage | NormalizedEntryType::UserFeedback { .. } => "user",
+        NormalizedEntryType::ErrorMessage => "error",
+        NormalizedEntryType::Thinking => "thinking",
+        NormalizedEntryType::ToolUse { .. } => "tool",
+        _ => "message",
+    };
+    let role = match &entry.entry_type {
+        NormalizedEntryType::ToolUse {
+            tool_name, status, ..
+        } => format!("Tool: {tool_name} ({})", tool_status_label(status)),
+        NormalizedEntryType::UserFeedback { denied_tool } => {
+            format!("User feedback (denied {denied_tool})")
+        }
+        entry_type => entry_label(entry_type).to_string(),
+    };
+
+    let _ = write!(
+        out,
+        "<div class=\"entry {class}\"><div class=\"role\">{}</div>",
+        escape_html(&role)
+    );
+    if !entry.content.trim().is_empty() {
+        let _ = write!(
+            out,
+            "<div class=\"text\">{}</div>",
+            escape_html(entry.content.trim())
+        );
+    }
+    if let NormalizedEntryType::ToolUse { action_type, .. } = &entry.entry_type {
+        html_action(out, action_type);
+    }
+    out.push_str("</div>\n");
+}
+
+fn html_action(out: &mut String, action: &ActionType) {
+    match action {
+        ActionType::FileRead { path } => {
+            let _ = write!(out, "<p>Read <code>{}</code></p>", escape_html(path));
+        }
+        ActionType::FileEdit { path, changes } => {
+            let _ = write!(out, "<p>Changed <code>{}</code></p>", escape_html(path));
+            for change in changes {
+                match change {
+                    FileChange::Edit { unified_diff, .. } => {
+                        out.push_str(&html_pre(unified_diff, Some("diff")))
+                    }
+                    FileChange::Write { content } => {
+                        let _ = write!(out, "<p>Wrote file:</p>{}", html_pre(content, None));
+                    }
+                    FileChange::Delete => out.push_str("<p>Deleted file</p>"),
+                    FileChange::Rename { new_path } => {
+                        let _ = write!(
+                            out,
+                            "<p>Renamed to <code>{}</code></p>",
+                            escape_html(new_path)
+                        );
+                    }
+                }
+            }
+        }
+        ActionType::CommandRun { command, result } => {
+            out.push_str(&html_pre(&format!("$ {command}"), None));
+            if let Some(result) = result {
+                if let Some(output) = result.output.as_deref().filter(|o| !o.trim().is_empty()) {
+                    out.push_str(&html_pre(output, None));
+                }
+                if let Some(exit_status) = &result.exit_status {
+                    let _ = write!(
+                        out,
+                        "<p class=\"meta\">{}</p>",
+                        escape_html(&exit_status_label(exit_status))
+                    );
+                }
+            }
+        }
+        ActionType::Search { query } => {
+            let _ = write!(
+                out,
+                "<p>Searched for <code>{}</code></p>",
+                escape_html(query)
+            );
+        }
+        ActionType::WebFetch { url } => {
+            let _ = write!(out, "<p>Fetched <code>{}</code></p>", escape_html(url));
+        }
+        ActionType::Tool {
+            arguments, result, ..
+        } => {
+            if let Some(arguments) = arguments {
+                let json = serde_json::to_string_pretty(arguments).unwrap_or_default();
+                out.push_str(&html_pre(&json, None));
+            }
+            if let Some(result) = result {
+                let text = match (&result.r#type, result.value.as_str()) {
+                    (ToolResultValueType::Markdown, Some(text)) => text.to_string(),
+                    _ => serde_json::to_string_pretty(&result.value).unwrap_or_default(),
+                };
+                out.push_str(&html_pre(&text, None));
+            }
+        }
+        ActionType::TaskCreate { description } => {
+            let _ = write!(out, "<p>Created task: {}</p>", escape_html(description));
+        }
+        ActionType::PlanPresentation { plan } => {
+            let _ = write!(
+                out,
+                "<div class=\"text\">{}</div>",
+                escape_html(plan.trim())
+            );
+        }
+        ActionType::TodoManagement { todos, .. } => {
+            out.push_str("<ul>");
+            for todo in todos {
+                let mark = if todo.status == "completed" {
+                    "&#9745;"
+                } else {
+                    "&#9744;"
+                };
+                let _ = write!(out, "<li>{mark} {}</li>", escape_html(&todo.content));
+            }
+            out.push_str("</ul>");
+        }
+        ActionType::Other { description } => {
+            let _ = write!(out, "<p>{}</p>", escape_html(description));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn code_block_fence_outgrows_backticks_in_content() {
+        let block = code_block("text", "uses ``` inside\n");
+        assert!(block.starts_with("````text\n"));
+        assert!(block.ends_with("\n````\n"));
+    }
+
+    #[test]
+    fn diff_lines_are_escaped_and_highlighted() {
+        let html = html_pre("--- a/x\n+++ b/x\n@@ -1 +1 @@\n-<a>\n+<b>", Some("diff"));
+        assert!(html.contains("<span class=\"del\">-&lt;a&gt;</span>"));
+        assert!(html.contains("<span class=\"add\">+&lt;b&gt;</span>"));
+        assert!(html.contains("<span class=\"hunk\">@@ -1 +1 @@</span>"));
+    }
+}
@@ -22,6 +22,7 @@ use serde::{Deserialize, Serialize};
 use sqlx::Error as SqlxError;
 use thiserror::Error;
 use ts_rs::TS;
+use utils::telemetry;
 use uuid::Uuid;
 
 use super::{
@@ -79,6 +80,19 @@ pub struct SetQueueRequest {
     pub expected_version: Option<i64>,
 }
 
+#[derive(Debug, Deserialize, TS)]
+pub struct SteerRequest {
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct SteerResponse {
+    /// True when the message reached the running agent
+    pub delivered: bool,
+    /// The follow-up draft the message was queued on when it could not be delivered
+    pub draft: Option<DraftResponse>,
+}
+
 #[derive(Clone)]
 pub struct DraftsService {
     db: DBService,
@@ -94,6 +108,14 @@ impl DraftsService {
         &self.db.pool
     }
 
+    /// Export the number of queued follow-ups after the queue changed
+    pub async fn record_queue_depth(pool: &sqlx::SqlitePool) {
+        match Draft::count_queued(pool).await {
+            Ok(depth) => telemetry::record_queue_depth(depth),
+            Err(e) => tracing::warn!("Failed to count queued follow-ups: {}", e),
+        }
+    }
+
     fn draft_to_response(d: Draft) -> DraftResponse {
         DraftResponse {
             task_attempt_id: d.task_attempt_id,
@@ -274,6 +296,7 @@ impl DraftsService {
             .await?;
 
         let _ = Draft::clear_after_send(self.pool(), task_attempt.id, DraftType::FollowUp).await;
+        Self::record_queue_depth(self.pool()).await;
 
         Ok(execution_process)
     }
@@ -431,6 +454,7 @@ impl DraftsService {
             payload.expected_version,
         )
         .await?;
+        Self::record_queue_depth(pool).await;
 
         let draft =
             Draft::find_by_task_attempt_and_type(pool, task_attempt.id, DraftType::FollowUp)
@@ -472,6 +496,78 @@ impl DraftsService {
         Ok(Self::draft_to_response(draft))
     }
 
+    /// Deliver a message to the attempt's running coding agent, or queue it as a follow-up
+    /// when the agent can't take input mid-run.
+    pub async fn steer_or_queue(
+        &self,
+        container: &(dyn ContainerService + Send + Sync),
+        task_attempt: &TaskAttempt,
+        payload: &SteerRequest,
+    ) -> Result<SteerResponse, DraftsServiceError> {
+        let pool = self.pool();
+        let message = payload.message.trim();
+
+        let running_agent = ExecutionProcess::find_by_task_attempt_id(pool, task_attempt.id, false)
+            .await?
+            .into_iter()
+            .find(|p| {
+                matches!(p.status, ExecutionProcessStatus::Running)
+                    && matches!(p.run_reason, ExecutionProcessRunReason::CodingAgent)
+            });
+
+        if let Some(process) = &running_agent
+            && container.steer_execution(process, message).await?
+        {
+            return Ok(SteerResponse {
+                delivered: true,
+                draft: None,
+            });
+        }
+
+        // Fall back to the follow-up queue, appending to anything already drafted
+        let d = self.ensure_follow_up_draft_row(task_attempt.id).await?;
+        let prompt = if d.prompt.trim().is_empty() {
+            message.to_string()
+        } else {
+            format!("{}\n\n{}", d.prompt.trim_end(), message)
+        };
+        Draft::update_partial(
+            pool,
+            task_attempt.id,
+            DraftType::FollowUp,
+            Some(prompt),
+            None,
+            None,
+            None,
+        )
+        .await?;
+        Draft::set_queued(pool, task_attempt.id, DraftType::FollowUp, true, None, None).await?;
+        Self::record_queue_depth(pool).await;
+
+        if !self
+            .has_running_processes_for_attempt(task_attempt.id)
+            .await?
+            && Draft::try_mark_sending(pool, task_attempt.id, DraftType::FollowUp)
+                .await
+                .unwrap_or(false)
+            && let Some(draft) =
+                Draft::find_by_task_attempt_and_type(pool, task_attempt.id, DraftType::FollowUp)
+                    .await?
+        {
+            let _ = self
+                .start_follow_up_from_draft(container, task_attempt, &draft)
+                .await;
+        }
+
+        let draft = self
+            .fetch_draft_response(task_attempt.id, DraftType::FollowUp)
+            .await?;
+        Ok(SteerResponse {
+            delivered: false,
+            draft: Some(draft),
+        })
+    }
+
     pub async fn get_draft(
         &self,
         task_attempt_id: Uuid,
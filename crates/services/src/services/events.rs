@@ -3,9 +3,10 @@ use std::{str::FromStr, sync::Arc};
 use db::{
     DBService,
     models::{
+        audit_event::AuditEvent,
         draft::{Draft, DraftType},
         execution_process::ExecutionProcess,
-        task::Task,
+        task::{Task, TaskFilter},
         task_attempt::TaskAttempt,
     },
 };
@@ -15,6 +16,8 @@ use tokio::sync::RwLock;
 use utils::msg_store::MsgStore;
 use uuid::Uuid;
 
+#[path = "events/batch.rs"]
+mod batch;
 #[path = "events/patches.rs"]
 pub mod patches;
 #[path = "events/streams.rs"]
@@ -22,8 +25,14 @@ mod streams;
 #[path = "events/types.rs"]
 pub mod types;
 
-pub use patches::{draft_patch, execution_process_patch, task_attempt_patch, task_patch};
-pub use types::{EventError, EventPatch, EventPatchInner, HookTables, RecordTypes};
+pub use batch::{TaskBatch, TaskBatches};
+pub use patches::{
+    activity_patch, draft_patch, execution_process_patch, task_attempt_patch, task_patch,
+};
+pub use streams::EventStream;
+pub use types::{
+    EventError, EventPatch, EventPatchInner, EventStreamMsg, HookTables, RecordTypes, SequencedMsg,
+};
 
 #[derive(Clone)]
 pub struct EventService {
@@ -31,25 +40,46 @@ pub struct EventService {
     db: DBService,
     #[allow(dead_code)]
     entry_count: Arc<RwLock<usize>>,
+    /// Sequence numbers are this plus the message's position in `msg_store`. Starting from the
+    /// startup time in microseconds keeps them increasing across restarts, so a client resuming
+    /// with a number from a previous run gets a resync.
+    seq_base: u64,
+    task_batches: TaskBatches,
 }
 
 impl EventService {
     /// Creates a new EventService that will work with a DBService configured with hooks
-    pub fn new(db: DBService, msg_store: Arc<MsgStore>, entry_count: Arc<RwLock<usize>>) -> Self {
+    pub fn new(
+        db: DBService,
+        msg_store: Arc<MsgStore>,
+        entry_count: Arc<RwLock<usize>>,
+        task_batches: TaskBatches,
+    ) -> Self {
         Self {
             msg_store,
             db,
             entry_count,
+            seq_base: chrono::Utc::now().timestamp_micros().max(0) as u64,
+            task_batches,
         }
     }
 
     async fn push_task_update_for_task(
         pool: &SqlitePool,
         msg_store: Arc<MsgStore>,
+        task_batches: &TaskBatches,
         task_id: Uuid,
     ) -> Result<(), SqlxError> {
+        if task_batches.is_held(task_id) {
+            return Ok(());
+        }
         if let Some(task) = Task::find_by_id(pool, task_id).await? {
-            let tasks = Task::find_by_project_id_with_attempt_status(pool, task.project_id).await?;
+            let tasks = Task::find_by_project_id_with_attempt_status(
+                pool,
+                task.project_id,
+                &TaskFilter::default(),
+            )
+            .await?;
 
             if let Some(task_with_status) = tasks
                 .into_iter()
@@ -65,10 +95,11 @@ impl EventService {
     async fn push_task_update_for_attempt(
         pool: &SqlitePool,
         msg_store: Arc<MsgStore>,
+        task_batches: &TaskBatches,
         attempt_id: Uuid,
     ) -> Result<(), SqlxError> {
         if let Some(attempt) = TaskAttempt::find_by_id(pool, attempt_id).await? {
-            Self::push_task_update_for_task(pool, msg_store, attempt.task_id).await?;
+            Self::push_task_update_for_task(pool, msg_store, task_batches, attempt.task_id).await?;
         }
 
         Ok(())
@@ -78,6 +109,7 @@ impl EventService {
     pub fn create_hook(
         msg_store: Arc<MsgStore>,
         entry_count: Arc<RwLock<usize>>,
+        task_batches: TaskBatches,
         db_service: DBService,
     ) -> impl for<'a> Fn(
         &'a mut sqlx::sqlite::SqliteConnection,
@@ -90,11 +122,13 @@ impl EventService {
             let msg_store_for_hook = msg_store.clone();
             let entry_count_for_hook = entry_count.clone();
             let db_for_hook = db_service.clone();
+            let task_batches_for_hook = task_batches.clone();
             Box::pin(async move {
                 let mut handle = conn.lock_handle().await?;
                 let runtime_handle = tokio::runtime::Handle::current();
                 handle.set_preupdate_hook({
                     let msg_store_for_preupdate = msg_store_for_hook.clone();
+                    let task_batches_for_preupdate = task_batches_for_hook.clone();
                     move |preupdate: sqlx::sqlite::PreupdateHookResult<'_>| {
                         if preupdate.operation != SqliteOperation::Delete {
                             return;
@@ -104,6 +138,7 @@ impl EventService {
                             "tasks" => {
                                 if let Ok(value) = preupdate.get_old_column_value(0)
                                     && let Ok(task_id) = <Uuid as Decode<Sqlite>>::decode(value)
+                                    && !task_batches_for_preupdate.is_held(task_id)
                                 {
                                     let patch = task_patch::remove(task_id);
                                     msg_store_for_preupdate.push_patch(patch);
@@ -160,10 +195,31 @@ impl EventService {
                     let entry_count_for_hook = entry_count_for_hook.clone();
                     let msg_store_for_hook = msg_store_for_hook.clone();
                     let db = db_for_hook.clone();
+                    let task_batches = task_batches_for_hook.clone();
 
                     if let Ok(table) = HookTables::from_str(hook.table) {
                         let rowid = hook.rowid;
+                        // A bulk operation finishing waits for this, see `TaskBatch::finish`
+                        let running = task_batches.hook_started();
                         runtime_handle.spawn(async move {
+                            let _running = running;
+                            // Audit events are append-only and feed the activity stream directly
+                            if matches!(table, HookTables::AuditEvents) {
+                                if matches!(hook.operation, SqliteOperation::Insert) {
+                                    match AuditEvent::find_by_rowid(&db.pool, rowid).await {
+                                        Ok(Some(event)) => {
+                                            msg_store_for_hook
+                                                .push_patch(activity_patch::add(&event));
+                                        }
+                                        Ok(None) => {}
+                                        Err(e) => {
+                                            tracing::error!("Failed to fetch audit event: {:?}", e);
+                                        }
+                                    }
+                                }
+                                return;
+                            }
+
                             let record_type: RecordTypes = match (table, hook.operation.clone()) {
                                 (HookTables::Tasks, SqliteOperation::Delete)
                                 | (HookTables::TaskAttempts, SqliteOperation::Delete)
@@ -172,6 +228,7 @@ impl EventService {
                                     // Deletions handled in preupdate hook for reliable data capture
                                     return;
                                 }
+                                (HookTables::AuditEvents, _) => return,
                                 (HookTables::Tasks, _) => {
                                     match Task::find_by_rowid(&db.pool, rowid).await {
                                         Ok(Some(task)) => RecordTypes::Task(task),
@@ -238,6 +295,18 @@ impl EventService {
                                 }
                             };
 
+                            // Tasks in a bulk operation get a single patch once it finishes
+                            let task_id = match &record_type {
+                                RecordTypes::Task(task) => Some(task.id),
+                                RecordTypes::DeletedTask { task_id, .. } => *task_id,
+                                RecordTypes::TaskAttempt(attempt) => Some(attempt.task_id),
+                                RecordTypes::DeletedTaskAttempt { task_id, .. } => *task_id,
+                                _ => None,
+                            };
+                            if task_id.is_some_and(|task_id| task_batches.is_held(task_id)) {
+                                return;
+                            }
+
                             let db_op: &str = match hook.operation {
                                 SqliteOperation::Insert => "insert",
                                 SqliteOperation::Delete => "delete",
@@ -253,6 +322,7 @@ impl EventService {
                                         Task::find_by_project_id_with_attempt_status(
                                             &db.pool,
                                             task.project_id,
+                                            &TaskFilter::default(),
                                         )
                                         .await
                                         && let Some(task_with_status) =
@@ -306,6 +376,7 @@ impl EventService {
                                             Task::find_by_project_id_with_attempt_status(
                                                 &db.pool,
                                                 task.project_id,
+                                                &TaskFilter::default(),
                                             )
                                             .await
                                         && let Some(task_with_status) =
@@ -327,6 +398,7 @@ impl EventService {
                                             Task::find_by_project_id_with_attempt_status(
                                                 &db.pool,
                                                 task.project_id,
+                                                &TaskFilter::default(),
                                             )
                                             .await
                                         && let Some(task_with_status) =
@@ -352,6 +424,7 @@ impl EventService {
                                     if let Err(err) = EventService::push_task_update_for_attempt(
                                         &db.pool,
                                         msg_store_for_hook.clone(),
+                                        &task_batches,
                                         process.task_attempt_id,
                                     )
                                     .await
@@ -377,6 +450,7 @@ impl EventService {
                                             EventService::push_task_update_for_attempt(
                                                 &db.pool,
                                                 msg_store_for_hook.clone(),
+                                                &task_batches,
                                                 *task_attempt_id,
                                             )
                                             .await
@@ -0,0 +1,379 @@
+use std::{
+    collections::{BTreeSet, HashMap, hash_map},
+    sync::{Arc, Mutex},
+};
+
+use dashmap::{DashMap, mapref::entry::Entry};
+use db::models::task::{Task, TaskFilter, TaskWithAttemptStatus};
+use json_patch::Patch;
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+use super::{EventService, patches::task_patch};
+
+/// Tasks whose patches are held back while a bulk operation runs on them, counted so batches
+/// may overlap
+#[derive(Clone, Default)]
+pub struct TaskBatches {
+    held: Arc<DashMap<Uuid, usize>>,
+    hooks: Arc<RunningHooks>,
+}
+
+/// Database hook tasks that are still running, numbered in the order they started
+#[derive(Default)]
+struct RunningHooks {
+    state: Mutex<RunningHooksState>,
+    finished: Notify,
+}
+
+#[derive(Default)]
+struct RunningHooksState {
+    next: u64,
+    running: BTreeSet<u64>,
+}
+
+/// Held by a database hook task while it runs
+pub struct HookGuard {
+    hooks: Arc<RunningHooks>,
+    id: u64,
+}
+
+impl Drop for HookGuard {
+    fn drop(&mut self) {
+        self.hooks.state.lock().unwrap().running.remove(&self.id);
+        self.hooks.finished.notify_waiters();
+    }
+}
+
+impl TaskBatches {
+    pub fn is_held(&self, task_id: Uuid) -> bool {
+        self.held.contains_key(&task_id)
+    }
+
+    /// Called by the database hook when a write spawns a task to push its patch. A batch that
+    /// finishes waits for the tasks of its writes, so they still see the batch's tasks as held.
+    pub fn hook_started(&self) -> HookGuard {
+        let mut state = self.hooks.state.lock().unwrap();
+        let id = state.next;
+        state.next += 1;
+        state.running.insert(id);
+        HookGuard {
+            hooks: self.hooks.clone(),
+            id,
+        }
+    }
+
+    /// Wait until the hook tasks that started before this was called have finished
+    async fn hooks_settled(&self) {
+        let started_before = self.hooks.state.lock().unwrap().next;
+        loop {
+            let finished = self.hooks.finished.notified();
+            tokio::pin!(finished);
+            finished.as_mut().enable();
+            let oldest = self.hooks.state.lock().unwrap().running.first().copied();
+            if oldest.is_none_or(|id| id >= started_before) {
+                return;
+            }
+            finished.await;
+        }
+    }
+
+    fn hold(&self, task_ids: impl Iterator<Item = Uuid>) {
+        for task_id in task_ids {
+            *self.held.entry(task_id).or_insert(0) += 1;
+        }
+    }
+
+    fn release(&self, task_ids: impl Iterator<Item = Uuid>) {
+        for task_id in task_ids {
+            if let Entry::Occupied(mut entry) = self.held.entry(task_id) {
+                *entry.get_mut() -= 1;
+                if *entry.get() == 0 {
+                    entry.remove();
+                }
+            }
+        }
+    }
+}
+
+/// The tasks of a bulk operation. Their changes reach task streams as a single patch, pushed
+/// by [`TaskBatch::finish`] once the operation's writes are committed. A batch that is dropped
+/// without finishing (e.g. on an error) still releases its tasks.
+pub struct TaskBatch {
+    events: EventService,
+    /// Each task's project when the batch began, to spot tasks that moved
+    projects: HashMap<Uuid, Uuid>,
+}
+
+impl EventService {
+    pub fn begin_task_batch<'a>(&self, tasks: impl IntoIterator<Item = &'a Task>) -> TaskBatch {
+        let projects: HashMap<Uuid, Uuid> = tasks
+            .into_iter()
+            .map(|task| (task.id, task.project_id))
+            .collect();
+        self.task_batches.hold(projects.keys().copied());
+        TaskBatch {
+            events: self.clone(),
+            projects,
+        }
+    }
+}
+
+impl TaskBatch {
+    /// Release the tasks and push their current state
+    pub async fn finish(mut self) {
+        let projects = std::mem::take(&mut self.projects);
+        finish_batch(&self.events, &projects).await;
+    }
+}
+
+impl Drop for TaskBatch {
+    fn drop(&mut self) {
+        if self.projects.is_empty() {
+            return;
+        }
+        let events = self.events.clone();
+        let projects = std::mem::take(&mut self.projects);
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move { finish_batch(&events, &projects).await });
+            }
+            // Nothing can be pushed without a runtime, but the tasks mustn't stay held
+            Err(_) => events.task_batches.release(projects.keys().copied()),
+        }
+    }
+}
+
+async fn finish_batch(events: &EventService, projects: &HashMap<Uuid, Uuid>) {
+    events.task_batches.hooks_settled().await;
+    events.task_batches.release(projects.keys().copied());
+    match batch_patch(events, projects).await {
+        Ok(patch) if !patch.0.is_empty() => events.msg_store.push_patch(patch),
+        Ok(_) => {}
+        Err(e) => tracing::error!("Failed to push task batch patch: {}", e),
+    }
+}
+
+/// The current state of the tasks. Deleted tasks are removed, and moved tasks are removed before
+/// being added under their new project.
+async fn batch_patch(
+    events: &EventService,
+    projects: &HashMap<Uuid, Uuid>,
+) -> Result<Patch, sqlx::Error> {
+    let pool = &events.db.pool;
+    let mut project_tasks: HashMap<Uuid, Vec<TaskWithAttemptStatus>> = HashMap::new();
+    let mut ops = Vec::new();
+
+    for (&task_id, &previous_project_id) in projects {
+        let Some(task) = Task::find_by_id(pool, task_id).await? else {
+            ops.extend(task_patch::remove(task_id).0);
+            continue;
+        };
+        if task.project_id != previous_project_id {
+            ops.extend(task_patch::remove(task_id).0);
+        }
+        let tasks = match project_tasks.entry(task.project_id) {
+            hash_map::Entry::Occupied(entry) => entry.into_mut(),
+            hash_map::Entry::Vacant(entry) => entry.insert(
+                Task::find_by_project_id_with_attempt_status(
+                    pool,
+                    task.project_id,
+                    &TaskFilter::default(),
+                )
+                .await?,
+            ),
+        };
+        if let Some(task_with_status) = tasks
+            .iter()
+            .find(|task_with_status| task_with_status.id == task_id)
+        {
+            // Add replaces an existing task and creates a moved one
+            ops.extend(task_patch::add(task_with_status).0);
+        }
+    }
+
+    Ok(Patch(ops))
+}
+
+#[cfg(test)]
+mod tests {
+    use db::{
+        DBService,
+        models::{
+            project::{CreateProject, Project},
+            task::CreateTask,
+        },
+    };
+    use serde_json::Value;
+    use tokio::sync::RwLock;
+    use utils::{log_msg::LogMsg, msg_store::MsgStore};
+
+    use super::*;
+
+    async fn events() -> EventService {
+        let db = DBService::new_in_memory().await.unwrap();
+        EventService::new(
+            db,
+            Arc::new(MsgStore::new()),
+            Arc::new(RwLock::new(0)),
+            TaskBatches::default(),
+        )
+    }
+
+    async fn project(events: &EventService) -> Uuid {
+        let project_id = Uuid::new_v4();
+        Project::create(
+            &events.db.pool,
+            &CreateProject::from_name_repo_path("p".to_string(), project_id.to_string()),
+            project_id,
+        )
+        .await
+        .unwrap();
+        project_id
+    }
+
+    async fn task(events: &EventService, project_id: Uuid, title: &str) -> Task {
+        let data = CreateTask::from_title_description(project_id, title.to_string(), None);
+        Task::create(&events.db.pool, &data, Uuid::new_v4())
+            .await
+            .unwrap()
+    }
+
+    /// The ops of a patch as JSON, e.g. `{"op": "remove", "path": ...}`
+    fn ops(patch: &Patch) -> Vec<Value> {
+        serde_json::from_value(serde_json::to_value(patch).unwrap()).unwrap()
+    }
+
+    fn patches(events: &EventService) -> Vec<Patch> {
+        events
+            .msg_store
+            .get_history()
+            .into_iter()
+            .filter_map(|msg| match msg {
+                LogMsg::JsonPatch(patch) => Some(patch),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn tasks_stay_held_until_every_batch_holding_them_is_released() {
+        let batches = TaskBatches::default();
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        batches.hold([a, b].into_iter());
+        batches.hold([b].into_iter());
+
+        batches.release([a, b].into_iter());
+        assert!(!batches.is_held(a));
+        assert!(batches.is_held(b));
+
+        batches.release([b].into_iter());
+        assert!(!batches.is_held(b));
+    }
+
+    #[tokio::test]
+    async fn a_batch_pushes_one_patch_when_it_ends() {
+        let events = events().await;
+        let project_id = project(&events).await;
+        let first = task(&events, project_id, "first").await;
+        let second = task(&events, project_id, "second").await;
+
+        let batch = events.begin_task_batch([&first, &second]);
+        assert!(events.task_batches.is_held(first.id));
+        assert!(events.task_batches.is_held(second.id));
+        assert!(patches(&events).is_empty());
+        batch.finish().await;
+
+        assert!(!events.task_batches.is_held(first.id));
+        assert!(!events.task_batches.is_held(second.id));
+        let patches = patches(&events);
+        assert_eq!(patches.len(), 1);
+        let ops = ops(&patches[0]);
+        assert_eq!(ops.len(), 2);
+        assert!(ops.iter().all(|op| op["op"] == "add"));
+    }
+
+    #[tokio::test]
+    async fn moved_tasks_are_removed_then_added_and_deleted_ones_removed() {
+        let events = events().await;
+        let project_id = project(&events).await;
+        let other_project_id = project(&events).await;
+        let moved = task(&events, project_id, "moved").await;
+        let deleted = task(&events, project_id, "deleted").await;
+        Task::set_project(&events.db.pool, moved.id, other_project_id)
+            .await
+            .unwrap();
+        Task::delete(&events.db.pool, deleted.id).await.unwrap();
+
+        let moved_patch = batch_patch(&events, &HashMap::from([(moved.id, project_id)]))
+            .await
+            .unwrap();
+        let moved_ops = ops(&moved_patch);
+        assert_eq!(moved_ops.len(), 2);
+        assert_eq!(moved_ops[0], ops(&task_patch::remove(moved.id))[0]);
+        assert_eq!(moved_ops[1]["op"], "add");
+        assert_eq!(
+            moved_ops[1]["value"]["project_id"],
+            other_project_id.to_string()
+        );
+
+        let deleted_patch = batch_patch(&events, &HashMap::from([(deleted.id, project_id)]))
+            .await
+            .unwrap();
+        assert_eq!(ops(&deleted_patch), ops(&task_patch::remove(deleted.id)));
+    }
+
+    #[tokio::test]
+    async fn overlapping_batches_keep_tasks_held_until_the_last_one_ends() {
+        let events = events().await;
+        let project_id = project(&events).await;
+        let shared = task(&events, project_id, "shared").await;
+        let only_first = task(&events, project_id, "only first").await;
+
+        let first = events.begin_task_batch([&shared, &only_first]);
+        let second = events.begin_task_batch([&shared]);
+        first.finish().await;
+        assert!(!events.task_batches.is_held(only_first.id));
+        assert!(events.task_batches.is_held(shared.id));
+        assert_eq!(patches(&events).len(), 1);
+
+        second.finish().await;
+        assert!(!events.task_batches.is_held(shared.id));
+        assert_eq!(patches(&events).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn finishing_waits_for_the_hooks_of_earlier_writes_only() {
+        let events = events().await;
+        let project_id = project(&events).await;
+        let task = task(&events, project_id, "task").await;
+
+        let batch = events.begin_task_batch([&task]);
+        let earlier_write = events.task_batches.hook_started();
+        let mut finish = Box::pin(batch.finish());
+        assert!(futures::poll!(&mut finish).is_pending());
+
+        // A write after the batch finished doesn't keep it waiting
+        let later_write = events.task_batches.hook_started();
+        drop(earlier_write);
+        finish.await;
+        assert!(!events.task_batches.is_held(task.id));
+        assert_eq!(patches(&events).len(), 1);
+        drop(later_write);
+    }
+
+    #[test]
+    fn a_batch_dropped_outside_a_runtime_releases_its_tasks() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let (events, task_id, batch) = runtime.block_on(async {
+            let events = events().await;
+            let project_id = project(&events).await;
+            let task = task(&events, project_id, "task").await;
+            let batch = events.begin_task_batch([&task]);
+            (events, task.id, batch)
+        });
+
+        drop(batch);
+        assert!(!events.task_batches.is_held(task_id));
+    }
+}
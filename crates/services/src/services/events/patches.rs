@@ -1,4 +1,5 @@
 use db::models::{
+    audit_event::AuditEvent,
     draft::{Draft, DraftType},
     execution_process::ExecutionProcess,
     task::TaskWithAttemptStatus,
@@ -202,3 +203,25 @@ pub mod task_attempt_patch {
         })])
     }
 }
+
+/// Helper functions for creating activity feed patches
+pub mod activity_patch {
+    use super::*;
+
+    fn activity_path(event_id: Uuid) -> String {
+        format!(
+            "/activity/{}",
+            escape_pointer_segment(&event_id.to_string())
+        )
+    }
+
+    /// Create patch for adding a new audit event to the feed
+    pub fn add(event: &AuditEvent) -> Patch {
+        Patch(vec![PatchOperation::Add(AddOperation {
+            path: activity_path(event.id)
+                .try_into()
+                .expect("Activity path should be valid"),
+            value: serde_json::to_value(event).expect("Audit event serialization should not fail"),
+        })])
+    }
+}
@@ -1,374 +1,507 @@
 use db::models::{
+    audit_event::{AuditEvent, AuditQuery},
     draft::{Draft, DraftType},
     execution_process::ExecutionProcess,
-    task::{Task, TaskWithAttemptStatus},
+    task::{Task, TaskFilter, TaskWithAttemptStatus},
 };
-use futures::StreamExt;
+use futures::{StreamExt, future, stream::BoxStream};
 use serde_json::json;
-use tokio_stream::wrappers::BroadcastStream;
-use utils::log_msg::LogMsg;
+use utils::log_msg::{LogMsg, TimedLogMsg};
 use uuid::Uuid;
 
 use super::{
     EventService,
     patches::execution_process_patch,
-    types::{EventError, EventPatch, RecordTypes},
+    types::{EventError, EventPatch, EventStreamMsg, RecordTypes, SequencedMsg},
 };
 
+/// Number of recent audit events sent as the activity feed's snapshot
+const ACTIVITY_SNAPSHOT_LEN: i64 = 100;
+
+/// Messages of an event stream with their sequence numbers
+pub type EventStream = BoxStream<'static, Result<EventStreamMsg, std::io::Error>>;
+
+/// Whether a task patch's value is a task of `project_id`
+fn task_in_project(value: &serde_json::Value, project_id: Uuid) -> bool {
+    serde_json::from_value::<TaskWithAttemptStatus>(value.clone())
+        .is_ok_and(|task| task.project_id == project_id)
+}
+
 impl EventService {
-    /// Stream raw task messages for a specific project with initial snapshot
+    /// Stream raw task messages for a specific project with initial snapshot, or resumed after
+    /// the sequence number `after`
     pub async fn stream_tasks_raw(
         &self,
         project_id: Uuid,
-    ) -> Result<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>, EventError>
-    {
-        // Get initial snapshot of tasks
-        let tasks = Task::find_by_project_id_with_attempt_status(&self.db.pool, project_id).await?;
+        after: Option<u64>,
+    ) -> Result<EventStream, EventError> {
+        let pool = self.db.pool.clone();
+        let snapshot = async move {
+            // Get initial snapshot of tasks
+            let tasks = Task::find_by_project_id_with_attempt_status(
+                &pool,
+                project_id,
+                &TaskFilter::default(),
+            )
+            .await?;
 
-        // Convert task array to object keyed by task ID
-        let tasks_map: serde_json::Map<String, serde_json::Value> = tasks
-            .into_iter()
-            .map(|task| (task.id.to_string(), serde_json::to_value(task).unwrap()))
-            .collect();
+            // Convert task array to object keyed by task ID
+            let tasks_map: serde_json::Map<String, serde_json::Value> = tasks
+                .into_iter()
+                .map(|task| (task.id.to_string(), serde_json::to_value(task).unwrap()))
+                .collect();
 
-        let initial_patch = json!([{
-            "op": "replace",
-            "path": "/tasks",
-            "value": tasks_map
-        }]);
-        let initial_msg = LogMsg::JsonPatch(serde_json::from_value(initial_patch).unwrap());
+            let initial_patch = json!([{
+                "op": "replace",
+                "path": "/tasks",
+                "value": tasks_map
+            }]);
+            Ok::<_, EventError>(LogMsg::JsonPatch(serde_json::from_value(initial_patch)?))
+        };
 
         // Clone necessary data for the async filter
         let db_pool = self.db.pool.clone();
 
-        // Get filtered event stream
-        let filtered_stream =
-            BroadcastStream::new(self.msg_store.get_receiver()).filter_map(move |msg_result| {
-                let db_pool = db_pool.clone();
-                async move {
-                    match msg_result {
-                        Ok(LogMsg::JsonPatch(patch)) => {
-                            // Filter events based on project_id
-                            if let Some(patch_op) = patch.0.first() {
-                                // Check if this is a direct task patch (new format)
-                                if patch_op.path().starts_with("/tasks/") {
-                                    match patch_op {
+        // Filter live events down to this project
+        let filter = move |msg: LogMsg| {
+            let db_pool = db_pool.clone();
+            async move {
+                match msg {
+                    LogMsg::JsonPatch(patch) => {
+                        // Filter events based on project_id
+                        if let Some(patch_op) = patch.0.first() {
+                            // Check if this is a direct task patch (new format). Bulk operations
+                            // send several tasks, possibly of different projects, in one patch.
+                            if patch_op.path().starts_with("/tasks/") {
+                                let ops: Vec<_> = patch
+                                    .0
+                                    .into_iter()
+                                    .filter(|op| match op {
                                         json_patch::PatchOperation::Add(op) => {
-                                            // Parse task data directly from value
-                                            if let Ok(task) =
-                                                serde_json::from_value::<TaskWithAttemptStatus>(
-                                                    op.value.clone(),
-                                                )
-                                                && task.project_id == project_id
-                                            {
-                                                return Some(Ok(LogMsg::JsonPatch(patch)));
-                                            }
+                                            task_in_project(&op.value, project_id)
                                         }
                                         json_patch::PatchOperation::Replace(op) => {
-                                            // Parse task data directly from value
-                                            if let Ok(task) =
-                                                serde_json::from_value::<TaskWithAttemptStatus>(
-                                                    op.value.clone(),
-                                                )
-                                                && task.project_id == project_id
-                                            {
-                                                return Some(Ok(LogMsg::JsonPatch(patch)));
-                                            }
+                                            task_in_project(&op.value, project_id)
                                         }
-                                        json_patch::PatchOperation::Remove(_) => {
-                                            // For remove operations, we need to check project membership differently
-                                            // We could cache this information or let it pass through for now
-                                            // Since we don't have the task data, we'll allow all removals
-                                            // and let the client handle filtering
-                                            return Some(Ok(LogMsg::JsonPatch(patch)));
+                                        // For remove operations, we need to check project membership differently
+                                        // We could cache this information or let it pass through for now
+                                        // Since we don't have the task data, we'll allow all removals
+                                        // and let the client handle filtering
+                                        json_patch::PatchOperation::Remove(_) => true,
+                                        _ => false,
+                                    })
+                                    .collect();
+                                if !ops.is_empty() {
+                                    return Some(LogMsg::JsonPatch(json_patch::Patch(ops)));
+                                }
+                            } else if let Ok(event_patch_value) = serde_json::to_value(patch_op)
+                                && let Ok(event_patch) =
+                                    serde_json::from_value::<EventPatch>(event_patch_value)
+                            {
+                                // Handle old EventPatch format for non-task records
+                                match &event_patch.value.record {
+                                    RecordTypes::Task(task) => {
+                                        if task.project_id == project_id {
+                                            return Some(LogMsg::JsonPatch(patch));
                                         }
-                                        _ => {}
                                     }
-                                } else if let Ok(event_patch_value) = serde_json::to_value(patch_op)
-                                    && let Ok(event_patch) =
-                                        serde_json::from_value::<EventPatch>(event_patch_value)
-                                {
-                                    // Handle old EventPatch format for non-task records
-                                    match &event_patch.value.record {
-                                        RecordTypes::Task(task) => {
-                                            if task.project_id == project_id {
-                                                return Some(Ok(LogMsg::JsonPatch(patch)));
-                                            }
-                                        }
-                                        RecordTypes::DeletedTask {
-                                            project_id: Some(deleted_project_id),
-                                            ..
-                                        } => {
-                                            if *deleted_project_id == project_id {
-                                                return Some(Ok(LogMsg::JsonPatch(patch)));
-                                            }
+                                    RecordTypes::DeletedTask {
+                                        project_id: Some(deleted_project_id),
+                                        ..
+                                    } => {
+                                        if *deleted_project_id == project_id {
+                                            return Some(LogMsg::JsonPatch(patch));
                                         }
-                                        RecordTypes::TaskAttempt(attempt) => {
-                                            // Check if this task_attempt belongs to a task in our project
-                                            if let Ok(Some(task)) =
-                                                Task::find_by_id(&db_pool, attempt.task_id).await
-                                                && task.project_id == project_id
-                                            {
-                                                return Some(Ok(LogMsg::JsonPatch(patch)));
-                                            }
+                                    }
+                                    RecordTypes::TaskAttempt(attempt) => {
+                                        // Check if this task_attempt belongs to a task in our project
+                                        if let Ok(Some(task)) =
+                                            Task::find_by_id(&db_pool, attempt.task_id).await
+                                            && task.project_id == project_id
+                                        {
+                                            return Some(LogMsg::JsonPatch(patch));
                                         }
-                                        RecordTypes::DeletedTaskAttempt {
-                                            task_id: Some(deleted_task_id),
-                                            ..
-                                        } => {
-                                            // Check if deleted attempt belonged to a task in our project
-                                            if let Ok(Some(task)) =
-                                                Task::find_by_id(&db_pool, *deleted_task_id).await
-                                                && task.project_id == project_id
-                                            {
-                                                return Some(Ok(LogMsg::JsonPatch(patch)));
-                                            }
+                                    }
+                                    RecordTypes::DeletedTaskAttempt {
+                                        task_id: Some(deleted_task_id),
+                                        ..
+                                    } => {
+                                        // Check if deleted attempt belonged to a task in our project
+                                        if let Ok(Some(task)) =
+                                            Task::find_by_id(&db_pool, *deleted_task_id).await
+                                            && task.project_id == project_id
+                                        {
+                                            return Some(LogMsg::JsonPatch(patch));
                                         }
-                                        _ => {}
                                     }
+                                    _ => {}
                                 }
                             }
-                            None
                         }
-                        Ok(other) => Some(Ok(other)), // Pass through non-patch messages
-                        Err(_) => None,               // Filter out broadcast errors
+                        None
                     }
+                    other => Some(other), // Pass through non-patch messages
                 }
-            });
-
-        // Start with initial snapshot, then live updates
-        let initial_stream = futures::stream::once(async move { Ok(initial_msg) });
-        let combined_stream = initial_stream.chain(filtered_stream).boxed();
+            }
+        };
 
-        Ok(combined_stream)
+        self.resumable_stream(after, snapshot, filter).await
     }
 
-    /// Stream execution processes for a specific task attempt with initial snapshot (raw LogMsg format for WebSocket)
+    /// Stream execution processes for a specific task attempt with initial snapshot (raw LogMsg format for WebSocket),
+    /// or resumed after the sequence number `after`
     pub async fn stream_execution_processes_for_attempt_raw(
         &self,
         task_attempt_id: Uuid,
         show_soft_deleted: bool,
-    ) -> Result<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>, EventError>
-    {
-        // Get initial snapshot of execution processes (filtering at SQL level)
-        let processes = ExecutionProcess::find_by_task_attempt_id(
-            &self.db.pool,
-            task_attempt_id,
-            show_soft_deleted,
-        )
-        .await?;
+        after: Option<u64>,
+    ) -> Result<EventStream, EventError> {
+        let pool = self.db.pool.clone();
+        let snapshot = async move {
+            // Get initial snapshot of execution processes (filtering at SQL level)
+            let processes = ExecutionProcess::find_by_task_attempt_id(
+                &pool,
+                task_attempt_id,
+                show_soft_deleted,
+            )
+            .await?;
 
-        // Convert processes array to object keyed by process ID
-        let processes_map: serde_json::Map<String, serde_json::Value> = processes
-            .into_iter()
-            .map(|process| {
-                (
-                    process.id.to_string(),
-                    serde_json::to_value(process).unwrap(),
-                )
-            })
-            .collect();
+            // Convert processes array to object keyed by process ID
+            let processes_map: serde_json::Map<String, serde_json::Value> = processes
+                .into_iter()
+                .map(|process| {
+                    (
+                        process.id.to_string(),
+                        serde_json::to_value(process).unwrap(),
+                    )
+                })
+                .collect();
 
-        let initial_patch = json!([{
-            "op": "replace",
-            "path": "/execution_processes",
-            "value": processes_map
-        }]);
-        let initial_msg = LogMsg::JsonPatch(serde_json::from_value(initial_patch).unwrap());
+            let initial_patch = json!([{
+                "op": "replace",
+                "path": "/execution_processes",
+                "value": processes_map
+            }]);
+            Ok::<_, EventError>(LogMsg::JsonPatch(serde_json::from_value(initial_patch)?))
+        };
 
-        // Get filtered event stream
-        let filtered_stream = BroadcastStream::new(self.msg_store.get_receiver()).filter_map(
-            move |msg_result| async move {
-                match msg_result {
-                    Ok(LogMsg::JsonPatch(patch)) => {
-                        // Filter events based on task_attempt_id
-                        if let Some(patch_op) = patch.0.first() {
-                            // Check if this is a modern execution process patch
-                            if patch_op.path().starts_with("/execution_processes/") {
-                                match patch_op {
-                                    json_patch::PatchOperation::Add(op) => {
-                                        // Parse execution process data directly from value
-                                        if let Ok(process) =
-                                            serde_json::from_value::<ExecutionProcess>(
-                                                op.value.clone(),
-                                            )
-                                            && process.task_attempt_id == task_attempt_id
-                                        {
-                                            if !show_soft_deleted && process.dropped {
-                                                let remove_patch =
-                                                    execution_process_patch::remove(process.id);
-                                                return Some(Ok(LogMsg::JsonPatch(remove_patch)));
-                                            }
-                                            return Some(Ok(LogMsg::JsonPatch(patch)));
+        // Filter live events down to this attempt
+        let filter = move |msg: LogMsg| async move {
+            match msg {
+                LogMsg::JsonPatch(patch) => {
+                    // Filter events based on task_attempt_id
+                    if let Some(patch_op) = patch.0.first() {
+                        // Check if this is a modern execution process patch
+                        if patch_op.path().starts_with("/execution_processes/") {
+                            match patch_op {
+                                json_patch::PatchOperation::Add(op) => {
+                                    // Parse execution process data directly from value
+                                    if let Ok(process) =
+                                        serde_json::from_value::<ExecutionProcess>(op.value.clone())
+                                        && process.task_attempt_id == task_attempt_id
+                                    {
+                                        if !show_soft_deleted && process.dropped {
+                                            let remove_patch =
+                                                execution_process_patch::remove(process.id);
+                                            return Some(LogMsg::JsonPatch(remove_patch));
                                         }
+                                        return Some(LogMsg::JsonPatch(patch));
                                     }
-                                    json_patch::PatchOperation::Replace(op) => {
-                                        // Parse execution process data directly from value
-                                        if let Ok(process) =
-                                            serde_json::from_value::<ExecutionProcess>(
-                                                op.value.clone(),
-                                            )
-                                            && process.task_attempt_id == task_attempt_id
-                                        {
-                                            if !show_soft_deleted && process.dropped {
-                                                let remove_patch =
-                                                    execution_process_patch::remove(process.id);
-                                                return Some(Ok(LogMsg::JsonPatch(remove_patch)));
-                                            }
-                                            return Some(Ok(LogMsg::JsonPatch(patch)));
+                                }
+                                json_patch::PatchOperation::Replace(op) => {
+                                    // Parse execution process data directly from value
+                                    if let Ok(process) =
+                                        serde_json::from_value::<ExecutionProcess>(op.value.clone())
+                                        && process.task_attempt_id == task_attempt_id
+                                    {
+                                        if !show_soft_deleted && process.dropped {
+                                            let remove_patch =
+                                                execution_process_patch::remove(process.id);
+                                            return Some(LogMsg::JsonPatch(remove_patch));
                                         }
+                                        return Some(LogMsg::JsonPatch(patch));
                                     }
-                                    json_patch::PatchOperation::Remove(_) => {
-                                        // For remove operations, we can't verify task_attempt_id
-                                        // so we allow all removals and let the client handle filtering
-                                        return Some(Ok(LogMsg::JsonPatch(patch)));
-                                    }
-                                    _ => {}
                                 }
+                                json_patch::PatchOperation::Remove(_) => {
+                                    // For remove operations, we can't verify task_attempt_id
+                                    // so we allow all removals and let the client handle filtering
+                                    return Some(LogMsg::JsonPatch(patch));
+                                }
+                                _ => {}
                             }
-                            // Fallback to legacy EventPatch format for backward compatibility
-                            else if let Ok(event_patch_value) = serde_json::to_value(patch_op)
-                                && let Ok(event_patch) =
-                                    serde_json::from_value::<EventPatch>(event_patch_value)
-                            {
-                                match &event_patch.value.record {
-                                    RecordTypes::ExecutionProcess(process) => {
-                                        if process.task_attempt_id == task_attempt_id {
-                                            if !show_soft_deleted && process.dropped {
-                                                let remove_patch =
-                                                    execution_process_patch::remove(process.id);
-                                                return Some(Ok(LogMsg::JsonPatch(remove_patch)));
-                                            }
-                                            return Some(Ok(LogMsg::JsonPatch(patch)));
+                        }
+                        // Fallback to legacy EventPatch format for backward compatibility
+                        else if let Ok(event_patch_value) = serde_json::to_value(patch_op)
+                            && let Ok(event_patch) =
+                                serde_json::from_value::<EventPatch>(event_patch_value)
+                        {
+                            match &event_patch.value.record {
+                                RecordTypes::ExecutionProcess(process) => {
+                                    if process.task_attempt_id == task_attempt_id {
+                                        if !show_soft_deleted && process.dropped {
+                                            let remove_patch =
+                                                execution_process_patch::remove(process.id);
+                                            return Some(LogMsg::JsonPatch(remove_patch));
                                         }
+                                        return Some(LogMsg::JsonPatch(patch));
                                     }
-                                    RecordTypes::DeletedExecutionProcess {
-                                        task_attempt_id: Some(deleted_attempt_id),
-                                        ..
-                                    } => {
-                                        if *deleted_attempt_id == task_attempt_id {
-                                            return Some(Ok(LogMsg::JsonPatch(patch)));
-                                        }
+                                }
+                                RecordTypes::DeletedExecutionProcess {
+                                    task_attempt_id: Some(deleted_attempt_id),
+                                    ..
+                                } => {
+                                    if *deleted_attempt_id == task_attempt_id {
+                                        return Some(LogMsg::JsonPatch(patch));
                                     }
-                                    _ => {}
                                 }
+                                _ => {}
                             }
                         }
-                        None
                     }
-                    Ok(other) => Some(Ok(other)), // Pass through non-patch messages
-                    Err(_) => None,               // Filter out broadcast errors
+                    None
                 }
-            },
-        );
-
-        // Start with initial snapshot, then live updates
-        let initial_stream = futures::stream::once(async move { Ok(initial_msg) });
-        let combined_stream = initial_stream.chain(filtered_stream).boxed();
+                other => Some(other), // Pass through non-patch messages
+            }
+        };
 
-        Ok(combined_stream)
+        self.resumable_stream(after, snapshot, filter).await
     }
 
-    /// Stream drafts for all task attempts in a project with initial snapshot (raw LogMsg)
+    /// Stream drafts for all task attempts in a project with initial snapshot (raw LogMsg),
+    /// or resumed after the sequence number `after`
     pub async fn stream_drafts_for_project_raw(
         &self,
         project_id: Uuid,
-    ) -> Result<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>, EventError>
-    {
-        // Load all attempt ids for tasks in this project
-        let attempt_ids: Vec<Uuid> = sqlx::query_scalar(
-            r#"SELECT ta.id
-               FROM task_attempts ta
-               JOIN tasks t ON t.id = ta.task_id
-              WHERE t.project_id = ?"#,
-        )
-        .bind(project_id)
-        .fetch_all(&self.db.pool)
-        .await?;
-
-        // Build initial drafts map keyed by attempt_id
-        let mut drafts_map: serde_json::Map<String, serde_json::Value> = serde_json::Map::new();
-        for attempt_id in attempt_ids {
-            let fu = Draft::find_by_task_attempt_and_type(
-                &self.db.pool,
-                attempt_id,
-                DraftType::FollowUp,
+        after: Option<u64>,
+    ) -> Result<EventStream, EventError> {
+        let pool = self.db.pool.clone();
+        let snapshot = async move {
+            // Load all attempt ids for tasks in this project
+            let attempt_ids: Vec<Uuid> = sqlx::query_scalar(
+                r#"SELECT ta.id
+                   FROM task_attempts ta
+                   JOIN tasks t ON t.id = ta.task_id
+                  WHERE t.project_id = ?"#,
             )
-            .await?
-            .unwrap_or(Draft {
-                id: uuid::Uuid::new_v4(),
-                task_attempt_id: attempt_id,
-                draft_type: DraftType::FollowUp,
-                retry_process_id: None,
-                prompt: String::new(),
-                queued: false,
-                sending: false,
-                variant: None,
-                image_ids: None,
-                created_at: chrono::Utc::now(),
-                updated_at: chrono::Utc::now(),
-                version: 0,
-            });
-            let re =
-                Draft::find_by_task_attempt_and_type(&self.db.pool, attempt_id, DraftType::Retry)
-                    .await?;
-            let entry = json!({
-                "follow_up": fu,
-                "retry": serde_json::to_value(re).unwrap_or(serde_json::Value::Null),
-            });
-            drafts_map.insert(attempt_id.to_string(), entry);
-        }
+            .bind(project_id)
+            .fetch_all(&pool)
+            .await?;
 
-        let initial_patch = json!([
-            {
-                "op": "replace",
-                "path": "/drafts",
-                "value": drafts_map
+            // Build initial drafts map keyed by attempt_id
+            let mut drafts_map: serde_json::Map<String, serde_json::Value> = serde_json::Map::new();
+            for attempt_id in attempt_ids {
+                let fu =
+                    Draft::find_by_task_attempt_and_type(&pool, attempt_id, DraftType::FollowUp)
+                        .await?
+                        .unwrap_or(Draft {
+                            id: uuid::Uuid::new_v4(),
+                            task_attempt_id: attempt_id,
+                            draft_type: DraftType::FollowUp,
+                            retry_process_id: None,
+                            prompt: String::new(),
+                            queued: false,
+                            sending: false,
+                            variant: None,
+                            image_ids: None,
+                            created_at: chrono::Utc::now(),
+                            updated_at: chrono::Utc::now(),
+                            version: 0,
+                        });
+                let re = Draft::find_by_task_attempt_and_type(&pool, attempt_id, DraftType::Retry)
+                    .await?;
+                let entry = json!({
+                    "follow_up": fu,
+                    "retry": serde_json::to_value(re).unwrap_or(serde_json::Value::Null),
+                });
+                drafts_map.insert(attempt_id.to_string(), entry);
             }
-        ]);
-        let initial_msg = LogMsg::JsonPatch(serde_json::from_value(initial_patch).unwrap());
+
+            let initial_patch = json!([
+                {
+                    "op": "replace",
+                    "path": "/drafts",
+                    "value": drafts_map
+                }
+            ]);
+            Ok::<_, EventError>(LogMsg::JsonPatch(serde_json::from_value(initial_patch)?))
+        };
 
         let db_pool = self.db.pool.clone();
         // Live updates: accept direct draft patches and filter by project membership
-        let filtered_stream =
-            BroadcastStream::new(self.msg_store.get_receiver()).filter_map(move |msg_result| {
-                let db_pool = db_pool.clone();
-                async move {
-                    match msg_result {
-                        Ok(LogMsg::JsonPatch(patch)) => {
-                            if let Some(op) = patch.0.first() {
-                                let path = op.path();
-                                if let Some(rest) = path.strip_prefix("/drafts/")
-                                    && let Some((attempt_str, _)) = rest.split_once('/')
-                                    && let Ok(attempt_id) = Uuid::parse_str(attempt_str)
+        let filter = move |msg: LogMsg| {
+            let db_pool = db_pool.clone();
+            async move {
+                match msg {
+                    LogMsg::JsonPatch(patch) => {
+                        if let Some(op) = patch.0.first() {
+                            let path = op.path();
+                            if let Some(rest) = path.strip_prefix("/drafts/")
+                                && let Some((attempt_str, _)) = rest.split_once('/')
+                                && let Ok(attempt_id) = Uuid::parse_str(attempt_str)
+                            {
+                                // Check project membership
+                                if let Ok(Some(task_attempt)) =
+                                    db::models::task_attempt::TaskAttempt::find_by_id(
+                                        &db_pool, attempt_id,
+                                    )
+                                    .await
+                                    && let Ok(Some(task)) = db::models::task::Task::find_by_id(
+                                        &db_pool,
+                                        task_attempt.task_id,
+                                    )
+                                    .await
+                                    && task.project_id == project_id
                                 {
-                                    // Check project membership
-                                    if let Ok(Some(task_attempt)) =
-                                        db::models::task_attempt::TaskAttempt::find_by_id(
-                                            &db_pool, attempt_id,
-                                        )
-                                        .await
-                                        && let Ok(Some(task)) = db::models::task::Task::find_by_id(
-                                            &db_pool,
-                                            task_attempt.task_id,
-                                        )
-                                        .await
-                                        && task.project_id == project_id
-                                    {
-                                        return Some(Ok(LogMsg::JsonPatch(patch)));
-                                    }
+                                    return Some(LogMsg::JsonPatch(patch));
                                 }
                             }
-                            None
                         }
-                        Ok(other) => Some(Ok(other)),
-                        Err(_) => None,
+                        None
                     }
+                    other => Some(other),
                 }
-            });
+            }
+        };
+
+        self.resumable_stream(after, snapshot, filter).await
+    }
+
+    /// Stream the activity feed: the latest audit events as a snapshot keyed by id, then new
+    /// events as they are recorded. Optionally limited to one project.
+    pub async fn stream_activity_raw(
+        &self,
+        project_id: Option<Uuid>,
+        after: Option<u64>,
+    ) -> Result<EventStream, EventError> {
+        let pool = self.db.pool.clone();
+        let snapshot = async move {
+            let query = AuditQuery {
+                project_id,
+                limit: Some(ACTIVITY_SNAPSHOT_LEN),
+                ..Default::default()
+            };
+            let activity: serde_json::Map<String, serde_json::Value> =
+                AuditEvent::find(&pool, &query)
+                    .await?
+                    .into_iter()
+                    .map(|event| (event.id.to_string(), serde_json::to_value(event).unwrap()))
+                    .collect();
+
+            let initial_patch = json!([{
+                "op": "replace",
+                "path": "/activity",
+                "value": activity
+            }]);
+            Ok::<_, EventError>(LogMsg::JsonPatch(serde_json::from_value(initial_patch)?))
+        };
 
-        let initial_stream = futures::stream::once(async move { Ok(initial_msg) });
-        let combined_stream = initial_stream.chain(filtered_stream).boxed();
-        Ok(combined_stream)
+        let filter = move |msg: LogMsg| {
+            future::ready(match msg {
+                LogMsg::JsonPatch(patch) => match patch.0.first() {
+                    Some(json_patch::PatchOperation::Add(op))
+                        if op.path.starts_with("/activity/") =>
+                    {
+                        let in_project = project_id.is_none_or(|project_id| {
+                            op.value.get("project_id").and_then(|v| v.as_str())
+                                == Some(project_id.to_string().as_str())
+                        });
+                        in_project.then_some(LogMsg::JsonPatch(patch))
+                    }
+                    _ => None,
+                },
+                other => Some(other),
+            })
+        };
+
+        self.resumable_stream(after, snapshot, filter).await
+    }
+
+    /// The SSE event stream: the replay buffer then live events, or the events after the
+    /// sequence number `after`
+    pub fn stream_events(&self, after: Option<u64>) -> EventStream {
+        let pass = |msg: LogMsg| future::ready(Some(msg));
+        match after {
+            Some(after) => match self.stream_after(after) {
+                Some(live) => self.sequenced(live, pass),
+                None => futures::stream::once(future::ready(Ok(EventStreamMsg::ResyncRequired)))
+                    .chain(self.sequenced(self.msg_store.history_plus_stream_indexed(), pass))
+                    .boxed(),
+            },
+            None => self.sequenced(self.msg_store.history_plus_stream_indexed(), pass),
+        }
+    }
+
+    /// Sequence number of the newest event, for streams that only want what comes next
+    pub fn latest_seq(&self) -> u64 {
+        self.seq_base + self.msg_store.next_index() as u64
+    }
+
+    /// Live events after the sequence number `after`, if it is still in the replay buffer
+    fn stream_after(&self, after: u64) -> Option<BoxStream<'static, (usize, TimedLogMsg)>> {
+        let start = after.checked_sub(self.seq_base)?;
+        self.msg_store.stream_from(usize::try_from(start).ok()?)
+    }
+
+    /// Resume after `after` when the replay buffer still holds it. Otherwise send `snapshot`,
+    /// preceded by a resync signal if a resume was asked for. Live events are kept by `filter`.
+    async fn resumable_stream<S, F, Fut>(
+        &self,
+        after: Option<u64>,
+        snapshot: S,
+        filter: F,
+    ) -> Result<EventStream, EventError>
+    where
+        S: Future<Output = Result<LogMsg, EventError>>,
+        F: Fn(LogMsg) -> Fut + Send + 'static,
+        Fut: Future<Output = Option<LogMsg>> + Send + 'static,
+    {
+        if let Some(live) = after.and_then(|after| self.stream_after(after)) {
+            return Ok(self.sequenced(live, filter));
+        }
+
+        // Subscribe before taking the snapshot so no event in between is missed
+        let start = self.msg_store.next_index();
+        let live = self
+            .msg_store
+            .stream_from(start)
+            .unwrap_or_else(|| futures::stream::empty().boxed());
+        let snapshot = EventStreamMsg::Event(SequencedMsg {
+            seq: self.seq_base + start as u64,
+            msg: snapshot.await?,
+        });
+
+        let mut initial = Vec::new();
+        if after.is_some() {
+            initial.push(EventStreamMsg::ResyncRequired);
+        }
+        initial.push(snapshot);
+        Ok(futures::stream::iter(initial.into_iter().map(Ok))
+            .chain(self.sequenced(live, filter))
+            .boxed())
+    }
+
+    /// Number events by their position in the store, keeping those `filter` passes
+    fn sequenced<F, Fut>(
+        &self,
+        live: BoxStream<'static, (usize, TimedLogMsg)>,
+        filter: F,
+    ) -> EventStream
+    where
+        F: Fn(LogMsg) -> Fut + Send + 'static,
+        Fut: Future<Output = Option<LogMsg>> + Send + 'static,
+    {
+        let seq_base = self.seq_base;
+        live.filter_map(move |(index, timed)| {
+            let kept = filter(timed.msg);
+            async move {
+                kept.await.map(|msg| {
+                    Ok(EventStreamMsg::Event(SequencedMsg {
+                        seq: seq_base + index as u64 + 1,
+                        msg,
+                    }))
+                })
+            }
+        })
+        .boxed()
     }
 }
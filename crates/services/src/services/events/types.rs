@@ -1,4 +1,5 @@
 use anyhow::Error as AnyhowError;
+use axum::{extract::ws::Message, response::sse::Event};
 use db::models::{
     draft::{Draft, DraftType},
     execution_process::ExecutionProcess,
@@ -10,8 +11,12 @@ use sqlx::Error as SqlxError;
 use strum_macros::{Display, EnumString};
 use thiserror::Error;
 use ts_rs::TS;
+use utils::log_msg::LogMsg;
 use uuid::Uuid;
 
+/// SSE event name of [`EventStreamMsg::ResyncRequired`]
+pub const EV_RESYNC_REQUIRED: &str = "resync_required";
+
 #[derive(Debug, Error)]
 pub enum EventError {
     #[error(transparent)]
@@ -32,6 +37,8 @@ pub enum HookTables {
     ExecutionProcesses,
     #[strum(to_string = "drafts")]
     Drafts,
+    #[strum(to_string = "audit_events")]
+    AuditEvents,
 }
 
 #[derive(Serialize, Deserialize, TS)]
@@ -75,3 +82,44 @@ pub struct EventPatch {
     pub(crate) path: String,
     pub(crate) value: EventPatchInner,
 }
+
+/// A message of an event stream with its sequence number. Sequence numbers increase across
+/// server restarts; clients pass the last one they saw to resume a stream.
+#[derive(Debug, Clone, Serialize)]
+pub struct SequencedMsg {
+    pub seq: u64,
+    #[serde(flatten)]
+    pub msg: LogMsg,
+}
+
+#[derive(Debug, Clone)]
+pub enum EventStreamMsg {
+    Event(SequencedMsg),
+    /// The requested resume point is no longer in the replay buffer. The stream continues with
+    /// a fresh snapshot, which replaces whatever state the client holds.
+    ResyncRequired,
+}
+
+impl EventStreamMsg {
+    /// WebSocket message, e.g. `{"seq":42,"JsonPatch":[...]}` or `{"resync_required":true}`
+    pub fn to_ws_message(&self) -> Message {
+        let json = match self {
+            EventStreamMsg::Event(SequencedMsg {
+                msg: LogMsg::Finished,
+                ..
+            }) => return LogMsg::Finished.to_ws_message_unchecked(),
+            EventStreamMsg::Event(event) => serde_json::to_string(event)
+                .unwrap_or_else(|_| r#"{"error":"serialization_failed"}"#.to_string()),
+            EventStreamMsg::ResyncRequired => r#"{"resync_required":true}"#.to_string(),
+        };
+        Message::Text(json.into())
+    }
+
+    /// SSE event, with the sequence number as its id so `EventSource` resumes on reconnect
+    pub fn to_sse_event(&self) -> Event {
+        match self {
+            EventStreamMsg::Event(event) => event.msg.to_sse_event().id(event.seq.to_string()),
+            EventStreamMsg::ResyncRequired => Event::default().event(EV_RESYNC_REQUIRED).data(""),
+        }
+    }
+}
@@ -1,6 +1,9 @@
 use std::{
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
     time::{Duration, Instant},
 };
 
@@ -95,6 +98,15 @@ pub struct FileSearchCache {
     file_ranker: FileRanker,
     build_queue: mpsc::UnboundedSender<PathBuf>,
     watchers: DashMap<PathBuf, RecommendedWatcher>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// Lookups served from the cache since startup
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
 }
 
 impl FileSearchCache {
@@ -130,6 +142,15 @@ impl FileSearchCache {
             file_ranker,
             build_queue: build_sender,
             watchers: DashMap::new(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
         }
     }
 
@@ -148,10 +169,12 @@ impl FileSearchCache {
             && head_info.oid == cached.head_sha
         {
             // Cache hit - perform fast search with mode-based filtering
+            self.hits.fetch_add(1, Ordering::Relaxed);
             return Ok(self.search_in_cache(&cached, query, mode).await);
         }
 
         // Cache miss - trigger background refresh and return error
+        self.misses.fetch_add(1, Ordering::Relaxed);
         if let Err(e) = self.build_queue.send(repo_path_buf) {
             warn!("Failed to enqueue cache build: {}", e);
         }
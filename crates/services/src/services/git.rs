@@ -761,6 +761,10 @@ impl GitService {
     }
 
     /// Merge changes from a task branch into the base branch.
+    #[tracing::instrument(
+        name = "git.merge",
+        skip(self, base_worktree_path, task_worktree_path, commit_message)
+    )]
     pub fn merge_changes(
         &self,
         base_worktree_path: &Path,
@@ -1378,6 +1382,10 @@ impl GitService {
     }
 
     /// Rebase a worktree branch onto a new base
+    #[tracing::instrument(
+        name = "git.rebase",
+        skip(self, repo_path, worktree_path, github_token)
+    )]
     pub fn rebase_branch(
         &self,
         repo_path: &Path,
@@ -1729,6 +1737,7 @@ impl GitService {
         })
     }
 
+    #[tracing::instrument(name = "git.push", skip(self, worktree_path, github_token))]
     pub fn push_to_github(
         &self,
         worktree_path: &Path,
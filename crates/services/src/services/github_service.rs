@@ -147,6 +147,15 @@ pub struct CreatePrRequest {
     pub base_branch: String,
 }
 
+/// An open issue, excluding pull requests, which GitHub also lists as issues
+#[derive(Debug, Clone)]
+pub struct GitHubIssue {
+    pub number: i64,
+    pub title: String,
+    pub body: Option<String>,
+    pub url: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct RepositoryInfo {
     pub id: i64,
@@ -408,6 +417,82 @@ impl GitHubService {
         Ok(pr_infos)
     }
 
+    /// List the open issues of a repository, optionally only those with `label`
+    pub async fn list_open_issues(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        label: Option<&str>,
+    ) -> Result<Vec<GitHubIssue>, GitHubServiceError> {
+        let labels: Vec<String> = label.map(str::to_string).into_iter().collect();
+        let issues = (|| async {
+            let mut request = self
+                .client
+                .issues(&repo_info.owner, &repo_info.repo_name)
+                .list()
+                .state(octocrab::params::State::Open)
+                .per_page(100);
+            if !labels.is_empty() {
+                request = request.labels(&labels);
+            }
+            let page = request.send().await?;
+            Ok::<_, GitHubServiceError>(self.client.all_pages(page).await?)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &GitHubServiceError| e.should_retry())
+        .notify(|err: &GitHubServiceError, dur: Duration| {
+            tracing::warn!(
+                "GitHub API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await?;
+
+        Ok(issues
+            .into_iter()
+            .filter(|issue| issue.pull_request.is_none())
+            .map(|issue| GitHubIssue {
+                number: issue.number as i64,
+                title: issue.title,
+                body: issue.body,
+                url: issue.html_url.to_string(),
+            })
+            .collect())
+    }
+
+    pub async fn comment_on_issue(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        issue_number: i64,
+        body: &str,
+    ) -> Result<(), GitHubServiceError> {
+        self.client
+            .issues(&repo_info.owner, &repo_info.repo_name)
+            .create_comment(issue_number as u64, body)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn close_issue(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        issue_number: i64,
+    ) -> Result<(), GitHubServiceError> {
+        self.client
+            .issues(&repo_info.owner, &repo_info.repo_name)
+            .update(issue_number as u64)
+            .state(IssueState::Closed)
+            .send()
+            .await?;
+        Ok(())
+    }
+
     /// List repositories for the authenticated user with pagination
     #[cfg(feature = "cloud")]
     pub async fn list_repositories(
@@ -0,0 +1,799 @@
+use std::{sync::Arc, time::Duration};
+
+use db::{
+    DBService,
+    models::{
+        issue_tracker::{IssueTracker, IssueTrackerKind, TaskIssueLink},
+        project::Project,
+        task::{CreateTask, Task, TaskStatus},
+    },
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{SqlitePool, error::Error as SqlxError};
+use thiserror::Error;
+use tokio::{sync::RwLock, time::interval};
+use tracing::{debug, error, info, warn};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::services::{
+    config::Config,
+    git::{GitService, GitServiceError},
+    github_service::{GitHubRepoInfo, GitHubService, GitHubServiceError},
+    project_env::ProjectEnvService,
+};
+
+#[derive(Debug, Error)]
+pub enum IssueSyncError {
+    #[error(transparent)]
+    Database(#[from] SqlxError),
+    #[error(transparent)]
+    GitHub(#[from] GitHubServiceError),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    #[error("No GitHub token configured")]
+    NoGitHubToken,
+    #[error("Project not found")]
+    ProjectNotFound,
+    #[error("Invalid issue tracker: {0}")]
+    InvalidTracker(String),
+}
+
+/// What a sync did
+#[derive(Debug, Clone, Default, Serialize, TS)]
+pub struct SyncSummary {
+    /// Issues imported as new tasks
+    pub imported: usize,
+    /// Tasks whose title or description changed on the tracker
+    pub updated: usize,
+    /// Status changes posted back to the tracker
+    pub posted: usize,
+    /// Issues that failed to sync, with why. The sync goes on with the other issues.
+    pub errors: Vec<String>,
+}
+
+/// An open issue on a tracker
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExternalIssue {
+    pub id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub url: Option<String>,
+}
+
+/// An issue as a REST tracker serves it. Ids may be numbers or strings.
+#[derive(Debug, Deserialize)]
+struct RestIssue {
+    id: Value,
+    title: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    state: Option<String>,
+}
+
+/// Body posted to a REST tracker when a task moves to review or done
+#[derive(Debug, Serialize)]
+struct RestStatusUpdate<'a> {
+    status: &'a TaskStatus,
+    pr_url: Option<&'a str>,
+    task_id: Uuid,
+}
+
+/// Client of a tracker with a JSON API. `GET {url}` lists issues and
+/// `POST {url}/{id}/status` receives status changes.
+#[derive(Debug, Clone)]
+pub struct RestTracker {
+    client: reqwest::Client,
+    url: reqwest::Url,
+    token: Option<String>,
+}
+
+impl RestTracker {
+    pub fn new(url: &str, token: Option<String>) -> Result<Self, IssueSyncError> {
+        let invalid = || IssueSyncError::InvalidTracker(format!("Invalid tracker URL: {url}"));
+        let mut url = reqwest::Url::parse(url).map_err(|_| invalid())?;
+        url.path_segments_mut()
+            .map_err(|_| invalid())?
+            .pop_if_empty();
+        Ok(Self {
+            client: reqwest::Client::new(),
+            url,
+            token,
+        })
+    }
+
+    /// `{url}/{id}/status`, with the id escaped as a single path segment
+    fn status_url(&self, issue_id: &str) -> reqwest::Url {
+        let mut url = self.url.clone();
+        url.path_segments_mut()
+            .expect("checked in RestTracker::new")
+            .push(issue_id)
+            .push("status");
+        url
+    }
+
+    fn request(&self, method: reqwest::Method, url: reqwest::Url) -> reqwest::RequestBuilder {
+        let request = self.client.request(method, url);
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    pub async fn list_open_issues(&self) -> Result<Vec<ExternalIssue>, IssueSyncError> {
+        let issues: Vec<RestIssue> = self
+            .request(reqwest::Method::GET, self.url.clone())
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(issues
+            .into_iter()
+            .filter(|issue| !matches!(issue.state.as_deref(), Some("closed")))
+            .map(|issue| ExternalIssue {
+                id: match issue.id {
+                    Value::String(id) => id,
+                    other => other.to_string(),
+                },
+                title: issue.title,
+                description: issue.description,
+                url: issue.url,
+            })
+            .collect())
+    }
+
+    pub async fn post_status(
+        &self,
+        issue_id: &str,
+        task_id: Uuid,
+        status: &TaskStatus,
+        pr_url: Option<&str>,
+    ) -> Result<(), IssueSyncError> {
+        self.request(reqwest::Method::POST, self.status_url(issue_id))
+            .json(&RestStatusUpdate {
+                status,
+                pr_url,
+                task_id,
+            })
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+pub enum TrackerClient {
+    GitHub {
+        service: GitHubService,
+        repo: GitHubRepoInfo,
+        label: Option<String>,
+    },
+    Rest(RestTracker),
+}
+
+impl TrackerClient {
+    pub async fn list_open_issues(&self) -> Result<Vec<ExternalIssue>, IssueSyncError> {
+        match self {
+            Self::GitHub {
+                service,
+                repo,
+                label,
+            } => Ok(service
+                .list_open_issues(repo, label.as_deref())
+                .await?
+                .into_iter()
+                .map(|issue| ExternalIssue {
+                    id: issue.number.to_string(),
+                    title: issue.title,
+                    description: issue.body.filter(|body| !body.trim().is_empty()),
+                    url: Some(issue.url),
+                })
+                .collect()),
+            Self::Rest(tracker) => tracker.list_open_issues().await,
+        }
+    }
+
+    /// Tell the tracker the task moved to review or done. GitHub issues get a comment and are
+    /// closed once the task is done.
+    pub async fn post_status(
+        &self,
+        link: &TaskIssueLink,
+        task: &Task,
+        pr_url: Option<&str>,
+    ) -> Result<(), IssueSyncError> {
+        match self {
+            Self::GitHub { service, repo, .. } => {
+                let number = link.external_id.parse::<i64>().map_err(|_| {
+                    IssueSyncError::InvalidTracker(format!(
+                        "'{}' is not a GitHub issue number",
+                        link.external_id
+                    ))
+                })?;
+                service
+                    .comment_on_issue(repo, number, &status_comment(&task.status, pr_url))
+                    .await?;
+                if task.status == TaskStatus::Done {
+                    service.close_issue(repo, number).await?;
+                }
+                Ok(())
+            }
+            Self::Rest(tracker) => {
+                tracker
+                    .post_status(&link.external_id, task.id, &task.status, pr_url)
+                    .await
+            }
+        }
+    }
+}
+
+fn status_comment(status: &TaskStatus, pr_url: Option<&str>) -> String {
+    let status = match status {
+        TaskStatus::Done => "This is done in Vibe Kanban.",
+        _ => "This is in review in Vibe Kanban.",
+    };
+    match pr_url {
+        Some(pr_url) => format!("{status}\n\nPull request: {pr_url}"),
+        None => status.to_string(),
+    }
+}
+
+/// Check a tracker's settings before they are saved
+pub fn validate(kind: IssueTrackerKind, url: Option<&str>) -> Result<(), IssueSyncError> {
+    if kind == IssueTrackerKind::Rest {
+        let url = url.unwrap_or_default();
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            return Err(IssueSyncError::InvalidTracker(
+                "A REST tracker needs an http(s) URL".to_string(),
+            ));
+        }
+        RestTracker::new(url, None)?;
+    }
+    Ok(())
+}
+
+/// Connect to a tracker. GitHub trackers use the repository the project's remote points to.
+pub async fn client_for(
+    pool: &SqlitePool,
+    tracker: &IssueTracker,
+    github_token: Option<String>,
+    git: &GitService,
+    project_env: &ProjectEnvService,
+) -> Result<TrackerClient, IssueSyncError> {
+    match tracker.kind {
+        IssueTrackerKind::GitHub => {
+            let token = github_token.ok_or(IssueSyncError::NoGitHubToken)?;
+            let project = Project::find_by_id(pool, tracker.project_id)
+                .await?
+                .ok_or(IssueSyncError::ProjectNotFound)?;
+            Ok(TrackerClient::GitHub {
+                service: GitHubService::new(&token)?,
+                repo: git.get_github_repo_info(&project.git_repo_path)?,
+                label: tracker.label.clone(),
+            })
+        }
+        IssueTrackerKind::Rest => {
+            let url = tracker
+                .url
+                .as_deref()
+                .ok_or_else(|| IssueSyncError::InvalidTracker("No URL set".to_string()))?;
+            let token = match &tracker.token_env {
+                Some(name) => {
+                    let token = project_env
+                        .env_for_project(tracker.project_id)
+                        .await
+                        .remove(name);
+                    if token.is_none() {
+                        warn!(
+                            "Project env var {} for issue tracker {} is not set",
+                            name, tracker.id
+                        );
+                    }
+                    token
+                }
+                None => None,
+            };
+            Ok(TrackerClient::Rest(RestTracker::new(url, token)?))
+        }
+    }
+}
+
+/// What syncing one issue or link did
+enum Synced {
+    Imported,
+    Updated,
+    Posted,
+    Unchanged,
+}
+
+/// Import the issue as a task, or carry title and description changes over to its task
+async fn sync_issue(
+    pool: &SqlitePool,
+    tracker: &IssueTracker,
+    issue: &ExternalIssue,
+    link: Option<&TaskIssueLink>,
+) -> Result<Synced, IssueSyncError> {
+    let Some(link) = link else {
+        let task = Task::create(
+            pool,
+            &CreateTask::from_title_description(
+                tracker.project_id,
+                issue.title.clone(),
+                issue.description.clone(),
+            ),
+            Uuid::new_v4(),
+        )
+        .await?;
+        TaskIssueLink::create(
+            pool,
+            tracker.id,
+            task.id,
+            &issue.id,
+            issue.url.as_deref(),
+            &issue.title,
+            issue.description.as_deref(),
+        )
+        .await?;
+        return Ok(Synced::Imported);
+    };
+
+    let Some(task_id) = link.task_id else {
+        return Ok(Synced::Unchanged);
+    };
+    if link.remote_title == issue.title && link.remote_description == issue.description {
+        return Ok(Synced::Unchanged);
+    }
+    let Some(task) = Task::find_by_id(pool, task_id).await? else {
+        return Ok(Synced::Unchanged);
+    };
+    // Only fields edited on the tracker are copied, so local edits of the other one are kept
+    let title = if link.remote_title != issue.title {
+        issue.title.clone()
+    } else {
+        task.title
+    };
+    let description = if link.remote_description != issue.description {
+        issue.description.clone()
+    } else {
+        task.description
+    };
+    Task::update(
+        pool,
+        task.id,
+        task.project_id,
+        title,
+        description,
+        task.status,
+        task.parent_task_attempt,
+    )
+    .await?;
+    TaskIssueLink::set_remote(
+        pool,
+        link.id,
+        issue.url.as_deref(),
+        &issue.title,
+        issue.description.as_deref(),
+    )
+    .await?;
+    Ok(Synced::Updated)
+}
+
+/// Post the task's status back to the tracker if it moved to review or done since it was last
+/// posted
+async fn post_back(
+    pool: &SqlitePool,
+    client: &TrackerClient,
+    link: &TaskIssueLink,
+) -> Result<Synced, IssueSyncError> {
+    let Some(task_id) = link.task_id else {
+        return Ok(Synced::Unchanged);
+    };
+    let Some(task) = Task::find_by_id(pool, task_id).await? else {
+        return Ok(Synced::Unchanged);
+    };
+    if !matches!(task.status, TaskStatus::InReview | TaskStatus::Done) {
+        return Ok(Synced::Unchanged);
+    }
+    let pr_url = TaskIssueLink::latest_pr_url(pool, task.id).await?;
+    if link.posted_status.as_ref() == Some(&task.status) && link.posted_pr_url == pr_url {
+        return Ok(Synced::Unchanged);
+    }
+    client.post_status(link, &task, pr_url.as_deref()).await?;
+    TaskIssueLink::set_posted(pool, link.id, &task.status, pr_url.as_deref()).await?;
+    Ok(Synced::Posted)
+}
+
+impl SyncSummary {
+    /// Count what syncing an issue did, or record why it failed on its link if it has one
+    async fn record(
+        &mut self,
+        pool: &SqlitePool,
+        external_id: &str,
+        link: Option<&TaskIssueLink>,
+        result: Result<Synced, IssueSyncError>,
+    ) -> Result<(), IssueSyncError> {
+        match result {
+            Ok(Synced::Imported) => self.imported += 1,
+            Ok(Synced::Updated) => self.updated += 1,
+            Ok(Synced::Posted) => self.posted += 1,
+            Ok(Synced::Unchanged) => {}
+            Err(e) => {
+                warn!("Failed to sync issue {}: {}", external_id, e);
+                if let Some(link) = link {
+                    TaskIssueLink::record_error(pool, link.id, &e.to_string()).await?;
+                }
+                self.errors.push(format!("Issue {external_id}: {e}"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Import new issues as tasks, carry title and description changes over to their tasks, and
+/// post back tasks that moved to review or done. An issue that fails doesn't stop the others;
+/// its error is recorded on its link and in the summary.
+pub async fn sync(
+    pool: &SqlitePool,
+    client: &TrackerClient,
+    tracker: &IssueTracker,
+) -> Result<SyncSummary, IssueSyncError> {
+    let mut summary = SyncSummary::default();
+
+    for issue in client.list_open_issues().await? {
+        let link = TaskIssueLink::find_by_external_id(pool, tracker.id, &issue.id).await?;
+        let result = sync_issue(pool, tracker, &issue, link.as_ref()).await;
+        summary
+            .record(pool, &issue.id, link.as_ref(), result)
+            .await?;
+    }
+
+    for link in TaskIssueLink::find_by_tracker_id(pool, tracker.id).await? {
+        let result = post_back(pool, client, &link).await;
+        summary
+            .record(pool, &link.external_id, Some(&link), result)
+            .await?;
+    }
+
+    Ok(summary)
+}
+
+/// Connect to the tracker and sync it, recording the outcome on the tracker
+pub async fn sync_tracker(
+    pool: &SqlitePool,
+    tracker: &IssueTracker,
+    github_token: Option<String>,
+    git: &GitService,
+    project_env: &ProjectEnvService,
+) -> Result<SyncSummary, IssueSyncError> {
+    let result = async {
+        let client = client_for(pool, tracker, github_token, git, project_env).await?;
+        sync(pool, &client, tracker).await
+    }
+    .await;
+    let error = match &result {
+        Ok(summary) if summary.errors.is_empty() => None,
+        Ok(summary) => Some(summary.errors.join("\n")),
+        Err(e) => Some(e.to_string()),
+    };
+    IssueTracker::record_sync(pool, tracker.id, error.as_deref()).await?;
+    result
+}
+
+/// Background service that syncs issue trackers with auto sync on
+pub struct IssueSyncService {
+    db: DBService,
+    config: Arc<RwLock<Config>>,
+    git: GitService,
+    project_env: ProjectEnvService,
+    poll_interval: Duration,
+}
+
+impl IssueSyncService {
+    pub async fn spawn(
+        db: DBService,
+        config: Arc<RwLock<Config>>,
+        git: GitService,
+        project_env: ProjectEnvService,
+    ) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            db,
+            config,
+            git,
+            project_env,
+            poll_interval: Duration::from_secs(300),
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        info!(
+            "Starting issue sync service with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.sync_all().await {
+                error!("Error syncing issue trackers: {}", e);
+            }
+        }
+    }
+
+    async fn sync_all(&self) -> Result<(), IssueSyncError> {
+        let trackers = IssueTracker::find_auto_sync(&self.db.pool).await?;
+        for tracker in trackers {
+            let github_token = self.config.read().await.github.token();
+            match sync_tracker(
+                &self.db.pool,
+                &tracker,
+                github_token,
+                &self.git,
+                &self.project_env,
+            )
+            .await
+            {
+                Ok(summary) => debug!("Synced issue tracker {}: {:?}", tracker.id, summary),
+                Err(e) => warn!("Error syncing issue tracker {}: {}", tracker.id, e),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use axum::{
+        Json, Router,
+        extract::{Path, State},
+        http::StatusCode,
+        routing::{get, post},
+    };
+    use db::models::{issue_tracker::CreateIssueTracker, project::CreateProject};
+    use serde_json::json;
+
+    use super::*;
+
+    type Posted = Arc<Mutex<Vec<(String, Value)>>>;
+
+    /// Serve a tracker with one open and one closed issue on a local port. Only the open issue
+    /// takes status changes.
+    async fn stub_tracker() -> (String, Posted) {
+        let posted = Posted::default();
+        let app = Router::new()
+            .route(
+                "/issues",
+                get(|| async {
+                    Json(json!([
+                        { "id": 7, "title": "Fix login", "description": "It 500s", "url": "http://tracker/7" },
+                        { "id": "ABC-1", "title": "Old", "state": "closed" },
+                    ]))
+                }),
+            )
+            .route(
+                "/issues/{id}/status",
+                post(
+                    |State(posted): State<Posted>, Path(id): Path<String>, Json(body): Json<Value>| async move {
+                        if id != "7" {
+                            return StatusCode::NOT_FOUND;
+                        }
+                        posted.lock().unwrap().push((id, body));
+                        StatusCode::OK
+                    },
+                ),
+            )
+            .with_state(posted.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{addr}/issues"), posted)
+    }
+
+    #[tokio::test]
+    async fn rest_tracker_lists_open_issues() {
+        let (url, _) = stub_tracker().await;
+        let issues = RestTracker::new(&url, None)
+            .unwrap()
+            .list_open_issues()
+            .await
+            .unwrap();
+        assert_eq!(
+            issues,
+            vec![ExternalIssue {
+                id: "7".to_string(),
+                title: "Fix login".to_string(),
+                description: Some("It 500s".to_string()),
+                url: Some("http://tracker/7".to_string()),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn rest_tracker_posts_status() {
+        let (url, posted) = stub_tracker().await;
+        let task_id = Uuid::new_v4();
+        RestTracker::new(&format!("{url}/"), Some("secret".to_string()))
+            .unwrap()
+            .post_status("7", task_id, &TaskStatus::InReview, Some("https://pr/1"))
+            .await
+            .unwrap();
+        assert_eq!(
+            posted.lock().unwrap().clone(),
+            vec![(
+                "7".to_string(),
+                json!({ "status": "inreview", "pr_url": "https://pr/1", "task_id": task_id }),
+            )]
+        );
+    }
+
+    #[test]
+    fn rest_tracker_escapes_issue_ids() {
+        let tracker = RestTracker::new("https://tracker/api/issues/", None).unwrap();
+        assert_eq!(
+            tracker.status_url("team/7 #2").as_str(),
+            "https://tracker/api/issues/team%2F7%20%232/status"
+        );
+        assert!(RestTracker::new("not a url", None).is_err());
+    }
+
+    /// A project with a REST tracker served by `stub_tracker`
+    async fn synced_project(url: &str) -> (DBService, IssueTracker) {
+        let db = DBService::new_in_memory().await.unwrap();
+        let project_id = Uuid::new_v4();
+        Project::create(
+            &db.pool,
+            &CreateProject::from_name_repo_path("p".to_string(), project_id.to_string()),
+            project_id,
+        )
+        .await
+        .unwrap();
+        let tracker = IssueTracker::create(
+            &db.pool,
+            &CreateIssueTracker {
+                project_id,
+                kind: IssueTrackerKind::Rest,
+                url: Some(url.to_string()),
+                token_env: None,
+                label: None,
+                auto_sync: false,
+            },
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        (db, tracker)
+    }
+
+    #[tokio::test]
+    async fn sync_imports_updates_and_posts_back_once() {
+        let (url, posted) = stub_tracker().await;
+        let (db, tracker) = synced_project(&url).await;
+        let pool = &db.pool;
+        let client = TrackerClient::Rest(RestTracker::new(&url, None).unwrap());
+
+        let summary = sync(pool, &client, &tracker).await.unwrap();
+        assert_eq!((summary.imported, summary.updated), (1, 0));
+        let summary = sync(pool, &client, &tracker).await.unwrap();
+        assert_eq!((summary.imported, summary.updated), (0, 0));
+        let tasks = Task::find_by_project_id_with_attempt_status(
+            pool,
+            tracker.project_id,
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(tasks.len(), 1);
+        let task = tasks[0].task.clone();
+        assert_eq!(task.title, "Fix login");
+
+        // The title changed on the tracker and the description locally: both edits are kept
+        let link = TaskIssueLink::find_by_task_id(pool, task.id)
+            .await
+            .unwrap()
+            .unwrap();
+        TaskIssueLink::set_remote(
+            pool,
+            link.id,
+            link.url.as_deref(),
+            "Fix logn",
+            Some("It 500s"),
+        )
+        .await
+        .unwrap();
+        Task::update(
+            pool,
+            task.id,
+            task.project_id,
+            task.title.clone(),
+            Some("Only with SSO".to_string()),
+            TaskStatus::InReview,
+            None,
+        )
+        .await
+        .unwrap();
+        let summary = sync(pool, &client, &tracker).await.unwrap();
+        assert_eq!((summary.updated, summary.posted), (1, 1));
+        let task = Task::find_by_id(pool, task.id).await.unwrap().unwrap();
+        assert_eq!(task.title, "Fix login");
+        assert_eq!(task.description.as_deref(), Some("Only with SSO"));
+
+        let summary = sync(pool, &client, &tracker).await.unwrap();
+        assert_eq!((summary.updated, summary.posted), (0, 0));
+        Task::set_status(pool, task.id, &TaskStatus::Done)
+            .await
+            .unwrap();
+        let summary = sync(pool, &client, &tracker).await.unwrap();
+        assert_eq!(summary.posted, 1);
+        let statuses: Vec<Value> = posted
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, body)| body["status"].clone())
+            .collect();
+        assert_eq!(statuses, [json!("inreview"), json!("done")]);
+    }
+
+    #[tokio::test]
+    async fn sync_records_a_failing_issue_and_goes_on() {
+        let (url, posted) = stub_tracker().await;
+        let (db, tracker) = synced_project(&url).await;
+        let pool = &db.pool;
+        let client = TrackerClient::Rest(RestTracker::new(&url, None).unwrap());
+
+        // A task linked to an issue the tracker rejects status changes for, created before the
+        // open issue so it's posted first
+        let closed = Task::create(
+            pool,
+            &CreateTask::from_title_description(tracker.project_id, "Old".to_string(), None),
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        let closed_link =
+            TaskIssueLink::create(pool, tracker.id, closed.id, "ABC-1", None, "Old", None)
+                .await
+                .unwrap();
+        Task::set_status(pool, closed.id, &TaskStatus::Done)
+            .await
+            .unwrap();
+        sync(pool, &client, &tracker).await.unwrap();
+        let open_link = TaskIssueLink::find_by_external_id(pool, tracker.id, "7")
+            .await
+            .unwrap()
+            .unwrap();
+        Task::set_status(pool, open_link.task_id.unwrap(), &TaskStatus::InReview)
+            .await
+            .unwrap();
+
+        let summary = sync(pool, &client, &tracker).await.unwrap();
+        assert_eq!(summary.posted, 1);
+        assert_eq!(summary.errors.len(), 1);
+        assert_eq!(posted.lock().unwrap().len(), 1);
+        let closed_link =
+            TaskIssueLink::find_by_external_id(pool, tracker.id, &closed_link.external_id)
+                .await
+                .unwrap()
+                .unwrap();
+        assert!(closed_link.last_error.is_some());
+        assert_eq!(closed_link.posted_status, None);
+    }
+
+    #[test]
+    fn rest_trackers_need_a_url() {
+        assert!(validate(IssueTrackerKind::Rest, None).is_err());
+        assert!(validate(IssueTrackerKind::Rest, Some("ftp://tracker")).is_err());
+        assert!(validate(IssueTrackerKind::Rest, Some("https://tracker/issues")).is_ok());
+        assert!(validate(IssueTrackerKind::GitHub, None).is_ok());
+    }
+}
@@ -3,6 +3,7 @@ pub mod approvals;
 pub mod auth;
 pub mod config;
 pub mod container;
+pub mod conversation_export;
 pub mod diff_stream;
 pub mod drafts;
 pub mod events;
@@ -14,6 +15,12 @@ pub mod git;
 pub mod git_cli;
 pub mod github_service;
 pub mod image;
+pub mod issue_sync;
 pub mod notification;
 pub mod pr_monitor;
+pub mod project_env;
+pub mod project_instructions;
+pub mod prompt_template;
+pub mod retention;
+pub mod scheduler;
 pub mod worktree_manager;
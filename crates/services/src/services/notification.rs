@@ -34,6 +34,10 @@ impl NotificationService {
                 "🛑 '{}' execution cancelled by user\nBranch: {:?}\nExecutor: {}",
                 ctx.task.title, ctx.task_attempt.branch, ctx.task_attempt.executor
             ),
+            ExecutionProcessStatus::LimitExceeded => format!(
+                "⏱️ '{}' execution stopped for exceeding its resource limits\nBranch: {:?}\nExecutor: {}",
+                ctx.task.title, ctx.task_attempt.branch, ctx.task_attempt.executor
+            ),
             _ => {
                 tracing::warn!(
                     "Tried to notify attempt completion for {} but process is still running!",
@@ -1,8 +1,15 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
 
 use db::{
     DBService,
     models::{
+        audit_event::AuditActor,
         merge::{Merge, MergeStatus, PrMerge},
         task::{Task, TaskStatus},
         task_attempt::{TaskAttempt, TaskAttemptError},
@@ -32,6 +39,13 @@ enum PrMonitorError {
     Sqlx(#[from] SqlxError),
 }
 
+/// Failed polls since startup, counting both whole polls and individual PR checks
+static POLL_ERRORS: AtomicU64 = AtomicU64::new(0);
+
+pub fn poll_error_count() -> u64 {
+    POLL_ERRORS.load(Ordering::Relaxed)
+}
+
 /// Service to monitor GitHub PRs and update task status when they are merged
 pub struct PrMonitorService {
     db: DBService,
@@ -68,6 +82,7 @@ impl PrMonitorService {
         loop {
             interval.tick().await;
             if let Err(e) = self.check_all_open_prs().await {
+                POLL_ERRORS.fetch_add(1, Ordering::Relaxed);
                 error!("Error checking open PRs: {}", e);
             }
         }
@@ -90,6 +105,7 @@ impl PrMonitorService {
                     warn!("No GitHub token configured, cannot check PR status");
                 }
                 Err(e) => {
+                    POLL_ERRORS.fetch_add(1, Ordering::Relaxed);
                     error!(
                         "Error checking PR #{} for attempt {}: {}",
                         pr_merge.pr_info.number, pr_merge.task_attempt_id, e
@@ -139,7 +155,14 @@ impl PrMonitorService {
                     "PR #{} was merged, updating task {} to done",
                     pr_merge.pr_info.number, task_attempt.task_id
                 );
-                Task::update_status(&self.db.pool, task_attempt.task_id, TaskStatus::Done).await?;
+                Task::transition_status(
+                    &self.db.pool,
+                    task_attempt.task_id,
+                    TaskStatus::Done,
+                    AuditActor::PrMonitor,
+                    Some(task_attempt.id),
+                )
+                .await?;
 
                 // Track analytics event
                 if let Some(analytics) = &self.analytics
@@ -0,0 +1,351 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use aes_gcm::{
+    Aes256Gcm, Key, Nonce,
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::RwLock;
+use ts_rs::TS;
+use utils::{
+    assets::{project_env_key_path, project_env_path},
+    redact::Redactor,
+};
+use uuid::Uuid;
+
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+
+#[derive(Debug, Error)]
+pub enum ProjectEnvError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Project environment store is unreadable: {0}")]
+    Crypto(String),
+    #[error("Invalid environment variable: {0}")]
+    Validation(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredEnvVar {
+    key: String,
+    value: String,
+    secret: bool,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ProjectEnvVar {
+    pub key: String,
+    /// Always null for secrets
+    pub value: Option<String>,
+    pub secret: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct ProjectEnvVarInput {
+    pub key: String,
+    /// Leave null on an existing secret to keep its current value
+    pub value: Option<String>,
+    pub secret: bool,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateProjectEnv {
+    pub vars: Vec<ProjectEnvVarInput>,
+}
+
+/// Per-project environment variables and secrets, kept AES-256-GCM encrypted in the asset dir
+/// and injected into every process started for the project's attempts.
+#[derive(Clone)]
+pub struct ProjectEnvService {
+    path: PathBuf,
+    cipher: Aes256Gcm,
+    vars: Arc<RwLock<HashMap<Uuid, Vec<StoredEnvVar>>>>,
+}
+
+impl ProjectEnvService {
+    pub fn new() -> Result<Self, ProjectEnvError> {
+        Self::open(project_env_path(), &project_env_key_path())
+    }
+
+    fn open(path: PathBuf, key_path: &Path) -> Result<Self, ProjectEnvError> {
+        let cipher = Aes256Gcm::new(&load_or_create_key(key_path)?);
+        let vars = if path.exists() {
+            let data = fs::read(&path)?;
+            if data.len() < NONCE_LEN {
+                return Err(ProjectEnvError::Crypto("file is truncated".to_string()));
+            }
+            let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+            let plaintext = cipher
+                .decrypt(Nonce::from_slice(nonce), ciphertext)
+                .map_err(|e| ProjectEnvError::Crypto(e.to_string()))?;
+            serde_json::from_slice(&plaintext)?
+        } else {
+            HashMap::new()
+        };
+
+        Ok(Self {
+            path,
+            cipher,
+            vars: Arc::new(RwLock::new(vars)),
+        })
+    }
+
+    fn persist(&self, vars: &HashMap<Uuid, Vec<StoredEnvVar>>) -> Result<(), ProjectEnvError> {
+        let plaintext = serde_json::to_vec(vars)?;
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext.as_ref())
+            .map_err(|e| ProjectEnvError::Crypto(e.to_string()))?;
+
+        let mut data = nonce.to_vec();
+        data.extend_from_slice(&ciphertext);
+        let tmp_path = self.path.with_extension("enc.tmp");
+        fs::write(&tmp_path, data)?;
+        fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+
+    pub async fn list(&self, project_id: Uuid) -> Vec<ProjectEnvVar> {
+        self.vars
+            .read()
+            .await
+            .get(&project_id)
+            .map(|vars| {
+                vars.iter()
+                    .map(|v| ProjectEnvVar {
+                        key: v.key.clone(),
+                        value: (!v.secret).then(|| v.value.clone()),
+                        secret: v.secret,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Replace the project's variables. Secrets sent without a value keep their stored value.
+    pub async fn update(
+        &self,
+        project_id: Uuid,
+        inputs: Vec<ProjectEnvVarInput>,
+    ) -> Result<Vec<ProjectEnvVar>, ProjectEnvError> {
+        {
+            let mut vars = self.vars.write().await;
+            let existing = vars.get(&project_id).cloned().unwrap_or_default();
+
+            let mut seen = HashSet::new();
+            let mut updated = Vec::with_capacity(inputs.len());
+            for input in inputs {
+                let key = input.key.trim().to_string();
+                validate_key(&key)?;
+                if !seen.insert(key.clone()) {
+                    return Err(ProjectEnvError::Validation(format!(
+                        "{key} is defined more than once"
+                    )));
+                }
+
+                let value = match input.value {
+                    Some(value) => value,
+                    None => existing
+                        .iter()
+                        .find(|v| v.key == key && v.secret && input.secret)
+                        .map(|v| v.value.clone())
+                        .ok_or_else(|| {
+                            ProjectEnvError::Validation(format!("{key} is missing a value"))
+                        })?,
+                };
+
+                updated.push(StoredEnvVar {
+                    key,
+                    value,
+                    secret: input.secret,
+                });
+            }
+
+            let mut next = vars.clone();
+            if updated.is_empty() {
+                next.remove(&project_id);
+            } else {
+                next.insert(project_id, updated);
+            }
+            self.persist(&next)?;
+            *vars = next;
+        }
+
+        Ok(self.list(project_id).await)
+    }
+
+    pub async fn remove_project(&self, project_id: Uuid) -> Result<(), ProjectEnvError> {
+        let mut vars = self.vars.write().await;
+        if vars.contains_key(&project_id) {
+            let mut next = vars.clone();
+            next.remove(&project_id);
+            self.persist(&next)?;
+            *vars = next;
+        }
+        Ok(())
+    }
+
+    /// Variables to set on processes started for the project
+    pub async fn env_for_project(&self, project_id: Uuid) -> HashMap<String, String> {
+        self.vars
+            .read()
+            .await
+            .get(&project_id)
+            .map(|vars| {
+                vars.iter()
+                    .map(|v| (v.key.clone(), v.value.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Redactor that hides the project's secret values in process output
+    pub async fn redactor_for_project(&self, project_id: Uuid) -> Redactor {
+        let vars = self.vars.read().await;
+        Redactor::new(
+            vars.get(&project_id)
+                .into_iter()
+                .flatten()
+                .filter(|v| v.secret)
+                .map(|v| v.value.clone()),
+        )
+    }
+}
+
+fn validate_key(key: &str) -> Result<(), ProjectEnvError> {
+    let mut chars = key.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(ProjectEnvError::Validation(format!(
+            "'{key}' is not a valid name, use letters, digits and underscores"
+        )))
+    }
+}
+
+fn load_or_create_key(key_path: &Path) -> Result<Key<Aes256Gcm>, ProjectEnvError> {
+    if key_path.exists() {
+        return load_key(key_path);
+    }
+
+    // The file is only readable by the owner from the moment it exists
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = match options.open(key_path) {
+        Ok(file) => file,
+        // Created by someone else in the meantime
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => return load_key(key_path),
+        Err(e) => return Err(e.into()),
+    };
+    let key = Aes256Gcm::generate_key(OsRng);
+    file.write_all(key.as_slice())?;
+    Ok(key)
+}
+
+fn load_key(key_path: &Path) -> Result<Key<Aes256Gcm>, ProjectEnvError> {
+    let bytes = fs::read(key_path)?;
+    if bytes.len() != KEY_LEN {
+        return Err(ProjectEnvError::Crypto(format!(
+            "key file {} is invalid",
+            key_path.display()
+        )));
+    }
+    Ok(*Key::<Aes256Gcm>::from_slice(&bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(key: &str, value: Option<&str>, secret: bool) -> ProjectEnvVarInput {
+        ProjectEnvVarInput {
+            key: key.to_string(),
+            value: value.map(str::to_string),
+            secret,
+        }
+    }
+
+    #[tokio::test]
+    async fn secrets_are_encrypted_and_kept_across_updates() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("project_env.enc");
+        let key_path = dir.path().join("project_env.key");
+        let project_id = Uuid::new_v4();
+
+        let service = ProjectEnvService::open(path.clone(), &key_path).unwrap();
+        service
+            .update(
+                project_id,
+                vec![
+                    input("NODE_ENV", Some("test"), false),
+                    input("API_TOKEN", Some("tok-secret-value"), true),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let on_disk = fs::read(&path).unwrap();
+        assert!(!String::from_utf8_lossy(&on_disk).contains("tok-secret-value"));
+
+        // Re-open and update without resending the secret value
+        let service = ProjectEnvService::open(path, &key_path).unwrap();
+        let listed = service
+            .update(
+                project_id,
+                vec![
+                    input("NODE_ENV", Some("ci"), false),
+                    input("API_TOKEN", None, true),
+                ],
+            )
+            .await
+            .unwrap();
+        assert!(listed.iter().all(|v| !v.secret || v.value.is_none()));
+
+        let env = service.env_for_project(project_id).await;
+        assert_eq!(env.get("NODE_ENV").map(String::as_str), Some("ci"));
+        assert_eq!(
+            env.get("API_TOKEN").map(String::as_str),
+            Some("tok-secret-value")
+        );
+        assert_eq!(
+            service
+                .redactor_for_project(project_id)
+                .await
+                .redact("token=tok-secret-value"),
+            "token=[REDACTED]"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn the_key_file_is_only_readable_by_its_owner() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let key_path = dir.path().join("project_env.key");
+        let key = load_or_create_key(&key_path).unwrap();
+        let mode = fs::metadata(&key_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(load_or_create_key(&key_path).unwrap(), key);
+    }
+}
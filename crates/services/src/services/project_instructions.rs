@@ -0,0 +1,208 @@
+use std::path::{Component, Path};
+
+use db::models::{
+    project::Project,
+    project_instructions::{
+        ProjectInstructionVersion, ProjectInstructions, UpdateProjectInstructions,
+    },
+    task_attempt::TaskAttempt,
+};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use utils::template::TemplateError;
+
+use crate::services::{git::GitService, prompt_template};
+
+#[derive(Debug, Error)]
+pub enum ProjectInstructionsError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Template(#[from] TemplateError),
+    #[error("Invalid sync path: {0}")]
+    InvalidSyncPath(String),
+}
+
+/// Check instructions before they are saved
+pub fn validate(data: &UpdateProjectInstructions) -> Result<(), ProjectInstructionsError> {
+    prompt_template::validate(&data.content)?;
+    if let Some(sync_path) = &data.sync_path {
+        let path = Path::new(sync_path);
+        let inside_repo = !sync_path.trim().is_empty()
+            && path
+                .components()
+                .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+        if !inside_repo {
+            return Err(ProjectInstructionsError::InvalidSyncPath(format!(
+                "{sync_path} must be a path inside the repository"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// The stored content followed by the sync file as it is in `repo_dir`. The file is taken as
+/// written, it is never rendered as a template.
+pub fn resolve(instructions: &ProjectInstructions, repo_dir: &Path) -> String {
+    let synced = instructions.sync_path.as_deref().and_then(|sync_path| {
+        std::fs::read_to_string(repo_dir.join(sync_path))
+            .map_err(|e| {
+                tracing::warn!(
+                    "Failed to read instructions from {} in {}: {}",
+                    sync_path,
+                    repo_dir.display(),
+                    e
+                )
+            })
+            .ok()
+    });
+    [Some(instructions.content.as_str()), synced.as_deref()]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// The instructions the agents of an attempt get. The first time an attempt has instructions,
+/// the project's current ones are recorded as a version and pinned to the attempt, so follow-ups
+/// get the same text as the initial prompt even if the project's instructions change. The stored
+/// content is rendered with the attempt's variables before the sync file is added.
+pub async fn for_attempt(
+    pool: &SqlitePool,
+    git: &GitService,
+    attempt: &TaskAttempt,
+) -> Result<Option<ProjectInstructionVersion>, sqlx::Error> {
+    if let Some(version) = ProjectInstructionVersion::find_for_attempt(pool, attempt.id).await? {
+        return Ok(Some(version));
+    }
+
+    let task = attempt
+        .parent_task(pool)
+        .await?
+        .ok_or(sqlx::Error::RowNotFound)?;
+    let project = Project::find_by_id(pool, task.project_id)
+        .await?
+        .ok_or(sqlx::Error::RowNotFound)?;
+    let instructions = ProjectInstructions::find_by_project_id(pool, project.id).await?;
+
+    // The sync file is read from the attempt's worktree, which is what its agent works on
+    let repo_dir = attempt
+        .container_ref
+        .as_deref()
+        .map(Path::new)
+        .unwrap_or(&project.git_repo_path);
+    let vars =
+        prompt_template::context_for_attempt(pool, git, attempt, &[&instructions.content]).await?;
+    let rendered = ProjectInstructions {
+        content: utils::template::render(&instructions.content, &vars),
+        ..instructions
+    };
+    let content = resolve(&rendered, repo_dir);
+    if content.is_empty() {
+        return Ok(None);
+    }
+
+    let version = ProjectInstructionVersion::record(pool, project.id, &content).await?;
+    version.pin_to_attempt(pool, attempt.id).await?;
+    ProjectInstructionVersion::find_for_attempt(pool, attempt.id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use db::models::project::CreateProject;
+
+    use super::*;
+
+    fn update(content: &str, sync_path: Option<&str>) -> UpdateProjectInstructions {
+        UpdateProjectInstructions {
+            content: content.to_string(),
+            sync_path: sync_path.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn sync_path_must_stay_inside_the_repository() {
+        assert!(validate(&update("", Some("AGENTS.md"))).is_ok());
+        assert!(validate(&update("", Some("docs/agents.md"))).is_ok());
+        assert!(validate(&update("", Some("../secrets.md"))).is_err());
+        assert!(validate(&update("", Some("/etc/passwd"))).is_err());
+        assert!(validate(&update("", Some(" "))).is_err());
+    }
+
+    #[test]
+    fn content_must_only_use_known_variables() {
+        assert!(validate(&update("Work on {{task.title}}", None)).is_ok());
+        assert!(validate(&update("{{task.nope}}", None)).is_err());
+    }
+
+    #[test]
+    fn resolve_appends_the_sync_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("AGENTS.md"), "Run the tests.\n").unwrap();
+        let instructions = ProjectInstructions {
+            project_id: uuid::Uuid::new_v4(),
+            content: "Use tabs.\n".to_string(),
+            sync_path: Some("AGENTS.md".to_string()),
+        };
+        assert_eq!(
+            resolve(&instructions, dir.path()),
+            "Use tabs.\n\nRun the tests."
+        );
+    }
+
+    #[tokio::test]
+    async fn for_attempt_renders_the_content_but_not_the_sync_file() {
+        let db = db::DBService::new_in_memory().await.unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("AGENTS.md"), "Keep {{task.title}} as is.\n").unwrap();
+        let (project_id, task_id, attempt_id) = (
+            uuid::Uuid::new_v4(),
+            uuid::Uuid::new_v4(),
+            uuid::Uuid::new_v4(),
+        );
+        Project::create(
+            &db.pool,
+            &CreateProject::from_name_repo_path(
+                "p".to_string(),
+                dir.path().to_string_lossy().to_string(),
+            ),
+            project_id,
+        )
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO tasks (id, project_id, title) VALUES (?, ?, 'Fix login')")
+            .bind(task_id)
+            .bind(project_id)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO task_attempts (id, task_id) VALUES (?, ?)")
+            .bind(attempt_id)
+            .bind(task_id)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        ProjectInstructions::upsert(
+            &db.pool,
+            project_id,
+            &update("Work on {{task.title}}.", Some("AGENTS.md")),
+        )
+        .await
+        .unwrap();
+
+        let attempt = TaskAttempt::find_by_id(&db.pool, attempt_id)
+            .await
+            .unwrap()
+            .unwrap();
+        let version = for_attempt(&db.pool, &GitService::new(), &attempt)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            version.content,
+            "Work on Fix login.\n\nKeep {{task.title}} as is."
+        );
+    }
+}
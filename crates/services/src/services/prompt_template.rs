@@ -0,0 +1,239 @@
+use std::path::Path;
+
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    execution_process_logs::ExecutionProcessLogs,
+    project::Project,
+    task::Task,
+    task_attempt::TaskAttempt,
+};
+use serde::Serialize;
+use serde_json::{Value, json};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use utils::{
+    diff::compute_line_change_counts,
+    log_msg::LogMsg,
+    template::{Template, TemplateError},
+};
+
+use crate::services::git::{DiffTarget, GitService};
+
+/// Log entries of the verification run available as `verify.output`
+const VERIFY_OUTPUT_ENTRIES: i64 = 200;
+
+/// A variable prompt templates can use
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct TemplateVariable {
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+const fn var(name: &'static str, description: &'static str) -> TemplateVariable {
+    TemplateVariable { name, description }
+}
+
+pub const TEMPLATE_VARIABLES: &[TemplateVariable] = &[
+    var("task.id", "ID of the task"),
+    var("task.title", "Title of the task"),
+    var(
+        "task.description",
+        "Description of the task, empty if it has none",
+    ),
+    var("task.status", "Status of the task, e.g. inprogress"),
+    var("project.id", "ID of the project"),
+    var("project.name", "Name of the project"),
+    var("project.git_repo_path", "Path of the project's repository"),
+    var("attempt.id", "ID of the task attempt"),
+    var("attempt.branch", "Branch the attempt works on"),
+    var("attempt.target_branch", "Branch the attempt merges into"),
+    var(
+        "attempt.executor",
+        "Coding agent of the attempt, e.g. CLAUDE_CODE",
+    ),
+    var("diff.files", "Paths changed by the attempt, one per line"),
+    var("diff.count", "Number of changed files"),
+    var("diff.additions", "Lines added by the attempt"),
+    var("diff.deletions", "Lines removed by the attempt"),
+    var(
+        "verify.status",
+        "Status of the latest cleanup script run, e.g. completed or failed",
+    ),
+    var(
+        "verify.exit_code",
+        "Exit code of the latest cleanup script run",
+    ),
+    var(
+        "verify.passed",
+        "Whether the latest cleanup script run exited with 0",
+    ),
+    var(
+        "verify.output",
+        "End of the output of the latest cleanup script run",
+    ),
+];
+
+/// Parse `source` and check it only uses known variables
+pub fn validate(source: &str) -> Result<Template, TemplateError> {
+    let template = Template::parse(source)?;
+    let known: Vec<&str> = TEMPLATE_VARIABLES.iter().map(|v| v.name).collect();
+    template.check_variables(&known)?;
+    Ok(template)
+}
+
+/// Whether any of `sources` uses a variable under `prefix`, e.g. `diff.`
+fn uses(sources: &[&str], prefix: &str) -> bool {
+    sources.iter().any(|source| {
+        Template::parse(source)
+            .map(|template| template.variables().iter().any(|v| v.starts_with(prefix)))
+            .unwrap_or(false)
+    })
+}
+
+/// Values of the template variables for a task attempt. The diff and the verification run are
+/// only looked up when one of `sources` uses them.
+pub async fn context_for_attempt(
+    pool: &SqlitePool,
+    git: &GitService,
+    attempt: &TaskAttempt,
+    sources: &[&str],
+) -> Result<Value, sqlx::Error> {
+    let task = Task::find_by_id(pool, attempt.task_id)
+        .await?
+        .ok_or(sqlx::Error::RowNotFound)?;
+    let project = Project::find_by_id(pool, task.project_id)
+        .await?
+        .ok_or(sqlx::Error::RowNotFound)?;
+
+    let mut context = json!({
+        "task": {
+            "id": task.id,
+            "title": task.title,
+            "description": task.description.clone().unwrap_or_default(),
+            "status": task.status,
+        },
+        "project": {
+            "id": project.id,
+            "name": project.name,
+            "git_repo_path": project.git_repo_path,
+        },
+        "attempt": {
+            "id": attempt.id,
+            "branch": attempt.branch,
+            "target_branch": attempt.target_branch,
+            "executor": attempt.executor,
+        },
+    });
+
+    if uses(sources, "diff.") {
+        context["diff"] = diff_context(git, &project.git_repo_path, attempt);
+    }
+    if uses(sources, "verify.") {
+        context["verify"] = verify_context(pool, attempt).await;
+    }
+    Ok(context)
+}
+
+/// Render `source` against the context of `attempt`, leaving it unchanged if that fails
+pub async fn render_for_attempt(
+    pool: &SqlitePool,
+    git: &GitService,
+    attempt: &TaskAttempt,
+    source: &str,
+) -> String {
+    match context_for_attempt(pool, git, attempt, &[source]).await {
+        Ok(context) => utils::template::render(source, &context),
+        Err(e) => {
+            tracing::warn!("Failed to load template context for {}: {}", attempt.id, e);
+            source.to_string()
+        }
+    }
+}
+
+fn diff_context(git: &GitService, repo_path: &Path, attempt: &TaskAttempt) -> Value {
+    let diffs = attempt.container_ref.as_deref().and_then(|worktree| {
+        let base_commit = git
+            .get_base_commit(repo_path, &attempt.branch, &attempt.target_branch)
+            .ok()?;
+        git.get_diffs(
+            DiffTarget::Worktree {
+                worktree_path: Path::new(worktree),
+                base_commit: &base_commit,
+            },
+            None,
+        )
+        .map_err(|e| {
+            tracing::warn!(
+                "Failed to diff attempt {} for a template: {}",
+                attempt.id,
+                e
+            )
+        })
+        .ok()
+    });
+
+    let (mut files, mut additions, mut deletions) = (Vec::new(), 0, 0);
+    for diff in diffs.unwrap_or_default() {
+        let (added, removed) = match (diff.additions, diff.deletions) {
+            (Some(added), Some(removed)) => (added, removed),
+            _ => compute_line_change_counts(
+                diff.old_content.as_deref().unwrap_or(""),
+                diff.new_content.as_deref().unwrap_or(""),
+            ),
+        };
+        additions += added;
+        deletions += removed;
+        if let Some(path) = diff.new_path.or(diff.old_path) {
+            files.push(path);
+        }
+    }
+    json!({
+        "count": files.len(),
+        "files": files,
+        "additions": additions,
+        "deletions": deletions,
+    })
+}
+
+/// The latest cleanup script run, which is where projects run their checks after the agent
+async fn verify_context(pool: &SqlitePool, attempt: &TaskAttempt) -> Value {
+    let process = match ExecutionProcess::find_latest_by_task_attempt_and_run_reason(
+        pool,
+        attempt.id,
+        &ExecutionProcessRunReason::CleanupScript,
+    )
+    .await
+    {
+        Ok(Some(process)) => process,
+        Ok(None) => {
+            return json!({ "status": "", "exit_code": null, "passed": false, "output": "" });
+        }
+        Err(e) => {
+            tracing::warn!("Failed to load the cleanup run of {}: {}", attempt.id, e);
+            return json!({ "status": "", "exit_code": null, "passed": false, "output": "" });
+        }
+    };
+
+    let output =
+        match ExecutionProcessLogs::read_tail(pool, process.id, VERIFY_OUTPUT_ENTRIES).await {
+            Ok(page) => page
+                .entries
+                .into_iter()
+                .filter_map(|entry| match entry.msg {
+                    LogMsg::Stdout(text) | LogMsg::Stderr(text) => Some(text),
+                    _ => None,
+                })
+                .collect::<String>(),
+            Err(e) => {
+                tracing::warn!("Failed to read the output of {}: {}", process.id, e);
+                String::new()
+            }
+        };
+
+    json!({
+        "status": process.status,
+        "exit_code": process.exit_code,
+        "passed": process.status == ExecutionProcessStatus::Completed && process.exit_code == Some(0),
+        "output": output,
+    })
+}
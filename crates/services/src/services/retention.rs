@@ -0,0 +1,201 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Duration, Utc};
+use db::models::execution_process_logs::{ExecutionProcessLogs, LogPruneReason, StoredLogUsage};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::services::config::RetentionConfig;
+
+/// Raw logs the retention policy removes, or would remove on a dry run
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct RetentionReport {
+    pub dry_run: bool,
+    pub candidates: Vec<RetentionCandidate>,
+    /// Database bytes freed by removing every candidate
+    pub total_bytes: i64,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct RetentionCandidate {
+    pub execution_id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub project_id: Uuid,
+    pub reason: LogPruneReason,
+    pub stored_size: i64,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+pub struct RetentionService;
+
+impl RetentionService {
+    /// Work out which raw logs the policy removes, without touching them
+    pub async fn plan(
+        pool: &SqlitePool,
+        config: &RetentionConfig,
+    ) -> Result<RetentionReport, sqlx::Error> {
+        let usage = ExecutionProcessLogs::find_stored_usage(pool).await?;
+        let candidates = plan_candidates(usage, config, Utc::now());
+        Ok(RetentionReport {
+            dry_run: true,
+            total_bytes: candidates.iter().map(|c| c.stored_size).sum(),
+            candidates,
+        })
+    }
+
+    /// Apply the policy. Raw logs are deleted while their summary and stored conversation are
+    /// kept. With `dry_run` this is the same as [`Self::plan`].
+    pub async fn run(
+        pool: &SqlitePool,
+        config: &RetentionConfig,
+        dry_run: bool,
+    ) -> Result<RetentionReport, sqlx::Error> {
+        let mut report = Self::plan(pool, config).await?;
+        if dry_run {
+            return Ok(report);
+        }
+
+        let mut pruned = Vec::with_capacity(report.candidates.len());
+        for candidate in report.candidates {
+            // Logs of a process without a stored conversation are kept
+            if ExecutionProcessLogs::prune(pool, candidate.execution_id, candidate.reason).await? {
+                pruned.push(candidate);
+            }
+        }
+        report.total_bytes = pruned.iter().map(|c| c.stored_size).sum();
+        report.candidates = pruned;
+        if !report.candidates.is_empty() {
+            tracing::info!(
+                "Log retention removed logs of {} execution processes ({} bytes)",
+                report.candidates.len(),
+                report.total_bytes
+            );
+        }
+        report.dry_run = false;
+        Ok(report)
+    }
+}
+
+/// `usage` is ordered oldest first, so quota candidates are always the oldest logs of a project
+fn plan_candidates(
+    usage: Vec<StoredLogUsage>,
+    config: &RetentionConfig,
+    now: DateTime<Utc>,
+) -> Vec<RetentionCandidate> {
+    let cutoff = config
+        .log_retention_days
+        .map(|days| now - Duration::days(days as i64));
+
+    let reason_for = |row: &StoredLogUsage| {
+        if row.dropped && config.drop_logs_of_dropped_processes {
+            Some(LogPruneReason::Dropped)
+        } else if let (Some(cutoff), Some(completed_at)) = (cutoff, row.completed_at)
+            && completed_at < cutoff
+        {
+            Some(LogPruneReason::Expired)
+        } else {
+            None
+        }
+    };
+
+    let mut candidates = Vec::new();
+    let mut kept = Vec::new();
+    for row in usage {
+        match reason_for(&row) {
+            Some(reason) => candidates.push(candidate(&row, reason)),
+            None => kept.push(row),
+        }
+    }
+
+    if let Some(max_mb) = config.max_log_storage_mb_per_project {
+        let max_bytes = (max_mb as i64).saturating_mul(1024 * 1024);
+        let mut project_bytes: HashMap<Uuid, i64> = HashMap::new();
+        for row in &kept {
+            *project_bytes.entry(row.project_id).or_default() += row.stored_size;
+        }
+        let over_quota: HashSet<Uuid> = project_bytes
+            .iter()
+            .filter(|(_, bytes)| **bytes > max_bytes)
+            .map(|(id, _)| *id)
+            .collect();
+
+        for row in kept.iter().filter(|r| over_quota.contains(&r.project_id)) {
+            let bytes = project_bytes.get_mut(&row.project_id).unwrap();
+            if *bytes <= max_bytes {
+                continue;
+            }
+            *bytes -= row.stored_size;
+            candidates.push(candidate(row, LogPruneReason::Quota));
+        }
+    }
+
+    candidates
+}
+
+fn candidate(row: &StoredLogUsage, reason: LogPruneReason) -> RetentionCandidate {
+    RetentionCandidate {
+        execution_id: row.execution_id,
+        task_attempt_id: row.task_attempt_id,
+        project_id: row.project_id,
+        reason,
+        stored_size: row.stored_size,
+        completed_at: row.completed_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(project_id: Uuid, days_ago: i64, dropped: bool, mb: i64) -> StoredLogUsage {
+        StoredLogUsage {
+            execution_id: Uuid::new_v4(),
+            task_attempt_id: Uuid::new_v4(),
+            project_id,
+            dropped,
+            completed_at: Some(Utc::now() - Duration::days(days_ago)),
+            stored_size: mb * 1024 * 1024,
+        }
+    }
+
+    #[test]
+    fn default_policy_keeps_everything() {
+        let project = Uuid::new_v4();
+        let rows = vec![usage(project, 400, true, 10), usage(project, 1, false, 10)];
+        assert!(plan_candidates(rows, &RetentionConfig::default(), Utc::now()).is_empty());
+    }
+
+    #[test]
+    fn quota_removes_oldest_logs_first() {
+        let project = Uuid::new_v4();
+        let other = Uuid::new_v4();
+        let rows = vec![
+            usage(project, 40, true, 1),
+            usage(project, 20, false, 4),
+            usage(project, 10, false, 4),
+            usage(other, 9, false, 4),
+            usage(project, 5, false, 4),
+        ];
+        let ids: Vec<Uuid> = rows.iter().map(|r| r.execution_id).collect();
+        let config = RetentionConfig {
+            log_retention_days: Some(30),
+            drop_logs_of_dropped_processes: false,
+            max_log_storage_mb_per_project: Some(8),
+        };
+
+        let candidates = plan_candidates(rows, &config, Utc::now());
+        let planned: Vec<(Uuid, LogPruneReason)> = candidates
+            .iter()
+            .map(|c| (c.execution_id, c.reason))
+            .collect();
+        assert_eq!(
+            planned,
+            vec![
+                (ids[0], LogPruneReason::Expired),
+                (ids[1], LogPruneReason::Quota),
+            ]
+        );
+    }
+}
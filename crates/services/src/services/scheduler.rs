@@ -0,0 +1,421 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Local, Utc};
+use db::{
+    DBService,
+    models::{
+        project::Project,
+        tag::Tag,
+        task::{CreateTask, Task},
+        task_attempt::{CreateTaskAttempt, TaskAttempt, TaskAttemptError},
+        task_schedule::{ScheduleCatchUp, ScheduleRunStatus, TaskSchedule, TaskScheduleRun},
+    },
+};
+use sqlx::{SqlitePool, error::Error as SqlxError};
+use thiserror::Error;
+use tokio::time::interval;
+use tracing::{error, info, warn};
+use utils::cron::{CronError, CronSchedule};
+use uuid::Uuid;
+
+use crate::services::{
+    container::{ContainerError, ContainerService},
+    git::GitService,
+    prompt_template,
+};
+
+/// Most missed times a schedule looks back on after downtime
+const MAX_CATCH_UP_RUNS: usize = 10;
+
+/// How late a run may start, in minutes, before it counts as missed
+const MISSED_AFTER_MINUTES: i64 = 5;
+
+#[derive(Debug, Error)]
+pub enum ScheduleError {
+    #[error(transparent)]
+    Database(#[from] SqlxError),
+    #[error(transparent)]
+    Cron(#[from] CronError),
+    #[error(transparent)]
+    TaskAttempt(#[from] TaskAttemptError),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+    #[error(transparent)]
+    Git(#[from] git2::Error),
+    #[error("Cron expression '{0}' never runs")]
+    NeverRuns(String),
+    #[error("Template not found")]
+    TemplateNotFound,
+    #[error("Project not found")]
+    ProjectNotFound,
+}
+
+/// The first time after `after` that `cron` fires, evaluated in the server's local time zone
+pub fn next_run_after(cron: &str, after: DateTime<Utc>) -> Result<DateTime<Utc>, ScheduleError> {
+    next_time(&CronSchedule::parse(cron)?, cron, after)
+}
+
+fn next_time(
+    schedule: &CronSchedule,
+    cron: &str,
+    after: DateTime<Utc>,
+) -> Result<DateTime<Utc>, ScheduleError> {
+    schedule
+        .next_after(&after.with_timezone(&Local))
+        .map(|time| time.with_timezone(&Utc))
+        .ok_or_else(|| ScheduleError::NeverRuns(cron.to_string()))
+}
+
+/// The times the schedule was due at up to `now`, each with whether it runs under the
+/// schedule's catch-up policy, and the time it is due at next
+fn catch_up_plan(
+    schedule: &TaskSchedule,
+    now: DateTime<Utc>,
+) -> Result<(Vec<(DateTime<Utc>, bool)>, DateTime<Utc>), ScheduleError> {
+    let cron = CronSchedule::parse(&schedule.cron)?;
+    let mut due = vec![schedule.next_run_at];
+    let mut next = next_time(&cron, &schedule.cron, schedule.next_run_at)?;
+    while next <= now {
+        due.push(next);
+        if due.len() > MAX_CATCH_UP_RUNS {
+            due.remove(0);
+        }
+        next = next_time(&cron, &schedule.cron, next)?;
+    }
+
+    let latest = due.len() - 1;
+    let plan = due
+        .into_iter()
+        .enumerate()
+        .map(|(index, scheduled_for)| {
+            let run = match schedule.catch_up {
+                ScheduleCatchUp::Skip => {
+                    now - scheduled_for <= chrono::Duration::minutes(MISSED_AFTER_MINUTES)
+                }
+                ScheduleCatchUp::Once => index == latest,
+                ScheduleCatchUp::All => true,
+            };
+            (scheduled_for, run)
+        })
+        .collect();
+    Ok((plan, next))
+}
+
+/// Create the schedule's task for `scheduled_for` and start an attempt on it if the schedule
+/// has an executor profile. Returns None if that time already ran.
+pub async fn run_schedule<C: ContainerService + Sync>(
+    pool: &SqlitePool,
+    container: &C,
+    git: &GitService,
+    schedule: &TaskSchedule,
+    scheduled_for: DateTime<Utc>,
+) -> Result<Option<TaskScheduleRun>, ScheduleError> {
+    let Some(run) =
+        TaskScheduleRun::claim(pool, schedule.id, scheduled_for, ScheduleRunStatus::Pending)
+            .await?
+    else {
+        return Ok(None);
+    };
+
+    let task = match create_task(pool, schedule).await {
+        Ok(task) => task,
+        Err(e) => {
+            warn!("Schedule {} failed to create a task: {}", schedule.id, e);
+            let run = TaskScheduleRun::finish(
+                pool,
+                run.id,
+                ScheduleRunStatus::Failed,
+                None,
+                None,
+                Some(&e.to_string()),
+            )
+            .await?;
+            return Ok(Some(run));
+        }
+    };
+
+    let (status, attempt_id, error) =
+        match start_attempt(pool, container, git, schedule, &task).await {
+            Ok(Some(attempt)) => (ScheduleRunStatus::Started, Some(attempt.id), None),
+            Ok(None) => (ScheduleRunStatus::Created, None, None),
+            Err((attempt_id, e)) => {
+                warn!(
+                    "Schedule {} failed to start an attempt on task {}: {}",
+                    schedule.id, task.id, e
+                );
+                (ScheduleRunStatus::Failed, attempt_id, Some(e.to_string()))
+            }
+        };
+    let run = TaskScheduleRun::finish(
+        pool,
+        run.id,
+        status,
+        Some(task.id),
+        attempt_id,
+        error.as_deref(),
+    )
+    .await?;
+    Ok(Some(run))
+}
+
+async fn create_task(pool: &SqlitePool, schedule: &TaskSchedule) -> Result<Task, ScheduleError> {
+    let template = Tag::find_by_id(pool, schedule.tag_id)
+        .await?
+        .ok_or(ScheduleError::TemplateNotFound)?;
+    let description = Some(template.content).filter(|content| !content.trim().is_empty());
+    let task = Task::create(
+        pool,
+        &CreateTask::from_title_description(
+            schedule.project_id,
+            schedule.title.clone(),
+            description,
+        ),
+        Uuid::new_v4(),
+    )
+    .await?;
+    Ok(task)
+}
+
+/// Start an attempt if the schedule asks for one. On failure, also returns the attempt if it
+/// was created.
+async fn start_attempt<C: ContainerService + Sync>(
+    pool: &SqlitePool,
+    container: &C,
+    git: &GitService,
+    schedule: &TaskSchedule,
+    task: &Task,
+) -> Result<Option<TaskAttempt>, (Option<Uuid>, ScheduleError)> {
+    let Some(executor_profile_id) = schedule.executor_profile_id().cloned() else {
+        return Ok(None);
+    };
+
+    let base_branch = match &schedule.base_branch {
+        Some(branch) => branch.clone(),
+        None => {
+            let project = Project::find_by_id(pool, schedule.project_id)
+                .await
+                .map_err(|e| (None, e.into()))?
+                .ok_or((None, ScheduleError::ProjectNotFound))?;
+            git.get_current_branch(&project.git_repo_path)
+                .map_err(|e| (None, e.into()))?
+        }
+    };
+
+    let attempt_id = Uuid::new_v4();
+    let branch = container
+        .git_branch_from_task_attempt(&attempt_id, &task.title)
+        .await;
+    let attempt = TaskAttempt::create(
+        pool,
+        &CreateTaskAttempt {
+            executor: executor_profile_id.executor,
+            base_branch,
+            branch,
+        },
+        attempt_id,
+        task.id,
+    )
+    .await
+    .map_err(|e| (None, e.into()))?;
+
+    // The description is the schedule's template, filled in now that there is an attempt
+    if let Some(description) = &task.description {
+        let rendered = prompt_template::render_for_attempt(pool, git, &attempt, description).await;
+        if &rendered != description {
+            Task::update(
+                pool,
+                task.id,
+                task.project_id,
+                task.title.clone(),
+                Some(rendered),
+                task.status.clone(),
+                task.parent_task_attempt,
+            )
+            .await
+            .map_err(|e| (Some(attempt.id), e.into()))?;
+        }
+    }
+
+    container
+        .start_attempt(&attempt, executor_profile_id)
+        .await
+        .map_err(|e| (Some(attempt.id), e.into()))?;
+    Ok(Some(attempt))
+}
+
+/// Background service that runs due schedules
+pub struct ScheduleService<C> {
+    db: DBService,
+    container: C,
+    git: GitService,
+    poll_interval: Duration,
+}
+
+impl<C: ContainerService + Send + Sync + 'static> ScheduleService<C> {
+    pub async fn spawn(
+        db: DBService,
+        container: C,
+        git: GitService,
+    ) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            db,
+            container,
+            git,
+            poll_interval: Duration::from_secs(30),
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        info!(
+            "Starting task schedule service with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.run_due_schedules().await {
+                error!("Error running task schedules: {}", e);
+            }
+        }
+    }
+
+    async fn run_due_schedules(&self) -> Result<(), ScheduleError> {
+        let now = Utc::now();
+        for schedule in TaskSchedule::find_due(&self.db.pool, now).await? {
+            if let Err(e) = self.catch_up(&schedule, now).await {
+                error!("Error running schedule {}: {}", schedule.id, e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Run or skip the times the schedule was due at up to `now` according to its catch-up
+    /// policy, then move it on to its next time
+    async fn catch_up(
+        &self,
+        schedule: &TaskSchedule,
+        now: DateTime<Utc>,
+    ) -> Result<(), ScheduleError> {
+        let pool = &self.db.pool;
+        let (plan, next) = catch_up_plan(schedule, now)?;
+        let mut last_run_at = None;
+        for (scheduled_for, run) in plan {
+            if run {
+                run_schedule(pool, &self.container, &self.git, schedule, scheduled_for).await?;
+                last_run_at = Some(scheduled_for);
+            } else {
+                TaskScheduleRun::claim(
+                    pool,
+                    schedule.id,
+                    scheduled_for,
+                    ScheduleRunStatus::Skipped,
+                )
+                .await?;
+            }
+        }
+
+        TaskSchedule::advance(pool, schedule.id, next, last_run_at).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    /// A schedule firing every minute that was last due at `next_run_at`
+    fn schedule(catch_up: ScheduleCatchUp, next_run_at: DateTime<Utc>) -> TaskSchedule {
+        TaskSchedule {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            tag_id: Uuid::new_v4(),
+            title: "Nightly".to_string(),
+            cron: "* * * * *".to_string(),
+            executor_profile_id: None,
+            base_branch: None,
+            catch_up,
+            paused: false,
+            next_run_at,
+            last_run_at: None,
+            created_at: next_run_at,
+            updated_at: next_run_at,
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap()
+    }
+
+    fn minutes(n: i64) -> chrono::Duration {
+        chrono::Duration::minutes(n)
+    }
+
+    /// Which of the planned times run, by how many minutes before `now()` they were due
+    fn runs(plan: &[(DateTime<Utc>, bool)]) -> Vec<(i64, bool)> {
+        plan.iter()
+            .map(|(scheduled_for, run)| ((now() - *scheduled_for).num_minutes(), *run))
+            .collect()
+    }
+
+    #[test]
+    fn a_schedule_due_on_time_runs_once() {
+        for catch_up in [
+            ScheduleCatchUp::Skip,
+            ScheduleCatchUp::Once,
+            ScheduleCatchUp::All,
+        ] {
+            let (plan, next) = catch_up_plan(&schedule(catch_up, now()), now()).unwrap();
+            assert_eq!(runs(&plan), vec![(0, true)]);
+            assert_eq!(next, now() + minutes(1));
+        }
+    }
+
+    #[test]
+    fn missed_times_are_limited_and_run_by_catch_up_policy() {
+        let missed_since = now() - minutes(30);
+
+        let (plan, next) =
+            catch_up_plan(&schedule(ScheduleCatchUp::All, missed_since), now()).unwrap();
+        assert_eq!(next, now() + minutes(1));
+        assert_eq!(plan.len(), MAX_CATCH_UP_RUNS);
+        assert_eq!(
+            runs(&plan),
+            (0..10).rev().map(|m| (m, true)).collect::<Vec<_>>()
+        );
+
+        let (plan, _) =
+            catch_up_plan(&schedule(ScheduleCatchUp::Once, missed_since), now()).unwrap();
+        assert_eq!(
+            runs(&plan),
+            (0..10).rev().map(|m| (m, m == 0)).collect::<Vec<_>>()
+        );
+
+        // Only times that are at most MISSED_AFTER_MINUTES late still run
+        let (plan, _) =
+            catch_up_plan(&schedule(ScheduleCatchUp::Skip, missed_since), now()).unwrap();
+        assert_eq!(
+            runs(&plan),
+            (0..10)
+                .rev()
+                .map(|m| (m, m <= MISSED_AFTER_MINUTES))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn invalid_cron_expressions_are_errors() {
+        let mut schedule = schedule(ScheduleCatchUp::Once, now());
+        schedule.cron = "every day".to_string();
+        assert!(matches!(
+            catch_up_plan(&schedule, now()),
+            Err(ScheduleError::Cron(_))
+        ));
+    }
+}
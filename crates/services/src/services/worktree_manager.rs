@@ -20,6 +20,13 @@ lazy_static::lazy_static! {
         Arc::new(Mutex::new(HashMap::new()));
 }
 
+/// Worktrees on disk under the worktree base directory
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WorktreeUsage {
+    pub count: u64,
+    pub bytes: u64,
+}
+
 #[derive(Debug, Error)]
 pub enum WorktreeError {
     #[error(transparent)]
@@ -44,6 +51,7 @@ pub struct WorktreeManager;
 
 impl WorktreeManager {
     /// Create a worktree with a new branch
+    #[tracing::instrument(name = "git.worktree_create", skip(repo_path, worktree_path))]
     pub async fn create_worktree(
         repo_path: &Path,
         branch_name: &str,
@@ -482,4 +490,48 @@ impl WorktreeManager {
     pub fn get_worktree_base_dir() -> std::path::PathBuf {
         utils::path::get_vibe_kanban_temp_dir().join("worktrees")
     }
+
+    /// Count the worktree directories and add up their size. Symlinks are not followed.
+    pub async fn usage() -> Result<WorktreeUsage, WorktreeError> {
+        let base_dir = Self::get_worktree_base_dir();
+        tokio::task::spawn_blocking(move || {
+            let mut usage = WorktreeUsage::default();
+            let entries = match std::fs::read_dir(&base_dir) {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(usage),
+                Err(e) => return Err(WorktreeError::Io(e)),
+            };
+            for entry in entries.flatten() {
+                if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                    usage.count += 1;
+                    usage.bytes += dir_size(&entry.path());
+                }
+            }
+            Ok(usage)
+        })
+        .await
+        .map_err(|e| WorktreeError::TaskJoin(format!("{e}")))?
+    }
+}
+
+/// Apparent size of the files under `path`, skipping anything unreadable
+fn dir_size(path: &Path) -> u64 {
+    let mut total = 0;
+    let mut pending = vec![path.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.path().symlink_metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else {
+                total += metadata.len();
+            }
+        }
+    }
+    total
 }
@@ -32,6 +32,14 @@ pub fn profiles_path() -> std::path::PathBuf {
     asset_dir().join("profiles.json")
 }
 
+pub fn project_env_path() -> std::path::PathBuf {
+    asset_dir().join("project_env.enc")
+}
+
+pub fn project_env_key_path() -> std::path::PathBuf {
+    asset_dir().join("project_env.key")
+}
+
 #[derive(RustEmbed)]
 #[folder = "../../assets/sounds"]
 pub struct SoundAssets;
@@ -0,0 +1,6 @@
+//! Environment variables set on every process started for a task attempt, so tools the agent
+//! launches (like the MCP task server) know which project, task and attempt they work for.
+
+pub const PROJECT_ID_ENV: &str = "VIBE_PROJECT_ID";
+pub const TASK_ID_ENV: &str = "VIBE_TASK_ID";
+pub const TASK_ATTEMPT_ID_ENV: &str = "VIBE_TASK_ATTEMPT_ID";
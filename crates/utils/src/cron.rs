@@ -0,0 +1,268 @@
+//! Five field cron expressions (`minute hour day-of-month month day-of-week`)
+
+use std::{fmt, str::FromStr};
+
+use chrono::{
+    DateTime, Datelike, Duration, LocalResult, NaiveDate, NaiveDateTime, TimeZone, Timelike,
+};
+
+/// How far ahead to look for the next run before giving up, e.g. on `0 0 30 2 *`
+const MAX_LOOKAHEAD_DAYS: i64 = 366 * 5;
+
+const MONTH_NAMES: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const DAY_NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronError(String);
+
+impl fmt::Display for CronError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid cron expression: {}", self.0)
+    }
+}
+
+impl std::error::Error for CronError {}
+
+/// A parsed cron expression. Besides the five fields, `@hourly`, `@daily`, `@weekly`,
+/// `@monthly` and `@yearly` are accepted. As in cron, when both the day of month and the day of
+/// week are restricted, a day matching either runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u32,
+    days_of_month: u32,
+    months: u16,
+    days_of_week: u8,
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+impl FromStr for CronSchedule {
+    type Err = CronError;
+
+    fn from_str(expr: &str) -> Result<Self, Self::Err> {
+        Self::parse(expr)
+    }
+}
+
+impl CronSchedule {
+    pub fn parse(expr: &str) -> Result<Self, CronError> {
+        let expanded = match expr.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(CronError(format!(
+                "expected 5 fields, got {} in '{expr}'",
+                fields.len()
+            )));
+        };
+
+        // Day of week 7 is another name for Sunday
+        let days_of_week = parse_field(day_of_week, 0, 7, &DAY_NAMES)?;
+        let days_of_week = ((days_of_week | (days_of_week >> 7)) & 0x7f) as u8;
+
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59, &[])?,
+            hours: parse_field(hour, 0, 23, &[])? as u32,
+            days_of_month: parse_field(day_of_month, 1, 31, &[])? as u32,
+            months: parse_field(month, 1, 12, &MONTH_NAMES)? as u16,
+            days_of_week,
+            any_day_of_month: day_of_month == "*",
+            any_day_of_week: day_of_week == "*",
+        })
+    }
+
+    /// The first time after `after` the schedule fires, in the time zone of `after`. Times that
+    /// don't exist because of a daylight saving change are skipped.
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let tz = after.timezone();
+        let local = after.naive_local();
+        let mut candidate = local.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = local + Duration::days(MAX_LOOKAHEAD_DAYS);
+
+        while candidate <= limit {
+            if !bit(self.months as u64, candidate.month()) {
+                let (year, month) = match candidate.month() {
+                    12 => (candidate.year() + 1, 1),
+                    month => (candidate.year(), month + 1),
+                };
+                candidate = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !self.matches_day(&candidate) {
+                candidate = (candidate.date() + Duration::days(1)).and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !bit(self.hours as u64, candidate.hour()) {
+                candidate = candidate.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if !bit(self.minutes, candidate.minute()) {
+                candidate += Duration::minutes(1);
+                continue;
+            }
+            match tz.from_local_datetime(&candidate) {
+                LocalResult::Single(time) | LocalResult::Ambiguous(time, _) if time > *after => {
+                    return Some(time);
+                }
+                _ => candidate += Duration::minutes(1),
+            }
+        }
+        None
+    }
+
+    fn matches_day(&self, time: &NaiveDateTime) -> bool {
+        let day_of_month = bit(self.days_of_month as u64, time.day());
+        let day_of_week = bit(
+            self.days_of_week as u64,
+            time.weekday().num_days_from_sunday(),
+        );
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (true, true) => true,
+            (false, true) => day_of_month,
+            (true, false) => day_of_week,
+            (false, false) => day_of_month || day_of_week,
+        }
+    }
+}
+
+fn bit(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+/// Bitmask of the values a field matches. `names` are accepted in place of numbers, starting
+/// at `min`.
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, CronError> {
+    let value = |text: &str| -> Result<u32, CronError> {
+        let upper = text.to_ascii_uppercase();
+        let value = match names.iter().position(|name| *name == upper) {
+            Some(index) => index as u32 + min,
+            None => text
+                .parse::<u32>()
+                .map_err(|_| CronError(format!("'{text}' is not a number")))?,
+        };
+        if !(min..=max).contains(&value) {
+            return Err(CronError(format!("{value} is outside {min}-{max}")));
+        }
+        Ok(value)
+    };
+
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step = step
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| CronError(format!("invalid step in '{part}'")))?;
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (value(start)?, value(end)?),
+                // `5/15` means from 5 to the end in steps of 15
+                None if step > 1 => (value(range)?, max),
+                None => {
+                    let single = value(range)?;
+                    (single, single)
+                }
+            },
+        };
+        if start > end {
+            return Err(CronError(format!("range '{range}' runs backwards")));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn at(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn next(expr: &str, after: &str) -> String {
+        CronSchedule::parse(expr)
+            .unwrap()
+            .next_after(&at(after))
+            .unwrap()
+            .to_rfc3339()
+    }
+
+    #[test]
+    fn finds_the_next_matching_minute() {
+        assert_eq!(
+            next("*/15 * * * *", "2025-01-01T10:07:30Z"),
+            "2025-01-01T10:15:00+00:00"
+        );
+        assert_eq!(
+            next("0 9 * * MON", "2025-01-01T10:00:00Z"),
+            "2025-01-06T09:00:00+00:00"
+        );
+        assert_eq!(
+            next("@monthly", "2025-01-31T23:59:00Z"),
+            "2025-02-01T00:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn runs_strictly_after_the_given_time() {
+        assert_eq!(
+            next("0 9 * * *", "2025-01-01T09:00:00Z"),
+            "2025-01-02T09:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn day_of_month_or_day_of_week() {
+        // The 13th or any Friday
+        assert_eq!(
+            next("0 0 13 * 5", "2025-01-01T00:00:00Z"),
+            "2025-01-03T00:00:00+00:00"
+        );
+        assert_eq!(
+            next("0 0 13 * 7", "2025-01-01T00:00:00Z"),
+            "2025-01-05T00:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        for expr in [
+            "* * * *",
+            "60 * * * *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "5-1 * * * *",
+        ] {
+            assert!(CronSchedule::parse(expr).is_err(), "{expr}");
+        }
+        assert!(
+            CronSchedule::parse("0 0 30 2 *")
+                .unwrap()
+                .next_after(&at("2025-01-01T00:00:00Z"))
+                .is_none()
+        );
+    }
+}
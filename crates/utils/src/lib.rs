@@ -4,18 +4,23 @@ use directories::ProjectDirs;
 
 pub mod approvals;
 pub mod assets;
+pub mod attempt_env;
 pub mod browser;
+pub mod cron;
 pub mod diff;
 pub mod git;
 pub mod log_msg;
 pub mod msg_store;
 pub mod path;
 pub mod port_file;
+pub mod redact;
 pub mod response;
 pub mod sentry;
 pub mod shell;
 pub mod stream_ext;
 pub mod stream_lines;
+pub mod telemetry;
+pub mod template;
 pub mod text;
 pub mod version;
 
@@ -1,4 +1,5 @@
 use axum::{extract::ws::Message, response::sse::Event};
+use chrono::{DateTime, Utc};
 use json_patch::Patch;
 use serde::{Deserialize, Serialize};
 
@@ -77,3 +78,46 @@ impl LogMsg {
         }
     }
 }
+
+/// A log message with the time it was captured. Serialized as the message with an extra `at`
+/// key, e.g. `{"at":"...","Stdout":"..."}`, so lines stored before timestamps existed still parse.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TimedLogMsg {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub at: Option<DateTime<Utc>>,
+    #[serde(flatten)]
+    pub msg: LogMsg,
+}
+
+impl TimedLogMsg {
+    pub fn now(msg: LogMsg) -> Self {
+        Self {
+            at: Some(Utc::now()),
+            msg,
+        }
+    }
+}
+
+impl From<LogMsg> for TimedLogMsg {
+    fn from(msg: LogMsg) -> Self {
+        Self { at: None, msg }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timed_log_msg_reads_untimed_lines() {
+        let line: TimedLogMsg = serde_json::from_str(r#"{"Stdout":"hello"}"#).unwrap();
+        assert!(line.at.is_none());
+        assert!(matches!(line.msg, LogMsg::Stdout(s) if s == "hello"));
+
+        let timed = TimedLogMsg::now(LogMsg::Stderr("oops".to_string()));
+        let json = serde_json::to_string(&timed).unwrap();
+        let parsed: TimedLogMsg = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.at, timed.at);
+        assert!(matches!(parsed.msg, LogMsg::Stderr(s) if s == "oops"));
+    }
+}
@@ -1,32 +1,287 @@
 use std::{
-    collections::VecDeque,
-    sync::{Arc, RwLock},
+    cmp::Reverse,
+    collections::{HashMap, VecDeque},
+    fs::File,
+    io::{BufRead, BufReader, Read, Seek, SeekFrom, Write},
+    path::PathBuf,
+    sync::{
+        Arc, LazyLock, Mutex, RwLock, Weak,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
 };
 
 use axum::response::sse::Event;
+use chrono::{DateTime, Utc};
 use futures::{StreamExt, TryStreamExt, future};
-use tokio::{sync::broadcast, task::JoinHandle};
-use tokio_stream::wrappers::BroadcastStream;
+use json_patch::{Patch, PatchOperation};
+use serde_json::Value;
+use tokio::{
+    sync::broadcast::{self, error::RecvError},
+    task::JoinHandle,
+};
+
+use crate::{
+    log_msg::{LogMsg, TimedLogMsg},
+    path::get_vibe_kanban_temp_dir,
+};
+
+/// Overrides the default memory budget shared by all stores, in megabytes
+pub const MEMORY_BUDGET_ENV: &str = "MSG_STORE_MEMORY_MB";
+
+// 256 MB across all stores
+const DEFAULT_MEMORY_BUDGET_MB: usize = 256;
+
+/// Messages read from history per step when a stream replays it
+const REPLAY_CHUNK: usize = 1000;
 
-use crate::{log_msg::LogMsg, stream_lines::LinesStreamExt};
+static MEMORY_BUDGET: LazyLock<usize> = LazyLock::new(|| {
+    let mb = std::env::var(MEMORY_BUDGET_ENV)
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(DEFAULT_MEMORY_BUDGET_MB);
+    mb.saturating_mul(1024 * 1024)
+});
 
-// 100 MB Limit
-const HISTORY_BYTES: usize = 100000 * 1024;
+/// In-memory history bytes of all live stores
+static MEMORY_USED: AtomicUsize = AtomicUsize::new(0);
+
+/// Every live store, so the budget can be enforced by spilling the largest ones
+static STORES: Mutex<Vec<Weak<RwLock<Inner>>>> = Mutex::new(Vec::new());
+
+/// Held while a store enforces the budget, so concurrent pushes don't all spill at once
+static SPILLING: Mutex<()> = Mutex::new(());
+
+/// Process output read from a store, with the time it was captured if that was recorded
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimedOutput {
+    pub at: Option<DateTime<Utc>>,
+    pub text: String,
+}
+
+/// Approximate history bytes held in memory by all stores
+pub fn memory_used() -> usize {
+    MEMORY_USED.load(Ordering::Relaxed)
+}
 
 #[derive(Clone)]
 struct StoredMsg {
-    msg: LogMsg,
+    msg: TimedLogMsg,
     bytes: usize,
 }
 
+/// The oldest part of a store's history, moved to a JSONL file to stay within the budget
+struct Spill {
+    path: PathBuf,
+    file: File,
+    /// Start offset of each spilled message in the file
+    offsets: Vec<u64>,
+    len: u64,
+}
+
 struct Inner {
+    /// Messages after the dropped and spilled ones. Indices count from the first message ever
+    /// pushed, so the message at index `i` is always the same one.
     history: VecDeque<StoredMsg>,
     total_bytes: usize,
+    /// Bounded stores drop their oldest messages instead of spilling them
+    max_bytes: Option<usize>,
+    /// Messages dropped from the front of a bounded store
+    dropped: usize,
+    spill: Option<Spill>,
+    /// Set when the spill file can't be written; the history then stays in memory
+    spill_failed: bool,
+    /// Timestamp given to each conversation entry path, kept when the entry is replaced
+    entry_times: HashMap<String, String>,
+}
+
+impl Inner {
+    fn spilled(&self) -> usize {
+        self.spill.as_ref().map_or(0, |spill| spill.offsets.len())
+    }
+
+    fn len(&self) -> usize {
+        self.dropped + self.spilled() + self.history.len()
+    }
+
+    /// Messages `start..end` of the history that are still kept, reading spilled ones back from
+    /// disk
+    fn range(&self, start: usize, end: usize) -> Vec<TimedLogMsg> {
+        let start = start.saturating_sub(self.dropped);
+        let end = end.min(self.len()).saturating_sub(self.dropped);
+        if start >= end {
+            return Vec::new();
+        }
+        let spilled = self.spilled();
+        let mut out = Vec::with_capacity(end - start);
+        if start < spilled
+            && let Some(spill) = &self.spill
+        {
+            match read_spill(spill, start, end.min(spilled)) {
+                Ok(msgs) => out.extend(msgs),
+                Err(e) => tracing::error!(
+                    "Failed to read spilled logs from {}: {}",
+                    spill.path.display(),
+                    e
+                ),
+            }
+        }
+        out.extend(
+            self.history
+                .range(start.max(spilled) - spilled..end.saturating_sub(spilled))
+                .map(|stored| stored.msg.clone()),
+        );
+        out
+    }
+
+    /// Free at least `want` bytes of memory by moving the oldest messages to the spill file,
+    /// or dropping them for a bounded store. Returns the bytes freed.
+    fn shed(&mut self, want: usize) -> usize {
+        if self.max_bytes.is_some() {
+            let mut freed = 0;
+            while freed < want
+                && let Some(front) = self.history.pop_front()
+            {
+                freed += front.bytes;
+                self.dropped += 1;
+            }
+            self.total_bytes = self.total_bytes.saturating_sub(freed);
+            MEMORY_USED.fetch_sub(freed, Ordering::Relaxed);
+            return freed;
+        }
+        if self.spill_failed || self.history.is_empty() {
+            return 0;
+        }
+        if self.spill.is_none() {
+            match create_spill() {
+                Ok(spill) => self.spill = Some(spill),
+                Err(e) => {
+                    tracing::warn!("Failed to create log spill file, keeping logs in memory: {e}");
+                    self.spill_failed = true;
+                    return 0;
+                }
+            }
+        }
+        let Some(spill) = self.spill.as_mut() else {
+            return 0;
+        };
+
+        let mut buf = Vec::new();
+        let mut offsets = Vec::new();
+        let mut freed = 0;
+        for stored in &self.history {
+            if freed >= want {
+                break;
+            }
+            offsets.push(spill.len + buf.len() as u64);
+            if let Err(e) = serde_json::to_writer(&mut buf, &stored.msg) {
+                tracing::warn!("Failed to serialize log message for spilling: {e}");
+                offsets.pop();
+                break;
+            }
+            buf.push(b'\n');
+            freed += stored.bytes;
+        }
+        if let Err(e) = spill.file.write_all(&buf) {
+            tracing::warn!(
+                "Failed to write log spill file {}, keeping logs in memory: {}",
+                spill.path.display(),
+                e
+            );
+            // Drop the partial write so offsets stay valid
+            let _ = spill.file.set_len(spill.len);
+            self.spill_failed = true;
+            return 0;
+        }
+
+        spill.len += buf.len() as u64;
+        let count = offsets.len();
+        spill.offsets.extend(offsets);
+        self.history.drain(..count);
+        self.total_bytes = self.total_bytes.saturating_sub(freed);
+        MEMORY_USED.fetch_sub(freed, Ordering::Relaxed);
+        freed
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        MEMORY_USED.fetch_sub(self.total_bytes, Ordering::Relaxed);
+        if let Some(spill) = &self.spill {
+            let _ = std::fs::remove_file(&spill.path);
+        }
+    }
+}
+
+fn create_spill() -> std::io::Result<Spill> {
+    let dir = get_vibe_kanban_temp_dir().join("msg-store");
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!(
+        "{}-{}.jsonl",
+        std::process::id(),
+        uuid::Uuid::new_v4()
+    ));
+    let file = std::fs::OpenOptions::new()
+        .create_new(true)
+        .append(true)
+        .open(&path)?;
+    Ok(Spill {
+        path,
+        file,
+        offsets: Vec::new(),
+        len: 0,
+    })
+}
+
+fn read_spill(spill: &Spill, start: usize, end: usize) -> std::io::Result<Vec<TimedLogMsg>> {
+    let from = spill.offsets[start];
+    let to = spill.offsets.get(end).copied().unwrap_or(spill.len);
+    let mut file = File::open(&spill.path)?;
+    file.seek(SeekFrom::Start(from))?;
+    let reader = BufReader::new(file.take(to - from));
+    reader
+        .lines()
+        .map(|line| Ok(serde_json::from_str(&line?)?))
+        .collect()
+}
+
+/// Spill the largest stores until the memory used is back to three quarters of the budget
+fn enforce_memory_budget() {
+    let Ok(_spilling) = SPILLING.try_lock() else {
+        return;
+    };
+    let budget = *MEMORY_BUDGET;
+    if memory_used() <= budget {
+        return;
+    }
+    let target = budget / 4 * 3;
+
+    let mut stores: Vec<(usize, Arc<RwLock<Inner>>)> = {
+        let mut registry = STORES.lock().unwrap();
+        registry.retain(|store| store.strong_count() > 0);
+        registry
+            .iter()
+            .filter_map(Weak::upgrade)
+            .map(|store| {
+                let total_bytes = store.read().unwrap().total_bytes;
+                (total_bytes, store)
+            })
+            .collect()
+    };
+    stores.sort_by_key(|(total_bytes, _)| Reverse(*total_bytes));
+
+    for (_, store) in stores {
+        let used = memory_used();
+        if used <= target {
+            break;
+        }
+        store.write().unwrap().shed(used - target);
+    }
 }
 
 pub struct MsgStore {
-    inner: RwLock<Inner>,
-    sender: broadcast::Sender<LogMsg>,
+    inner: Arc<RwLock<Inner>>,
+    sender: broadcast::Sender<TimedLogMsg>,
+    finished: AtomicBool,
 }
 
 impl Default for MsgStore {
@@ -36,31 +291,76 @@ impl Default for MsgStore {
 }
 
 impl MsgStore {
+    /// Store keeping its whole history, spilling the oldest part to disk when the memory
+    /// budget shared by all stores is exceeded
     pub fn new() -> Self {
+        Self::with_max_bytes(None)
+    }
+
+    /// Store keeping only the latest `max_bytes` of history, in memory
+    pub fn bounded(max_bytes: usize) -> Self {
+        Self::with_max_bytes(Some(max_bytes))
+    }
+
+    fn with_max_bytes(max_bytes: Option<usize>) -> Self {
         let (sender, _) = broadcast::channel(10000);
+        let inner = Arc::new(RwLock::new(Inner {
+            history: VecDeque::with_capacity(32),
+            total_bytes: 0,
+            max_bytes,
+            dropped: 0,
+            spill: None,
+            spill_failed: false,
+            entry_times: HashMap::new(),
+        }));
+        STORES.lock().unwrap().push(Arc::downgrade(&inner));
         Self {
-            inner: RwLock::new(Inner {
-                history: VecDeque::with_capacity(32),
-                total_bytes: 0,
-            }),
+            inner,
             sender,
+            finished: AtomicBool::new(false),
         }
     }
 
     pub fn push(&self, msg: LogMsg) {
-        let _ = self.sender.send(msg.clone()); // live listeners
-        let bytes = msg.approx_bytes();
+        self.push_timed(TimedLogMsg::now(msg));
+    }
 
-        let mut inner = self.inner.write().unwrap();
-        while inner.total_bytes.saturating_add(bytes) > HISTORY_BYTES {
-            if let Some(front) = inner.history.pop_front() {
-                inner.total_bytes = inner.total_bytes.saturating_sub(front.bytes);
-            } else {
-                break;
+    /// Push a message keeping its capture time, e.g. when replaying stored logs
+    pub fn push_timed(&self, msg: TimedLogMsg) {
+        self.push_inner(msg, None);
+    }
+
+    /// Push a patch a normalizer produced from output captured at `source_at`, the time of the
+    /// [`TimedOutput`] it read. Normalized entries the patch adds are stamped with that time.
+    pub fn push_patch_at(&self, patch: Patch, source_at: Option<DateTime<Utc>>) {
+        self.push_inner(TimedLogMsg::now(LogMsg::JsonPatch(patch)), source_at);
+    }
+
+    fn push_inner(&self, mut msg: TimedLogMsg, source_at: Option<DateTime<Utc>>) {
+        {
+            let mut inner = self.inner.write().unwrap();
+            if let LogMsg::JsonPatch(patch) = &mut msg.msg {
+                let source_at = source_at.map(|at| at.to_rfc3339());
+                stamp_entries(patch, source_at.as_deref(), &mut inner.entry_times);
             }
+
+            // Sent under the lock so subscribers see exactly the messages after their snapshot
+            let _ = self.sender.send(msg.clone()); // live listeners
+            let bytes = msg.msg.approx_bytes();
+            inner.history.push_back(StoredMsg { msg, bytes });
+            inner.total_bytes = inner.total_bytes.saturating_add(bytes);
+            MEMORY_USED.fetch_add(bytes, Ordering::Relaxed);
+            if let Some(max_bytes) = inner.max_bytes
+                && inner.total_bytes > max_bytes
+            {
+                let excess = inner.total_bytes - max_bytes;
+                inner.shed(excess);
+            }
+        }
+
+        if memory_used() > *MEMORY_BUDGET {
+            enforce_memory_budget();
         }
-        inner.history.push_back(StoredMsg { msg, bytes });
-        inner.total_bytes = inner.total_bytes.saturating_add(bytes);
     }
 
     // Convenience
@@ -79,75 +379,205 @@ impl MsgStore {
         self.push(LogMsg::SessionId(session_id));
     }
 
-    pub fn push_finished(&self) {
+    /// Push `Finished` unless it was pushed before. Returns whether this call pushed it, so
+    /// of several parties finishing a store only one cleans up after it.
+    pub fn push_finished(&self) -> bool {
+        if self.finished.swap(true, Ordering::AcqRel) {
+            return false;
+        }
         self.push(LogMsg::Finished);
+        true
     }
 
-    pub fn get_receiver(&self) -> broadcast::Receiver<LogMsg> {
+    /// Live messages only. A receiver that falls behind loses messages; use
+    /// [`Self::history_plus_stream_timed`] to get every message.
+    pub fn get_receiver(&self) -> broadcast::Receiver<TimedLogMsg> {
         self.sender.subscribe()
     }
 
+    /// Full history, including messages spilled to disk
     pub fn get_history(&self) -> Vec<LogMsg> {
-        self.inner
-            .read()
-            .unwrap()
-            .history
-            .iter()
-            .map(|s| s.msg.clone())
+        self.get_history_timed()
+            .into_iter()
+            .map(|timed| timed.msg)
             .collect()
     }
 
+    /// Approximate size of the history held in memory in bytes
+    pub fn history_bytes(&self) -> usize {
+        self.inner.read().unwrap().total_bytes
+    }
+
+    /// Size of the history spilled to disk in bytes
+    pub fn spilled_bytes(&self) -> u64 {
+        let inner = self.inner.read().unwrap();
+        inner.spill.as_ref().map_or(0, |spill| spill.len)
+    }
+
+    pub fn get_history_timed(&self) -> Vec<TimedLogMsg> {
+        let inner = self.inner.read().unwrap();
+        inner.range(0, inner.len())
+    }
+
     /// History then live, as `LogMsg`.
     pub fn history_plus_stream(
         &self,
     ) -> futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>> {
-        let (history, rx) = (self.get_history(), self.get_receiver());
+        self.history_plus_stream_timed()
+            .map_ok(|timed| timed.msg)
+            .boxed()
+    }
+
+    /// History then live, with the time each message was captured. History is read in chunks,
+    /// from disk for spilled messages. A subscriber that falls behind the live channel catches
+    /// up from the history instead of losing messages.
+    pub fn history_plus_stream_timed(
+        &self,
+    ) -> futures::stream::BoxStream<'static, Result<TimedLogMsg, std::io::Error>> {
+        self.history_plus_stream_indexed()
+            .map(|(_, msg)| Ok(msg))
+            .boxed()
+    }
+
+    /// History then live, each message with its index
+    pub fn history_plus_stream_indexed(
+        &self,
+    ) -> futures::stream::BoxStream<'static, (usize, TimedLogMsg)> {
+        let inner = self.inner.read().unwrap();
+        self.indexed_stream(&inner, inner.dropped)
+    }
 
-        let hist = futures::stream::iter(history.into_iter().map(Ok::<_, std::io::Error>));
-        let live = BroadcastStream::new(rx)
-            .filter_map(|res| async move { res.ok().map(Ok::<_, std::io::Error>) });
+    /// Index the next pushed message gets. Indices count every message ever pushed.
+    pub fn next_index(&self) -> usize {
+        self.inner.read().unwrap().len()
+    }
 
-        Box::pin(hist.chain(live))
+    /// Messages from index `start` on, each with its index, then live ones. Returns None when
+    /// `start` is no longer kept or hasn't been reached yet.
+    pub fn stream_from(
+        &self,
+        start: usize,
+    ) -> Option<futures::stream::BoxStream<'static, (usize, TimedLogMsg)>> {
+        let inner = self.inner.read().unwrap();
+        if start < inner.dropped || start > inner.len() {
+            return None;
+        }
+        Some(self.indexed_stream(&inner, start))
     }
 
-    pub fn stdout_chunked_stream(
+    /// Stream from `start`, subscribing while `guard` is held so no message is missed or
+    /// repeated. The stream ends if the subscriber falls so far behind a bounded store that
+    /// the messages it missed were dropped.
+    fn indexed_stream(
         &self,
-    ) -> futures::stream::BoxStream<'static, Result<String, std::io::Error>> {
-        self.history_plus_stream()
-            .take_while(|res| future::ready(!matches!(res, Ok(LogMsg::Finished))))
-            .filter_map(|res| async move {
-                match res {
-                    Ok(LogMsg::Stdout(s)) => Some(Ok(s)),
-                    _ => None,
+        guard: &Inner,
+        start: usize,
+    ) -> futures::stream::BoxStream<'static, (usize, TimedLogMsg)> {
+        let snapshot_len = guard.len();
+        let mut rx = self.sender.subscribe();
+        let inner = self.inner.clone();
+
+        async_stream::stream! {
+            let mut delivered = start;
+            while delivered < snapshot_len {
+                let end = (delivered + REPLAY_CHUNK).min(snapshot_len);
+                let (first, chunk) = {
+                    let inner = inner.read().unwrap();
+                    (delivered.max(inner.dropped), inner.range(delivered, end))
+                };
+                if first > delivered {
+                    return;
                 }
+                for (i, msg) in chunk.into_iter().enumerate() {
+                    yield (first + i, msg);
+                }
+                delivered = end;
+            }
+
+            loop {
+                match rx.recv().await {
+                    Ok(msg) => {
+                        yield (delivered, msg);
+                        delivered += 1;
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        let end = delivered + skipped as usize;
+                        let (first, missed) = {
+                            let inner = inner.read().unwrap();
+                            (delivered.max(inner.dropped), inner.range(delivered, end))
+                        };
+                        if first > delivered {
+                            return;
+                        }
+                        for (i, msg) in missed.into_iter().enumerate() {
+                            yield (first + i, msg);
+                        }
+                        delivered = end;
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        }
+        .boxed()
+    }
+
+    /// Stdout or stderr chunks until `Finished`, with their capture times
+    fn output_timed_chunks(
+        &self,
+        stderr: bool,
+    ) -> futures::stream::BoxStream<'static, Result<TimedOutput, std::io::Error>> {
+        self.history_plus_stream_timed()
+            .take_while(|res| {
+                future::ready(!matches!(
+                    res,
+                    Ok(TimedLogMsg {
+                        msg: LogMsg::Finished,
+                        ..
+                    })
+                ))
+            })
+            .filter_map(move |res| {
+                future::ready(match res {
+                    Ok(TimedLogMsg {
+                        at,
+                        msg: LogMsg::Stdout(text),
+                    }) if !stderr => Some(Ok(TimedOutput { at, text })),
+                    Ok(TimedLogMsg {
+                        at,
+                        msg: LogMsg::Stderr(text),
+                    }) if stderr => Some(Ok(TimedOutput { at, text })),
+                    Ok(_) => None,
+                    Err(e) => Some(Err(e)),
+                })
             })
             .boxed()
     }
 
-    pub fn stdout_lines_stream(
+    /// Stdout chunks with their capture times, for normalizers that stamp what they push with
+    /// [`Self::push_patch_at`]
+    pub fn stdout_timed_chunks(
         &self,
-    ) -> futures::stream::BoxStream<'static, std::io::Result<String>> {
-        self.stdout_chunked_stream().lines()
+    ) -> futures::stream::BoxStream<'static, Result<TimedOutput, std::io::Error>> {
+        self.output_timed_chunks(false)
     }
 
-    pub fn stderr_chunked_stream(
+    /// Stdout lines, each with the capture time of the chunk it started in
+    pub fn stdout_timed_lines(
         &self,
-    ) -> futures::stream::BoxStream<'static, Result<String, std::io::Error>> {
-        self.history_plus_stream()
-            .take_while(|res| future::ready(!matches!(res, Ok(LogMsg::Finished))))
-            .filter_map(|res| async move {
-                match res {
-                    Ok(LogMsg::Stderr(s)) => Some(Ok(s)),
-                    _ => None,
-                }
-            })
-            .boxed()
+    ) -> futures::stream::BoxStream<'static, std::io::Result<TimedOutput>> {
+        timed_lines(self.stdout_timed_chunks())
+    }
+
+    pub fn stderr_timed_chunks(
+        &self,
+    ) -> futures::stream::BoxStream<'static, Result<TimedOutput, std::io::Error>> {
+        self.output_timed_chunks(true)
     }
 
-    pub fn stderr_lines_stream(
+    pub fn stderr_timed_lines(
         &self,
-    ) -> futures::stream::BoxStream<'static, std::io::Result<String>> {
-        self.stderr_chunked_stream().lines()
+    ) -> futures::stream::BoxStream<'static, std::io::Result<TimedOutput>> {
+        timed_lines(self.stderr_timed_chunks())
     }
 
     /// Same stream but mapped to `Event` for SSE handlers.
@@ -175,3 +605,351 @@ impl MsgStore {
         })
     }
 }
+
+/// Split timed chunks into lines, each timed by the chunk it started in. Like
+/// [`crate::stream_lines::LinesStreamExt::lines`], line endings are dropped and an unterminated last line is kept.
+fn timed_lines(
+    chunks: futures::stream::BoxStream<'static, std::io::Result<TimedOutput>>,
+) -> futures::stream::BoxStream<'static, std::io::Result<TimedOutput>> {
+    struct State {
+        chunks: futures::stream::BoxStream<'static, std::io::Result<TimedOutput>>,
+        /// The line read so far, not yet terminated
+        partial: Option<TimedOutput>,
+        ready: VecDeque<TimedOutput>,
+        done: bool,
+    }
+
+    let state = State {
+        chunks,
+        partial: None,
+        ready: VecDeque::new(),
+        done: false,
+    };
+    futures::stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(line) = state.ready.pop_front() {
+                return Some((Ok(line), state));
+            }
+            if state.done {
+                return None;
+            }
+            match state.chunks.next().await {
+                Some(Ok(chunk)) => {
+                    let mut rest = chunk.text.as_str();
+                    while let Some(end) = rest.find('\n') {
+                        let mut line = state.partial.take().unwrap_or(TimedOutput {
+                            at: chunk.at,
+                            text: String::new(),
+                        });
+                        line.text.push_str(&rest[..end]);
+                        if line.text.ends_with('\r') {
+                            line.text.pop();
+                        }
+                        state.ready.push_back(line);
+                        rest = &rest[end + 1..];
+                    }
+                    if !rest.is_empty() {
+                        state
+                            .partial
+                            .get_or_insert_with(|| TimedOutput {
+                                at: chunk.at,
+                                text: String::new(),
+                            })
+                            .text
+                            .push_str(rest);
+                    }
+                }
+                Some(Err(e)) => return Some((Err(e), state)),
+                None => {
+                    state.done = true;
+                    state.ready.extend(state.partial.take());
+                }
+            }
+        }
+    })
+    .boxed()
+}
+
+/// Fill in the `timestamp` of normalized conversation entries (`{"type": "NORMALIZED_ENTRY",
+/// "content": {"timestamp": null, ..}}`) added or replaced by `patch`, with the time of the
+/// output they came from. A replaced entry keeps the time it was first added.
+fn stamp_entries(
+    patch: &mut Patch,
+    source_at: Option<&str>,
+    entry_times: &mut HashMap<String, String>,
+) {
+    for op in patch.0.iter_mut() {
+        let (path, value) = match op {
+            PatchOperation::Add(op) => (op.path.to_string(), &mut op.value),
+            PatchOperation::Replace(op) => (op.path.to_string(), &mut op.value),
+            _ => continue,
+        };
+        if value.get("type").and_then(Value::as_str) != Some("NORMALIZED_ENTRY") {
+            continue;
+        }
+        let Some(timestamp) = value
+            .get_mut("content")
+            .and_then(|content| content.get_mut("timestamp"))
+        else {
+            continue;
+        };
+        if !timestamp.is_null() {
+            continue;
+        }
+        let time = match (entry_times.get(&path), source_at) {
+            (Some(time), _) => time.clone(),
+            (None, Some(at)) => entry_times.entry(path).or_insert(at.to_string()).clone(),
+            (None, None) => continue,
+        };
+        *timestamp = Value::String(time);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn stdout_of(msgs: impl IntoIterator<Item = TimedLogMsg>) -> Vec<String> {
+        msgs.into_iter()
+            .filter_map(|timed| match timed.msg {
+                LogMsg::Stdout(s) => Some(s),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn spilled_history_is_read_back_in_order() {
+        let store = MsgStore::new();
+        for i in 0..10 {
+            store.push_stdout(i.to_string());
+        }
+        let freed = store.inner.write().unwrap().shed(usize::MAX);
+        assert!(freed > 0);
+        for i in 10..15 {
+            store.push_stdout(i.to_string());
+        }
+
+        assert!(store.spilled_bytes() > 0);
+        let expected: Vec<String> = (0..15).map(|i| i.to_string()).collect();
+        assert_eq!(stdout_of(store.get_history_timed()), expected);
+        assert_eq!(
+            stdout_of(store.inner.read().unwrap().range(8, 12)),
+            ["8", "9", "10", "11"]
+        );
+
+        let path = store
+            .inner
+            .read()
+            .unwrap()
+            .spill
+            .as_ref()
+            .unwrap()
+            .path
+            .clone();
+        drop(store);
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn lagged_subscriber_catches_up_from_history() {
+        let store = MsgStore::new();
+        store.push_stdout("first");
+        let stream = store.history_plus_stream_timed();
+
+        // More than the live channel holds, so the subscriber lags
+        for i in 0..10_050 {
+            store.push_stdout(i.to_string());
+        }
+        store.inner.write().unwrap().shed(usize::MAX);
+        store.push_finished();
+
+        let received: Vec<TimedLogMsg> = stream
+            .map(Result::unwrap)
+            .take_while(|timed| future::ready(!matches!(timed.msg, LogMsg::Finished)))
+            .collect()
+            .await;
+        let mut expected = vec!["first".to_string()];
+        expected.extend((0..10_050).map(|i| i.to_string()));
+        assert_eq!(stdout_of(received), expected);
+    }
+
+    #[test]
+    fn bounded_store_drops_oldest_history() {
+        let store = MsgStore::bounded(LogMsg::Stdout("0".to_string()).approx_bytes() * 3);
+        for i in 0..5 {
+            store.push_stdout(i.to_string());
+        }
+        assert_eq!(stdout_of(store.get_history_timed()), ["2", "3", "4"]);
+        assert_eq!(store.spilled_bytes(), 0);
+    }
+
+    #[tokio::test]
+    async fn stream_from_resumes_at_index() {
+        let store = MsgStore::bounded(LogMsg::Stdout("0".to_string()).approx_bytes() * 3);
+        for i in 0..5 {
+            store.push_stdout(i.to_string());
+        }
+        assert_eq!(store.next_index(), 5);
+        assert!(store.stream_from(1).is_none());
+        assert!(store.stream_from(6).is_none());
+
+        let stream = store.stream_from(3).unwrap();
+        store.push_stdout("5");
+        let received: Vec<(usize, String)> = stream
+            .take(3)
+            .map(|(index, timed)| (index, stdout_of([timed]).concat()))
+            .collect()
+            .await;
+        assert_eq!(
+            received,
+            [(3, "3"), (4, "4"), (5, "5")].map(|(index, s)| (index, s.to_string()))
+        );
+    }
+
+    #[test]
+    fn stamp_entries_keeps_first_timestamp_on_replace() {
+        let entry = |content: &str| {
+            json!({
+                "type": "NORMALIZED_ENTRY",
+                "content": { "timestamp": null, "content": content },
+            })
+        };
+        let mut entry_times = HashMap::new();
+
+        let mut add: Patch = serde_json::from_value(
+            json!([{ "op": "add", "path": "/entries/0", "value": entry("running") }]),
+        )
+        .unwrap();
+        stamp_entries(
+            &mut add,
+            Some("2025-01-01T00:00:00+00:00"),
+            &mut entry_times,
+        );
+
+        let mut replace: Patch = serde_json::from_value(
+            json!([{ "op": "replace", "path": "/entries/0", "value": entry("done") }]),
+        )
+        .unwrap();
+        stamp_entries(&mut replace, None, &mut entry_times);
+
+        for patch in [add, replace] {
+            let value = serde_json::to_value(&patch.0[0]).unwrap();
+            assert_eq!(
+                value["value"]["content"]["timestamp"],
+                "2025-01-01T00:00:00+00:00"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn interleaved_stdout_and_stderr_stamp_their_own_entries() {
+        let store = Arc::new(MsgStore::new());
+        let start = Utc::now();
+        let outputs = [
+            ("out-1", false),
+            ("err-1", true),
+            ("out-2", false),
+            ("err-2", true),
+        ];
+        for (i, (text, stderr)) in outputs.iter().enumerate() {
+            let msg = if *stderr {
+                LogMsg::Stderr(text.to_string())
+            } else {
+                LogMsg::Stdout(text.to_string())
+            };
+            store.push_timed(TimedLogMsg {
+                at: Some(start + chrono::Duration::seconds(i as i64)),
+                msg,
+            });
+        }
+        store.push_finished();
+
+        // Each normalizer reads a chunk, lets the other one read, then pushes its entry
+        let normalize = |stderr: bool| {
+            let store = store.clone();
+            tokio::spawn(async move {
+                let mut chunks = store.output_timed_chunks(stderr);
+                while let Some(Ok(TimedOutput { at, text: chunk })) = chunks.next().await {
+                    tokio::task::yield_now().await;
+                    let patch: Patch = serde_json::from_value(json!([{
+                        "op": "add",
+                        "path": format!("/entries/{chunk}"),
+                        "value": {
+                            "type": "NORMALIZED_ENTRY",
+                            "content": { "timestamp": null, "content": chunk },
+                        },
+                    }]))
+                    .unwrap();
+                    store.push_patch_at(patch, at);
+                }
+            })
+        };
+        let (stdout, stderr) = (normalize(false), normalize(true));
+        stdout.await.unwrap();
+        stderr.await.unwrap();
+
+        let stamped: HashMap<String, String> = store
+            .get_history()
+            .into_iter()
+            .filter_map(|msg| match msg {
+                LogMsg::JsonPatch(patch) => {
+                    let value = serde_json::to_value(&patch.0[0]).unwrap();
+                    let content = &value["value"]["content"];
+                    Some((
+                        content["content"].as_str()?.to_string(),
+                        content["timestamp"].as_str()?.to_string(),
+                    ))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(stamped.len(), outputs.len());
+        for (i, (text, _)) in outputs.iter().enumerate() {
+            let at = start + chrono::Duration::seconds(i as i64);
+            assert_eq!(stamped[*text], at.to_rfc3339(), "{text}");
+        }
+    }
+
+    #[tokio::test]
+    async fn timed_lines_take_the_time_of_the_chunk_they_start_in() {
+        let start = Utc::now();
+        let at = |secs: i64| Some(start + chrono::Duration::seconds(secs));
+        let chunks =
+            [(at(0), "one\r\ntw"), (at(1), "o\nthree\n"), (at(2), "four")].map(|(at, text)| {
+                Ok(TimedOutput {
+                    at,
+                    text: text.to_string(),
+                })
+            });
+        let lines: Vec<_> = timed_lines(futures::stream::iter(chunks).boxed())
+            .map(|line| line.unwrap())
+            .map(|line| (line.at, line.text))
+            .collect()
+            .await;
+        assert_eq!(
+            lines,
+            [
+                (at(0), "one".to_string()),
+                (at(0), "two".to_string()),
+                (at(1), "three".to_string()),
+                (at(2), "four".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn finished_is_pushed_once() {
+        let store = MsgStore::new();
+        assert!(store.push_finished());
+        assert!(!store.push_finished());
+        let finished = store
+            .get_history()
+            .into_iter()
+            .filter(|msg| matches!(msg, LogMsg::Finished))
+            .count();
+        assert_eq!(finished, 1);
+    }
+}
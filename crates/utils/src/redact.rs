@@ -0,0 +1,131 @@
+use std::{cmp::Reverse, io};
+
+use futures::{Stream, StreamExt, stream::BoxStream};
+
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Values shorter than this are not redacted, they would mangle unrelated output
+const MIN_SECRET_LEN: usize = 4;
+
+/// Replaces known secret values in process output
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    secrets: Vec<String>,
+}
+
+impl Redactor {
+    pub fn new<I, S>(secrets: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut secrets: Vec<String> = secrets
+            .into_iter()
+            .map(Into::into)
+            .filter(|s| s.len() >= MIN_SECRET_LEN)
+            .collect();
+        // Longest first so a secret containing another is replaced whole
+        secrets.sort_by_key(|secret| Reverse(secret.len()));
+        secrets.dedup();
+        Self { secrets }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.secrets.is_empty()
+    }
+
+    pub fn redact(&self, text: &str) -> String {
+        let mut out = text.to_string();
+        for secret in &self.secrets {
+            if out.contains(secret.as_str()) {
+                out = out.replace(secret.as_str(), REDACTED);
+            }
+        }
+        out
+    }
+
+    /// Length of the longest suffix of `text` that could be the start of a secret
+    fn partial_suffix_len(&self, text: &str) -> usize {
+        let Some(longest) = self.secrets.first().map(String::len) else {
+            return 0;
+        };
+        let window_start = text.len().saturating_sub(longest - 1);
+        text.char_indices()
+            .map(|(i, _)| i)
+            .filter(|&i| i >= window_start)
+            .find(|&i| {
+                let suffix = &text[i..];
+                self.secrets
+                    .iter()
+                    .any(|s| s.len() > suffix.len() && s.starts_with(suffix))
+            })
+            .map(|i| text.len() - i)
+            .unwrap_or(0)
+    }
+
+    /// Redact a stream of output chunks. The tail of a chunk that may be the start of a secret is
+    /// held back until the next chunk arrives, so secrets split across reads are still caught.
+    pub fn redact_stream<S>(self, stream: S) -> BoxStream<'static, io::Result<String>>
+    where
+        S: Stream<Item = io::Result<String>> + Send + 'static,
+    {
+        if self.is_empty() {
+            return stream.boxed();
+        }
+
+        async_stream::stream! {
+            let mut pending = String::new();
+            futures::pin_mut!(stream);
+            while let Some(item) = stream.next().await {
+                match item {
+                    Ok(chunk) => {
+                        pending.push_str(&chunk);
+                        let redacted = self.redact(&pending);
+                        let split = redacted.len() - self.partial_suffix_len(&redacted);
+                        pending = redacted[split..].to_string();
+                        if split > 0 {
+                            yield Ok(redacted[..split].to_string());
+                        }
+                    }
+                    Err(e) => yield Err(e),
+                }
+            }
+            if !pending.is_empty() {
+                yield Ok(pending);
+            }
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::stream;
+
+    use super::*;
+
+    #[test]
+    fn redacts_all_occurrences_and_skips_short_values() {
+        let redactor = Redactor::new(["sk-live-1234", "abc"]);
+        assert_eq!(
+            redactor.redact("key=sk-live-1234 again sk-live-1234 abc"),
+            "key=[REDACTED] again [REDACTED] abc"
+        );
+    }
+
+    #[tokio::test]
+    async fn redacts_secrets_split_across_chunks() {
+        let redactor = Redactor::new(["hunter22"]);
+        let chunks = vec![
+            Ok("password: hun".to_string()),
+            Ok("ter22\nnext line hu".to_string()),
+            Ok("nt\n".to_string()),
+        ];
+        let out: Vec<String> = redactor
+            .redact_stream(stream::iter(chunks))
+            .map(|r| r.unwrap())
+            .collect()
+            .await;
+        assert_eq!(out.concat(), "password: [REDACTED]\nnext line hunt\n");
+    }
+}
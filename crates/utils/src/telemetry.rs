@@ -0,0 +1,279 @@
+//! Optional OpenTelemetry export over OTLP/HTTP. Enabled when an endpoint is configured, either
+//! with `OTEL_EXPORTER_OTLP_ENDPOINT` or the `otlp_endpoint` config setting. When disabled the
+//! metric helpers below are no-ops.
+
+use std::{
+    sync::{LazyLock, OnceLock},
+    time::{Duration, SystemTime},
+};
+
+use opentelemetry::{
+    KeyValue, global,
+    metrics::{Counter, Gauge, Histogram, UpDownCounter},
+    trace::{Span as _, Tracer as _, TracerProvider as _},
+};
+use opentelemetry_otlp::{ExporterBuildError, MetricExporter, SpanExporter, WithExportConfig};
+use opentelemetry_sdk::{
+    Resource,
+    metrics::SdkMeterProvider,
+    trace::{SdkTracer, SdkTracerProvider},
+};
+use tracing_opentelemetry::{OpenTelemetryLayer, OpenTelemetrySpanExt};
+
+const SERVICE_NAME: &str = "vibe-kanban";
+pub const ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+static PROVIDERS: OnceLock<Providers> = OnceLock::new();
+
+struct Providers {
+    tracer_provider: SdkTracerProvider,
+    meter_provider: SdkMeterProvider,
+}
+
+/// Exporters for the collector at `endpoint`, e.g. `http://localhost:4318`
+fn build_providers(endpoint: &str) -> Result<Providers, ExporterBuildError> {
+    let endpoint = endpoint.trim_end_matches('/');
+    let resource = Resource::builder()
+        .with_service_name(SERVICE_NAME)
+        .with_attribute(KeyValue::new("service.version", env!("CARGO_PKG_VERSION")))
+        .build();
+
+    let span_exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(format!("{endpoint}/v1/traces"))
+        .build()?;
+    let tracer_provider = SdkTracerProvider::builder()
+        .with_batch_exporter(span_exporter)
+        .with_resource(resource.clone())
+        .build();
+
+    let metric_exporter = MetricExporter::builder()
+        .with_http()
+        .with_endpoint(format!("{endpoint}/v1/metrics"))
+        .build()?;
+    let meter_provider = SdkMeterProvider::builder()
+        .with_periodic_exporter(metric_exporter)
+        .with_resource(resource)
+        .build();
+
+    Ok(Providers {
+        tracer_provider,
+        meter_provider,
+    })
+}
+
+/// Start exporting to `endpoint`, or to `OTEL_EXPORTER_OTLP_ENDPOINT` when that is set.
+/// Returns whether export is enabled. This runs before the `tracing` subscriber is installed,
+/// so a failure is returned for the caller to log once it is.
+pub fn init_once(endpoint: Option<&str>) -> Result<bool, ExporterBuildError> {
+    let endpoint = std::env::var(ENDPOINT_ENV)
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+        .or_else(|| endpoint.map(str::to_string));
+    let Some(endpoint) = endpoint else {
+        return Ok(false);
+    };
+
+    if PROVIDERS.get().is_none() {
+        let providers = build_providers(&endpoint)?;
+        global::set_tracer_provider(providers.tracer_provider.clone());
+        global::set_meter_provider(providers.meter_provider.clone());
+        let _ = PROVIDERS.set(providers);
+    }
+    Ok(true)
+}
+
+/// Layer exporting `tracing` spans, if export is enabled
+pub fn otel_layer<S>() -> Option<OpenTelemetryLayer<S, SdkTracer>>
+where
+    S: tracing::Subscriber,
+    S: for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+{
+    let providers = PROVIDERS.get()?;
+    Some(tracing_opentelemetry::layer().with_tracer(providers.tracer_provider.tracer(SERVICE_NAME)))
+}
+
+/// Flush pending spans and metrics before exit
+pub fn shutdown() {
+    if let Some(providers) = PROVIDERS.get() {
+        if let Err(e) = providers.tracer_provider.shutdown() {
+            tracing::warn!("Failed to flush OpenTelemetry spans: {}", e);
+        }
+        if let Err(e) = providers.meter_provider.shutdown() {
+            tracing::warn!("Failed to flush OpenTelemetry metrics: {}", e);
+        }
+    }
+}
+
+struct Metrics {
+    running_processes: UpDownCounter<i64>,
+    process_duration: Histogram<f64>,
+    approval_wait: Histogram<f64>,
+    queue_depth: Gauge<i64>,
+    tool_calls: Counter<u64>,
+}
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(|| {
+    let meter = global::meter(SERVICE_NAME);
+    Metrics {
+        running_processes: meter
+            .i64_up_down_counter("vibe_kanban.execution_processes.running")
+            .with_description("Execution processes currently running, by run reason")
+            .build(),
+        process_duration: meter
+            .f64_histogram("vibe_kanban.execution_process.duration")
+            .with_description("Wall-clock duration of finished execution processes")
+            .with_unit("s")
+            .build(),
+        approval_wait: meter
+            .f64_histogram("vibe_kanban.approval.wait_time")
+            .with_description("Time tool calls waited for an approval response")
+            .with_unit("s")
+            .build(),
+        queue_depth: meter
+            .i64_gauge("vibe_kanban.follow_up_queue.depth")
+            .with_description("Follow-up messages queued to start when their attempt is idle")
+            .build(),
+        tool_calls: meter
+            .u64_counter("vibe_kanban.tool_calls")
+            .with_description("Tool calls parsed from normalized agent logs")
+            .build(),
+    }
+});
+
+pub fn process_started(run_reason: &str) {
+    METRICS
+        .running_processes
+        .add(1, &[KeyValue::new("run_reason", run_reason.to_string())]);
+}
+
+pub fn process_finished(
+    run_reason: &str,
+    executor: Option<&str>,
+    status: &str,
+    duration: Duration,
+) {
+    METRICS
+        .running_processes
+        .add(-1, &[KeyValue::new("run_reason", run_reason.to_string())]);
+
+    let mut attributes = vec![
+        KeyValue::new("run_reason", run_reason.to_string()),
+        KeyValue::new("status", status.to_string()),
+    ];
+    if let Some(executor) = executor {
+        attributes.push(KeyValue::new("executor", executor.to_string()));
+    }
+    METRICS
+        .process_duration
+        .record(duration.as_secs_f64(), &attributes);
+}
+
+pub fn approval_resolved(status: &str, wait: Duration) {
+    METRICS.approval_wait.record(
+        wait.as_secs_f64(),
+        &[KeyValue::new("status", status.to_string())],
+    );
+}
+
+pub fn record_queue_depth(depth: i64) {
+    METRICS.queue_depth.record(depth, &[]);
+}
+
+/// A tool call parsed from a process's conversation
+pub struct ToolCall<'a> {
+    pub tool_name: &'a str,
+    pub status: &'a str,
+    pub started: Option<SystemTime>,
+    pub ended: Option<SystemTime>,
+}
+
+/// Count a tool call and export it as a span under `parent`, using the times it was captured
+pub fn record_tool_call(parent: &tracing::Span, executor: Option<&str>, call: ToolCall<'_>) {
+    let mut attributes = vec![
+        KeyValue::new("tool.name", call.tool_name.to_string()),
+        KeyValue::new("tool.status", call.status.to_string()),
+    ];
+    if let Some(executor) = executor {
+        attributes.push(KeyValue::new("executor", executor.to_string()));
+    }
+    METRICS.tool_calls.add(1, &attributes);
+
+    if PROVIDERS.get().is_none() {
+        return;
+    }
+    let tracer = global::tracer(SERVICE_NAME);
+    let mut builder = tracer
+        .span_builder(format!("tool_call {}", call.tool_name))
+        .with_attributes(attributes);
+    if let Some(started) = call.started {
+        builder = builder.with_start_time(started);
+    }
+    let mut span = builder.start_with_context(&tracer, &parent.context());
+    match call.ended {
+        Some(ended) => span.end_with_timestamp(ended),
+        None => span.end(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::mpsc,
+    };
+
+    use super::*;
+
+    /// Minimal OTLP/HTTP collector that reports the path of every request it receives
+    fn collector_stub() -> (String, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let mut reader = BufReader::new(stream);
+                let mut request_line = String::new();
+                if reader.read_line(&mut request_line).is_err() {
+                    continue;
+                }
+                let mut content_length = 0;
+                loop {
+                    let mut header = String::new();
+                    if reader.read_line(&mut header).is_err() || header.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = header.split_once(':')
+                        && name.eq_ignore_ascii_case("content-length")
+                    {
+                        content_length = value.trim().parse().unwrap_or(0);
+                    }
+                }
+                let mut body = vec![0; content_length];
+                let _ = reader.read_exact(&mut body);
+                let _ = reader
+                    .get_mut()
+                    .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n");
+                let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+                let _ = tx.send(path.to_string());
+            }
+        });
+        (endpoint, rx)
+    }
+
+    #[test]
+    fn exports_spans_to_collector() {
+        let (endpoint, requests) = collector_stub();
+        let providers = build_providers(&endpoint).unwrap();
+
+        let tracer = providers.tracer_provider.tracer(SERVICE_NAME);
+        tracer.in_span("execution_process", |_| {});
+        providers.tracer_provider.force_flush().unwrap();
+
+        let path = requests.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(path, "/v1/traces");
+        let _ = providers.tracer_provider.shutdown();
+        let _ = providers.meter_provider.shutdown();
+    }
+}
@@ -0,0 +1,474 @@
+//! Prompt templates: `{{task.title}}` style variables and `{{#if ...}}` / `{{#unless ...}}`
+//! blocks with an optional `{{else}}`, rendered against a JSON context.
+//!
+//! Only well-formed tags are special. Any other `{{...}}`, e.g. in a code sample, is kept as
+//! text, and so are variables and blocks the context doesn't have, exactly as written.
+
+use std::{fmt, ops::Range};
+
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    /// A block was opened but never closed
+    UnclosedBlock(String),
+    /// `{{else}}` or a closing tag without a matching block
+    UnexpectedTag(String),
+    /// Variables that aren't available to templates
+    UnknownVariables(Vec<String>),
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnclosedBlock(kind) => write!(f, "Unclosed {{{{#{kind}}}}} block"),
+            Self::UnexpectedTag(tag) => write!(f, "Unexpected {{{{{tag}}}}}"),
+            Self::UnknownVariables(names) => {
+                write!(f, "Unknown template variables: {}", names.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BlockKind {
+    If,
+    Unless,
+}
+
+impl BlockKind {
+    fn name(self) -> &'static str {
+        match self {
+            Self::If => "if",
+            Self::Unless => "unless",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tag {
+    Var(String),
+    Open(BlockKind, String),
+    Else,
+    Close(BlockKind),
+}
+
+impl Tag {
+    fn parse(inner: &str) -> Option<Self> {
+        let inner = inner.trim();
+        if let Some(rest) = inner.strip_prefix('#') {
+            let (kind, path) = rest.split_once(char::is_whitespace)?;
+            let kind = match kind {
+                "if" => BlockKind::If,
+                "unless" => BlockKind::Unless,
+                _ => return None,
+            };
+            let path = path.trim();
+            return is_path(path).then(|| Self::Open(kind, path.to_string()));
+        }
+        match inner {
+            "else" => Some(Self::Else),
+            "/if" => Some(Self::Close(BlockKind::If)),
+            "/unless" => Some(Self::Close(BlockKind::Unless)),
+            _ => is_path(inner).then(|| Self::Var(inner.to_string())),
+        }
+    }
+
+    fn is_block(&self) -> bool {
+        !matches!(self, Self::Var(_))
+    }
+
+    fn source(&self) -> String {
+        match self {
+            Self::Var(path) => path.clone(),
+            Self::Open(kind, path) => format!("#{} {}", kind.name(), path),
+            Self::Else => "else".to_string(),
+            Self::Close(kind) => format!("/{}", kind.name()),
+        }
+    }
+}
+
+/// A dotted path of identifiers, e.g. `task.title`
+fn is_path(s: &str) -> bool {
+    !s.is_empty()
+        && s.split('.').all(|segment| {
+            let mut chars = segment.chars();
+            chars
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        })
+}
+
+#[derive(Debug, Clone)]
+enum Token {
+    Text(String),
+    /// A tag and the span of the source it was parsed from, including the whitespace stripped
+    /// around it
+    Tag(Tag, Range<usize>),
+}
+
+fn tokenize(source: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut text = String::new();
+    let mut offset = 0;
+    while let Some(start) = source[offset..].find("{{").map(|start| offset + start) {
+        let after_open = &source[start + 2..];
+        let tag = after_open
+            .find("}}")
+            .and_then(|end| Tag::parse(&after_open[..end]).map(|tag| (tag, end)));
+        match tag {
+            Some((tag, end)) => {
+                text.push_str(&source[offset..start]);
+                if !text.is_empty() {
+                    tokens.push(Token::Text(std::mem::take(&mut text)));
+                }
+                offset = start + 2 + end + 2;
+                tokens.push(Token::Tag(tag, start..offset));
+            }
+            None => {
+                text.push_str(&source[offset..start + 2]);
+                offset = start + 2;
+            }
+        }
+    }
+    text.push_str(&source[offset..]);
+    if !text.is_empty() {
+        tokens.push(Token::Text(text));
+    }
+    strip_standalone_blocks(&mut tokens);
+    tokens
+}
+
+/// Drop the line of block tags that stand alone on it, so blocks don't leave blank lines
+fn strip_standalone_blocks(tokens: &mut [Token]) {
+    let is_blank = |s: &str| s.chars().all(|c| c == ' ' || c == '\t');
+    let mut stripped = vec![false; tokens.len()];
+    for i in 0..tokens.len() {
+        if !matches!(&tokens[i], Token::Tag(tag, _) if tag.is_block()) {
+            continue;
+        }
+        let line_start = match i.checked_sub(1).map(|p| &tokens[p]) {
+            None => true,
+            Some(Token::Text(text)) => match text.rfind('\n') {
+                Some(newline) => is_blank(&text[newline + 1..]),
+                None => is_blank(text) && (i == 1 || stripped[i - 2]),
+            },
+            Some(Token::Tag(..)) => false,
+        };
+        let line_end = match tokens.get(i + 1) {
+            None => true,
+            Some(Token::Text(text)) => match text.find('\n') {
+                Some(newline) => is_blank(text[..newline].trim_end_matches('\r')),
+                None => is_blank(text) && i + 2 == tokens.len(),
+            },
+            Some(Token::Tag(..)) => false,
+        };
+        if !(line_start && line_end) {
+            continue;
+        }
+
+        let mut before = 0;
+        if let Some(Token::Text(text)) = i.checked_sub(1).map(|p| &mut tokens[p]) {
+            let keep = text.trim_end_matches([' ', '\t']).len();
+            before = text.len() - keep;
+            text.truncate(keep);
+        }
+        let mut after = 0;
+        if let Some(Token::Text(text)) = tokens.get_mut(i + 1) {
+            after = text.find('\n').map_or(text.len(), |newline| newline + 1);
+            text.drain(..after);
+        }
+        if let Token::Tag(_, span) = &mut tokens[i] {
+            *span = span.start - before..span.end + after;
+        }
+        stripped[i] = true;
+    }
+}
+
+/// Variables and blocks keep the span of the source they were parsed from, so they can be
+/// written back unchanged when the context doesn't have their variable
+#[derive(Debug, Clone)]
+enum Node {
+    Text(String),
+    Var {
+        path: String,
+        span: Range<usize>,
+    },
+    Block {
+        kind: BlockKind,
+        path: String,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+        span: Range<usize>,
+    },
+}
+
+struct Frame {
+    kind: BlockKind,
+    path: String,
+    then: Vec<Node>,
+    otherwise: Option<Vec<Node>>,
+    start: usize,
+}
+
+impl Frame {
+    fn nodes(&mut self) -> &mut Vec<Node> {
+        self.otherwise.as_mut().unwrap_or(&mut self.then)
+    }
+}
+
+/// A parsed template
+#[derive(Debug, Clone)]
+pub struct Template {
+    source: String,
+    nodes: Vec<Node>,
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Self, TemplateError> {
+        let mut root = Vec::new();
+        let mut stack: Vec<Frame> = Vec::new();
+        for token in tokenize(source) {
+            let node = match token {
+                Token::Text(text) if text.is_empty() => continue,
+                Token::Text(text) => Node::Text(text),
+                Token::Tag(Tag::Var(path), span) => Node::Var { path, span },
+                Token::Tag(Tag::Open(kind, path), span) => {
+                    stack.push(Frame {
+                        kind,
+                        path,
+                        then: Vec::new(),
+                        otherwise: None,
+                        start: span.start,
+                    });
+                    continue;
+                }
+                Token::Tag(tag @ Tag::Else, _) => {
+                    match stack.last_mut() {
+                        Some(frame) if frame.otherwise.is_none() => {
+                            frame.otherwise = Some(Vec::new())
+                        }
+                        _ => return Err(TemplateError::UnexpectedTag(tag.source())),
+                    }
+                    continue;
+                }
+                Token::Tag(tag @ Tag::Close(kind), span) => match stack.pop() {
+                    Some(frame) if frame.kind == kind => Node::Block {
+                        kind,
+                        path: frame.path,
+                        then: frame.then,
+                        otherwise: frame.otherwise.unwrap_or_default(),
+                        span: frame.start..span.end,
+                    },
+                    _ => return Err(TemplateError::UnexpectedTag(tag.source())),
+                },
+            };
+            match stack.last_mut() {
+                Some(frame) => frame.nodes().push(node),
+                None => root.push(node),
+            }
+        }
+        if let Some(frame) = stack.pop() {
+            return Err(TemplateError::UnclosedBlock(frame.kind.name().to_string()));
+        }
+        Ok(Self {
+            source: source.to_string(),
+            nodes: root,
+        })
+    }
+
+    /// Every variable the template refers to, in order of first use
+    pub fn variables(&self) -> Vec<String> {
+        fn collect(nodes: &[Node], out: &mut Vec<String>) {
+            for node in nodes {
+                match node {
+                    Node::Text(_) => {}
+                    Node::Var { path, .. } => {
+                        if !out.contains(path) {
+                            out.push(path.clone());
+                        }
+                    }
+                    Node::Block {
+                        path,
+                        then,
+                        otherwise,
+                        ..
+                    } => {
+                        if !out.contains(path) {
+                            out.push(path.clone());
+                        }
+                        collect(then, out);
+                        collect(otherwise, out);
+                    }
+                }
+            }
+        }
+        let mut out = Vec::new();
+        collect(&self.nodes, &mut out);
+        out
+    }
+
+    /// Fail if the template refers to a variable that isn't in `known`
+    pub fn check_variables(&self, known: &[&str]) -> Result<(), TemplateError> {
+        let unknown: Vec<String> = self
+            .variables()
+            .into_iter()
+            .filter(|path| !known.contains(&path.as_str()))
+            .collect();
+        if unknown.is_empty() {
+            Ok(())
+        } else {
+            Err(TemplateError::UnknownVariables(unknown))
+        }
+    }
+
+    pub fn render(&self, context: &Value) -> String {
+        let mut out = String::new();
+        render_nodes(&self.source, &self.nodes, context, &mut out);
+        out
+    }
+}
+
+/// Render `source`, or return it unchanged if it isn't a valid template
+pub fn render(source: &str, context: &Value) -> String {
+    match Template::parse(source) {
+        Ok(template) => template.render(context),
+        Err(e) => {
+            tracing::warn!("Not rendering invalid template: {}", e);
+            source.to_string()
+        }
+    }
+}
+
+fn render_nodes(source: &str, nodes: &[Node], context: &Value, out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Var { path, span } => match lookup(context, path) {
+                Some(value) => push_value(value, out),
+                None => out.push_str(&source[span.clone()]),
+            },
+            Node::Block {
+                kind,
+                path,
+                then,
+                otherwise,
+                span,
+            } => {
+                let Some(value) = lookup(context, path) else {
+                    out.push_str(&source[span.clone()]);
+                    continue;
+                };
+                let truthy = is_truthy(value);
+                let show_then = match kind {
+                    BlockKind::If => truthy,
+                    BlockKind::Unless => !truthy,
+                };
+                let nodes = if show_then { then } else { otherwise };
+                render_nodes(source, nodes, context, out);
+            }
+        }
+    }
+}
+
+fn lookup<'a>(context: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .try_fold(context, |value, segment| value.get(segment))
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+        Value::String(s) => !s.trim().is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(fields) => !fields.is_empty(),
+    }
+}
+
+fn push_value(value: &Value, out: &mut String) {
+    match value {
+        Value::Null => {}
+        Value::String(s) => out.push_str(s),
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push('\n');
+                }
+                push_value(item, out);
+            }
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn context() -> Value {
+        json!({
+            "task": {"title": "Fix login", "description": ""},
+            "diff": {"files": ["src/a.rs", "src/b.rs"], "additions": 3},
+        })
+    }
+
+    #[test]
+    fn renders_variables_and_lists() {
+        let out = render(
+            "{{task.title}} (+{{ diff.additions }})\n{{diff.files}}",
+            &context(),
+        );
+        assert_eq!(out, "Fix login (+3)\nsrc/a.rs\nsrc/b.rs");
+    }
+
+    #[test]
+    fn renders_conditionals_without_blank_lines() {
+        let source = "Title\n{{#if task.description}}\n{{task.description}}\n{{else}}\nNo description\n{{/if}}\n{{#unless diff.files}}\nNo changes\n{{/unless}}\nEnd";
+        assert_eq!(render(source, &context()), "Title\nNo description\nEnd");
+    }
+
+    #[test]
+    fn keeps_unknown_and_non_template_braces() {
+        let source = "{{ missing.value }} style={{ color: 'red' }}";
+        assert_eq!(render(source, &context()), source);
+    }
+
+    #[test]
+    fn keeps_blocks_of_unknown_variables_as_written() {
+        let block = "  {{#if  missing.flag }}\n{{ task.title}} and {{missing.value}}\n  {{else}}\nNo\n{{/if}}\n";
+        let source = format!(
+            "Before\n{block}{{{{#unless task.description}}}}\nEmpty\n{{{{/unless}}}}\nAfter"
+        );
+        assert_eq!(
+            render(&source, &context()),
+            format!("Before\n{block}Empty\nAfter")
+        );
+        // Nothing is rendered without a context
+        assert_eq!(render(&source, &Value::Null), source);
+    }
+
+    #[test]
+    fn reports_errors_and_variables() {
+        assert_eq!(
+            Template::parse("{{#if a}}x").unwrap_err(),
+            TemplateError::UnclosedBlock("if".to_string())
+        );
+        assert_eq!(
+            Template::parse("x{{/unless}}").unwrap_err(),
+            TemplateError::UnexpectedTag("/unless".to_string())
+        );
+        let template = Template::parse("{{#if task.title}}{{task.title}}{{oops}}{{/if}}").unwrap();
+        assert_eq!(template.variables(), vec!["task.title", "oops"]);
+        assert_eq!(
+            template.check_variables(&["task.title"]),
+            Err(TemplateError::UnknownVariables(vec!["oops".to_string()]))
+        );
+    }
+}
@@ -18,7 +18,9 @@ type ExecutorType =
   | 'CURSOR_AGENT'
   | 'COPILOT'
   | 'OPENCODE'
-  | 'QWEN_CODE';
+  | 'QWEN_CODE'
+  | 'ACP'
+  | 'GENERIC_CLI';
 
 interface ExecutorConfigFormProps {
   executor: ExecutorType;
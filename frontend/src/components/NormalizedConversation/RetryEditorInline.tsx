@@ -267,6 +267,7 @@ export function RetryEditorInline({
         disabled={isSending || !!isFinalizing}
         showLoadingOverlay={isSending || !!isFinalizing}
         textareaClassName="bg-background"
+        taskAttemptId={attemptId}
       />
 
       {/* Draft save/load status (no queue/sending for retry) */}
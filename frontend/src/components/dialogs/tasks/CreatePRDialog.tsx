@@ -7,7 +7,7 @@ import {
   DialogTitle,
 } from '@/components/ui/dialog';
 import { Label } from '@radix-ui/react-label';
-import { Textarea } from '@/components/ui/textarea.tsx';
+import { FileSearchTextarea } from '@/components/ui/file-search-textarea';
 import { Button } from '@/components/ui/button';
 import { Input } from '@/components/ui/input';
 import { Alert } from '@/components/ui/alert';
@@ -162,12 +162,13 @@ const CreatePrDialog = NiceModal.create(() => {
             </div>
             <div className="space-y-2">
               <Label htmlFor="pr-body">Description (optional)</Label>
-              <Textarea
-                id="pr-body"
+              <FileSearchTextarea
                 value={prBody}
-                onChange={(e) => setPrBody(e.target.value)}
-                placeholder="Enter PR description"
+                onChange={setPrBody}
+                placeholder="Enter PR description... Type @ to insert tags or search files."
                 rows={4}
+                projectId={data?.projectId}
+                taskAttemptId={data?.attempt.id}
               />
             </div>
             <div className="space-y-2">
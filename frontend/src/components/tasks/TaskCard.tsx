@@ -3,6 +3,7 @@ import { KanbanCard } from '@/components/ui/shadcn-io/kanban';
 import { CheckCircle, Loader2, XCircle } from 'lucide-react';
 import type { TaskWithAttemptStatus } from 'shared/types';
 import { ActionsDropdown } from '@/components/ui/ActionsDropdown';
+import { Badge } from '@/components/ui/badge';
 
 type Task = TaskWithAttemptStatus;
 
@@ -84,6 +85,31 @@ export function TaskCard({
             : task.description}
         </p>
       )}
+      {(task.priority ||
+        task.assignee ||
+        task.due_date ||
+        task.labels.length > 0) && (
+        <div className="flex flex-wrap gap-1 items-center text-xs text-muted-foreground">
+          {task.priority && (
+            <Badge
+              variant={task.priority === 'urgent' ? 'destructive' : 'outline'}
+            >
+              {task.priority}
+            </Badge>
+          )}
+          {task.labels.map((label) => (
+            <Badge
+              key={label.id}
+              variant="secondary"
+              style={label.color ? { borderColor: label.color } : undefined}
+            >
+              {label.name}
+            </Badge>
+          ))}
+          {task.assignee && <span>@{task.assignee}</span>}
+          {task.due_date && <span>Due {task.due_date}</span>}
+        </div>
+      )}
     </KanbanCard>
   );
 }
@@ -493,6 +493,7 @@ export function TaskFollowUpSection({
                 showLoadingOverlay={isUnqueuing || !isDraftLoaded}
                 onPasteFiles={handlePasteImages}
                 onFocusChange={setIsTextareaFocused}
+                taskAttemptId={selectedAttemptId}
               />
               <FollowUpStatusRow
                 status={{
@@ -15,6 +15,7 @@ type Props = {
   onPasteFiles?: (files: File[]) => void;
   textareaClassName?: string;
   onFocusChange?: (isFocused: boolean) => void;
+  taskAttemptId?: string;
 };
 
 export function FollowUpEditorCard({
@@ -27,6 +28,7 @@ export function FollowUpEditorCard({
   onPasteFiles,
   textareaClassName,
   onFocusChange,
+  taskAttemptId,
 }: Props) {
   const { projectId } = useProject();
 
@@ -50,6 +52,7 @@ export function FollowUpEditorCard({
         className={cn('flex-1 min-h-[40px] resize-none', textareaClassName)}
         disabled={disabled}
         projectId={projectId}
+        taskAttemptId={taskAttemptId}
         rows={1}
         maxRows={30}
         onPasteFiles={onPasteFiles}
@@ -1,7 +1,7 @@
 import { useEffect, useRef, useState, forwardRef } from 'react';
 import { createPortal } from 'react-dom';
 import { AutoExpandingTextarea } from '@/components/ui/auto-expanding-textarea';
-import { projectsApi, tagsApi } from '@/lib/api';
+import { projectsApi, tagsApi, templatesApi } from '@/lib/api';
 import { Tag as TagIcon, FileText } from 'lucide-react';
 
 import type { SearchResult, Tag } from 'shared/types';
@@ -27,6 +27,8 @@ interface FileSearchTextareaProps {
   disabled?: boolean;
   className?: string;
   projectId?: string;
+  // Tags inserted into a message for this attempt are rendered as templates against it
+  taskAttemptId?: string;
   onKeyDown?: (e: React.KeyboardEvent) => void;
   maxRows?: number;
   onPasteFiles?: (files: File[]) => void;
@@ -46,6 +48,7 @@ export const FileSearchTextarea = forwardRef<
     disabled = false,
     className,
     projectId,
+    taskAttemptId,
     onKeyDown,
     maxRows = 10,
     onPasteFiles,
@@ -183,8 +186,24 @@ export const FileSearchTextarea = forwardRef<
     setAtSymbolPosition(-1);
   };
 
+  // The content a tag inserts, rendered against the attempt when there is one
+  const tagText = async (tag: Tag): Promise<string> => {
+    const content = tag.content || '';
+    if (!taskAttemptId || !content.includes('{{')) return content;
+    try {
+      const preview = await templatesApi.preview({
+        template: content,
+        task_attempt_id: taskAttemptId,
+      });
+      return preview.rendered ?? content;
+    } catch (error) {
+      console.error('Failed to render template:', error);
+      return content;
+    }
+  };
+
   // Select a result item (either tag or file) and insert it
-  const selectResult = (result: SearchResultItem) => {
+  const selectResult = async (result: SearchResultItem) => {
     if (atSymbolPosition === -1) return;
 
     const beforeAt = value.slice(0, atSymbolPosition);
@@ -195,7 +214,7 @@ export const FileSearchTextarea = forwardRef<
 
     if (result.type === 'tag' && result.tag) {
       // Insert tag content
-      insertText = result.tag.content || '';
+      insertText = await tagText(result.tag);
       newCursorPos = atSymbolPosition + insertText.length;
     } else if (result.type === 'file' && result.file) {
       // Insert file path (keep @ for files)
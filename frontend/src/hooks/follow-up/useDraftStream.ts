@@ -1,4 +1,4 @@
-import { useCallback, useMemo } from 'react';
+import { useCallback, useEffect, useMemo, useRef } from 'react';
 import { useQuery, useQueryClient } from '@tanstack/react-query';
 import { applyPatch } from 'rfc6902';
 import type { Operation } from 'rfc6902';
@@ -14,9 +14,10 @@ type DraftsContainer = {
   drafts: Drafts;
 };
 
-type WsJsonPatchMsg = { JsonPatch: Operation[] };
+type WsJsonPatchMsg = { JsonPatch: Operation[]; seq?: number };
 type WsFinishedMsg = { finished: boolean };
-type WsMsg = WsJsonPatchMsg | WsFinishedMsg;
+type WsResyncMsg = { resync_required: boolean };
+type WsMsg = WsJsonPatchMsg | WsFinishedMsg | WsResyncMsg;
 
 export function useDraftStream(attemptId?: string) {
   const { projectId } = useProject();
@@ -59,8 +60,20 @@ function useDraftsStreamState(projectId?: string): Drafts | undefined {
     initialData,
   });
 
+  // Sequence number of the last patch applied; reconnects resume after it
+  const lastSeqRef = useRef<number | null>(null);
+  useEffect(() => {
+    lastSeqRef.current = null;
+  }, [wsUrl]);
+  const getWsUrl = useCallback(async () => {
+    const url = wsUrl ?? 'ws://invalid';
+    return lastSeqRef.current === null
+      ? url
+      : `${url}&after=${lastSeqRef.current}`;
+  }, [wsUrl]);
+
   const { getWebSocket } = useWebSocket(
-    wsUrl ?? 'ws://invalid',
+    getWsUrl,
     {
       share: true,
       shouldReconnect: () => true,
@@ -70,7 +83,13 @@ function useDraftsStreamState(projectId?: string): Drafts | undefined {
       onMessage: (event) => {
         try {
           const msg: WsMsg = JSON.parse(event.data);
-          if ('JsonPatch' in msg) {
+          if ('resync_required' in msg) {
+            // The resume point is gone; a fresh snapshot follows
+            queryClient.setQueryData<DraftsContainer>(queryKey, initialData());
+          } else if ('JsonPatch' in msg) {
+            if (msg.seq !== undefined) {
+              lastSeqRef.current = msg.seq;
+            }
             const patches = msg.JsonPatch;
             if (!patches.length) return;
             queryClient.setQueryData<DraftsContainer>(queryKey, (prev) => {
@@ -0,0 +1,41 @@
+import { useCallback } from 'react';
+import { useJsonPatchWsStream } from './useJsonPatchWsStream';
+import type { AuditEvent } from 'shared/types';
+
+type ActivityState = {
+  activity: Record<string, AuditEvent>;
+};
+
+interface UseActivityFeedResult {
+  events: AuditEvent[];
+  isLoading: boolean;
+  isConnected: boolean;
+  error: string | null;
+}
+
+/**
+ * Stream the audit log as an activity feed, across all projects unless one is given.
+ * Server sends initial snapshot: replace /activity with the latest events keyed by id.
+ * New events arrive at /activity/<id> via add operations.
+ */
+export const useActivityFeed = (projectId?: string): UseActivityFeedResult => {
+  const endpoint = projectId
+    ? `/api/audit/stream/ws?project_id=${encodeURIComponent(projectId)}`
+    : '/api/audit/stream/ws';
+
+  const initialData = useCallback((): ActivityState => ({ activity: {} }), []);
+
+  const { data, isConnected, error } = useJsonPatchWsStream(
+    endpoint,
+    true,
+    initialData
+  );
+
+  const events = Object.values(data?.activity ?? {}).sort(
+    (a, b) =>
+      new Date(b.created_at).getTime() - new Date(a.created_at).getTime()
+  );
+  const isLoading = !data && !error; // until first snapshot
+
+  return { events, isLoading, isConnected, error };
+};
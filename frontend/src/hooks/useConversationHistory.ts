@@ -301,7 +301,8 @@ export const useConversationHistory = ({
             liveProcessStatus === ExecutionProcessStatus.running;
           const processFailedOrKilled =
             liveProcessStatus === ExecutionProcessStatus.failed ||
-            liveProcessStatus === ExecutionProcessStatus.killed;
+            liveProcessStatus === ExecutionProcessStatus.killed ||
+            liveProcessStatus === ExecutionProcessStatus.limitexceeded;
 
           if (isProcessRunning) {
             hasRunningProcess = true;
@@ -343,7 +344,9 @@ export const useConversationHistory = ({
 
           if (
             (executionProcess?.status === ExecutionProcessStatus.failed ||
-              executionProcess?.status === ExecutionProcessStatus.killed) &&
+              executionProcess?.status === ExecutionProcessStatus.killed ||
+              executionProcess?.status ===
+                ExecutionProcessStatus.limitexceeded) &&
             index === Object.keys(executionProcessState).length - 1
           ) {
             lastProcessFailedOrKilled = true;
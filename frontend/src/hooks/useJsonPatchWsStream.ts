@@ -2,9 +2,10 @@ import { useEffect, useState, useRef } from 'react';
 import { applyPatch } from 'rfc6902';
 import type { Operation } from 'rfc6902';
 
-type WsJsonPatchMsg = { JsonPatch: Operation[] };
+type WsJsonPatchMsg = { JsonPatch: Operation[]; seq?: number };
 type WsFinishedMsg = { finished: boolean };
-type WsMsg = WsJsonPatchMsg | WsFinishedMsg;
+type WsResyncMsg = { resync_required: boolean };
+type WsMsg = WsJsonPatchMsg | WsFinishedMsg | WsResyncMsg;
 
 interface UseJsonPatchStreamOptions<T> {
   /**
@@ -41,6 +42,10 @@ export const useJsonPatchWsStream = <T>(
   const retryAttemptsRef = useRef<number>(0);
   const [retryNonce, setRetryNonce] = useState(0);
   const finishedRef = useRef<boolean>(false);
+  // Sequence number of the last message applied, to resume after a reconnect
+  const lastSeqRef = useRef<number | null>(null);
+  // Endpoint the current data was streamed from
+  const dataEndpointRef = useRef<string | undefined>(undefined);
 
   function scheduleReconnect() {
     if (retryTimerRef.current) return; // already scheduled
@@ -70,9 +75,19 @@ export const useJsonPatchWsStream = <T>(
       setIsConnected(false);
       setError(null);
       dataRef.current = undefined;
+      dataEndpointRef.current = undefined;
+      lastSeqRef.current = null;
       return;
     }
 
+    // Data from another endpoint can't be resumed
+    if (dataEndpointRef.current !== endpoint) {
+      dataEndpointRef.current = endpoint;
+      dataRef.current = undefined;
+      lastSeqRef.current = null;
+      setData(undefined);
+    }
+
     // Initialize data
     if (!dataRef.current) {
       dataRef.current = initialData();
@@ -88,8 +103,13 @@ export const useJsonPatchWsStream = <T>(
       // Reset finished flag for new connection
       finishedRef.current = false;
 
-      // Convert HTTP endpoint to WebSocket endpoint
-      const wsEndpoint = endpoint.replace(/^http/, 'ws');
+      // Convert HTTP endpoint to WebSocket endpoint, resuming where the last
+      // connection left off
+      let wsEndpoint = endpoint.replace(/^http/, 'ws');
+      if (lastSeqRef.current !== null) {
+        const separator = wsEndpoint.includes('?') ? '&' : '?';
+        wsEndpoint = `${wsEndpoint}${separator}after=${lastSeqRef.current}`;
+      }
       const ws = new WebSocket(wsEndpoint);
 
       ws.onopen = () => {
@@ -107,8 +127,20 @@ export const useJsonPatchWsStream = <T>(
         try {
           const msg: WsMsg = JSON.parse(event.data);
 
+          // The resume point is gone; a fresh snapshot follows
+          if ('resync_required' in msg) {
+            dataRef.current = initialData();
+            if (options.injectInitialEntry) {
+              options.injectInitialEntry(dataRef.current);
+            }
+            return;
+          }
+
           // Handle JsonPatch messages (same as SSE json_patch event)
           if ('JsonPatch' in msg) {
+            if (msg.seq !== undefined) {
+              lastSeqRef.current = msg.seq;
+            }
             const patches: Operation[] = msg.JsonPatch;
             const filtered = options.deduplicatePatches
               ? options.deduplicatePatches(patches)
@@ -179,9 +211,8 @@ export const useJsonPatchWsStream = <T>(
         window.clearTimeout(retryTimerRef.current);
         retryTimerRef.current = null;
       }
+      // Data is kept so a reconnect to the same endpoint can resume
       finishedRef.current = false;
-      dataRef.current = undefined;
-      setData(undefined);
     };
   }, [
     endpoint,
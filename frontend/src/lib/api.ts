@@ -3,6 +3,8 @@
 import {
   ApprovalStatus,
   ApiResponse,
+  AuditEvent,
+  AuditQuery,
   BranchStatus,
   CheckTokenResponse,
   Config,
@@ -11,6 +13,20 @@ import {
   CreateGitHubPrRequest,
   CreateTask,
   CreateAndStartTaskRequest,
+  BulkMoveTasksRequest,
+  BulkStartTasksRequest,
+  BulkTaskIdsRequest,
+  BulkTaskResult,
+  BulkUpdateTaskStatusRequest,
+  CreateCustomField,
+  CreateLabel,
+  CustomField,
+  Label,
+  TaskFields,
+  TaskFilter,
+  UpdateCustomField,
+  UpdateLabel,
+  UpdateTaskFields,
   CreateTaskAttemptBody,
   CreateTag,
   DeviceFlowStartResponse,
@@ -19,9 +35,13 @@ import {
   DirectoryEntry,
   EditorType,
   ExecutionProcess,
+  ExecutionProcessLogPage,
+  RetentionReport,
   GitBranch,
   Project,
   CreateProject,
+  ProjectEnvVar,
+  ProjectEnvVarInput,
   RepositoryInfo,
   SearchResult,
   Task,
@@ -42,11 +62,27 @@ import {
   ImageResponse,
   DraftResponse,
   UpdateFollowUpDraftRequest,
+  SteerResponse,
   GitOperationError,
   ApprovalResponse,
   RebaseTaskAttemptRequest,
   ChangeTargetBranchRequest,
   ChangeTargetBranchResponse,
+  PreviewTemplateRequest,
+  TemplatePreview,
+  TemplateVariable,
+  ProjectInstructions,
+  ProjectInstructionVersion,
+  UpdateProjectInstructions,
+  TaskSchedule,
+  TaskScheduleRun,
+  CreateTaskSchedule,
+  UpdateTaskSchedule,
+  IssueTracker,
+  CreateIssueTracker,
+  UpdateIssueTracker,
+  TaskIssueLink,
+  SyncSummary,
 } from 'shared/types';
 
 // Re-export types for convenience
@@ -232,6 +268,47 @@ export const projectsApi = {
     return handleApiResponse<void>(response);
   },
 
+  getEnv: async (id: string): Promise<ProjectEnvVar[]> => {
+    const response = await makeRequest(`/api/projects/${id}/env`);
+    return handleApiResponse<ProjectEnvVar[]>(response);
+  },
+
+  updateEnv: async (
+    id: string,
+    vars: ProjectEnvVarInput[]
+  ): Promise<ProjectEnvVar[]> => {
+    const response = await makeRequest(`/api/projects/${id}/env`, {
+      method: 'PUT',
+      body: JSON.stringify({ vars }),
+    });
+    return handleApiResponse<ProjectEnvVar[]>(response);
+  },
+
+  getInstructions: async (id: string): Promise<ProjectInstructions> => {
+    const response = await makeRequest(`/api/projects/${id}/instructions`);
+    return handleApiResponse<ProjectInstructions>(response);
+  },
+
+  updateInstructions: async (
+    id: string,
+    data: UpdateProjectInstructions
+  ): Promise<ProjectInstructions> => {
+    const response = await makeRequest(`/api/projects/${id}/instructions`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectInstructions>(response);
+  },
+
+  getInstructionVersions: async (
+    id: string
+  ): Promise<ProjectInstructionVersion[]> => {
+    const response = await makeRequest(
+      `/api/projects/${id}/instructions/versions`
+    );
+    return handleApiResponse<ProjectInstructionVersion[]>(response);
+  },
+
   openEditor: async (id: string, editorType?: EditorType): Promise<void> => {
     const requestBody: any = {};
     if (editorType) requestBody.editor_type = editorType;
@@ -267,8 +344,17 @@ export const projectsApi = {
 
 // Task Management APIs
 export const tasksApi = {
-  getAll: async (projectId: string): Promise<TaskWithAttemptStatus[]> => {
-    const response = await makeRequest(`/api/tasks?project_id=${projectId}`);
+  getAll: async (
+    projectId: string,
+    filter: Partial<TaskFilter> = {}
+  ): Promise<TaskWithAttemptStatus[]> => {
+    const params = new URLSearchParams({ project_id: projectId });
+    for (const [key, value] of Object.entries(filter)) {
+      if (value !== null && value !== undefined) {
+        params.set(key, value);
+      }
+    }
+    const response = await makeRequest(`/api/tasks?${params.toString()}`);
     return handleApiResponse<TaskWithAttemptStatus[]>(response);
   },
 
@@ -309,6 +395,126 @@ export const tasksApi = {
     });
     return handleApiResponse<void>(response);
   },
+
+  updateFields: async (
+    taskId: string,
+    data: UpdateTaskFields
+  ): Promise<TaskFields> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/fields`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TaskFields>(response);
+  },
+
+  bulkUpdateStatus: async (
+    data: BulkUpdateTaskStatusRequest
+  ): Promise<BulkTaskResult[]> => {
+    const response = await makeRequest(`/api/tasks/bulk/status`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<BulkTaskResult[]>(response);
+  },
+
+  bulkDelete: async (data: BulkTaskIdsRequest): Promise<BulkTaskResult[]> => {
+    const response = await makeRequest(`/api/tasks/bulk/delete`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<BulkTaskResult[]>(response);
+  },
+
+  bulkMove: async (data: BulkMoveTasksRequest): Promise<BulkTaskResult[]> => {
+    const response = await makeRequest(`/api/tasks/bulk/move`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<BulkTaskResult[]>(response);
+  },
+
+  bulkStart: async (data: BulkStartTasksRequest): Promise<BulkTaskResult[]> => {
+    const response = await makeRequest(`/api/tasks/bulk/start`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<BulkTaskResult[]>(response);
+  },
+
+  bulkStop: async (data: BulkTaskIdsRequest): Promise<BulkTaskResult[]> => {
+    const response = await makeRequest(`/api/tasks/bulk/stop`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<BulkTaskResult[]>(response);
+  },
+};
+
+// Labels APIs
+export const labelsApi = {
+  list: async (projectId: string): Promise<Label[]> => {
+    const response = await makeRequest(`/api/labels?project_id=${projectId}`);
+    return handleApiResponse<Label[]>(response);
+  },
+
+  create: async (data: CreateLabel): Promise<Label> => {
+    const response = await makeRequest('/api/labels', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Label>(response);
+  },
+
+  update: async (labelId: string, data: UpdateLabel): Promise<Label> => {
+    const response = await makeRequest(`/api/labels/${labelId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Label>(response);
+  },
+
+  delete: async (labelId: string): Promise<void> => {
+    const response = await makeRequest(`/api/labels/${labelId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Custom Fields APIs
+export const customFieldsApi = {
+  list: async (projectId: string): Promise<CustomField[]> => {
+    const response = await makeRequest(
+      `/api/custom-fields?project_id=${projectId}`
+    );
+    return handleApiResponse<CustomField[]>(response);
+  },
+
+  create: async (data: CreateCustomField): Promise<CustomField> => {
+    const response = await makeRequest('/api/custom-fields', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<CustomField>(response);
+  },
+
+  update: async (
+    fieldId: string,
+    data: UpdateCustomField
+  ): Promise<CustomField> => {
+    const response = await makeRequest(`/api/custom-fields/${fieldId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<CustomField>(response);
+  },
+
+  delete: async (fieldId: string): Promise<void> => {
+    const response = await makeRequest(`/api/custom-fields/${fieldId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
 };
 
 // Task Attempts APIs
@@ -320,6 +526,15 @@ export const attemptsApi = {
     return handleApiResponse<TaskRelationships>(response);
   },
 
+  getInstructions: async (
+    attemptId: string
+  ): Promise<ProjectInstructionVersion | null> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/instructions`
+    );
+    return handleApiResponse<ProjectInstructionVersion | null>(response);
+  },
+
   getAll: async (taskId: string): Promise<TaskAttempt[]> => {
     const response = await makeRequest(`/api/task-attempts?task_id=${taskId}`);
     return handleApiResponse<TaskAttempt[]>(response);
@@ -436,6 +651,20 @@ export const attemptsApi = {
     return handleApiResponse<DraftResponse>(response);
   },
 
+  steer: async (
+    attemptId: string,
+    message: string
+  ): Promise<SteerResponse> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/steer`,
+      {
+        method: 'POST',
+        body: JSON.stringify({ message }),
+      }
+    );
+    return handleApiResponse<SteerResponse>(response);
+  },
+
   deleteFile: async (
     attemptId: string,
     fileToDelete: string
@@ -601,6 +830,20 @@ export const executionProcessesApi = {
     return handleApiResponse<ExecutionProcess>(response);
   },
 
+  getRawLogs: async (
+    processId: string,
+    params: { offset?: number; limit?: number; tail?: number } = {}
+  ): Promise<ExecutionProcessLogPage> => {
+    const query = new URLSearchParams();
+    Object.entries(params).forEach(([key, value]) => {
+      if (value !== undefined) query.set(key, String(value));
+    });
+    const response = await makeRequest(
+      `/api/execution-processes/${processId}/raw-logs?${query.toString()}`
+    );
+    return handleApiResponse<ExecutionProcessLogPage>(response);
+  },
+
   stopExecutionProcess: async (processId: string): Promise<void> => {
     const response = await makeRequest(
       `/api/execution-processes/${processId}/stop`,
@@ -610,6 +853,26 @@ export const executionProcessesApi = {
     );
     return handleApiResponse<void>(response);
   },
+
+  renormalize: async (processId: string): Promise<boolean> => {
+    const response = await makeRequest(
+      `/api/execution-processes/${processId}/renormalize`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<boolean>(response);
+  },
+
+  renormalizeOutdated: async (): Promise<number> => {
+    const response = await makeRequest(
+      '/api/execution-processes/renormalize-outdated',
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<number>(response);
+  },
 };
 
 // File System APIs
@@ -644,6 +907,28 @@ export const configApi = {
     });
     return handleApiResponse<Config>(response);
   },
+  getRetentionReport: async (): Promise<RetentionReport> => {
+    const response = await makeRequest('/api/retention/report');
+    return handleApiResponse<RetentionReport>(response);
+  },
+  runRetention: async (): Promise<RetentionReport> => {
+    const response = await makeRequest('/api/retention/run', {
+      method: 'POST',
+    });
+    return handleApiResponse<RetentionReport>(response);
+  },
+};
+
+// Audit log APIs
+export const auditApi = {
+  list: async (query: AuditQuery = {}): Promise<AuditEvent[]> => {
+    const params = new URLSearchParams();
+    Object.entries(query).forEach(([key, value]) => {
+      if (value !== undefined) params.set(key, String(value));
+    });
+    const response = await makeRequest(`/api/audit?${params.toString()}`);
+    return handleApiResponse<AuditEvent[]>(response);
+  },
 };
 
 // GitHub Device Auth APIs
@@ -713,6 +998,144 @@ export const tagsApi = {
   },
 };
 
+// Prompt Templates APIs
+export const templatesApi = {
+  variables: async (): Promise<TemplateVariable[]> => {
+    const response = await makeRequest('/api/templates/variables');
+    return handleApiResponse<TemplateVariable[]>(response);
+  },
+
+  preview: async (data: PreviewTemplateRequest): Promise<TemplatePreview> => {
+    const response = await makeRequest('/api/templates/preview', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TemplatePreview>(response);
+  },
+};
+
+// Task Schedules APIs
+export const schedulesApi = {
+  list: async (projectId?: string): Promise<TaskSchedule[]> => {
+    const params = projectId ? `?project_id=${projectId}` : '';
+    const response = await makeRequest(`/api/schedules${params}`);
+    return handleApiResponse<TaskSchedule[]>(response);
+  },
+
+  getById: async (scheduleId: string): Promise<TaskSchedule> => {
+    const response = await makeRequest(`/api/schedules/${scheduleId}`);
+    return handleApiResponse<TaskSchedule>(response);
+  },
+
+  create: async (data: CreateTaskSchedule): Promise<TaskSchedule> => {
+    const response = await makeRequest('/api/schedules', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TaskSchedule>(response);
+  },
+
+  update: async (
+    scheduleId: string,
+    data: UpdateTaskSchedule
+  ): Promise<TaskSchedule> => {
+    const response = await makeRequest(`/api/schedules/${scheduleId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TaskSchedule>(response);
+  },
+
+  delete: async (scheduleId: string): Promise<void> => {
+    const response = await makeRequest(`/api/schedules/${scheduleId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  pause: async (scheduleId: string): Promise<TaskSchedule> => {
+    const response = await makeRequest(`/api/schedules/${scheduleId}/pause`, {
+      method: 'POST',
+    });
+    return handleApiResponse<TaskSchedule>(response);
+  },
+
+  resume: async (scheduleId: string): Promise<TaskSchedule> => {
+    const response = await makeRequest(`/api/schedules/${scheduleId}/resume`, {
+      method: 'POST',
+    });
+    return handleApiResponse<TaskSchedule>(response);
+  },
+
+  runNow: async (scheduleId: string): Promise<TaskScheduleRun> => {
+    const response = await makeRequest(`/api/schedules/${scheduleId}/run`, {
+      method: 'POST',
+    });
+    return handleApiResponse<TaskScheduleRun>(response);
+  },
+
+  getRuns: async (
+    scheduleId: string,
+    limit?: number
+  ): Promise<TaskScheduleRun[]> => {
+    const params = limit ? `?limit=${limit}` : '';
+    const response = await makeRequest(
+      `/api/schedules/${scheduleId}/runs${params}`
+    );
+    return handleApiResponse<TaskScheduleRun[]>(response);
+  },
+};
+
+// Issue Trackers APIs
+export const issueTrackersApi = {
+  list: async (projectId?: string): Promise<IssueTracker[]> => {
+    const params = projectId ? `?project_id=${projectId}` : '';
+    const response = await makeRequest(`/api/issue-trackers${params}`);
+    return handleApiResponse<IssueTracker[]>(response);
+  },
+
+  create: async (data: CreateIssueTracker): Promise<IssueTracker> => {
+    const response = await makeRequest('/api/issue-trackers', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<IssueTracker>(response);
+  },
+
+  update: async (
+    trackerId: string,
+    data: UpdateIssueTracker
+  ): Promise<IssueTracker> => {
+    const response = await makeRequest(`/api/issue-trackers/${trackerId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<IssueTracker>(response);
+  },
+
+  delete: async (trackerId: string): Promise<void> => {
+    const response = await makeRequest(`/api/issue-trackers/${trackerId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  sync: async (trackerId: string): Promise<SyncSummary> => {
+    const response = await makeRequest(
+      `/api/issue-trackers/${trackerId}/sync`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<SyncSummary>(response);
+  },
+
+  getLinks: async (trackerId: string): Promise<TaskIssueLink[]> => {
+    const response = await makeRequest(
+      `/api/issue-trackers/${trackerId}/links`
+    );
+    return handleApiResponse<TaskIssueLink[]>(response);
+  },
+};
+
 // MCP Servers APIs
 export const mcpServersApi = {
   load: async (query: McpServerQuery): Promise<GetMcpServerResponse> => {
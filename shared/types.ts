@@ -30,9 +30,9 @@ export type ScriptRequest = { script: string, language: ScriptRequestLanguage, c
 
 export type ScriptRequestLanguage = "Bash";
 
-export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT" }
+export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", ACP = "ACP", GENERIC_CLI = "GENERIC_CLI" }
 
-export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot };
+export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "ACP": Acp } | { "GENERIC_CLI": GenericCli };
 
 export type Tag = { id: string, tag_name: string, content: string, created_at: string, updated_at: string, };
 
@@ -42,18 +42,125 @@ export type UpdateTag = { tag_name: string | null, content: string | null, };
 
 export type TagSearchParams = { search: string | null, };
 
+export type PreviewTemplateRequest = { template: string, 
+/**
+ * Attempt to render against. Without one the template is only checked.
+ */
+task_attempt_id: string | null, };
+
+export type TemplatePreview = { 
+/**
+ * The rendered template, when it parses and an attempt was given
+ */
+rendered: string | null, variables: Array<string>, unknown_variables: Array<string>, 
+/**
+ * Why the template doesn't parse
+ */
+error: string | null, };
+
+export type TemplateVariable = { name: string, description: string, };
+
 export type TaskStatus = "todo" | "inprogress" | "inreview" | "done" | "cancelled";
 
+export type TaskPriority = "low" | "medium" | "high" | "urgent";
+
 export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_task_attempt: string | null, created_at: string, updated_at: string, };
 
-export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, has_merged_attempt: boolean, last_attempt_failed: boolean, executor: string, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_task_attempt: string | null, created_at: string, updated_at: string, };
+/**
+ * What a task is triaged by, beyond its status
+ */
+export type TaskFields = { priority: TaskPriority | null, 
+/**
+ * Who owns the task, free-form
+ */
+assignee: string | null, due_date: string | null, labels: Array<Label>, custom_fields: Array<TaskCustomFieldValue>, };
+
+export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, has_merged_attempt: boolean, last_attempt_failed: boolean, executor: string, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_task_attempt: string | null, created_at: string, updated_at: string, priority: TaskPriority | null, 
+/**
+ * Who owns the task, free-form
+ */
+assignee: string | null, due_date: string | null, labels: Array<Label>, custom_fields: Array<TaskCustomFieldValue>, };
 
 export type TaskRelationships = { parent_task: Task | null, current_attempt: TaskAttempt, children: Array<Task>, };
 
 export type CreateTask = { project_id: string, title: string, description: string | null, parent_task_attempt: string | null, image_ids: Array<string> | null, };
 
+/**
+ * Narrows down the tasks of a project. Every filter that's set must match.
+ */
+export type TaskFilter = { priority: TaskPriority | null, 
+/**
+ * Matched ignoring case
+ */
+assignee: string | null, 
+/**
+ * Name of a label the task must have
+ */
+label: string | null, 
+/**
+ * Only tasks due on or before this date
+ */
+due_before: string | null, 
+/**
+ * Name of a custom field the task must have a value for
+ */
+custom_field: string | null, 
+/**
+ * Value the custom field must have, as text
+ */
+custom_value: string | null, };
+
+/**
+ * Replaces all of a task's fields
+ */
+export type UpdateTaskFields = { priority: TaskPriority | null, assignee: string | null, due_date: string | null, label_ids: Array<string>, 
+/**
+ * Values by custom field id. Fields left out or set to null have no value.
+ */
+custom_fields: { [key in string]?: JsonValue }, };
+
 export type UpdateTask = { title: string | null, description: string | null, status: TaskStatus | null, parent_task_attempt: string | null, image_ids: Array<string> | null, };
 
+/**
+ * A label the tasks of a project can be tagged with
+ */
+export type Label = { id: string, project_id: string, 
+/**
+ * Unique within the project, ignoring case
+ */
+name: string, color: string | null, created_at: Date, updated_at: Date, };
+
+export type CreateLabel = { project_id: string, name: string, color: string | null, };
+
+export type UpdateLabel = { name: string, color: string | null, };
+
+export type CustomFieldType = "text" | "number" | "boolean" | "date" | "select";
+
+/**
+ * A typed field a project defines for its tasks
+ */
+export type CustomField = { id: string, project_id: string, 
+/**
+ * Unique within the project, ignoring case
+ */
+name: string, field_type: CustomFieldType, 
+/**
+ * Choices of a select field
+ */
+options: Array<string>, created_at: Date, updated_at: Date, };
+
+export type CreateCustomField = { project_id: string, name: string, field_type: CustomFieldType, options: Array<string>, };
+
+/**
+ * The type of a field can't change, as its values would no longer fit it
+ */
+export type UpdateCustomField = { name: string, options: Array<string>, };
+
+/**
+ * A task's value for one of its project's custom fields
+ */
+export type TaskCustomFieldValue = { field_id: string, name: string, field_type: CustomFieldType, value: JsonValue, };
+
 export type Image = { id: string, file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, created_at: string, updated_at: string, };
 
 export type CreateImage = { file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, };
@@ -82,19 +189,179 @@ export type UpdateFollowUpDraftRequest = { prompt: string | null, variant: strin
 
 export type UpdateRetryFollowUpDraftRequest = { retry_process_id: string, prompt: string | null, variant: string | null | null, image_ids: Array<string> | null, version: bigint | null, };
 
+export type SteerRequest = { message: string, };
+
+export type SteerResponse = { 
+/**
+ * True when the message reached the running agent
+ */
+delivered: boolean, 
+/**
+ * The follow-up draft the message was queued on when it could not be delivered
+ */
+draft: DraftResponse | null, };
+
+export type ProjectEnvVar = { key: string, 
+/**
+ * Always null for secrets
+ */
+value: string | null, secret: boolean, };
+
+export type ProjectEnvVarInput = { key: string, 
+/**
+ * Leave null on an existing secret to keep its current value
+ */
+value: string | null, secret: boolean, };
+
+export type UpdateProjectEnv = { vars: Array<ProjectEnvVarInput>, };
+
+export type ProjectInstructions = { project_id: string, content: string, 
+/**
+ * File in the repository whose contents follow `content`, relative to the repository root
+ */
+sync_path: string | null, };
+
+export type UpdateProjectInstructions = { content: string, sync_path: string | null, };
+
+export type ProjectInstructionVersion = { id: string, project_id: string, version: bigint, content: string, created_at: Date, };
+
+/**
+ * What a schedule does about times it missed while the server was down
+ */
+export type ScheduleCatchUp = "skip" | "once" | "all";
+
+export type ScheduleRunStatus = "pending" | "created" | "started" | "skipped" | "failed";
+
+/**
+ * A cron schedule creating tasks from a template
+ */
+export type TaskSchedule = { id: string, project_id: string, 
+/**
+ * The template whose content becomes the task description
+ */
+tag_id: string, title: string, 
+/**
+ * Five field cron expression in the server's local time zone
+ */
+cron: string, 
+/**
+ * Profile to start an attempt with, or null to only create the task
+ */
+executor_profile_id: ExecutorProfileId | null, base_branch: string | null, catch_up: ScheduleCatchUp, paused: boolean, next_run_at: Date, last_run_at: Date | null, created_at: Date, updated_at: Date, };
+
+export type CreateTaskSchedule = { project_id: string, tag_id: string, title: string, cron: string, executor_profile_id: ExecutorProfileId | null, base_branch: string | null, catch_up: ScheduleCatchUp, };
+
+export type UpdateTaskSchedule = { tag_id: string, title: string, cron: string, executor_profile_id: ExecutorProfileId | null, base_branch: string | null, catch_up: ScheduleCatchUp, };
+
+/**
+ * A time a schedule ran at, or skipped
+ */
+export type TaskScheduleRun = { id: string, schedule_id: string, scheduled_for: Date, status: ScheduleRunStatus, task_id: string | null, task_attempt_id: string | null, error: string | null, created_at: Date, };
+
+export type IssueTrackerKind = "github" | "rest";
+
+/**
+ * A tracker whose open issues are imported into a project as tasks
+ */
+export type IssueTracker = { id: string, project_id: string, kind: IssueTrackerKind, 
+/**
+ * Issues endpoint of a REST tracker
+ */
+url: string | null, 
+/**
+ * Project env var holding the REST tracker's bearer token
+ */
+token_env: string | null, 
+/**
+ * Only import GitHub issues with this label
+ */
+label: string | null, 
+/**
+ * Sync in the background, as well as when asked to
+ */
+auto_sync: boolean, last_synced_at: Date | null, last_error: string | null, created_at: Date, updated_at: Date, };
+
+export type CreateIssueTracker = { project_id: string, kind: IssueTrackerKind, url: string | null, token_env: string | null, label: string | null, auto_sync: boolean, };
+
+export type UpdateIssueTracker = { url: string | null, token_env: string | null, label: string | null, auto_sync: boolean, };
+
+/**
+ * An issue imported from a tracker, and what was last synced for it
+ */
+export type TaskIssueLink = { id: string, tracker_id: string, 
+/**
+ * Null once the task is deleted, which keeps the issue from being imported again
+ */
+task_id: string | null, external_id: string, url: string | null, remote_title: string, remote_description: string | null, 
+/**
+ * Task status last posted back to the tracker
+ */
+posted_status: TaskStatus | null, posted_pr_url: string | null, 
+/**
+ * Why the issue last failed to sync, cleared once a sync of it goes through
+ */
+last_error: string | null, created_at: Date, updated_at: Date, };
+
+/**
+ * What a sync did
+ */
+export type SyncSummary = { 
+/**
+ * Issues imported as new tasks
+ */
+imported: number, 
+/**
+ * Tasks whose title or description changed on the tracker
+ */
+updated: number, 
+/**
+ * Status changes posted back to the tracker
+ */
+posted: number, 
+/**
+ * Issues that failed to sync, with why. The sync goes on with the other issues.
+ */
+errors: Array<string>, };
+
 export type ChangeTargetBranchRequest = { new_target_branch: string, };
 
 export type ChangeTargetBranchResponse = { new_target_branch: string, status: [number, number], };
 
 export type CreateAndStartTaskRequest = { task: CreateTask, executor_profile_id: ExecutorProfileId, base_branch: string, };
 
+export type BulkTaskIdsRequest = { task_ids: Array<string>, };
+
+export type BulkUpdateTaskStatusRequest = { task_ids: Array<string>, status: TaskStatus, };
+
+export type BulkMoveTasksRequest = { task_ids: Array<string>, project_id: string, };
+
+export type BulkStartTasksRequest = { task_ids: Array<string>, executor_profile_id: ExecutorProfileId, 
+/**
+ * Defaults to the current branch of each task's repository
+ */
+base_branch: string | null, };
+
+/**
+ * Outcome of a bulk operation for one of its tasks
+ */
+export type BulkTaskResult = { task_id: string, success: boolean, error: string | null, 
+/**
+ * The attempt started on the task, for bulk starts
+ */
+task_attempt_id: string | null, };
+
 export type CreateGitHubPrRequest = { title: string, body: string | null, target_branch: string | null, };
 
 export type ImageResponse = { id: string, file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, created_at: string, updated_at: string, };
 
 export enum GitHubServiceError { TOKEN_INVALID = "TOKEN_INVALID", INSUFFICIENT_PERMISSIONS = "INSUFFICIENT_PERMISSIONS", REPO_NOT_FOUND_OR_NO_ACCESS = "REPO_NOT_FOUND_OR_NO_ACCESS" }
 
-export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, github_login_acknowledged: boolean, telemetry_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean | null, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, };
+export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, github_login_acknowledged: boolean, telemetry_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean | null, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, retention: RetentionConfig, 
+/**
+ * OpenTelemetry collector to export traces and metrics to, e.g. `http://localhost:4318`.
+ * `OTEL_EXPORTER_OTLP_ENDPOINT` takes precedence. Applied on restart.
+ */
+otlp_endpoint: string | null, };
 
 export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
 
@@ -112,6 +379,23 @@ export type UiLanguage = "BROWSER" | "EN" | "JA" | "ES" | "KO";
 
 export type ShowcaseState = { seen_features: Array<string>, };
 
+/**
+ * How long raw execution logs are kept. Everything is kept by default.
+ */
+export type RetentionConfig = { 
+/**
+ * Delete raw logs of finished processes older than this many days
+ */
+log_retention_days: number | null, 
+/**
+ * Delete raw logs of processes dropped from the attempt history
+ */
+drop_logs_of_dropped_processes: boolean, 
+/**
+ * Cap on stored raw logs per project, oldest logs are deleted first
+ */
+max_log_storage_mb_per_project: bigint | null, };
+
 export type DeviceFlowStartResponse = { user_code: string, verification_uri: string, expires_in: number, interval: number, };
 
 export enum DevicePollStatus { SLOW_DOWN = "SLOW_DOWN", AUTHORIZATION_PENDING = "AUTHORIZATION_PENDING", SUCCESS = "SUCCESS" }
@@ -144,6 +428,20 @@ base: string,
  */
 params: Array<string> | null, };
 
+export type ResourceLimits = { 
+/**
+ * CPU cores the processes may use, e.g. 2.0 (requires cgroups v2)
+ */
+cpus?: number | null, 
+/**
+ * Memory limit in MiB (requires cgroups v2)
+ */
+memory_mb?: bigint | null, 
+/**
+ * Wall-clock limit in seconds (dev servers are exempt)
+ */
+timeout_secs?: bigint | null, };
+
 export type ExecutorProfileId = { 
 /**
  * The executor type (e.g., "CLAUDE_CODE", "AMP")
@@ -154,19 +452,19 @@ executor: BaseCodingAgent,
  */
 variant: string | null, };
 
-export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } };
+export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "ACP": Acp } | { "GENERIC_CLI": GenericCli } };
 
 export type BaseAgentCapability = "SESSION_FORK";
 
-export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, dangerously_skip_permissions?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, dangerously_skip_permissions?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, limits?: ResourceLimits | null, fs_sandbox?: boolean | null, sandbox_writable_paths?: Array<string> | null, };
 
-export type Gemini = { append_prompt: AppendPrompt, model: GeminiModel, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type Gemini = { append_prompt: AppendPrompt, model: GeminiModel, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, limits?: ResourceLimits | null, fs_sandbox?: boolean | null, sandbox_writable_paths?: Array<string> | null, };
 
 export type GeminiModel = "default" | "flash";
 
-export type Amp = { append_prompt: AppendPrompt, dangerously_allow_all?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type Amp = { append_prompt: AppendPrompt, dangerously_allow_all?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, limits?: ResourceLimits | null, fs_sandbox?: boolean | null, sandbox_writable_paths?: Array<string> | null, };
 
-export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, ask_for_approval?: AskForApproval | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, profile?: string | null, base_instructions?: string | null, include_plan_tool?: boolean | null, include_apply_patch_tool?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, ask_for_approval?: AskForApproval | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, profile?: string | null, base_instructions?: string | null, include_plan_tool?: boolean | null, include_apply_patch_tool?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, limits?: ResourceLimits | null, fs_sandbox?: boolean | null, sandbox_writable_paths?: Array<string> | null, };
 
 export type SandboxMode = "auto" | "read-only" | "workspace-write" | "danger-full-access";
 
@@ -178,13 +476,96 @@ export type ReasoningSummary = "auto" | "concise" | "detailed" | "none";
 
 export type ReasoningSummaryFormat = "none" | "experimental";
 
-export type CursorAgent = { append_prompt: AppendPrompt, force?: boolean | null, model?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type CursorAgent = { append_prompt: AppendPrompt, force?: boolean | null, model?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, limits?: ResourceLimits | null, fs_sandbox?: boolean | null, sandbox_writable_paths?: Array<string> | null, };
 
-export type Copilot = { append_prompt: AppendPrompt, model?: string | null, allow_all_tools?: boolean | null, allow_tool?: string | null, deny_tool?: string | null, add_dir?: Array<string> | null, disable_mcp_server?: Array<string> | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type Copilot = { append_prompt: AppendPrompt, model?: string | null, allow_all_tools?: boolean | null, allow_tool?: string | null, deny_tool?: string | null, add_dir?: Array<string> | null, disable_mcp_server?: Array<string> | null, base_command_override?: string | null, additional_params?: Array<string> | null, limits?: ResourceLimits | null, fs_sandbox?: boolean | null, sandbox_writable_paths?: Array<string> | null, };
 
-export type Opencode = { append_prompt: AppendPrompt, model?: string | null, agent?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type Opencode = { append_prompt: AppendPrompt, model?: string | null, agent?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, limits?: ResourceLimits | null, fs_sandbox?: boolean | null, sandbox_writable_paths?: Array<string> | null, };
 
-export type QwenCode = { append_prompt: AppendPrompt, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type QwenCode = { append_prompt: AppendPrompt, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, limits?: ResourceLimits | null, fs_sandbox?: boolean | null, sandbox_writable_paths?: Array<string> | null, };
+
+/**
+ * Generic executor for any agent that speaks the Agent Client Protocol over stdio.
+ * Everything needed to launch the agent comes from the profile, so new ACP agents
+ * can be added through `profiles.json` without a code change.
+ */
+export type Acp = { append_prompt: AppendPrompt, command: string, args?: Array<string> | null, env?: { [key in string]?: string } | null, session_namespace?: string | null, mcp_config_path?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, limits?: ResourceLimits | null, fs_sandbox?: boolean | null, sandbox_writable_paths?: Array<string> | null, };
+
+/**
+ * Generic executor for command line agents that don't have a dedicated integration.
+ * The command, prompt delivery, output parsing and session handling all come from the profile.
+ */
+export type GenericCli = { append_prompt: AppendPrompt, command: string, args?: Array<string> | null, env?: { [key in string]?: string } | null, prompt_input: PromptInput, output: OutputParser, unmatched_lines: UnmatchedLines, session?: SessionResume | null, mcp_config_path?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, limits?: ResourceLimits | null, fs_sandbox?: boolean | null, sandbox_writable_paths?: Array<string> | null, };
+
+/**
+ * How the prompt is handed to the agent
+ */
+export type PromptInput = "stdin" | "argument";
+
+/**
+ * How stdout is turned into normalized entries. The first matching rule wins.
+ */
+export type OutputParser = { "format": "plain_text" } | { "format": "json_lines", rules: Array<JsonLineRule>, } | { "format": "regex", rules: Array<RegexRule>, };
+
+export type JsonLineRule = { 
+/**
+ * Dotted JSON paths that must equal the given values for the rule to apply
+ */
+when: { [key in string]?: JsonValue }, } & EntryMapping;
+
+export type RegexRule = { pattern: string, } & EntryMapping;
+
+/**
+ * Describes how a matched line becomes a normalized entry.
+ *
+ * String fields are templates: `{field}` is replaced with the value at a dotted JSON path
+ * (`json_lines`) or with a capture group, by name or number (`regex`). Text outside braces is kept
+ * as-is, so `"bash"` is a literal and `"{tool.name}"` a lookup.
+ */
+export type EntryMapping = { entry_type: MappedEntryType, 
+/**
+ * Entry content; defaults to the whole line
+ */
+content?: string | null, tool_name?: string | null, action?: MappedAction | null, 
+/**
+ * Path, command, query or URL depending on `action`
+ */
+target?: string | null, 
+/**
+ * Unified diff for `file_edit` actions
+ */
+unified_diff?: string | null, 
+/**
+ * Correlates `tool_use` and `tool_result` entries
+ */
+tool_call_id?: string | null, 
+/**
+ * For `tool_result`: renders to `true`/`1` when the tool failed
+ */
+failed?: string | null, };
+
+/**
+ * Kind of normalized entry produced by a matching rule
+ */
+export type MappedEntryType = "user_message" | "assistant_message" | "thinking" | "system_message" | "error_message" | "tool_use" | "tool_result";
+
+/**
+ * Action shown for a `tool_use` entry
+ */
+export type MappedAction = "file_read" | "file_edit" | "command_run" | "search" | "web_fetch" | "tool" | "other";
+
+/**
+ * What to do with output lines that no rule matches
+ */
+export type UnmatchedLines = "drop" | "assistant_message" | "system_message";
+
+export type SessionResume = { extractor: SessionIdExtractor, 
+/**
+ * Arguments added for follow-ups, with `{session_id}` replaced by the captured id
+ */
+resume_args: Array<string>, };
+
+export type SessionIdExtractor = { "json_path": string } | { "regex": string };
 
 export type AppendPrompt = string | null;
 
@@ -268,10 +649,46 @@ after_head_commit: string | null, status: ExecutionProcessStatus, exit_code: big
  */
 dropped: boolean, started_at: string, completed_at: string | null, created_at: string, updated_at: string, };
 
-export enum ExecutionProcessStatus { running = "running", completed = "completed", failed = "failed", killed = "killed" }
+export enum ExecutionProcessStatus { running = "running", completed = "completed", failed = "failed", killed = "killed", limitexceeded = "limitexceeded" }
 
 export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver";
 
+export type ExecutionProcessLogPage = { 
+/**
+ * Index of the first entry in `entries`
+ */
+offset: bigint, 
+/**
+ * Number of entries stored for the process
+ */
+total: bigint, 
+/**
+ * `at` is the capture time, missing for logs stored before it was recorded
+ */
+entries: Array<({ Stdout: string } | { Stderr: string }) & { at?: string }>, 
+/**
+ * Set when the retention policy removed the logs
+ */
+pruned: ExecutionProcessLogPrune | null, };
+
+/**
+ * What was removed when an execution's raw logs were pruned
+ */
+export type ExecutionProcessLogPrune = { execution_id: string, line_count: bigint, byte_size: bigint, stored_size: bigint, reason: LogPruneReason, pruned_at: string, };
+
+export type LogPruneReason = "expired" | "dropped" | "quota";
+
+/**
+ * Raw logs the retention policy removes, or would remove on a dry run
+ */
+export type RetentionReport = { dry_run: boolean, candidates: Array<RetentionCandidate>, 
+/**
+ * Database bytes freed by removing every candidate
+ */
+total_bytes: bigint, };
+
+export type RetentionCandidate = { execution_id: string, task_attempt_id: string, project_id: string, reason: LogPruneReason, stored_size: bigint, completed_at: string | null, };
+
 export type Merge = { "type": "direct" } & DirectMerge | { "type": "pr" } & PrMerge;
 
 export type DirectMerge = { id: string, task_attempt_id: string, merge_commit: string, target_branch_name: string, created_at: string, };
@@ -282,6 +699,39 @@ export type MergeStatus = "open" | "merged" | "closed" | "unknown";
 
 export type PullRequestInfo = { number: bigint, url: string, status: MergeStatus, merged_at: string | null, merge_commit_sha: string | null, };
 
+/**
+ * An entry of the append-only audit log
+ */
+export type AuditEvent = { id: string, project_id: string | null, task_id: string | null, task_attempt_id: string | null, actor: AuditActor, action: AuditAction, 
+/**
+ * Action specific context, e.g. the previous and new status of a task
+ */
+details: JsonValue | null, created_at: string, };
+
+/**
+ * Who caused an audited change
+ */
+export type AuditActor = "user" | "agent" | "mcp" | "pr_monitor" | "system";
+
+export type AuditAction = "task_status_changed" | "task_deleted" | "task_moved" | "attempt_merged" | "branch_pushed" | "pr_created" | "pr_attached" | "approval_responded" | "config_updated" | "profiles_updated" | "mcp_servers_updated" | "project_updated" | "project_deleted";
+
+/**
+ * Filters of an audit log query. Results are newest first.
+ */
+export type AuditQuery = { project_id?: string, actor?: AuditActor, 
+/**
+ * Only events at or after this time
+ */
+from?: string, 
+/**
+ * Only events before this time
+ */
+to?: string, 
+/**
+ * Defaults to 100, capped at 500
+ */
+limit?: number, };
+
 export type Draft = { id: string, task_attempt_id: string, draft_type: DraftType, retry_process_id: string | null, prompt: string, queued: boolean, sending: boolean, variant: string | null, image_ids: Array<string> | null, created_at: string, updated_at: string, version: bigint, };
 
 export type DraftType = "follow_up" | "retry";